@@ -0,0 +1,46 @@
+//! Parses a command line against 100 flags
+//!
+//! Run with `cargo bench --bench flags`.
+
+use seahorse::{Context, Flag, FlagType};
+use std::time::Instant;
+
+const FLAGS: usize = 100;
+const ITERATIONS: u32 = 1_000;
+
+fn flags() -> Vec<Flag> {
+    (0..FLAGS)
+        .map(|i| Flag::new(format!("flag-{}", i), FlagType::String).alias(format!("f{}", i)))
+        .collect()
+}
+
+fn args() -> Vec<String> {
+    let mut args = vec!["positional".to_string()];
+    for i in (0..FLAGS).step_by(2) {
+        args.push(format!("--flag-{}", i));
+        args.push(format!("value-{}", i));
+        args.push(format!("-f{}", i + 1));
+        args.push(format!("value-{}", i + 1));
+    }
+    args
+}
+
+fn main() {
+    let flags = flags();
+    let args = args();
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let context = Context::new(args.clone(), Some(flags.clone()), String::new());
+        for i in 0..FLAGS {
+            assert!(context.string_flag(&format!("flag-{}", i)).is_ok());
+        }
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "parse and read {} flags: {:?} per iteration",
+        FLAGS,
+        elapsed / ITERATIONS
+    );
+}
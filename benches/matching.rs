@@ -0,0 +1,70 @@
+//! Looks up 50 flags in 200 tokens with `Flag::option_index`, against
+//! matching that builds the dashed forms of the flag for every token
+//!
+//! Run with `cargo bench --bench matching`.
+
+use seahorse::{Flag, FlagType};
+use std::time::Instant;
+
+const FLAGS: usize = 50;
+const TOKENS: usize = 200;
+const ITERATIONS: u32 = 200;
+
+fn flags() -> Vec<Flag> {
+    (0..FLAGS)
+        .map(|i| Flag::new(format!("flag-{}", i), FlagType::String).alias(format!("f{}", i)))
+        .collect()
+}
+
+/// Tokens giving the flags in reverse order, between values and positional
+/// arguments, so that lookups scan most of them
+fn tokens() -> Vec<String> {
+    (0..TOKENS)
+        .map(|i| match i % 4 {
+            0 => format!("--flag-{}", FLAGS - 1 - (i / 4) % FLAGS),
+            2 => format!("-f{}", FLAGS - 1 - (i / 4) % FLAGS),
+            _ => format!("value-{}", i),
+        })
+        .collect()
+}
+
+/// Position of the flag like `option_index` did before it compared tokens
+/// without allocating
+fn allocating_index(flag: &Flag, v: &[String]) -> Option<usize> {
+    v.iter().position(|r| {
+        r == &format!("--{}", flag.name)
+            || (flag.name.chars().count() == 1 && r == &format!("-{}", flag.name))
+            || (r != "-"
+                && flag
+                    .alias
+                    .iter()
+                    .flatten()
+                    .chain(flag.hidden_alias.iter().flatten())
+                    .any(|a| r == &format!("-{}", a)))
+    })
+}
+
+fn main() {
+    let flags = flags();
+    let tokens = tokens();
+
+    let time = |label: &str, index: &dyn Fn(&Flag, &[String]) -> Option<usize>| {
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            for flag in &flags {
+                assert!(index(flag, &tokens).is_some());
+            }
+        }
+        println!(
+            "{}: {:?} per iteration looking up {} flags in {} tokens",
+            label,
+            start.elapsed() / ITERATIONS,
+            FLAGS,
+            TOKENS
+        );
+    };
+    time("allocating", &allocating_index);
+    time("option_index", &|flag: &Flag, v: &[String]| {
+        flag.option_index(v)
+    });
+}
@@ -0,0 +1,72 @@
+//! Runs a medium-sized app 10k times, as is and compiled
+//!
+//! Run with `cargo bench --bench run`.
+
+use seahorse::{App, Command, Context, Flag, FlagType};
+use std::time::Instant;
+
+const ITERATIONS: u32 = 10_000;
+
+fn action(c: &Context) {
+    assert_eq!(c.str_flag("name"), Ok("origin"));
+}
+
+fn app() -> App {
+    let mut remote = Command::new("remote")
+        .description("Manage remotes")
+        .flag(Flag::new("verbose", FlagType::Bool).alias("v"));
+    for name in &["add", "remove", "rename", "show", "prune"] {
+        remote = remote.command(
+            Command::new(*name)
+                .description("Remote subcommand")
+                .action(action)
+                .flag(Flag::new("name", FlagType::String).alias("n"))
+                .flag(Flag::new("fetch", FlagType::Bool).alias("f"))
+                .flag(Flag::new("depth", FlagType::Int).alias("d"))
+                .flag(Flag::new("ratio", FlagType::Float)),
+        );
+    }
+
+    App::new("cli")
+        .description("Medium-sized app")
+        .flag(Flag::new("config", FlagType::String).alias("c"))
+        .command(remote)
+        .command(Command::new("status").action(|_: &Context| {}))
+}
+
+fn main() {
+    let app = app();
+    let args: Vec<String> = [
+        "cli",
+        "remote",
+        "prune",
+        "--name=origin",
+        "-f",
+        "-d",
+        "3",
+        "one",
+        "two",
+        "three",
+    ]
+    .iter()
+    .map(|a| a.to_string())
+    .collect();
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        app.run(args.clone());
+    }
+    let elapsed = start.elapsed();
+    println!("run medium app: {:?} per iteration", elapsed / ITERATIONS);
+
+    let app = app.compile();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        app.run(args.clone());
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "run compiled medium app: {:?} per iteration",
+        elapsed / ITERATIONS
+    );
+}
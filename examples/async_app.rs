@@ -0,0 +1,78 @@
+//! Async actions awaited with `App::run_async`
+//!
+//! Built with `cargo run --example async_app --features async -- fetch a b`.
+//! The app is awaited on the current thread here; in an app using tokio,
+//! `main` is `#[tokio::main] async fn main()` and awaits `run_async` the
+//! same way.
+
+// The `async` feature needs a newer Rust than the crate's minimum
+#![allow(clippy::incompatible_msrv)]
+
+use seahorse::{Action, App, Command, Context, Flag, FlagType};
+use std::env;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll, Wake, Waker};
+
+/// Client shared by the actions, like a connection pool
+struct Client {
+    base: String,
+}
+
+impl Client {
+    async fn get(&self, path: &str) -> String {
+        format!("{}/{}", self.base, path)
+    }
+}
+
+fn main() {
+    let client = Arc::new(Client {
+        base: "https://example.com".to_string(),
+    });
+    let app = App::new("async_app")
+        .description("Async actions example")
+        .command(fetch_command(client))
+        .after_action(|c: &Context| println!("done, status {:?}", c.exit_code()));
+
+    block_on(app.run_async(env::args().collect()));
+}
+
+fn fetch_command(client: Arc<Client>) -> Command {
+    Command::new("fetch")
+        .usage("async_app fetch [paths...]")
+        .flag(Flag::new("fail-fast", FlagType::Bool))
+        .action(Action::from_async(move |c: &Context| {
+            let client = client.clone();
+            Box::pin(async move {
+                for path in &c.args {
+                    println!("{}", client.get(path).await);
+                }
+                if c.args.is_empty() && c.bool_flag("fail-fast") {
+                    c.exit(1);
+                }
+            })
+        }))
+}
+
+/// Poll the future until it's ready, standing in for the executor of a
+/// runtime
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unpark(std::thread::Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    let mut cx = TaskContext::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = Pin::as_mut(&mut future).poll(&mut cx) {
+            return output;
+        }
+        std::thread::park();
+    }
+}
@@ -0,0 +1,89 @@
+//! Print the completion script of a sample app
+//!
+//! ```bash
+//! # bash
+//! $ cargo run --example completion -- bash > /tmp/completion.bash
+//! $ source /tmp/completion.bash
+//! $ completion <TAB>
+//!
+//! # zsh (the script must be named `_completion` and placed in `$fpath`)
+//! $ cargo run --example completion -- zsh > ~/.zfunc/_completion
+//!
+//! # fish
+//! $ cargo run --example completion -- fish > ~/.config/fish/completions/completion.fish
+//! ```
+//!
+//! PowerShell:
+//!
+//! ```powershell
+//! PS> cargo run --example completion -- powershell | Out-String | Invoke-Expression
+//! PS> completion <TAB>          # offers hello, h, --bye, -b, --help, -h
+//! PS> completion hello -<TAB>   # offers --age, -a, --format, --name, --help, -h
+//! PS> completion hello --format <TAB>  # offers json and yaml
+//! PS> completion hello --name <TAB>    # offers alice and bob from `__complete`
+//! ```
+//!
+//! Descriptions appear as tooltips in the menu shown by `Ctrl+Space`.
+//! The binary has to be on `PATH` as `completion` for the completer to be used.
+//!
+//! Values of `--name` come from the app itself through the hidden
+//! `__complete` command, which can also be run directly:
+//!
+//! ```bash
+//! $ cargo run --example completion -- __complete 2 hello --name
+//! alice
+//! bob
+//! ```
+
+use seahorse::{App, Command, Context, Flag, FlagType, Shell};
+use std::env;
+use std::io;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let shell = match args.get(1).map(String::as_str).unwrap_or_default() {
+        "bash" => Shell::Bash,
+        "zsh" => Shell::Zsh,
+        "fish" => Shell::Fish,
+        "powershell" => Shell::PowerShell,
+        _ => {
+            app().run(args);
+            return;
+        }
+    };
+
+    app().generate_completion(shell, &mut io::stdout()).unwrap();
+}
+
+fn app() -> App {
+    App::new("completion")
+        .description("completion example")
+        .action(|c: &Context| println!("{:?}", c.args))
+        .flag(
+            Flag::new("bye", FlagType::Bool)
+                .description("Bye flag")
+                .alias("b"),
+        )
+        .command(
+            Command::new("hello")
+                .description("hello command")
+                .alias("h")
+                .flag(
+                    Flag::new("age", FlagType::Int)
+                        .description("Age flag")
+                        .alias("a"),
+                )
+                .flag(
+                    Flag::new("format", FlagType::String)
+                        .description("Output format")
+                        .choices(&["json", "yaml"]),
+                )
+                .flag(
+                    Flag::new("name", FlagType::String)
+                        .description("Name to greet")
+                        .complete_with(|_: &Context, _: &str| {
+                            vec!["alice".to_string(), "bob".to_string()]
+                        }),
+                ),
+        )
+}
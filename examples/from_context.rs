@@ -0,0 +1,63 @@
+use seahorse::{error::FlagError, App, Command, Context, Flag, FlagType, FromContext};
+use std::env;
+
+struct BuildOpts {
+    jobs: i64,
+    out: String,
+    release: bool,
+    targets: Vec<String>,
+}
+
+impl FromContext for BuildOpts {
+    fn from_context(c: &Context) -> Result<Self, FlagError> {
+        Ok(Self {
+            jobs: c.get_or("jobs", 1i64)?,
+            out: c.get_or("out", "a.out")?,
+            release: c.bool_flag("release"),
+            targets: c.args.clone(),
+        })
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let app = App::new("from_context")
+        .description("FromContext example")
+        .usage("from_context build [targets...]")
+        .command(build_command());
+
+    app.run(args);
+}
+
+fn build_action(c: &Context) {
+    match c.parse::<BuildOpts>() {
+        Ok(opts) => build(opts),
+        Err(e) => eprintln!("{}", e),
+    }
+}
+
+fn build(opts: BuildOpts) {
+    let profile = if opts.release { "release" } else { "debug" };
+    println!(
+        "Building {:?} into {} with {} jobs ({})",
+        opts.targets, opts.out, opts.jobs, profile
+    );
+}
+
+fn build_command() -> Command {
+    Command::new("build")
+        .description("Build targets")
+        .usage("from_context build [targets...]")
+        .action(build_action)
+        .flag(
+            Flag::new("jobs", FlagType::Int)
+                .description("Parallel jobs")
+                .alias("j"),
+        )
+        .flag(
+            Flag::new("out", FlagType::String)
+                .description("Output file")
+                .alias("o"),
+        )
+        .flag(Flag::new("release", FlagType::Bool).description("Release build"))
+}
@@ -0,0 +1,30 @@
+use seahorse::{App, Command, Context, Flag, FlagType};
+use std::fs::{self, File};
+
+fn main() {
+    let app = App::new("cli")
+        .description(env!("CARGO_PKG_DESCRIPTION"))
+        .usage("cli [command] [arg]")
+        .version(env!("CARGO_PKG_VERSION"))
+        .action(|c: &Context| println!("{:?}", c.args))
+        .flag(
+            Flag::new("bye", FlagType::Bool)
+                .description("Bye flag")
+                .alias("b"),
+        )
+        .command(
+            Command::new("calc")
+                .description("calc command")
+                .usage("cli calc [nums...]")
+                .flag(
+                    Flag::new("operator", FlagType::String)
+                        .description("Operator flag")
+                        .alias("op")
+                        .default_value("add"),
+                ),
+        );
+
+    fs::create_dir_all("docs").unwrap();
+    let mut file = File::create("docs/cli.md").unwrap();
+    app.generate_markdown(&mut file).unwrap();
+}
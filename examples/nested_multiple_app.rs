@@ -35,6 +35,7 @@ fn hello_action(c: &Context) {
             FlagError::Undefined => println!("undefined age flag"),
             FlagError::ArgumentError => println!("age flag argument error"),
             FlagError::NotFound => println!("not found age flag"),
+            e @ FlagError::ParseError { .. } => println!("{}", e),
         },
     }
 
@@ -46,6 +47,7 @@ fn hello_action(c: &Context) {
             FlagError::Undefined => println!("undefined neko flag"),
             FlagError::ArgumentError => println!("neko flag argument error"),
             FlagError::NotFound => println!("not found neko flag"),
+            e @ FlagError::ParseError { .. } => println!("{}", e),
         },
     }
 }
@@ -0,0 +1,53 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use seahorse::{App, Arg, Command, Context, Flag, FlagGroup, FlagType, Prompt};
+
+/// App with a flag of each type and a couple of commands, the same as the
+/// one of `tests/no_panic.rs`
+fn app() -> App {
+    let action = |c: &Context| {
+        let _ = c.string_flag("name");
+        let _ = c.values::<i64>("point");
+        let _ = c.get::<u16>("port");
+    };
+    App::new("cli")
+        .use_pager(false)
+        .prompt(Prompt::non_interactive())
+        .action(action)
+        .flag(Flag::new("verbose", FlagType::Bool).alias("v").global())
+        .flag(Flag::new("name", FlagType::String).alias("n"))
+        .flag(Flag::new("count", FlagType::Int).alias("c"))
+        .flag(Flag::new("port", FlagType::Uint).alias("p"))
+        .flag(Flag::new("ratio", FlagType::Float))
+        .flag(Flag::new("token", FlagType::Secret))
+        .command(
+            Command::new("add")
+                .alias("a")
+                .action(action)
+                .flag(Flag::new("point", FlagType::Int).number_of_values(2))
+                .flag(Flag::new("tag", FlagType::String).repeatable())
+                .arg(Arg::new("NAME", FlagType::String).required())
+                .arg(Arg::new("PORT", FlagType::Uint)),
+        )
+        .command(
+            Command::new("remote")
+                .command(
+                    Command::new("prune")
+                        .action(action)
+                        .flag(Flag::new("dry-run", FlagType::Bool))
+                        .flag(Flag::new("all", FlagType::Bool))
+                        .flag_group(FlagGroup::new("mode").member("dry-run").member("all")),
+                )
+                .command(Command::new("show").action(action).max_args(1)),
+        )
+}
+
+// Arguments are the input split at NUL bytes, as the kernel passes them
+fuzz_target!(|data: &[u8]| {
+    let args = String::from_utf8_lossy(data)
+        .split('\0')
+        .map(String::from)
+        .collect();
+    let _ = app().try_run(args);
+});
@@ -0,0 +1,491 @@
+//! Derive macro for defining [seahorse](https://docs.rs/seahorse) commands from structs
+//!
+//! Enable it with the `derive` feature of seahorse and use it as
+//! `seahorse::Command`. Each field of the struct is a flag, or a positional
+//! argument when marked with `#[arg]`.
+//!
+//! | Field type | Flag type | Missing flag |
+//! |---|---|---|
+//! | `bool` | `FlagType::Bool` | `false` |
+//! | `String` | `FlagType::String` | `FlagError::NotFound` |
+//! | `i64` | `FlagType::Int` | `FlagError::NotFound` |
+//! | `u64` | `FlagType::Uint` | `FlagError::NotFound` |
+//! | `f64` | `FlagType::Float` | `FlagError::NotFound` |
+//! | `Secret` | `FlagType::Secret` | `FlagError::NotFound` |
+//! | `DateTime` | `FlagType::DateTime` | `FlagError::NotFound` |
+//! | `Uuid` | `FlagType::Uuid`, with the `uuid` feature | `FlagError::NotFound` |
+//! | `Url` | `FlagType::Url`, with the `url` feature | `FlagError::NotFound` |
+//! | `Option<T>` | type of `T` | `None` |
+//!
+//! Positional arguments are `String`, `Option<String>` or, as the last one,
+//! `Vec<String>` collecting the remaining arguments.
+//!
+//! Attributes:
+//!
+//! - `#[command(name = "..", description = "..", usage = "..", alias = "..")]`
+//!   on the struct. The name defaults to the struct name in kebab case and
+//!   the description to the doc comment.
+//! - `#[flag(long = "..", short = 'x', alias = "..", default = "..", description = "..", hidden)]`
+//!   on flag fields. The name defaults to the field name with `-` for `_`
+//!   and the description to the doc comment.
+//! - `#[arg]` on positional fields.
+//!
+//! Example
+//!
+//! ```
+//! use seahorse::{App, CommandArgs};
+//!
+//! /// Build the project
+//! #[derive(seahorse::Command)]
+//! struct Build {
+//!     /// Output directory
+//!     #[flag(short = 'o', default = "out")]
+//!     output: String,
+//!     /// Number of parallel jobs
+//!     #[flag(short = 'j')]
+//!     jobs: Option<u64>,
+//!     #[arg]
+//!     targets: Vec<String>,
+//! }
+//!
+//! let app = App::new("cli").command(Build::command_with(|build: Build| {
+//!     println!("{} {:?} {:?}", build.output, build.jobs, build.targets);
+//! }));
+//! ```
+
+extern crate proc_macro;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Expr, ExprLit, Field, Fields,
+    GenericArgument, Lit, LitStr, PathArguments, Result, Type,
+};
+
+/// Derive `seahorse::CommandArgs` and `seahorse::FromContext`
+#[proc_macro_derive(Command, attributes(command, flag, arg))]
+pub fn derive_command(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Value type of a flag or positional argument
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Bool,
+    String,
+    Int,
+    Uint,
+    Float,
+    Secret,
+    DateTime,
+    Uuid,
+    Url,
+}
+
+impl Kind {
+    fn from_ident(ident: &str) -> Option<Self> {
+        match ident {
+            "bool" => Some(Kind::Bool),
+            "String" => Some(Kind::String),
+            "i64" => Some(Kind::Int),
+            "u64" => Some(Kind::Uint),
+            "f64" => Some(Kind::Float),
+            "Secret" => Some(Kind::Secret),
+            "DateTime" => Some(Kind::DateTime),
+            "Uuid" => Some(Kind::Uuid),
+            "Url" => Some(Kind::Url),
+            _ => None,
+        }
+    }
+
+    fn flag_type(self) -> TokenStream {
+        match self {
+            Kind::Bool => quote!(::seahorse::FlagType::Bool),
+            Kind::String => quote!(::seahorse::FlagType::String),
+            Kind::Int => quote!(::seahorse::FlagType::Int),
+            Kind::Uint => quote!(::seahorse::FlagType::Uint),
+            Kind::Float => quote!(::seahorse::FlagType::Float),
+            Kind::Secret => quote!(::seahorse::FlagType::Secret),
+            Kind::DateTime => quote!(::seahorse::FlagType::DateTime),
+            Kind::Uuid => quote!(::seahorse::FlagType::Uuid),
+            Kind::Url => quote!(::seahorse::FlagType::Url),
+        }
+    }
+
+    fn getter(self) -> TokenStream {
+        match self {
+            Kind::Bool => quote!(bool_flag),
+            Kind::String => quote!(string_flag),
+            Kind::Int => quote!(int_flag),
+            Kind::Uint => quote!(uint_flag),
+            Kind::Float => quote!(float_flag),
+            Kind::Secret => quote!(secret_flag),
+            Kind::DateTime => quote!(datetime_flag),
+            Kind::Uuid => quote!(uuid_flag),
+            Kind::Url => quote!(url_flag),
+        }
+    }
+}
+
+#[derive(Default)]
+struct CommandAttrs {
+    name: Option<String>,
+    description: Option<String>,
+    usage: Option<String>,
+    aliases: Vec<String>,
+}
+
+#[derive(Default)]
+struct FlagAttrs {
+    long: Option<String>,
+    aliases: Vec<String>,
+    default: Option<String>,
+    description: Option<String>,
+    hidden: bool,
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter().collect::<Vec<&Field>>(),
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(_) => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "`Command` can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "`Command` can only be derived for structs",
+            ))
+        }
+    };
+
+    let attrs = command_attrs(input)?;
+    let name = attrs
+        .name
+        .unwrap_or_else(|| kebab_case(&input.ident.to_string()));
+    let mut command = quote!(::seahorse::Command::new(#name));
+    if let Some(description) = attrs.description {
+        command = quote!(#command.description(#description));
+    }
+    if let Some(usage) = attrs.usage {
+        command = quote!(#command.usage(#usage));
+    }
+    for alias in attrs.aliases {
+        command = quote!(#command.alias(#alias));
+    }
+
+    let mut values = Vec::new();
+    let mut position = 0usize;
+    let mut rest: Option<&Field> = None;
+
+    for field in &fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let is_arg = field.attrs.iter().any(|a| a.path().is_ident("arg"));
+        let is_flag = field.attrs.iter().any(|a| a.path().is_ident("flag"));
+
+        if is_arg && is_flag {
+            return Err(Error::new_spanned(
+                ident,
+                "a field cannot be both `#[flag]` and `#[arg]`",
+            ));
+        }
+
+        let value = if is_arg {
+            if let Some(rest) = rest {
+                return Err(Error::new_spanned(
+                    &rest.ty,
+                    "`Vec<String>` argument must be the last positional argument",
+                ));
+            }
+            for attr in field.attrs.iter().filter(|a| a.path().is_ident("arg")) {
+                attr.meta.require_path_only()?;
+            }
+            let value = arg_value(field, position)?;
+            if is_vec_string(&field.ty) {
+                rest = Some(field);
+            }
+            position += 1;
+            value
+        } else {
+            let (flag, value) = flag(field)?;
+            command = quote!(#command.flag(#flag));
+            value
+        };
+
+        values.push(quote!(#ident: #value));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::seahorse::FromContext for #ident #ty_generics #where_clause {
+            fn from_context(
+                c: &::seahorse::Context,
+            ) -> ::std::result::Result<Self, ::seahorse::error::FlagError> {
+                ::std::result::Result::Ok(Self { #(#values,)* })
+            }
+        }
+
+        impl #impl_generics ::seahorse::CommandArgs for #ident #ty_generics #where_clause {
+            fn command() -> ::seahorse::Command {
+                #command
+            }
+        }
+    })
+}
+
+fn command_attrs(input: &DeriveInput) -> Result<CommandAttrs> {
+    let mut attrs = CommandAttrs {
+        description: doc(&input.attrs),
+        ..CommandAttrs::default()
+    };
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("command")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                attrs.name = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("description") {
+                attrs.description = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("usage") {
+                attrs.usage = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("alias") {
+                attrs.aliases.push(meta.value()?.parse::<LitStr>()?.value());
+            } else {
+                return Err(meta.error(
+                    "unknown `command` attribute, expected `name`, `description`, `usage` or `alias`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(attrs)
+}
+
+/// Flag of the field and the expression reading it from the context
+fn flag(field: &Field) -> Result<(TokenStream, TokenStream)> {
+    let ident = field.ident.as_ref().expect("named field");
+    let mut attrs = FlagAttrs {
+        description: doc(&field.attrs),
+        ..FlagAttrs::default()
+    };
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("flag")) {
+        if let syn::Meta::Path(_) = attr.meta {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("long") {
+                let long = meta.value()?.parse::<LitStr>()?;
+                validate_name(&long)?;
+                attrs.long = Some(long.value());
+            } else if meta.path.is_ident("short") {
+                let short = match meta.value()?.parse::<Lit>()? {
+                    Lit::Char(c) => c.value(),
+                    lit => return Err(Error::new_spanned(lit, "`short` must be a char like 'o'")),
+                };
+                attrs.aliases.push(short.to_string());
+            } else if meta.path.is_ident("alias") {
+                let alias = meta.value()?.parse::<LitStr>()?;
+                validate_name(&alias)?;
+                attrs.aliases.push(alias.value());
+            } else if meta.path.is_ident("default") {
+                attrs.default = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("description") {
+                attrs.description = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("hidden") {
+                attrs.hidden = true;
+            } else {
+                return Err(meta.error(
+                    "unknown `flag` attribute, expected `long`, `short`, `alias`, `default`, `description` or `hidden`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+
+    let (kind, optional) = match flag_kind(&field.ty) {
+        Some(kind) => kind,
+        None => {
+            return Err(Error::new_spanned(
+                &field.ty,
+                "unsupported flag type, expected `bool`, `String`, `i64`, `u64`, `f64` or an `Option` of them",
+            ))
+        }
+    };
+    if kind == Kind::Bool && optional {
+        return Err(Error::new_spanned(
+            &field.ty,
+            "bool flags are false when missing, use `bool` instead of `Option<bool>`",
+        ));
+    }
+    if optional && attrs.default.is_some() {
+        return Err(Error::new_spanned(
+            &field.ty,
+            "an `Option` flag with a default is never `None`, remove the `Option`",
+        ));
+    }
+
+    let long = attrs
+        .long
+        .unwrap_or_else(|| ident.to_string().trim_start_matches("r#").replace('_', "-"));
+    let flag_type = kind.flag_type();
+    let mut flag = quote!(::seahorse::Flag::new(#long, #flag_type));
+    if let Some(description) = attrs.description {
+        flag = quote!(#flag.description(#description));
+    }
+    for alias in attrs.aliases {
+        flag = quote!(#flag.alias(#alias));
+    }
+    if let Some(default) = attrs.default {
+        flag = quote!(#flag.default_value(#default));
+    }
+    if attrs.hidden {
+        flag = quote!(#flag.hidden());
+    }
+
+    let getter = kind.getter();
+    let value = if kind == Kind::Bool {
+        quote!(c.bool_flag(#long))
+    } else if optional {
+        quote! {
+            match c.#getter(#long) {
+                ::std::result::Result::Ok(value) => ::std::option::Option::Some(value),
+                ::std::result::Result::Err(::seahorse::error::FlagError::NotFound) => {
+                    ::std::option::Option::None
+                }
+                ::std::result::Result::Err(e) => return ::std::result::Result::Err(e),
+            }
+        }
+    } else {
+        quote!(c.#getter(#long)?)
+    };
+
+    Ok((flag, value))
+}
+
+/// Expression reading the positional argument at the position
+fn arg_value(field: &Field, position: usize) -> Result<TokenStream> {
+    if is_vec_string(&field.ty) {
+        return Ok(quote! {
+            c.args.iter().skip(#position).cloned().collect()
+        });
+    }
+
+    match flag_kind(&field.ty) {
+        Some((Kind::String, false)) => Ok(quote! {
+            c.args
+                .get(#position)
+                .cloned()
+                .ok_or(::seahorse::error::FlagError::ArgumentError)?
+        }),
+        Some((Kind::String, true)) => Ok(quote!(c.args.get(#position).cloned())),
+        _ => Err(Error::new_spanned(
+            &field.ty,
+            "unsupported argument type, expected `String`, `Option<String>` or `Vec<String>`",
+        )),
+    }
+}
+
+/// Kind of the type and whether it is wrapped in `Option`
+fn flag_kind(ty: &Type) -> Option<(Kind, bool)> {
+    let (ident, inner) = last_segment(ty)?;
+    if ident == "Option" {
+        let (ident, inner) = last_segment(inner?)?;
+        if inner.is_some() {
+            return None;
+        }
+        return Kind::from_ident(&ident).map(|kind| (kind, true));
+    }
+    if inner.is_some() {
+        return None;
+    }
+    Kind::from_ident(&ident).map(|kind| (kind, false))
+}
+
+fn is_vec_string(ty: &Type) -> bool {
+    match last_segment(ty) {
+        Some((ident, Some(inner))) if ident == "Vec" => {
+            flag_kind(inner) == Some((Kind::String, false))
+        }
+        _ => false,
+    }
+}
+
+/// Identifier of the last path segment and its only type argument
+fn last_segment(ty: &Type) -> Option<(String, Option<&Type>)> {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    let inner = match &segment.arguments {
+        PathArguments::None => None,
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some((segment.ident.to_string(), inner))
+}
+
+/// Doc comment lines joined by spaces
+fn doc(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => Some(s.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join(" "))
+    }
+}
+
+/// Reject names that `Flag::new` would panic on
+fn validate_name(name: &LitStr) -> Result<()> {
+    let value = name.value();
+    if value.is_empty() || value.starts_with('-') || value.contains('=') || value.contains(' ') {
+        return Err(Error::new_spanned(
+            name,
+            "flag names cannot be empty, start with `-` or contain `=` or whitespaces",
+        ));
+    }
+    Ok(())
+}
+
+fn kebab_case(ident: &str) -> String {
+    let mut name = String::new();
+    for (i, c) in ident.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                name.push('-');
+            }
+            name.extend(c.to_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
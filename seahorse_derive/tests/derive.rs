@@ -0,0 +1,144 @@
+use std::sync::{Arc, Mutex};
+
+use seahorse::error::FlagError;
+use seahorse::{App, CommandArgs, Context, FlagType};
+
+/// Build the project
+#[derive(seahorse::Command, Debug, PartialEq)]
+#[command(alias = "b", usage = "cli build [targets...]")]
+struct Build {
+    /// Verbose output
+    #[flag(short = 'v')]
+    verbose: bool,
+    #[flag(long = "output", short = 'o', default = "out")]
+    out_dir: String,
+    #[flag(short = 'j', description = "Parallel jobs")]
+    jobs: i64,
+    retries: u64,
+    #[flag(alias = "thr")]
+    threshold: f64,
+    #[flag(hidden)]
+    note: Option<String>,
+    #[arg]
+    profile: String,
+    #[arg]
+    targets: Vec<String>,
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+fn parse<T: CommandArgs>(v: &[&str]) -> Result<T, FlagError> {
+    let command = T::command();
+    T::from_context(&Context::new(args(v), command.flags, String::new()))
+}
+
+#[test]
+fn command_test() {
+    let command = Build::command();
+    assert_eq!(command.name, "build");
+    assert_eq!(command.description, Some("Build the project".to_string()));
+    assert_eq!(command.usage, Some("cli build [targets...]".to_string()));
+    assert_eq!(command.alias, Some(vec!["b".to_string()]));
+
+    let flags = command.flags.unwrap();
+    let summary: Vec<(&str, FlagType, Option<&str>)> = flags
+        .iter()
+        .map(|f| {
+            (
+                f.name.as_str(),
+                f.flag_type.clone(),
+                f.description.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("verbose", FlagType::Bool, Some("Verbose output")),
+            ("output", FlagType::String, None),
+            ("jobs", FlagType::Int, Some("Parallel jobs")),
+            ("retries", FlagType::Uint, None),
+            ("threshold", FlagType::Float, None),
+            ("note", FlagType::String, None),
+        ]
+    );
+    assert_eq!(flags[1].alias, Some(vec!["o".to_string()]));
+    assert_eq!(flags[1].default_value, Some("out".to_string()));
+    assert_eq!(flags[4].alias, Some(vec!["thr".to_string()]));
+    assert!(flags[5].hidden);
+}
+
+#[test]
+fn round_trip_test() {
+    let build: Build = parse(&[
+        "release",
+        "-v",
+        "-j",
+        "-4",
+        "--retries",
+        "3",
+        "-thr",
+        "0.5",
+        "--note",
+        "hi",
+        "app",
+        "lib",
+    ])
+    .unwrap();
+
+    assert_eq!(
+        build,
+        Build {
+            verbose: true,
+            out_dir: "out".to_string(),
+            jobs: -4,
+            retries: 3,
+            threshold: 0.5,
+            note: Some("hi".to_string()),
+            profile: "release".to_string(),
+            targets: args(&["app", "lib"]),
+        }
+    );
+}
+
+#[test]
+fn missing_test() {
+    let all = ["dev", "-j", "1", "--retries", "1", "--threshold", "1"];
+    let build: Build = parse(&all).unwrap();
+    assert!(!build.verbose);
+    assert_eq!(build.note, None);
+    assert!(build.targets.is_empty());
+
+    assert_eq!(parse::<Build>(&all[1..]), Err(FlagError::ArgumentError));
+    assert_eq!(parse::<Build>(&all[..5]), Err(FlagError::NotFound));
+    assert_eq!(
+        parse::<Build>(&["dev", "-j", "x", "--retries", "1", "--threshold", "1"]),
+        Err(FlagError::ValueTypeError)
+    );
+}
+
+#[derive(seahorse::Command)]
+struct DryRun {
+    #[flag(short = 'n')]
+    dry_run: bool,
+}
+
+#[test]
+fn command_with_test() {
+    let seen = Arc::new(Mutex::new(None));
+    let handler_seen = seen.clone();
+    let app = App::new("cli").command(DryRun::command_with(move |d: DryRun| {
+        *handler_seen.lock().unwrap() = Some(d.dry_run);
+    }));
+
+    app.run(args(&["cli", "dry-run", "-n"]));
+    assert_eq!(*seen.lock().unwrap(), Some(true));
+}
+
+#[test]
+fn ui_test() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
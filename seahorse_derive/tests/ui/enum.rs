@@ -0,0 +1,6 @@
+#[derive(seahorse::Command)]
+enum Cli {
+    Build,
+}
+
+fn main() {}
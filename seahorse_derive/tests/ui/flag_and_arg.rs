@@ -0,0 +1,8 @@
+#[derive(seahorse::Command)]
+struct Cli {
+    #[flag]
+    #[arg]
+    file: String,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(seahorse::Command)]
+struct Cli {
+    #[flag(long = "--output")]
+    output: String,
+}
+
+fn main() {}
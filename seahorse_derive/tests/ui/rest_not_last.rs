@@ -0,0 +1,9 @@
+#[derive(seahorse::Command)]
+struct Cli {
+    #[arg]
+    files: Vec<String>,
+    #[arg]
+    output: String,
+}
+
+fn main() {}
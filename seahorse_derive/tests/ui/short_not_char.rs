@@ -0,0 +1,7 @@
+#[derive(seahorse::Command)]
+struct Cli {
+    #[flag(short = "o")]
+    output: String,
+}
+
+fn main() {}
@@ -0,0 +1,5 @@
+#[derive(seahorse::Command)]
+#[command(title = "cli")]
+struct Cli {}
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[derive(seahorse::Command)]
+struct Cli {
+    port: u16,
+}
+
+fn main() {}
@@ -1,9 +1,17 @@
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::Arc;
 
-use crate::Context;
+#[cfg(feature = "async")]
+use crate::executor;
+#[cfg(feature = "interrupt")]
+use crate::interrupt::Interrupt;
+use crate::{Context, Outcome};
 
 /// Command and application action type
 ///
+/// Actions are `Send + Sync` like the app holding them, so closures keep
+/// shared state in types like `Arc<Mutex<_>>` rather than `Rc<RefCell<_>>`.
+///
 /// Example
 ///
 /// ```
@@ -15,12 +23,115 @@ use crate::Context;
 /// ```
 #[derive(Clone)]
 pub struct Action {
-    inner: Arc<dyn Fn(&Context)>,
+    pub(crate) inner: ActionFn,
+}
+
+/// Function of an action
+#[derive(Clone)]
+pub(crate) enum ActionFn {
+    Sync(Arc<dyn Fn(&Context) + Send + Sync>),
+    #[cfg(feature = "async")]
+    Async(Arc<executor::AsyncActionFn>),
 }
 
 impl Action {
+    /// Run the action, waiting on the current thread for async actions
     pub fn run(&self, context: &Context) {
-        (self.inner)(context);
+        match &self.inner {
+            ActionFn::Sync(action) => action(context),
+            #[cfg(feature = "async")]
+            ActionFn::Async(action) => executor::block_on(action(context)),
+        }
+    }
+
+    /// Action running the action and then the other one
+    ///
+    /// An action leaving with `Context::exit` ends the combined action, so
+    /// the other one does not run. Async actions are waited on the current
+    /// thread, like `run` does.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Action, App, Context};
+    ///
+    /// let action = Action::from(|_: &Context| println!("building"))
+    ///     .then(|_: &Context| println!("testing"));
+    /// let app = App::new("cli").action(action);
+    /// ```
+    pub fn then<A: Into<Action>>(self, other: A) -> Self {
+        let other = other.into();
+        Action::from(move |c: &Context| {
+            self.run(c);
+            other.run(c);
+        })
+    }
+
+    /// Action running the middleware with the context and the action, which
+    /// the middleware runs when and as often as it calls it
+    ///
+    /// Middleware runs code before and after the action, like timing or
+    /// logging it, and may skip it. Wrapping a wrapped action puts the new
+    /// middleware outside.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use std::time::Instant;
+    ///
+    /// use seahorse::{Action, App, Context};
+    ///
+    /// let action = Action::from(|c: &Context| println!("{:?}", c.args)).wrap(
+    ///     |c: &Context, action: &dyn Fn(&Context)| {
+    ///         let start = Instant::now();
+    ///         action(c);
+    ///         eprintln!("took {:?}", start.elapsed());
+    ///     },
+    /// );
+    /// let app = App::new("cli").action(action);
+    /// ```
+    pub fn wrap<F>(self, middleware: F) -> Self
+    where
+        F: Fn(&Context, &dyn Fn(&Context)) + Send + Sync + 'static,
+    {
+        Action::from(move |c: &Context| middleware(c, &|c: &Context| self.run(c)))
+    }
+
+    /// Run the action, returning when it leaves with `Context::exit`
+    pub(crate) fn run_to_exit(&self, context: &Context) {
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| self.run(context))) {
+            if !payload.is::<Exit>() {
+                panic::resume_unwind(payload);
+            }
+        }
+    }
+
+    /// Create an action returning a future, awaited by `App::run_async`
+    ///
+    /// The future may borrow the context. `App::run` and `App::try_run`
+    /// wait for it on the current thread, so actions needing a runtime
+    /// like tokio's are run with `App::run_async` inside that runtime.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Action, App, Context};
+    ///
+    /// let action = Action::from_async(|c: &Context| {
+    ///     Box::pin(async move {
+    ///         println!("{:?}", c.args);
+    ///     })
+    /// });
+    /// let app = App::new("cli").action(action);
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn from_async<F>(action: F) -> Self
+    where
+        for<'a> F: Fn(&'a Context) -> executor::ActionFuture<'a> + Send + Sync + 'static,
+    {
+        Self {
+            inner: ActionFn::Async(Arc::new(action)),
+        }
     }
 }
 
@@ -33,13 +144,151 @@ impl From<fn(_: &Context)> for Action {
     }
 }*/
 
+/// Payload unwinding out of an action from `Context::exit`
+pub(crate) struct Exit;
+
+/// After actions of the app and of the mounted apps a command is under,
+/// innermost first
+#[derive(Clone, Copy, Default)]
+pub(crate) struct AfterActions<'a> {
+    action: Option<&'a Action>,
+    outer: Option<&'a AfterActions<'a>>,
+}
+
+impl<'a> AfterActions<'a> {
+    pub(crate) fn new(action: Option<&'a Action>) -> Self {
+        Self {
+            action,
+            outer: None,
+        }
+    }
+
+    /// After actions with the action of a mounted app run first
+    pub(crate) fn inner(&'a self, action: &'a Action) -> Self {
+        Self {
+            action: Some(action),
+            outer: Some(self),
+        }
+    }
+}
+
+/// Actions left to run once the command line is parsed
+// Made once per run, boxing the context would only add an allocation
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum Dispatch {
+    /// Help of the command at the path is shown, the run ends with status 0
+    Help(Vec<String>),
+    /// Version is shown, the run ends with status 0
+    Version,
+    /// No action runs, the run ends with the status
+    Done(i32),
+    /// Actions to run in order with the context
+    Run(Vec<Action>, Context),
+}
+
+impl Dispatch {
+    /// Run the actions set in order with the context, then the after actions
+    pub(crate) fn actions(
+        actions: &[Option<&Action>],
+        after_actions: &AfterActions,
+        context: Context,
+    ) -> Self {
+        let mut after_actions = Some(after_actions);
+        let after = std::iter::from_fn(|| {
+            let current = after_actions?;
+            after_actions = current.outer;
+            Some(current.action)
+        });
+        let actions = actions.iter().cloned().chain(after).flatten().cloned();
+        Dispatch::Run(actions.collect(), context)
+    }
+
+    /// Run the actions, an action leaving with `Context::exit` moving on to
+    /// the next one, and tell what the run did
+    pub(crate) fn run(self) -> Outcome {
+        let (actions, context) = match self {
+            Dispatch::Help(target) => return Outcome::HelpShown { target },
+            Dispatch::Version => return Outcome::VersionShown,
+            Dispatch::Done(exit_code) => return Outcome::Done { exit_code },
+            Dispatch::Run(actions, context) => (actions, context),
+        };
+        #[cfg(feature = "interrupt")]
+        Interrupt::install(&context);
+        let mut actions = actions.iter();
+        // The first action is the one of the command, the after actions follow
+        if let Some(action) = actions.next() {
+            action.run_to_exit(&context);
+            #[cfg(feature = "interrupt")]
+            Interrupt::handle(&context);
+        }
+        for action in actions {
+            action.run_to_exit(&context);
+        }
+        Outcome::Ran {
+            exit_code: context.exit_code().unwrap_or(0),
+            command: context.into_path(),
+        }
+    }
+}
+
 impl<F> From<F> for Action
 where
-    for<'a> F: Fn(&'a Context) + 'static,
+    for<'a> F: Fn(&'a Context) + Send + Sync + 'static,
 {
     fn from(value: F) -> Self {
         Self {
-            inner: Arc::new(value),
+            inner: ActionFn::Sync(Arc::new(value)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Action;
+    use crate::{App, Context};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn combinators_test() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let push = |event: &'static str| {
+            let events = events.clone();
+            move |_: &Context| events.lock().unwrap().push(event.to_string())
+        };
+        let middleware = |name: &'static str| {
+            let events = events.clone();
+            move |c: &Context, action: &dyn Fn(&Context)| {
+                events.lock().unwrap().push(format!("{} before", name));
+                action(c);
+                events.lock().unwrap().push(format!("{} after", name));
+            }
+        };
+        let action = Action::from(push("build"))
+            .wrap(middleware("time"))
+            .then(Action::from(push("test")).then(push("lint")))
+            .wrap(middleware("log"));
+        let app = App::new("cli").action(action.clone());
+
+        assert_eq!(app.try_run(vec!["cli".to_string()]), Ok(0));
+        assert_eq!(
+            std::mem::replace(&mut *events.lock().unwrap(), Vec::new()),
+            [
+                "log before",
+                "time before",
+                "build",
+                "time after",
+                "test",
+                "lint",
+                "log after"
+            ]
+        );
+
+        let skipping = action.wrap(|_: &Context, _: &dyn Fn(&Context)| {});
+        let app = App::new("cli").action(skipping.then(push("after")));
+        assert_eq!(app.try_run(vec!["cli".to_string()]), Ok(0));
+        assert_eq!(
+            std::mem::replace(&mut *events.lock().unwrap(), Vec::new()),
+            ["after"]
+        );
+    }
+}
@@ -1,10 +1,70 @@
-use crate::{Action, Command, Context, Flag, FlagType, Help};
+use std::env;
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::action::{AfterActions, Dispatch};
+use crate::command::{self, check_command, Settings};
+use crate::compiled::{Compiled, CompiledApp};
+use crate::config::{self, Config};
+#[cfg(feature = "dotenv")]
+use crate::dotenv;
+use crate::error::{Error, ErrorHandler, ErrorKind};
+#[cfg(feature = "async")]
+use crate::executor;
+use crate::flag::{check_aliases, check_registered};
+use crate::help::HelpForm;
+#[cfg(feature = "interrupt")]
+use crate::interrupt::{Interrupt, SignalSource};
+use crate::messages::{DefaultMessages, Messages};
+use crate::pager::{self, Pager};
+use crate::streams::Streams;
+use crate::terminal::{self, Stream};
+use crate::theme::{self, ColorChoice, Theme};
+use crate::{
+    check, completion, help, json, markdown, parser, split, tree, Action, AppSettings, Command,
+    Completer, Context, Flag, FlagSet, FlagType, Help, HelpFlag, Outcome, Problem, Prompt, Shell,
+    SplitError, Terminal, UnknownCommand, UnknownCommandHandler, UnknownFlagHandler, VersionFlag,
+};
+
+/// Exit the process with the status unless it is 0, the only place the crate
+/// exits
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn exit_process(code: i32) {
+    if code != 0 {
+        std::process::exit(code);
+    }
+}
+
+/// Leave the status to the host, which exiting would stop
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn exit_process(_code: i32) {}
+
+/// Program name without its directory and `.exe` extension
+fn program_name(program: Option<&String>) -> Option<&str> {
+    program
+        .and_then(|program| program.rsplit(std::path::is_separator).next())
+        .map(|name| name.trim_end_matches(".exe"))
+        .filter(|name| !name.is_empty())
+}
+
+/// Whether `SEAHORSE_DEBUG` asks for a parse trace
+fn debug_env() -> bool {
+    env::var_os("SEAHORSE_DEBUG").map_or(false, |value| !value.is_empty() && value != "0")
+}
+
+/// Most names an unknown command handler can retry in one run
+const MAX_UNKNOWN_COMMAND_RETRIES: usize = 4;
 
 /// Multiple action application entry point
 #[derive(Default)]
 pub struct App {
     /// Application name
     pub name: String,
+    /// Binary name used in usage lines instead of the invoked name
+    pub bin_name: Option<String>,
     /// Application author
     pub author: Option<String>,
     /// Application description
@@ -14,11 +74,88 @@ pub struct App {
     /// Application version
     pub version: Option<String>,
     /// Application commands
-    pub commands: Option<Vec<Command>>,
+    pub commands: Option<Vec<Arc<Command>>>,
     /// Application action
     pub action: Option<Action>,
+    /// Action run after the action of the app or of any command, even when it
+    /// leaves with `Context::exit`
+    pub after_action: Option<Action>,
     /// Application flags
     pub flags: Option<Vec<Flag>>,
+    /// Text shown before the help
+    pub before_help: Option<String>,
+    /// Text shown after the help
+    pub after_help: Option<String>,
+    /// Application long version printed by `--version`
+    pub long_version: Option<String>,
+    /// Sort help items alphabetically instead of by declaration order
+    pub sort_help_alphabetically: bool,
+    /// Completer of the application arguments
+    pub args_completer: Option<Completer>,
+    /// Built-in help flag, `--help, -h` unless set
+    pub help_flag: HelpFlag,
+    /// Built-in version flag, `--version, -V` unless set
+    pub version_flag: VersionFlag,
+    /// Expand `@file` arguments to the arguments read from the file
+    pub argfile: bool,
+    /// Prompt asking for missing flags
+    pub prompt: Prompt,
+    /// Handler of command names matching no command
+    pub unknown_command_handler: Option<UnknownCommandHandler>,
+    /// Handler of flags matching no flag
+    pub unknown_flag_handler: Option<UnknownFlagHandler>,
+    /// Writer of command line errors
+    pub error_handler: ErrorHandler,
+    /// Match commands by unique prefixes of their names and aliases
+    pub command_abbreviation: bool,
+    /// Report errors without the usage line and help hint
+    pub terse_errors: bool,
+    /// Show help longer than the terminal through the pager
+    pub use_pager: bool,
+    /// Pager showing long help
+    pub pager: Pager,
+    /// Styles of help and errors written to a terminal
+    pub theme: Theme,
+    /// When help and errors are styled, overridden by `--color` when added
+    pub color: ColorChoice,
+    /// Read `--color <auto|always|never>` as the color choice
+    pub color_flag: bool,
+    /// Read `/name` and `/name:value` as flags
+    pub slash_flags: bool,
+    /// Read every argument after the first positional argument as
+    /// positional, inherited by commands
+    pub stop_at_first_positional: bool,
+    /// Leave arguments after `--` out of the argument count of commands
+    pub exclude_trailing_args: bool,
+    /// Write a parse trace to stderr, also written when `SEAHORSE_DEBUG` is set
+    pub debug: bool,
+    /// Prefix of the environment variables read for flags not given
+    pub env_prefix: Option<String>,
+    /// Terminal actions see through `Context`, detected when a command line is
+    /// dispatched when `None`
+    pub terminal: Option<Terminal>,
+    /// Run the command named like the binary when invoked under another name
+    /// than the app, like busybox
+    pub multicall: bool,
+    /// Commands run for invoked names other than their own, as pairs of
+    /// invoked name and command name
+    pub multicall_names: Vec<(String, String)>,
+    /// Aliases of the users of the app, as pairs of alias and the command
+    /// line it expands to
+    pub user_aliases: Vec<(String, String)>,
+    /// Catalog of the built-in messages, English when `None`
+    pub(crate) messages: Option<Arc<dyn Messages>>,
+    /// Writers of help and of diagnostics, stdout and stderr when not set
+    pub(crate) streams: Streams,
+    /// Config file providing flag defaults
+    #[cfg(feature = "config")]
+    pub config_file: Option<PathBuf>,
+    /// Dotenv file setting environment variables missing from the environment
+    #[cfg(feature = "dotenv")]
+    pub dotenv_file: Option<PathBuf>,
+    /// Handling of Ctrl-C while an action runs
+    #[cfg(feature = "interrupt")]
+    pub(crate) interrupt: Interrupt,
 }
 
 impl App {
@@ -38,6 +175,23 @@ impl App {
         }
     }
 
+    /// Set binary name shown in usage lines and completion scripts
+    ///
+    /// By default the name the binary is invoked with is used, without its
+    /// directory and `.exe` extension, falling back to the app name.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("Remote manager").bin_name("remote");
+    /// ```
+    pub fn bin_name<T: Into<String>>(mut self, name: T) -> Self {
+        self.bin_name = Some(name.into());
+        self
+    }
+
     /// Set author of the app
     ///
     /// Example
@@ -70,6 +224,9 @@ impl App {
 
     /// Set usage of the app
     ///
+    /// The usage replaces the generated one in help and generated documents.
+    /// Lines of a multi-line usage are kept as written.
+    ///
     /// Example
     ///
     /// ```
@@ -95,6 +252,137 @@ impl App {
     /// ```
     pub fn version<T: Into<String>>(mut self, version: T) -> Self {
         self.version = Some(version.into());
+        self.check_version_flag();
+        self
+    }
+
+    /// Set text shown before the help of the app
+    ///
+    /// The text is rendered verbatim above the generated help.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .before_help("cli - a great tool");
+    /// ```
+    pub fn before_help<T: Into<String>>(mut self, text: T) -> Self {
+        self.before_help = Some(text.into());
+        self
+    }
+
+    /// Set text shown after the help of the app
+    ///
+    /// The text is rendered verbatim below the generated help.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .after_help("See https://example.com/docs for more");
+    /// ```
+    pub fn after_help<T: Into<String>>(mut self, text: T) -> Self {
+        self.after_help = Some(text.into());
+        self
+    }
+
+    /// Set long version of the app
+    ///
+    /// `--version` prints the long version instead of the `name version` line,
+    /// which is still printed by `-V`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .version("1.0.0")
+    ///     .long_version("cli 1.0.0 (3a1b2c4 2024-01-15)");
+    /// ```
+    pub fn long_version<T: Into<String>>(mut self, long_version: T) -> Self {
+        self.long_version = Some(long_version.into());
+        self.check_version_flag();
+        self
+    }
+
+    /// Set config file providing flag defaults
+    ///
+    /// The TOML file maps flag names to values, used when a flag is not
+    /// given on the command line. Top-level keys apply to the app and to
+    /// every command, and tables named after commands apply to that command
+    /// and its subcommands, the deepest table winning. A missing file is
+    /// ignored, and a value that does not match the flag type is reported
+    /// with the file and key before the action runs.
+    ///
+    /// Precedence is command line, then config file, then `default_value`.
+    /// As bool flags cannot be turned off on the command line, a bool set
+    /// to `true` in the config stays on.
+    ///
+    /// ```toml
+    /// region = "eu-west-1"
+    ///
+    /// [remote.add]
+    /// force = true
+    /// ```
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .config_file("/home/user/.clirc");
+    /// ```
+    #[cfg(feature = "config")]
+    pub fn config_file<T: Into<PathBuf>>(mut self, path: T) -> Self {
+        self.config_file = Some(path.into());
+        self
+    }
+
+    /// Load `.env` of the current directory before reading flags, see
+    /// `dotenv_file`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli").load_dotenv();
+    /// ```
+    #[cfg(feature = "dotenv")]
+    pub fn load_dotenv(self) -> Self {
+        self.dotenv_file(".env")
+    }
+
+    /// Set dotenv file loaded before reading flags
+    ///
+    /// Each `KEY=value` line of the file sets the environment variable when
+    /// it is not set already, so flags reading variables with `Flag::env` or
+    /// `env_prefix` see the values while the real environment keeps
+    /// precedence. A missing file is ignored, and malformed lines are
+    /// skipped with a warning on stderr naming the file and line.
+    ///
+    /// ```text
+    /// # Credentials of the staging registry
+    /// export REGISTRY_TOKEN=abc123
+    /// REGISTRY_URL="https://registry.example.com"
+    /// ```
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli").dotenv_file("/home/user/.cli.env");
+    /// ```
+    #[cfg(feature = "dotenv")]
+    pub fn dotenv_file<T: Into<PathBuf>>(mut self, path: T) -> Self {
+        self.dotenv_file = Some(path.into());
         self
     }
 
@@ -103,11 +391,11 @@ impl App {
     /// Example
     ///
     /// ```
-    /// use seahorse::{App, Command};
+    /// use seahorse::{App, Command, Context};
     ///
     /// let command = Command::new("hello")
     ///     .usage("cli hello [arg]")
-    ///     .action(|c| println!("{:?}", c.args));
+    ///     .action(|c: &Context| println!("{:?}", c.args));
     ///
     /// let app = App::new("cli")
     ///     .command(command);
@@ -118,27 +406,62 @@ impl App {
     /// You cannot set a command named as same as registered ones.
     ///
     /// ```should_panic
-    /// use seahorse::{App, Command};
+    /// use seahorse::{App, Command, Context};
     ///
     /// let command1 = Command::new("hello")
     ///     .usage("cli hello [arg]")
-    ///     .action(|c| println!("{:?}", c.args));
+    ///     .action(|c: &Context| println!("{:?}", c.args));
     ///
     /// let command2 = Command::new("hello")
     ///     .usage("cli hello [arg]")
-    ///     .action(|c| println!("{:?}", c.args));
+    ///     .action(|c: &Context| println!("{:?}", c.args));
     ///
     /// let app = App::new("cli")
     ///     .command(command1)
     ///     .command(command2);
     /// ```
-    pub fn command(mut self, command: Command) -> Self {
+    pub fn command(self, command: Command) -> Self {
+        self.command_arc(Arc::new(command))
+    }
+
+    /// Set command shared with other apps, made with `Command::shared`
+    ///
+    /// The command is kept as it is unless the app changes it, like an app
+    /// with its own version flag or sorting help alphabetically does, which
+    /// changes a copy of the commands it needs to.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context};
+    ///
+    /// let status = Command::new("status")
+    ///     .action(|c: &Context| println!("{:?}", c.args))
+    ///     .shared();
+    ///
+    /// let full = App::new("cli").command_arc(status.clone());
+    /// let slim = App::new("cli-slim").command_arc(status);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// You cannot set a command named as same as registered ones.
+    pub fn command_arc(mut self, mut command: Arc<Command>) -> Self {
+        command.check_help_flag(&self.help_flag);
+        command::set_shared_version_flag(&mut command, &self.version_flag);
+        command.check_version_flag(self.has_version());
+        for global in self.flags.iter().flatten().filter(|flag| flag.global) {
+            if let Err(e) = command.check_global_aliases(global) {
+                panic!("{}", e);
+            }
+        }
+        if self.sort_help_alphabetically {
+            command::sort_shared_help_alphabetically(&mut command);
+        }
+
         if let Some(ref mut commands) = self.commands {
-            if commands
-                .iter()
-                .any(|registered| registered.name == command.name)
-            {
-                panic!(r#"Command name "{}" is already registered."#, command.name);
+            if let Err(e) = check_command(&command, commands.iter().map(|c| &**c)) {
+                panic!("{}", e);
             }
             (*commands).push(command);
         } else {
@@ -147,477 +470,4559 @@ impl App {
         self
     }
 
-    /// Set action of the app
+    /// Add the app as a command with the name, its commands becoming
+    /// subcommands
+    ///
+    /// The app is converted with `Command::from`. Like other commands, a
+    /// name that is already registered panics.
     ///
     /// Example
     ///
     /// ```
-    /// use seahorse::{Action, App, Context};
+    /// use seahorse::{App, Command};
     ///
-    /// let action: Action = |c: &Context| println!("{:?}", c.args);
-    /// let app = App::new("cli")
-    ///     .action(action);
+    /// let fmt = App::new("rustfmt").command(Command::new("check"));
+    /// let app = App::new("devtool").mount("fmt", fmt);
     /// ```
-    pub fn action(mut self, action: impl Into<Action>) -> Self {
-        self.action = Some(action.into());
-        self
+    pub fn mount<T: Into<String>>(self, name: T, app: App) -> Self {
+        let mut command = Command::from(app);
+        command.name = name.into();
+        self.command(command)
     }
 
-    /// Set flag of the app
+    /// Turn on the parser behavior, see [`AppSettings`] for those commands
+    /// inherit
     ///
     /// Example
     ///
     /// ```
-    /// use seahorse::{App, Flag, FlagType};
+    /// use seahorse::{App, AppSettings, Command};
     ///
     /// let app = App::new("cli")
-    ///     .flag(Flag::new("bool", FlagType::Bool))
-    ///     .flag(Flag::new("int", FlagType::Int));
+    ///     .setting(AppSettings::AllowCommandAbbreviation)
+    ///     .setting(AppSettings::TerseErrors)
+    ///     .command(Command::new("status"));
     /// ```
-    pub fn flag(mut self, flag: Flag) -> Self {
-        if let Some(ref mut flags) = self.flags {
-            (*flags).push(flag);
-        } else {
-            self.flags = Some(vec![flag]);
+    pub fn setting(mut self, setting: AppSettings) -> Self {
+        match setting {
+            AppSettings::AllowCommandAbbreviation => self.command_abbreviation = true,
+            AppSettings::AllowSlashFlags => self.slash_flags = true,
+            AppSettings::StopAtFirstPositional => self.stop_at_first_positional = true,
+            AppSettings::ExcludeTrailingArgs => self.exclude_trailing_args = true,
+            AppSettings::SortHelpAlphabetically => return self.sort_help_alphabetically(),
+            AppSettings::AllowArgfile => self.argfile = true,
+            AppSettings::TerseErrors => self.terse_errors = true,
         }
         self
     }
 
-    /// Run app
+    /// Sort flags and commands alphabetically in help, completions and generated documents
+    ///
+    /// Items with an explicit display order are still sorted by that order first.
     ///
     /// Example
     ///
     /// ```
-    /// use std::env;
-    /// use seahorse::App;
+    /// use seahorse::{App, Command};
     ///
-    /// let args: Vec<String> = env::args().collect();
-    /// let app = App::new("cli");
-    /// app.run(args);
+    /// let app = App::new("cli")
+    ///     .command(Command::new("world"))
+    ///     .command(Command::new("hello"))
+    ///     .sort_help_alphabetically();
     /// ```
-    pub fn run(&self, args: Vec<String>) {
-        let args = Self::normalized_args(args);
-        let (cmd_v, args_v) = match args.len() {
-            1 => args.split_at(1),
-            _ => args[1..].split_at(1),
-        };
-
-        let cmd = match cmd_v.first() {
-            Some(c) => c,
-            None => {
-                self.help();
-                return;
-            }
-        };
-
-        match self.select_command(cmd) {
-            Some(command) => command.run(args_v.to_vec()),
-            None => match &self.action {
-                Some(action) => {
-                    if args.contains(&"-h".to_string()) || args.contains(&"--help".to_string()) {
-                        self.help();
-                        return;
-                    }
-                    action.run(&Context::new(
-                        args[1..].to_vec(),
-                        self.flags.clone(),
-                        self.help_text(),
-                    ));
-                }
-                None => self.help(),
-            },
+    pub fn sort_help_alphabetically(mut self) -> Self {
+        self.sort_help_alphabetically = true;
+        for command in self.commands.iter_mut().flatten() {
+            command::sort_shared_help_alphabetically(command);
         }
+        self
     }
 
-    /// Select command
-    /// Gets the Command that matches the string passed in the argument
-    fn select_command(&self, cmd: &str) -> Option<&Command> {
-        match &self.commands {
-            Some(commands) => commands.iter().find(|command| match &command.alias {
-                Some(alias) => command.name == cmd || alias.iter().any(|a| a == cmd),
-                None => command.name == cmd,
-            }),
-            None => None,
-        }
-    }
-
-    /// Split arg with "=" to unify arg notations.
-    /// --flag=value => ["--flag", "value"]
-    /// --flag value => ["--flag", "value"]
-    fn normalized_args(raw_args: Vec<String>) -> Vec<String> {
-        raw_args.iter().fold(Vec::<String>::new(), |mut acc, cur| {
-            if cur.starts_with('-') && cur.contains('=') {
-                let mut splitted_flag: Vec<String> =
-                    cur.splitn(2, '=').map(|s| s.to_owned()).collect();
-                acc.append(&mut splitted_flag);
-            } else {
-                acc.push(cur.to_owned());
-            }
-            acc
-        })
+    /// Set action of the app
+    ///
+    /// The action is a closure, a function taking `&Context` or an `Action`.
+    /// Closures name the type of their parameter, `|c: &Context|`, for it
+    /// cannot be inferred from an `Into<Action>` argument.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Context};
+    ///
+    /// fn greet(c: &Context) {
+    ///     println!("Hello, {:?}", c.args);
+    /// }
+    ///
+    /// let app = App::new("cli").action(greet);
+    /// let app = App::new("cli").action(|c: &Context| println!("{:?}", c.args));
+    /// ```
+    pub fn action(mut self, action: impl Into<Action>) -> Self {
+        self.action = Some(action.into());
+        self
     }
 
-    fn flag_help_text(&self) -> String {
-        let mut text = String::new();
-        text += "Flags:\n";
-        let help_flag = "-h, --help";
-
-        if let Some(flags) = &self.flags {
-            let int_val = "<int>";
-            let float_val = "<float>";
-            let string_val = "<string>";
+    /// Set action run after the action of the app or of the command running,
+    /// and after the after action of the command, with the same context
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Context};
+    ///
+    /// let app = App::new("cli")
+    ///     .action(|c: &Context| println!("{:?}", c.args))
+    ///     .after_action(|c: &Context| println!("done, status {:?}", c.exit_code()));
+    /// ```
+    pub fn after_action(mut self, action: impl Into<Action>) -> Self {
+        self.after_action = Some(action.into());
+        self
+    }
 
-            let flag_helps = &flags.iter().map(|f| {
-                let alias = match &f.alias {
-                    Some(alias) => alias
-                        .iter()
-                        .map(|a| format!("-{}", a))
-                        .collect::<Vec<String>>()
-                        .join(", "),
-                    None => String::new(),
-                };
-                let val = match f.flag_type {
-                    FlagType::Int => int_val,
-                    FlagType::Float => float_val,
-                    FlagType::String => string_val,
-                    _ => "",
-                };
+    /// Set action run when Ctrl-C is pressed while an action runs, receiving
+    /// its context
+    ///
+    /// Once the interrupted action returns, checking
+    /// [`Context::interrupted`] to return early, the interrupt action runs,
+    /// then the after actions, and the app exits with the status set with
+    /// [`App::interrupt_exit_code`]. Async actions run with `App::run_async`
+    /// are cancelled instead, their future dropped. Ctrl-C is left alone
+    /// unless this is set, for apps handling signals themselves.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Context};
+    ///
+    /// let app = App::new("serve")
+    ///     .action(|c: &Context| {
+    ///         while !c.interrupted() {
+    ///             // Serve requests
+    ///             # break;
+    ///         }
+    ///     })
+    ///     .on_interrupt(|c: &Context| eprintln!("shutting down {}", c.args.join(" ")))
+    ///     .after_action(|c: &Context| println!("exiting with {:?}", c.exit_code()));
+    /// ```
+    #[cfg(feature = "interrupt")]
+    pub fn on_interrupt(mut self, action: impl Into<Action>) -> Self {
+        self.interrupt.action = Some(action.into());
+        self
+    }
 
-                let help = if alias.is_empty() {
-                    format!("--{} {}", f.name, val)
-                } else {
-                    format!("{}, --{} {}", alias, f.name, val)
-                };
+    /// Set status the app exits with when interrupted, 130 unless set
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Context};
+    ///
+    /// let app = App::new("serve")
+    ///     .on_interrupt(|_: &Context| eprintln!("shutting down"))
+    ///     .interrupt_exit_code(2);
+    /// ```
+    #[cfg(feature = "interrupt")]
+    pub fn interrupt_exit_code(mut self, code: i32) -> Self {
+        self.interrupt.code = code;
+        self
+    }
 
-                (help, f.description.clone())
-            });
+    /// Set source of the interrupts, Ctrl-C unless set, used to test
+    /// interrupt handling
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Context, SignalSource};
+    ///
+    /// struct Interrupted;
+    ///
+    /// impl SignalSource for Interrupted {
+    ///     fn interrupted(&self) -> bool {
+    ///         true
+    ///     }
+    /// }
+    ///
+    /// let app = App::new("serve")
+    ///     .action(|c: &Context| assert!(c.interrupted()))
+    ///     .on_interrupt(|_: &Context| eprintln!("shutting down"))
+    ///     .signal_source(Interrupted);
+    /// assert_eq!(app.try_run(vec!["serve".to_string()]), Ok(130));
+    /// ```
+    #[cfg(feature = "interrupt")]
+    pub fn signal_source(mut self, source: impl SignalSource + 'static) -> Self {
+        self.interrupt.source = Arc::new(source);
+        self
+    }
 
-            let flag_name_max_len = flag_helps
-                .clone()
-                .map(|h| h.0.len())
-                .chain(vec![help_flag.len()].into_iter())
-                .max()
-                .unwrap();
+    /// Set flag of the app
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::new("bool", FlagType::Bool))
+    ///     .flag(Flag::new("int", FlagType::Int));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Aliases of the flag cannot be the name or an alias of another flag,
+    /// or for global flags of a flag of a command.
+    ///
+    /// ```should_panic
+    /// use seahorse::{App, Command, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .command(Command::new("rm").flag(Flag::new("force", FlagType::Bool).alias("f")))
+    ///     .flag(Flag::new("file", FlagType::String).alias("f").global());
+    /// ```
+    pub fn flag(self, flag: Flag) -> Self {
+        match self.try_flag(flag) {
+            Ok(app) => app,
+            Err(e) => panic!("{}", e),
+        }
+    }
 
-            for flag_help in flag_helps.clone() {
-                text += &format!("\t{}", flag_help.0);
-
-                if let Some(usage) = &flag_help.1 {
-                    let flag_name_len = flag_help.0.len();
-                    text += &format!(
-                        "{} : {}\n",
-                        " ".repeat(flag_name_max_len - flag_name_len),
-                        usage
-                    );
-                } else {
-                    text += "\n";
-                }
+    /// Set flag of the app like `flag`, returning why the flag conflicts
+    /// with another flag instead of panicking
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Flag, FlagType};
+    ///
+    /// let app = App::new("cli").flag(Flag::new("force", FlagType::Bool).alias("f"));
+    /// assert!(app.try_flag(Flag::new("f", FlagType::Bool)).is_err());
+    /// ```
+    pub fn try_flag(mut self, flag: Flag) -> Result<Self, String> {
+        self.help_flag.try_check(Some(&flag))?;
+        if self.has_version() {
+            self.version_flag.try_check(Some(&flag))?;
+        }
+        check_registered(&flag, self.flags.iter().flatten())?;
+        check_aliases(&flag, self.flags.iter().flatten())?;
+        if flag.global {
+            for command in self.commands.iter().flatten() {
+                command.check_global_aliases(&flag)?;
             }
-
-            text += &format!(
-                "\t{}{} : Show help\n",
-                help_flag,
-                " ".repeat(flag_name_max_len - help_flag.len())
-            );
+        }
+        if let Some(ref mut flags) = self.flags {
+            (*flags).push(flag);
         } else {
-            text += &format!("\t{} : Show help\n", help_flag);
+            self.flags = Some(vec![flag]);
         }
-
-        text
+        Ok(self)
+    }
+
+    /// Set flags of the app in order, like `flag` for each
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Flag, FlagType};
+    ///
+    /// let app = App::new("cli").with_flags(vec![
+    ///     Flag::new("format", FlagType::String),
+    ///     Flag::new("no-header", FlagType::Bool),
+    /// ]);
+    /// ```
+    pub fn with_flags(self, flags: impl IntoIterator<Item = Flag>) -> Self {
+        flags.into_iter().fold(self, |app, flag| app.flag(flag))
+    }
+
+    /// Set a copy of the flags of the set, like `flag` for each, listed in
+    /// help in a section of their own when the set is a section
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Flag, FlagSet, FlagType};
+    ///
+    /// let output = FlagSet::new("Output").flag(Flag::new("format", FlagType::String));
+    /// let app = App::new("cli").flag_set(&output);
+    /// ```
+    pub fn flag_set(self, set: &FlagSet) -> Self {
+        self.with_flags(set.to_flags())
+    }
+
+    /// Set help flag of the app and the commands that don't set their own
+    ///
+    /// The short form is left out when `short` is `None`. Set it before
+    /// registering flags that conflict with the default `--help, -h`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .help_flag("assist", Some('?'))
+    ///     .flag(Flag::new("host", FlagType::String).alias("h"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// The help flag cannot be named as a registered flag or its alias.
+    ///
+    /// ```should_panic
+    /// use seahorse::{App, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::new("host", FlagType::String).alias("h"));
+    /// ```
+    pub fn help_flag<T: Into<String>>(mut self, name: T, short: Option<char>) -> Self {
+        self.help_flag = HelpFlag::Enabled {
+            name: name.into(),
+            short,
+        };
+        self.help_flag.check(self.flags.iter().flatten());
+        for command in self.commands.iter().flatten() {
+            command.check_help_flag(&self.help_flag);
+        }
+        self
+    }
+
+    /// Disable built-in help flag of the app and the commands that don't set their own
+    ///
+    /// The help stays available through `App::write_help`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .disable_help_flag()
+    ///     .flag(Flag::new("help", FlagType::Bool));
+    /// ```
+    pub fn disable_help_flag(mut self) -> Self {
+        self.help_flag = HelpFlag::Disabled;
+        self
+    }
+
+    /// Set version flag of the app and its commands
+    ///
+    /// The short form is left out when `short` is `None`. Set it before
+    /// registering flags that conflict with the default `--version, -V`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .version("1.0.0")
+    ///     .version_flag("version", None)
+    ///     .flag(Flag::new("verbose-validation", FlagType::Bool).alias("V"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// The version flag of an app or command with a version cannot be named
+    /// as a registered flag or its alias.
+    ///
+    /// ```should_panic
+    /// use seahorse::{App, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::new("verbose-validation", FlagType::Bool).alias("V"))
+    ///     .version("1.0.0");
+    /// ```
+    pub fn version_flag<T: Into<String>>(mut self, name: T, short: Option<char>) -> Self {
+        self.set_version_flag(VersionFlag::Enabled {
+            name: name.into(),
+            short,
+        });
+        self.check_version_flag();
+        self
+    }
+
+    /// Disable built-in version flag of the app and its commands
+    ///
+    /// `--version` is then read like any other flag, failing as an unknown
+    /// flag unless registered.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context};
+    ///
+    /// let app = App::new("cli")
+    ///     .version("1.0.0")
+    ///     .disable_version_flag()
+    ///     .command(
+    ///         Command::new("version").action(|c: &Context| println!("cli 1.0.0")),
+    ///     );
+    /// ```
+    pub fn disable_version_flag(mut self) -> Self {
+        self.set_version_flag(VersionFlag::Disabled);
+        self
+    }
+
+    fn set_version_flag(&mut self, version_flag: VersionFlag) {
+        for command in self.commands.iter_mut().flatten() {
+            command::set_shared_version_flag(command, &version_flag);
+        }
+        self.version_flag = version_flag;
+    }
+
+    /// Panic when a flag of the app or its commands conflicts with the
+    /// version flag, for those with a version
+    fn check_version_flag(&self) {
+        if self.has_version() {
+            self.version_flag.check(self.flags.iter().flatten());
+        }
+        for command in self.commands.iter().flatten() {
+            command.check_version_flag(self.has_version());
+        }
+    }
+
+    /// Expand `@file` arguments to the arguments read from the file before parsing
+    ///
+    /// The file holds one argument per line, kept as written, and empty lines
+    /// are skipped. `@file` lines are expanded in turn, up to 4 files deep,
+    /// and `@@arg` stands for the argument `@arg`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .allow_argfile();
+    /// ```
+    pub fn allow_argfile(self) -> Self {
+        self.setting(AppSettings::AllowArgfile)
+    }
+
+    /// Run commands named by a prefix of their name or alias shared with
+    /// no other command, like `stat` for `status`
+    ///
+    /// Exact names and aliases always win. A prefix shared by several commands
+    /// fails listing them, hidden commands being matched but never listed.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command};
+    ///
+    /// let app = App::new("cli")
+    ///     .command(Command::new("status"))
+    ///     .allow_command_abbreviation();
+    /// ```
+    pub fn allow_command_abbreviation(self) -> Self {
+        self.setting(AppSettings::AllowCommandAbbreviation)
+    }
+
+    /// Report command line errors with their message only, leaving out the
+    /// usage line and the hint to run the help flag
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command};
+    ///
+    /// let app = App::new("cli")
+    ///     .command(Command::new("serve"))
+    ///     .terse_errors();
+    /// let error = app
+    ///     .try_run(vec!["cli".to_string(), "help".to_string(), "serv".to_string()])
+    ///     .unwrap_err();
+    /// assert_eq!(error.to_string(), r#"Unknown command "serv" in "cli". Did you mean "serve"?"#);
+    /// ```
+    pub fn terse_errors(self) -> Self {
+        self.setting(AppSettings::TerseErrors)
+    }
+
+    /// Show help longer than the terminal through the pager
+    ///
+    /// Help is only paged when stdout is a terminal, and never when `NOPAGER`
+    /// is set or the app is run with `--no-pager`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .use_pager(true);
+    /// ```
+    pub fn use_pager(mut self, enabled: bool) -> Self {
+        self.use_pager = enabled;
+        self
+    }
+
+    /// Set pager showing long help, running `$PAGER` or `less -FRX` by default
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    /// use std::process::{Command, Stdio};
+    /// use std::io::Write;
+    ///
+    /// let app = App::new("cli")
+    ///     .use_pager(true)
+    ///     .pager(|text: &str| {
+    ///         let mut child = Command::new("more").stdin(Stdio::piped()).spawn()?;
+    ///         child.stdin.take().unwrap().write_all(text.as_bytes())?;
+    ///         child.wait().map(|_| ())
+    ///     });
+    /// ```
+    pub fn pager(mut self, pager: impl Into<Pager>) -> Self {
+        self.pager = pager.into();
+        self
+    }
+
+    /// Set styles of help and errors written to a terminal,
+    /// `Theme::default()` unless set
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::theme::{Color, Style, Theme};
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli").theme(Theme {
+    ///     header: Style::color(Color::Cyan).bold(),
+    ///     ..Theme::default()
+    /// });
+    /// let plain = App::new("cli").theme(Theme::plain());
+    /// ```
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Set when help and errors are styled, `ColorChoice::Auto` unless set
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::theme::ColorChoice;
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli").color(ColorChoice::Never);
+    /// ```
+    pub fn color(mut self, color: ColorChoice) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Add a global `--color <auto|always|never>` flag setting when help and
+    /// errors are styled, overriding `App::color`
+    ///
+    /// `always` styles output even when it's not written to a terminal, as
+    /// for CI logs shown with their colors, and `never` leaves it plain
+    /// even on a terminal. Other values are rejected.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli").add_color_flag();
+    /// assert_eq!(app.try_run(vec!["cli".to_string(), "--color".to_string(), "never".to_string()]), Ok(0));
+    /// assert!(app.try_run(vec!["cli".to_string(), "--color=rainbow".to_string()]).is_err());
+    /// ```
+    pub fn add_color_flag(mut self) -> Self {
+        self.color_flag = true;
+        self.flag(
+            Flag::new("color", FlagType::String)
+                .description("When to color output: auto, always or never")
+                .choices(&ColorChoice::NAMES)
+                .global(),
+        )
+    }
+
+    /// Color choice of the run, from `--color` when the flag is added
+    pub(crate) fn color_choice(&self, args: &[String]) -> Result<ColorChoice, Error> {
+        if !self.color_flag {
+            return Ok(self.color);
+        }
+        let end = self.pass_through_at(args).unwrap_or(args.len());
+        let mut args = args[..end].iter().skip(1).take_while(|arg| *arg != "--");
+        let mut color = self.color;
+        while let Some(arg) = args.next() {
+            let value = if arg == "--color" {
+                match args.next() {
+                    Some(value) => value.as_str(),
+                    None => break,
+                }
+            } else if arg.starts_with("--color=") {
+                &arg["--color=".len()..]
+            } else {
+                continue;
+            };
+            color = value.parse().map_err(|e: String| {
+                Error::new(ErrorKind::InvalidValue, move |m| m.invalid_color(&e))
+                    .token(value)
+                    .flag("color")
+            })?;
+        }
+        Ok(color)
+    }
+
+    /// Read flags written Windows style, `/name` as `--name` and
+    /// `/name:value` as `--name=value`, aliases included
+    ///
+    /// Arguments starting with `/` that name no flag of the command, like
+    /// `/usr/bin/env`, stay positional.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Context, Flag, FlagType};
+    ///
+    /// let app = App::new("setup")
+    ///     .allow_slash_flags(true)
+    ///     .flag(Flag::new("out", FlagType::String))
+    ///     .action(|c: &Context| assert_eq!(c.string_flag("out"), Ok("log.txt".to_string())));
+    /// app.run(vec!["setup".to_string(), "/out:log.txt".to_string()]);
+    /// ```
+    pub fn allow_slash_flags(mut self, enabled: bool) -> Self {
+        self.slash_flags = enabled;
+        self
+    }
+
+    /// Write a parse trace to stderr, telling how each argument was read and
+    /// the value each flag ends up with
+    ///
+    /// The trace is also written when the `SEAHORSE_DEBUG` environment
+    /// variable is set to anything but `0`, so users can send it along with
+    /// bug reports. Every line starts with `seahorse: `, values of secret
+    /// flags are hidden.
+    ///
+    /// ```text
+    /// seahorse: parsing "cli add"
+    /// seahorse: arg 0 "-n": flag "name" by alias
+    /// seahorse: arg 1 "origin": value of flag "name"
+    /// seahorse: arg 2 "url": positional
+    /// seahorse: flag "name" = "origin" (command line)
+    /// seahorse: flag "force" unset
+    /// ```
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli").debug(true);
+    /// ```
+    pub fn debug(mut self, enabled: bool) -> Self {
+        self.debug = enabled;
+        self
+    }
+
+    /// Read flags not given on the command line from environment variables
+    /// named after them with the prefix
+    ///
+    /// A flag `--dry-run` of the command `remote add` falls back to
+    /// `PREFIX_REMOTE_ADD_DRY_RUN` and then to `PREFIX_DRY_RUN`, names being
+    /// uppercased with dashes written as underscores. Flags of the app only
+    /// read `PREFIX_DRY_RUN`. A variable set with `Flag::env` is read before
+    /// those, and values given on the command line take precedence over all
+    /// of them, which take precedence over default values. Long help lists
+    /// the variables of each flag.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .env_prefix("MYCLI")
+    ///     .command(
+    ///         Command::new("deploy")
+    ///             .flag(Flag::new("region", FlagType::String))
+    ///             .action(|c: &Context| println!("{:?}", c.string_flag("region"))),
+    ///     );
+    /// ```
+    pub fn env_prefix<T: Into<String>>(mut self, prefix: T) -> Self {
+        self.env_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Set catalog of the messages the app writes itself
+    ///
+    /// Errors, help headings, the descriptions of the built-in flags and the
+    /// hints after errors are taken from the catalog, whose methods not
+    /// overridden keep the English messages. Descriptions and other texts
+    /// given to the app are shown as given.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Messages};
+    ///
+    /// struct French;
+    ///
+    /// impl Messages for French {
+    ///     fn flags_heading(&self) -> String {
+    ///         "Options".to_string()
+    ///     }
+    /// }
+    ///
+    /// let app = App::new("cli").messages(French);
+    /// ```
+    pub fn messages<M: Messages + 'static>(mut self, messages: M) -> Self {
+        self.messages = Some(Arc::new(messages));
+        self
+    }
+
+    /// Set terminal actions see through `Context` instead of detecting it on
+    /// each run, used to test actions adapting their output to the terminal
+    ///
+    /// Help and errors still detect the terminal.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Context, Terminal};
+    ///
+    /// let app = App::new("cli")
+    ///     .action(|c: &Context| assert!(!c.stdout_is_tty()))
+    ///     .terminal(Terminal {
+    ///         stdout_is_tty: false,
+    ///         stderr_is_tty: false,
+    ///         width: None,
+    ///     });
+    /// ```
+    pub fn terminal(mut self, terminal: Terminal) -> Self {
+        self.terminal = Some(terminal);
+        self
+    }
+
+    /// Set writer of help, version, completion candidates and the other
+    /// output the app writes on request instead of stdout
+    ///
+    /// Help written to it goes through no pager and is styled only when
+    /// the color choice is `ColorChoice::Always`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli").out(Vec::new());
+    /// ```
+    pub fn out(mut self, writer: impl Write + Send + 'static) -> Self {
+        self.streams.out = Some(Arc::new(Mutex::new(writer)));
+        self
+    }
+
+    /// Set writer of errors, warnings and parse traces instead of stderr
+    ///
+    /// Errors written to it are styled only when the color choice is
+    /// `ColorChoice::Always`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli").err(std::io::sink());
+    /// ```
+    pub fn err(mut self, writer: impl Write + Send + 'static) -> Self {
+        self.streams.err = Some(Arc::new(Mutex::new(writer)));
+        self
+    }
+
+    /// Run the command named like the binary when it is invoked under
+    /// another name than the app, through a link named like the command
+    ///
+    /// The invoked name takes the place of the app name and the command name
+    /// in usage lines and help, so `fmt --help` shows `fmt [flags]`. Invoked
+    /// under the app name, or a name matching no command, the app runs as
+    /// usual.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context};
+    ///
+    /// let app = App::new("tools")
+    ///     .multicall(true)
+    ///     .command(Command::new("fmt").action(|c: &Context| println!("{:?}", c.args)));
+    ///
+    /// // Both run `fmt` with the argument `main.rs`
+    /// app.run(vec!["/usr/bin/fmt".to_string(), "main.rs".to_string()]);
+    /// app.run(vec!["tools".to_string(), "fmt".to_string(), "main.rs".to_string()]);
+    /// ```
+    pub fn multicall(mut self, multicall: bool) -> Self {
+        self.multicall = multicall;
+        self
+    }
+
+    /// Run the command when the binary is invoked under the name, for
+    /// multicall apps linked under names other than their commands
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context};
+    ///
+    /// let app = App::new("tools")
+    ///     .multicall(true)
+    ///     .multicall_name("myfmt", "fmt")
+    ///     .command(Command::new("fmt").action(|c: &Context| println!("{:?}", c.args)));
+    ///
+    /// app.run(vec!["myfmt".to_string(), "main.rs".to_string()]);
+    /// ```
+    pub fn multicall_name<T: Into<String>, U: Into<String>>(mut self, name: T, command: U) -> Self {
+        self.multicall_names.push((name.into(), command.into()));
+        self
+    }
+
+    /// Add aliases users define for command lines they run often, like git's
+    /// `alias.st = status -s`, usually read from their config file
+    ///
+    /// When the first positional argument is an alias, it is replaced by the
+    /// arguments its expansion splits into like `split` does, with the rest
+    /// of the command line after them. An expansion starting with another
+    /// alias is expanded again, and one leading back to an alias already
+    /// expanded is an error of kind `ErrorKind::RecursiveAlias`. Commands
+    /// and their aliases take precedence over user aliases of the same name.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .user_aliases(vec![("st", "status --short")])
+    ///     .command(
+    ///         Command::new("status")
+    ///             .flag(Flag::new("short", FlagType::Bool))
+    ///             .action(|c: &Context| assert!(c.bool_flag("short"))),
+    ///     );
+    ///
+    /// assert_eq!(app.try_run(vec!["cli".to_string(), "st".to_string()]), Ok(0));
+    /// ```
+    pub fn user_aliases<K: Into<String>, V: Into<String>>(
+        mut self,
+        aliases: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.user_aliases.extend(
+            aliases
+                .into_iter()
+                .map(|(alias, expansion)| (alias.into(), expansion.into())),
+        );
+        self
+    }
+
+    /// Set prompt asking for the values of missing flags and for confirmations
+    ///
+    /// The default prompt asks on stderr and reads from stdin when stdin
+    /// is a terminal. Running the app with `--no-input` disables the prompt.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .prompt(|_: &str| Ok("alice".to_string()));
+    /// ```
+    pub fn prompt(mut self, prompt: impl Into<Prompt>) -> Self {
+        self.prompt = prompt.into();
+        self
+    }
+
+    /// Set handler of command names matching no command
+    ///
+    /// The handler receives the name and the arguments following it, and is
+    /// called when the app has commands and the first argument is neither a
+    /// command, `help` nor a flag. Returning `Retry` runs the command with the
+    /// new name, the handler being called again when that name is unknown too,
+    /// up to 4 times before the app runs as with `Fallthrough`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, UnknownCommand};
+    ///
+    /// let app = App::new("cli")
+    ///     .command(Command::new("remove"))
+    ///     .on_unknown_command(|name: &str, _: &[String]| match name {
+    ///         "rm" => UnknownCommand::Retry("remove".to_string()),
+    ///         _ => UnknownCommand::Fallthrough,
+    ///     });
+    /// ```
+    pub fn on_unknown_command(mut self, handler: impl Into<UnknownCommandHandler>) -> Self {
+        self.unknown_command_handler = Some(handler.into());
+        self
+    }
+
+    /// Set handler of flags matching no flag
+    ///
+    /// The handler receives each argument written like a flag that matches no
+    /// flag of the command running, in order, and the path of the command.
+    /// Arguments after `--` and negative numbers are not flags. Returning
+    /// `Replace` reads the argument as the flag with the new name, taking its
+    /// values as usual, and returning `Error`, or `Replace` with a name matching
+    /// no flag, fails with the closest known flags suggested. Without a handler
+    /// unknown flags are positional arguments.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Flag, FlagType, UnknownFlag};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::new("color", FlagType::String))
+    ///     .on_unknown_flag(|flag: &str, _: &[String]| match flag {
+    ///         "--colour" => UnknownFlag::Replace("color".to_string()),
+    ///         _ => UnknownFlag::Error,
+    ///     });
+    /// ```
+    pub fn on_unknown_flag(mut self, handler: impl Into<UnknownFlagHandler>) -> Self {
+        self.unknown_flag_handler = Some(handler.into());
+        self
+    }
+
+    /// Set writer of command line errors, called by `run` with stderr
+    /// before exiting
+    ///
+    /// `ErrorHandler::default()` writes errors the way they are written
+    /// without a handler, and can be called for kinds of errors the handler
+    /// leaves as they are.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::error::{ErrorHandler, ErrorKind};
+    /// use seahorse::{App, Error};
+    /// use std::io::Write;
+    ///
+    /// let app = App::new("cli").error_handler(|e: &Error, w: &mut dyn Write| match e.kind {
+    ///     ErrorKind::MissingFlag => {
+    ///         let _ = writeln!(w, "{}\nSee https://example.com/cli", e);
+    ///     }
+    ///     _ => ErrorHandler::default().handle(e, w),
+    /// });
+    /// ```
+    pub fn error_handler(mut self, handler: impl Into<ErrorHandler>) -> Self {
+        self.error_handler = handler.into();
+        self
+    }
+
+    /// Set standard verbosity flags of the app
+    ///
+    /// Adds a repeatable `--verbose`/`-v` flag and a `--quiet`/`-q` flag,
+    /// read back with `Context::log_level`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Context};
+    ///
+    /// let app = App::new("cli")
+    ///     .verbosity_flags()
+    ///     .action(|c: &Context| log::set_max_level(c.log_level()));
+    /// ```
+    #[cfg(feature = "log")]
+    pub fn verbosity_flags(self) -> Self {
+        Flag::verbosity()
+            .into_iter()
+            .fold(self, |app, flag| app.flag(flag))
+    }
+
+    /// Set completer of the app arguments
+    ///
+    /// The completer is called by the generated completion scripts.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Context};
+    ///
+    /// let app = App::new("cli")
+    ///     .complete_args_with(|_: &Context, _: &str| vec!["README.md".to_string()]);
+    /// ```
+    pub fn complete_args_with(mut self, completer: impl Into<Completer>) -> Self {
+        self.args_completer = Some(completer.into());
+        self
+    }
+
+    /// Name of the app
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Description of the app, if set
+    pub fn get_description(&self) -> Option<&str> {
+        self.description.as_ref().map(String::as_str)
+    }
+
+    /// Version of the app, if set
+    pub fn get_version(&self) -> Option<&str> {
+        self.version.as_ref().map(String::as_str)
+    }
+
+    /// Commands of the app, in registration order
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command};
+    ///
+    /// let app = App::new("cli").command(Command::new("serve"));
+    /// let names: Vec<&str> = app.commands().iter().map(|c| c.name()).collect();
+    /// assert_eq!(names, ["serve"]);
+    /// ```
+    pub fn commands(&self) -> &[Arc<Command>] {
+        self.commands.as_ref().map_or(&[], Vec::as_slice)
+    }
+
+    /// Flags of the app, in registration order
+    pub fn flags(&self) -> &[Flag] {
+        self.flags.as_ref().map_or(&[], Vec::as_slice)
+    }
+
+    /// Build the lookup tables and help texts of the app once, for running
+    /// many command lines with the returned `CompiledApp`
+    ///
+    /// The compiled app owns the app, so nothing can be added to it until
+    /// `CompiledApp::into_app` gives it back to compile again.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context};
+    ///
+    /// let app = App::new("cli")
+    ///     .command(Command::new("status").action(|_: &Context| {}))
+    ///     .compile();
+    /// assert_eq!(app.try_run(vec!["cli".to_string(), "status".to_string()]), Ok(0));
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use seahorse::{App, Command};
+    ///
+    /// let app = App::new("cli").compile().command(Command::new("status"));
+    /// ```
+    pub fn compile(self) -> CompiledApp {
+        CompiledApp::new(self)
+    }
+
+    /// Add the entries of the app action and of the commands to the
+    /// compiled app
+    pub(crate) fn compile_into(&self, compiled: &mut Compiled) {
+        let path = vec![compiled.bin().to_string()];
+        if self.action.is_some() {
+            let help_text = if compiled.keeps_help_texts() {
+                self.help_text_as(&path[0])
+            } else {
+                String::new()
+            };
+            compiled.insert(&path, help_text, self.flags().to_vec());
+        }
+        let env_prefix = self.env_prefix.as_ref().map(String::as_str);
+        for command in self.commands() {
+            command.compile_into(
+                &path,
+                &self.help_flag,
+                self.flags(),
+                env_prefix,
+                self.message_catalog(),
+                compiled,
+            );
+        }
+    }
+
+    /// Run app
+    ///
+    /// Errors of the command line are written to stderr with the error handler
+    /// before exiting with status 1. This and the other `run` methods are the
+    /// only ones exiting the process, `try_run` returning the status instead.
+    /// On wasm32 targets, where exiting would stop the host running the app,
+    /// `run` returns after writing the error.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use std::env;
+    /// use seahorse::App;
+    ///
+    /// let args: Vec<String> = env::args().collect();
+    /// let app = App::new("cli");
+    /// app.run(args);
+    /// ```
+    pub fn run(&self, args: Vec<String>) {
+        let color = self.color_choice(&args).unwrap_or(self.color);
+        self.exit(self.try_run(args), color);
+    }
+
+    /// Exit with the status of the run unless it is 0, reporting the error
+    /// with the error handler, styled by the color choice of the run
+    pub(crate) fn exit(&self, result: Result<i32, Error>, color: ColorChoice) {
+        let code = match result {
+            Ok(code) => code,
+            Err(e) => {
+                theme::report(
+                    &self.error_handler,
+                    Some(&self.theme),
+                    color,
+                    &self.streams,
+                    &e,
+                );
+                1
+            }
+        };
+        exit_process(code);
+    }
+
+    /// Run app like `run`, awaiting the actions
+    ///
+    /// Actions made with `Action::from_async` are awaited in the order and
+    /// with the handling of `Context::exit` of `run`, other actions running
+    /// as futures ready at once.
+    ///
+    /// Example
+    ///
+    /// ```no_run
+    /// use seahorse::{Action, App, Context};
+    ///
+    /// async fn run() {
+    ///     let app = App::new("cli").action(Action::from_async(|c: &Context| {
+    ///         Box::pin(async move { println!("{:?}", c.args) })
+    ///     }));
+    ///     app.run_async(std::env::args().collect()).await;
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn run_async(&self, args: Vec<String>) -> impl Future<Output = ()> + '_ {
+        executor::run(self, args)
+    }
+
+    /// Run app like `try_run`, awaiting the actions like `run_async`
+    #[cfg(feature = "async")]
+    pub fn try_run_async(
+        &self,
+        args: Vec<String>,
+    ) -> impl Future<Output = Result<i32, Error>> + '_ {
+        executor::try_run(self, args)
+    }
+
+    /// Run app, returning the status requested with `Context::exit`, 0 when
+    /// none is, or the error of the command line instead of exiting
+    ///
+    /// No arguments make `try_run` panic: anything the command line gets
+    /// wrong is returned as an error. Panics left are those of the actions
+    /// and of printing help to a closed stdout. `tests/no_panic.rs` and the
+    /// fuzz target in `fuzz/` check this, any panic they find is a bug.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::error::ErrorKind;
+    /// use seahorse::{App, Command};
+    ///
+    /// let app = App::new("cli").command(Command::new("serve"));
+    /// let error = app
+    ///     .try_run(vec!["cli".to_string(), "serv".to_string()])
+    ///     .unwrap_err();
+    /// assert_eq!(error.kind, ErrorKind::UnknownCommand);
+    /// assert_eq!(error.suggestion, Some("serve".to_string()));
+    /// ```
+    pub fn try_run(&self, args: Vec<String>) -> Result<i32, Error> {
+        self.try_run_compiled(args, None)
+    }
+
+    /// Run app like `try_run`, reading the parts built by `compile` when given
+    pub(crate) fn try_run_compiled(
+        &self,
+        args: Vec<String>,
+        compiled: Option<&Compiled>,
+    ) -> Result<i32, Error> {
+        self.handle_compiled(args, compiled).into_result()
+    }
+
+    /// Run app like `try_run`, telling what the run did: whether help or the
+    /// version was shown, which command ran, or the error of the command line
+    ///
+    /// `run` and `try_run` report the same runs by their status.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context, Outcome};
+    ///
+    /// let app = App::new("cli")
+    ///     .command(Command::new("remote").command(Command::new("add").action(|c: &Context| c.exit(2))));
+    /// let args = |line: &[&str]| line.iter().map(|a| a.to_string()).collect();
+    ///
+    /// assert_eq!(
+    ///     app.handle(args(&["cli", "remote", "add"])),
+    ///     Outcome::Ran {
+    ///         command: args(&["cli", "remote", "add"]),
+    ///         exit_code: 2
+    ///     }
+    /// );
+    /// assert_eq!(
+    ///     app.handle(args(&["cli", "remote", "--help"])),
+    ///     Outcome::HelpShown {
+    ///         target: args(&["cli", "remote"])
+    ///     }
+    /// );
+    /// ```
+    pub fn handle(&self, args: Vec<String>) -> Outcome {
+        self.handle_compiled(args, None)
+    }
+
+    /// Run app like `handle`, reading the parts built by `compile` when given
+    pub(crate) fn handle_compiled(
+        &self,
+        args: Vec<String>,
+        compiled: Option<&Compiled>,
+    ) -> Outcome {
+        match self.parse_args(args, compiled) {
+            Ok(dispatch) => dispatch.run(),
+            Err(e) => Outcome::Error(e),
+        }
+    }
+
+    /// Run app like `run` with the command line split into arguments by
+    /// `split`, the app name included
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context, Flag, FlagType};
+    ///
+    /// let app = App::new("cli").command(
+    ///     Command::new("add")
+    ///         .flag(Flag::new("name", FlagType::String))
+    ///         .action(|c: &Context| println!("{:?} {:?}", c.string_flag("name"), c.args)),
+    /// );
+    /// app.run_from_str(r#"cli add --name "Alice Smith" file.txt"#);
+    /// ```
+    pub fn run_from_str(&self, line: &str) {
+        match split(line) {
+            Ok(args) => self.run(args),
+            Err(e) => self.exit(Err(self.split_error(e)), self.color),
+        }
+    }
+
+    /// Run app like `try_run` with the command line split into arguments by
+    /// `split`, the app name included
+    ///
+    /// A line that cannot be split is an error of kind `ErrorKind::Split`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::error::ErrorKind;
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli");
+    /// assert_eq!(app.try_run_from_str("cli 'a b'"), Ok(0));
+    /// assert_eq!(app.try_run_from_str("cli 'a b").unwrap_err().kind, ErrorKind::Split);
+    /// ```
+    pub fn try_run_from_str(&self, line: &str) -> Result<i32, Error> {
+        let args = split(line).map_err(|e| self.split_error(e))?;
+        self.try_run(args)
+    }
+
+    /// Run app on the command lines read from stdin until the end of input
+    /// or a line reading `exit` or `quit`, like `run_repl_with` on stdin and
+    /// stdout
+    ///
+    /// Example
+    ///
+    /// ```no_run
+    /// use seahorse::{App, Command, Context};
+    ///
+    /// let app = App::new("cli").command(Command::new("status").action(|_: &Context| {}));
+    /// app.run_repl("cli> ").unwrap();
+    /// ```
+    pub fn run_repl(&self, prompt: &str) -> io::Result<()> {
+        let stdin = io::stdin();
+        self.run_repl_with(prompt, stdin.lock(), io::stdout())
+    }
+
+    /// Run app on each line read from the reader, writing the prompt to the
+    /// writer before reading it, until the end of input or a line reading
+    /// `exit` or `quit`
+    ///
+    /// Lines are split into arguments by `split` and run like `try_run` with
+    /// the app name in front. Errors are written to the writer with the error
+    /// handler and the loop goes on, the status of the actions is not used.
+    /// Help and versions are printed to stdout as in `run`.
+    /// `exit` and `quit` run the commands of those names when the app has them.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context};
+    /// use std::io::Cursor;
+    ///
+    /// let app = App::new("cli").command(Command::new("status").action(|_: &Context| {}));
+    /// let mut output = Vec::new();
+    /// app.run_repl_with("> ", Cursor::new("status\nstatus 'x\nquit\n"), &mut output)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(output).unwrap(),
+    ///     "> > Unterminated single quote starting at character 8\n> "
+    /// );
+    /// ```
+    pub fn run_repl_with<R: BufRead, W: Write>(
+        &self,
+        prompt: &str,
+        mut reader: R,
+        mut writer: W,
+    ) -> io::Result<()> {
+        let mut line = String::new();
+        loop {
+            write!(writer, "{}", prompt)?;
+            writer.flush()?;
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            let args = match split(&line) {
+                Ok(args) => args,
+                Err(e) => {
+                    let error = self.split_error(e);
+                    self.error_handler.handle(&error, &mut writer);
+                    continue;
+                }
+            };
+            match args.first() {
+                None => continue,
+                Some(first)
+                    if (first == "exit" || first == "quit")
+                        && parser::find_command(&self.commands, first).is_none() =>
+                {
+                    return Ok(())
+                }
+                Some(_) => {}
+            }
+            let mut argv = Vec::with_capacity(args.len() + 1);
+            argv.push(self.display_name().to_string());
+            argv.extend(args);
+            if let Err(e) = self.try_run(argv) {
+                self.error_handler.handle(&e, &mut writer);
+            }
+        }
+    }
+
+    /// Actions left to run for the arguments, or the error of the command line
+    pub(crate) fn parse_args(
+        &self,
+        args: Vec<String>,
+        compiled: Option<&Compiled>,
+    ) -> Result<Dispatch, Error> {
+        let outcome = self.run_args(args, compiled).map_err(|e| {
+            let e = self.localized(e);
+            if self.terse_errors {
+                e.terse()
+            } else {
+                e
+            }
+        })?;
+        #[cfg(feature = "interrupt")]
+        let outcome = match outcome {
+            Dispatch::Run(actions, context) => {
+                Dispatch::Run(actions, context.with_interrupt(&self.interrupt))
+            }
+            outcome => outcome,
+        };
+        Ok(outcome)
+    }
+
+    /// Error written with the catalog of the app
+    fn localized(&self, error: Error) -> Error {
+        match &self.messages {
+            Some(messages) => error.localize(messages),
+            None => error,
+        }
+    }
+
+    /// Error of a command line that cannot be split
+    fn split_error(&self, e: SplitError) -> Error {
+        let error = match e {
+            SplitError::UnterminatedQuote { quote, position } => {
+                Error::new(ErrorKind::Split, move |m| {
+                    m.unterminated_quote(quote, position)
+                })
+            }
+            SplitError::TrailingBackslash => {
+                Error::new(ErrorKind::Split, |m| m.trailing_backslash())
+            }
+        };
+        self.localized(error.cause(e))
+    }
+
+    /// Replace the user alias the first argument after the program name is
+    /// by its expansion, until it starts with no alias
+    fn expand_user_alias(&self, args: &mut Vec<String>) -> Result<(), Error> {
+        let mut expanded: Vec<String> = Vec::new();
+        loop {
+            let name = match args.get(1) {
+                Some(name) if !name.starts_with('-') && name != "help" => name,
+                _ => return Ok(()),
+            };
+            if parser::find_command(&self.commands, name).is_some() {
+                return Ok(());
+            }
+            let expansion = match self.user_aliases.iter().find(|(alias, _)| alias == name) {
+                Some((_, expansion)) => expansion,
+                None => return Ok(()),
+            };
+            if expanded.contains(name) {
+                let alias = name.clone();
+                return Err(self.localized(
+                    Error::new(ErrorKind::RecursiveAlias, move |m| {
+                        m.recursive_alias(&alias)
+                    })
+                    .token(name),
+                ));
+            }
+            let tokens = split(expansion).map_err(|e| self.split_error(e))?;
+            expanded.push(name.clone());
+            let empty = tokens.is_empty();
+            args.splice(1..2, tokens);
+            if empty {
+                return Ok(());
+            }
+        }
+    }
+
+    fn run_args(&self, args: Vec<String>, compiled: Option<&Compiled>) -> Result<Dispatch, Error> {
+        if args.get(1).map(String::as_str) == Some("__complete") {
+            self.complete(&args[2..]);
+            return Ok(Dispatch::Done(0));
+        }
+        if args.get(1).map(String::as_str) == Some("__tree") {
+            let flags = args.get(2).map(String::as_str) == Some("--flags");
+            let written = self
+                .streams
+                .with(Stream::Stdout, |w| tree::write_app(self, flags, w));
+            written.map_err(|e| {
+                let reason = e.to_string();
+                Error::new(ErrorKind::Io, move |m| m.help_failed(&reason)).cause(e)
+            })?;
+            return Ok(Dispatch::Done(0));
+        }
+        let applet = self.applet(args.first());
+
+        let mut args = args;
+        let passed = match self.pass_through_at(&args) {
+            Some(position) => args.split_off(position),
+            None => Vec::new(),
+        };
+        let args = if self.argfile {
+            parser::expand_argfiles(args)?
+        } else {
+            args
+        };
+        let mut args = args;
+        let color = self.color_choice(&args)?;
+        let no_input = args
+            .iter()
+            .take_while(|arg| *arg != "--")
+            .position(|arg| arg == "--no-input");
+        let prompt = match no_input {
+            Some(index) => {
+                args.remove(index);
+                self.prompt.without_input()
+            }
+            None => self.prompt.clone(),
+        };
+        let no_pager = args
+            .iter()
+            .take_while(|arg| *arg != "--")
+            .position(|arg| arg == "--no-pager");
+        let pager = match no_pager {
+            Some(index) if self.use_pager => {
+                args.remove(index);
+                None
+            }
+            _ if self.use_pager => Some(&self.pager),
+            _ => None,
+        };
+        let spans = parser::leading_global_spans(args.get(1..).unwrap_or(&[]), self.flags());
+        let mut leading = spans.last().map_or(0, |span| span.1.end);
+        // Only among the flags of the app, before any command name
+        let dump = applet.is_none()
+            && self
+                .help_flag
+                .asks_json(args.get(leading + 1..).unwrap_or(&[]));
+        let mut shadowed = Vec::new();
+        if leading > 0
+            && applet.is_none()
+            && args
+                .get(leading + 1)
+                .map_or(false, |arg| !arg.starts_with('-'))
+        {
+            // Global flags the command declares too stay with the global
+            // definitions, read by `Context::global_flag`
+            let command = self.select_command(&args[leading + 1]).ok().and_then(|c| c);
+            let flags = command.map_or(&[][..], Command::flags);
+            for (index, span) in spans.into_iter().rev() {
+                if flags
+                    .iter()
+                    .any(|flag| flag.name == self.flags()[index].name)
+                {
+                    leading -= span.len();
+                    let tokens: Vec<String> = args.drain(span.start + 1..span.end + 1).collect();
+                    shadowed.splice(0..0, tokens);
+                }
+            }
+            args[1..leading + 2].rotate_right(1);
+        }
+        if applet.is_none() {
+            self.expand_user_alias(&mut args)?;
+        }
+        if let (Some(handler), None) = (&self.unknown_command_handler, &applet) {
+            if !self.resolve_unknown_command(handler, &mut args) {
+                return Ok(Dispatch::Done(0));
+            }
+        }
+        let bin = self.invoked_name(args.first());
+        let (cmd_v, args_v) = match args.len() {
+            0 | 1 => args.split_at(args.len()),
+            _ => args[1..].split_at(1),
+        };
+
+        if dump {
+            let written = self
+                .streams
+                .with(Stream::Stdout, |mut w| self.generate_json(&mut w));
+            written.map_err(|e| {
+                let reason = e.to_string();
+                Error::new(ErrorKind::Io, move |m| m.help_failed(&reason)).cause(e)
+            })?;
+            return Ok(Dispatch::Done(0));
+        }
+
+        let path = std::slice::from_ref(&bin);
+        let within = |e: Error| e.within(path, self.usage_text(&bin), &self.help_flag);
+        let settings = Settings {
+            config: None,
+            prompt: &prompt,
+            unknown_flag: self.unknown_flag_handler.as_ref(),
+            abbreviate_commands: self.command_abbreviation,
+            slash_flags: self.slash_flags,
+            stop_at_first_positional: self.stop_at_first_positional,
+            exclude_trailing_args: self.exclude_trailing_args,
+            after_actions: AfterActions::new(self.after_action.as_ref()),
+            pager,
+            theme: Some(&self.theme),
+            color,
+            global_flags: self.flags(),
+            app: Some(self),
+            debug: self.debug || debug_env(),
+            env_prefix: self.env_prefix.as_ref().map(String::as_str),
+            compiled,
+            messages: self.message_catalog(),
+            passed: &passed,
+            shadowed_globals: &shadowed,
+            terminal: self.terminal,
+            streams: &self.streams,
+        };
+        if let Some((name, command)) = applet {
+            self.load_dotenv_file();
+            // Compiled entries are found by the path under the app name
+            let settings = Settings {
+                compiled: None,
+                ..settings
+            };
+            return command.run_at(&[name], args, 1, &self.help_flag, &settings);
+        }
+
+        let cmd = match cmd_v.first() {
+            Some(c) => c,
+            None => {
+                pager::show(
+                    pager,
+                    Some(&self.theme),
+                    color,
+                    self.message_catalog(),
+                    &self.streams,
+                    &self.help_text_as(&bin),
+                );
+                return Ok(Dispatch::Help(vec![bin]));
+            }
+        };
+
+        self.load_dotenv_file();
+
+        // A lone program name is never taken for a command
+        let command = match args.len() {
+            1 => None,
+            _ => self.select_command(cmd).map_err(within)?,
+        };
+        match command {
+            Some(command) => command.run_with_path(path, args, 2, &self.help_flag, &settings),
+            None if cmd == "help" => {
+                let (help_text, target) = self.help_path_text(&bin, args_v)?;
+                pager::show(
+                    pager,
+                    Some(&self.theme),
+                    color,
+                    self.message_catalog(),
+                    &self.streams,
+                    &help_text,
+                );
+                Ok(Dispatch::Help(target))
+            }
+            None => {
+                if let Some(version) = self.version_text(&args) {
+                    self.streams.out(&format!("{}\n", version));
+                    return Ok(Dispatch::Version);
+                }
+
+                match &self.action {
+                    Some(action) => {
+                        if let Some(form) = self.help_flag.form(&args) {
+                            pager::show(
+                                pager,
+                                Some(&self.theme),
+                                color,
+                                self.message_catalog(),
+                                &self.streams,
+                                &self.help_text_in(&bin, form),
+                            );
+                            return Ok(Dispatch::Help(vec![bin]));
+                        }
+                        let config = self.load_config().map_err(within)?;
+                        let settings = Settings {
+                            config: config.as_ref(),
+                            ..settings
+                        };
+                        let flags =
+                            config::apply(config.as_ref(), path, &self.flags).map_err(within)?;
+                        args.remove(0);
+                        let help_text = match compiled.and_then(|c| c.help_text(path)) {
+                            Some(help_text) => help_text,
+                            None => self.help_text_as(&bin).into(),
+                        };
+                        let context = Context::for_action(path, args, &flags, help_text, &settings)
+                            .map_err(within)?;
+                        Ok(Dispatch::actions(
+                            &[Some(action)],
+                            &settings.after_actions,
+                            context,
+                        ))
+                    }
+                    None => {
+                        if self.commands.is_some() && !self.help_flag.matches(cmd) {
+                            parser::check_known_flag(cmd, self.flags()).map_err(within)?;
+                        }
+                        if self.commands.is_some() && args.len() > 1 && !cmd.starts_with('-') {
+                            let error = parser::unknown_command_error(&self.commands, cmd, &bin);
+                            return Err(within(error));
+                        }
+                        let form = self.help_flag.form(&args).unwrap_or(HelpForm::Long);
+                        pager::show(
+                            pager,
+                            Some(&self.theme),
+                            color,
+                            self.message_catalog(),
+                            &self.streams,
+                            &self.help_text_in(&bin, form),
+                        );
+                        Ok(Dispatch::Help(vec![bin]))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Name shown in usage lines of a run with the program name, the first
+    /// argument, when given
+    fn invoked_name(&self, program: Option<&String>) -> String {
+        if let Some(bin_name) = &self.bin_name {
+            return bin_name.clone();
+        }
+        program_name(program).unwrap_or(&self.name).to_string()
+    }
+
+    /// Invoked name and command a multicall app runs under the program name,
+    /// `None` for the app name and names matching no command
+    fn applet(&self, program: Option<&String>) -> Option<(String, &Command)> {
+        if !self.multicall {
+            return None;
+        }
+        let name = program_name(program)?;
+        if name == self.name || Some(name) == self.bin_name.as_ref().map(String::as_str) {
+            return None;
+        }
+        let command_name = self
+            .multicall_names
+            .iter()
+            .find(|(invoked, _)| invoked == name)
+            .map_or(name, |(_, command)| command.as_str());
+        self.commands()
+            .iter()
+            .find(|command| command.name == command_name)
+            .map(|command| (name.to_string(), &**command))
+    }
+
+    /// Load the dotenv file of the app before the flags are read
+    fn load_dotenv_file(&self) {
+        #[cfg(feature = "dotenv")]
+        {
+            if let Some(path) = &self.dotenv_file {
+                dotenv::load(path, &self.streams);
+            }
+        }
+    }
+
+    /// Name shown in usage lines outside of a run
+    /// Catalog of the built-in messages
+    pub(crate) fn message_catalog(&self) -> &dyn Messages {
+        match &self.messages {
+            Some(messages) => messages.as_ref(),
+            None => &DefaultMessages,
+        }
+    }
+
+    pub(crate) fn display_name(&self) -> &str {
+        self.bin_name.as_ref().unwrap_or(&self.name)
+    }
+
+    /// Let the handler resolve an unknown command name in the arguments,
+    /// renaming the command on retry, returning whether the run goes on
+    fn resolve_unknown_command(
+        &self,
+        handler: &UnknownCommandHandler,
+        args: &mut [String],
+    ) -> bool {
+        for _ in 0..=MAX_UNKNOWN_COMMAND_RETRIES {
+            let name = match args.get(1) {
+                Some(name) => name,
+                None => return true,
+            };
+            if self.commands.is_none()
+                || name == "help"
+                || name.starts_with('-')
+                || self
+                    .select_command(name)
+                    .map(|c| c.is_some())
+                    .unwrap_or(true)
+            {
+                return true;
+            }
+
+            match handler.handle(name, &args[2..]) {
+                UnknownCommand::Handled => return false,
+                UnknownCommand::Retry(name) => args[1] = name,
+                UnknownCommand::Fallthrough => return true,
+            }
+        }
+
+        true
+    }
+
+    /// Config file of the app, loaded once help and versions are answered
+    #[cfg(feature = "config")]
+    pub(crate) fn load_config(&self) -> Result<Option<Config>, Error> {
+        match &self.config_file {
+            Some(path) => Config::load(path),
+            None => Ok(None),
+        }
+    }
+
+    #[cfg(not(feature = "config"))]
+    pub(crate) fn load_config(&self) -> Result<Option<Config>, Error> {
+        Ok(None)
+    }
+
+    /// Print completion candidates, one per line, for the hidden
+    /// `__complete <position> [words...]` command used by completion scripts
+    ///
+    /// `position` is the index of the word being completed among the words
+    /// following the app name.
+    fn complete(&self, args: &[String]) {
+        let position = match args.first().and_then(|p| p.parse::<usize>().ok()) {
+            Some(position) => position,
+            None => return,
+        };
+
+        for candidate in completion::candidates(self, position, &args[1..]) {
+            self.streams.out(&format!("{}\n", candidate));
+        }
+    }
+
+    /// Help text of the command at the path given to the `help` command, with
+    /// the path of the command by the names of its commands
+    ///
+    /// Unknown names are reported with suggestions from the commands
+    /// at the level they were looked up.
+    fn help_path_text(&self, bin: &str, path: &[String]) -> Result<(String, Vec<String>), Error> {
+        let mut command_path = vec![bin.to_string()];
+        let mut commands = &self.commands;
+        let mut current: Option<&Command> = None;
+        let mut help_flag = &self.help_flag;
+        let mut abbreviate = self.command_abbreviation;
+
+        for name in path {
+            let within = |e: Error| {
+                let usage = match current {
+                    Some(command) => command.usage_text(&command_path),
+                    None => self.usage_text(bin),
+                };
+                e.within(&command_path, usage, help_flag)
+            };
+            abbreviate = current
+                .and_then(|command| command.command_abbreviation)
+                .unwrap_or(abbreviate);
+            let command = parser::select_command(commands, name, abbreviate).map_err(within)?;
+
+            match command {
+                Some(command) => {
+                    command_path.push(command.name.clone());
+                    commands = &command.commands;
+                    current = Some(command);
+                    help_flag = command.help_flag.or(help_flag);
+                }
+                None => {
+                    let error =
+                        parser::unknown_command_error(commands, name, &command_path.join(" "));
+                    return Err(within(error));
+                }
+            }
+        }
+
+        let help_text = match current {
+            Some(command) => command.help_text_at(
+                &command_path,
+                help_flag,
+                self.flags(),
+                self.env_prefix.as_ref().map(String::as_str),
+                self.message_catalog(),
+            ),
+            None => self.help_text_as(bin),
+        };
+        Ok((help_text, command_path))
+    }
+
+    fn has_version(&self) -> bool {
+        self.version.is_some() || self.long_version.is_some()
+    }
+
+    /// Version text requested by the arguments
+    ///
+    /// The long form of the version flag prints the long version when it's
+    /// set and the short form always prints the short `name version` line.
+    pub(crate) fn version_text<'a>(
+        &self,
+        args: impl IntoIterator<Item = &'a String>,
+    ) -> Option<String> {
+        if !self.has_version() {
+            return None;
+        }
+        let mut short = false;
+        for arg in args {
+            if self.version_flag.matches_long(arg) {
+                return self.long_version.clone().or_else(|| self.short_version());
+            }
+            short |= self.version_flag.matches_short(arg);
+        }
+        if short {
+            self.short_version()
+        } else {
+            None
+        }
+    }
+
+    /// `name version` line, the long version when only that is set
+    fn short_version(&self) -> Option<String> {
+        match &self.version {
+            Some(version) => Some(format!("{} {}", self.name, version)),
+            None => self.long_version.clone(),
+        }
+    }
+
+    /// Generate markdown document of the app
+    ///
+    /// The document has a section per command, headed by the command path,
+    /// with its usage, description and a table of flags.
+    /// Hidden commands and flags are skipped and command categories become sub-headings.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::new("bool", FlagType::Bool))
+    ///     .command(Command::new("hello"));
+    ///
+    /// let mut doc = Vec::new();
+    /// app.generate_markdown(&mut doc).unwrap();
+    /// ```
+    pub fn generate_markdown(&self, w: &mut impl Write) -> io::Result<()> {
+        markdown::write_app(self, w)
+    }
+
+    /// Write help of the app
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli").disable_help_flag();
+    ///
+    /// let mut help = Vec::new();
+    /// app.write_help(&mut help).unwrap();
+    /// ```
+    pub fn write_help(&self, w: &mut impl Write) -> io::Result<()> {
+        w.write_all(self.render_help().as_bytes())
+    }
+
+    /// Render help of the app as `--help` prints it, without styles or pager
+    ///
+    /// Usage lines start with the binary name set with `App::bin_name`, or
+    /// the app name. The text suits snapshot tests of the help.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Flag, FlagType};
+    ///
+    /// let app = App::new("cli").flag(Flag::new("force", FlagType::Bool).description("Never ask"));
+    /// assert_eq!(
+    ///     app.render_help(),
+    ///     "Name:\n\tcli\n\n\
+    ///      Usage:\n\tcli [flags]\n\n\
+    ///      Flags:\n\
+    ///      \t--force    : Never ask\n\
+    ///      \t--help, -h : Show help\n\n"
+    /// );
+    /// ```
+    pub fn render_help(&self) -> String {
+        help::render(&self.help_text())
+    }
+
+    /// Generate JSON dump of the app structure
+    ///
+    /// The dump is also printed by running the app with `--help=json`.
+    /// Unlike help, hidden commands and flags are included and marked.
+    /// Items appear in declaration order. The schema is:
+    ///
+    /// ```text
+    /// app:     { "schema_version": 1, "name": string, "version": string?,
+    ///            "author": string?, "description": string?, "usage": string?,
+    ///            "flags": [flag], "commands": [command] }
+    /// command: { "name": string, "path": [string], "aliases": [string],
+    ///            "hidden_aliases": [string], "category": string?,
+    ///            "hidden": bool, "description": string?, "usage": string?,
+    ///            "flags": [flag], "commands": [command] }
+    /// flag:    { "name": string, "aliases": [string],
+    ///            "hidden_aliases": [string], "type": string, "hidden": bool,
+    ///            "description": string?, "default": string?, "choices": [string],
+    ///            "sources": [source] }
+    /// source:  { "kind": "command_line" } | { "kind": "environment", "var": string }
+    ///          | { "kind": "prompt" } | { "kind": "default" }
+    ///          | { "kind": "config_file", "file": string, "keys": [string] }
+    /// ```
+    ///
+    /// `string?` fields are `null` when unset, and `type` is one of
+    /// `bool`, `string`, `int`, `uint`, `float`, `secret`, `datetime`, `uuid`,
+    /// `url` and, with the `regex` feature, `regex`. `sources` lists where
+    /// values of the flag can come from, in order of precedence, the keys of
+    /// the config file most specific first. Fields may be added
+    /// without notice; `schema_version` changes when any are removed or
+    /// change meaning.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command};
+    ///
+    /// let app = App::new("cli")
+    ///     .command(Command::new("hello"));
+    ///
+    /// let mut dump = Vec::new();
+    /// app.generate_json(&mut dump).unwrap();
+    /// ```
+    pub fn generate_json(&self, w: &mut impl Write) -> io::Result<()> {
+        json::write_app(self, w)
+    }
+
+    /// Check the definition of the app for problems that do not stop it from
+    /// running, like a command with neither an action nor subcommands
+    ///
+    /// Problems of the app come first, then those of each command before
+    /// those of its subcommands. Errors are mistakes leaving part of the
+    /// definition useless, warnings are matters of style, so a test can fail
+    /// on errors only.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context, Flag, FlagType, Severity};
+    ///
+    /// let app = App::new("cli")
+    ///     .command(Command::new("add").action(|_: &Context| {}))
+    ///     .command(Command::new("remove"))
+    ///     .flag(Flag::new("verbose", FlagType::Bool));
+    ///
+    /// let problems = app.check();
+    /// assert_eq!(problems.len(), 2);
+    /// assert_eq!(
+    ///     problems[0].to_string(),
+    ///     r#"warning: cli: Flag "verbose" has no description"#
+    /// );
+    /// let errors: Vec<_> = problems.iter().filter(|p| p.severity == Severity::Error).collect();
+    /// assert_eq!(errors[0].path, ["cli", "remove"]);
+    /// ```
+    pub fn check(&self) -> Vec<Problem> {
+        check::check_app(self)
+    }
+
+    /// Print the tree of commands of the app, for checking which commands
+    /// are registered
+    ///
+    /// Each line is a command indented by its depth, with its aliases,
+    /// category, a `[hidden]` marker and the number of its flags. Hidden
+    /// commands are included. Running the app with the hidden `__tree`
+    /// command prints the tree too, `__tree --flags` like
+    /// `App::print_flag_tree`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::new("verbose", FlagType::Bool))
+    ///     .command(Command::new("remote").alias("r").command(Command::new("add")));
+    ///
+    /// let mut tree = Vec::new();
+    /// app.print_tree(&mut tree).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(tree).unwrap(),
+    ///     "cli (1 flag)\n  remote [aliases: r]\n    add\n"
+    /// );
+    /// ```
+    pub fn print_tree(&self, w: &mut impl Write) -> io::Result<()> {
+        tree::write_app(self, false, w)
+    }
+
+    /// Print the tree of commands like `App::print_tree`, listing the flags of
+    /// each command with their type above its subcommands
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .command(Command::new("serve").flag(Flag::new("port", FlagType::Uint).alias("p")));
+    ///
+    /// let mut tree = Vec::new();
+    /// app.print_flag_tree(&mut tree).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(tree).unwrap(),
+    ///     "cli\n  serve (1 flag)\n    --port, -p : uint\n"
+    /// );
+    /// ```
+    pub fn print_flag_tree(&self, w: &mut impl Write) -> io::Result<()> {
+        tree::write_app(self, true, w)
+    }
+
+    /// Generate completion script of the app for the shell
+    ///
+    /// Hidden commands and flags are not completed. Values of flags and
+    /// arguments with a completer are completed by running the app with the
+    /// hidden `__complete` command.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Shell};
+    ///
+    /// let app = App::new("cli")
+    ///     .command(Command::new("hello"));
+    ///
+    /// let mut script = Vec::new();
+    /// app.generate_completion(Shell::Bash, &mut script).unwrap();
+    /// ```
+    pub fn generate_completion(&self, shell: Shell, w: &mut impl Write) -> io::Result<()> {
+        completion::write_app(self, shell, w)
+    }
+
+    /// Install the completion script of the app for the shell where the
+    /// shell loads it for the user, returning the path of the script
+    ///
+    /// Scripts go to `~/.local/share/bash-completion/completions/<bin>` under
+    /// `XDG_DATA_HOME` when set, `~/.zfunc/_<bin>`, which zsh loads once
+    /// `~/.zfunc` is in its `fpath`, and `~/.config/fish/completions/<bin>.fish`
+    /// under `XDG_CONFIG_HOME` when set. Directories are made as needed. An
+    /// installed script is kept, an error of kind `ErrorKind::Completion`
+    /// like a script that cannot be written, or PowerShell, which has no
+    /// such directory. `Command::completions` installs scripts from the
+    /// command line.
+    ///
+    /// Example
+    ///
+    /// ```no_run
+    /// use seahorse::{App, Shell};
+    ///
+    /// let app = App::new("cli");
+    /// let path = app.install_completion(Shell::Fish).unwrap();
+    /// ```
+    pub fn install_completion(&self, shell: Shell) -> Result<PathBuf, Error> {
+        completion::install::install(self, shell, false).map_err(|e| self.localized(e))
+    }
+
+    /// Install the completion script like `App::install_completion`,
+    /// overwriting a script installed before
+    ///
+    /// Example
+    ///
+    /// ```no_run
+    /// use seahorse::{App, Shell};
+    ///
+    /// let app = App::new("cli");
+    /// let path = app.reinstall_completion(Shell::Bash).unwrap();
+    /// ```
+    pub fn reinstall_completion(&self, shell: Shell) -> Result<PathBuf, Error> {
+        completion::install::install(self, shell, true).map_err(|e| self.localized(e))
+    }
+
+    /// Select command
+    /// Gets the Command that matches the string passed in the argument
+    fn select_command(&self, cmd: &str) -> Result<Option<&Command>, Error> {
+        parser::select_command(&self.commands, cmd, self.command_abbreviation)
+    }
+
+    /// Position after the name of the pass-through command the arguments
+    /// run, whose arguments from there are given to it as they are
+    pub(crate) fn pass_through_at(&self, args: &[String]) -> Option<usize> {
+        let mut position = 1;
+        while let Some(arg) = args.get(position) {
+            position += match arg.as_str() {
+                "--no-input" | "--no-pager" => 1,
+                _ => match parser::leading_global_flags(&args[position..], self.flags()) {
+                    0 => break,
+                    n => n,
+                },
+            };
+        }
+        let mut commands = &self.commands;
+        let mut abbreviate = self.command_abbreviation;
+        loop {
+            let token = args.get(position)?;
+            let command = parser::select_command(commands, token, abbreviate).ok()??;
+            position += 1;
+            if command.pass_through {
+                return Some(position);
+            }
+            commands = &command.commands;
+            abbreviate = command.command_abbreviation.unwrap_or(abbreviate);
+        }
+    }
+
+    /// Usage line of the app, generated from the binary name unless set
+    fn usage_text(&self, bin: &str) -> String {
+        if let Some(usage) = &self.usage {
+            return usage.clone();
+        }
+
+        let mut usage = bin.to_string();
+        if !help::visible(&self.commands, false).is_empty() {
+            usage += if self.action.is_some() {
+                " [command]"
+            } else {
+                " <command>"
+            };
+        }
+        usage += " [flags]";
+        if self.action.is_some() {
+            usage += " [args]";
+        }
+        usage
+    }
+
+    fn flag_help_text(&self, form: HelpForm) -> String {
+        let messages = self.message_catalog();
+        let mut builtins = Vec::new();
+        if let Some(help_flag) = self.help_flag.usage() {
+            builtins.push((help_flag, messages.help_flag_description()));
+        }
+        if let (true, Some(version_flag)) = (self.has_version(), self.version_flag.usage()) {
+            builtins.push((version_flag, messages.version_flag_description()));
+        }
+
+        let flags = help::visible(&self.flags, self.sort_help_alphabetically);
+        let prefix = self.env_prefix.as_ref().map(String::as_str);
+        help::flag_list(&flags, &builtins, form, messages, |flag| {
+            flag.env_names(prefix, &[])
+        })
     }
 
     fn command_help_text(&self) -> String {
+        let commands = help::visible(&self.commands, self.sort_help_alphabetically);
+        help::command_list(&commands, terminal::width(), self.message_catalog())
+    }
+}
+
+impl App {
+    /// Help text of the app with usage lines starting with the binary name
+    fn help_text_as(&self, bin: &str) -> String {
+        self.help_text_in(bin, HelpForm::Long)
+    }
+
+    /// Help of the app run as the binary in the form, the short form leaving
+    /// out the author, the text before and after help and the version
+    fn help_text_in(&self, bin: &str, form: HelpForm) -> String {
+        let messages = self.message_catalog();
         let mut text = String::new();
+        let long = form == HelpForm::Long;
+
+        if let (true, Some(before_help)) = (long, &self.before_help) {
+            text += &format!("{}\n\n", before_help);
+        }
+
+        text += &format!("{}:\n\t{}\n\n", messages.name_heading(), self.name);
+
+        if let (true, Some(author)) = (long, &self.author) {
+            text += &format!("{}:\n\t{}\n\n", messages.author_heading(), author);
+        }
+
+        if let Some(description) = &self.description {
+            text += &format!("{}:\n\t{}\n\n", messages.description_heading(), description);
+        }
+
+        text += &format!(
+            "{}:\n\t{}\n\n",
+            messages.usage_heading(),
+            help::indent(&self.usage_text(bin))
+        );
+        text += &self.flag_help_text(form);
+        let command_help = self.command_help_text();
+        if !command_help.is_empty() {
+            text += &command_help;
+            text += &format!("\n{}\n", messages.command_help_hint(bin));
+        }
+
+        if let (true, Some(version)) = (long, self.long_version.as_ref().or(self.version.as_ref()))
+        {
+            text += &format!("\n{}:\n\t{}\n", messages.version_heading(), version);
+        }
+
+        if let (true, Some(after_help)) = (long, &self.after_help) {
+            text += &format!("\n{}\n", after_help);
+        }
+
+        text
+    }
+}
+
+impl Help for App {
+    fn help_text(&self) -> String {
+        self.help_text_as(self.display_name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{Error, ErrorHandler, ErrorKind};
+    use crate::{
+        Action, App, AppSettings, Command, Context, Flag, FlagGroup, FlagType, FlagValue, Help,
+        HelpFlag, Messages, Outcome, Pager, Terminal, UnknownCommand, UnknownFlag, ValueSource,
+    };
+    use std::io::{self, Cursor, Write};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn app_new_only_test() {
+        let app = App::new("cli");
+        app.run(vec!["cli".to_string()]);
+
+        assert_eq!(app.name, "cli".to_string());
+        assert_eq!(app.usage, None);
+        assert_eq!(app.author, None);
+        assert_eq!(app.description, None);
+        assert_eq!(app.version, None);
+    }
+
+    #[test]
+    fn multiple_app_test() {
+        let a: Action = Action::from(|c: &Context| {
+            assert!(c.bool_flag("bool"));
+            match c.string_flag("string") {
+                Ok(flag) => assert_eq!("string".to_string(), flag),
+                _ => panic!("string test false..."),
+            }
+            match c.int_flag("int") {
+                Ok(flag) => assert_eq!(100, flag),
+                _ => panic!("int test false..."),
+            }
+            match c.float_flag("float") {
+                Ok(flag) => assert_eq!(1.23, flag),
+                _ => panic!("float test false..."),
+            }
+        });
+        let c = Command::new("hello")
+            .alias("h")
+            .description("hello command")
+            .usage("test hello(h) args")
+            .action(a)
+            .flag(Flag::new("bool", FlagType::Bool))
+            .flag(Flag::new("string", FlagType::String))
+            .flag(Flag::new("int", FlagType::Int))
+            .flag(Flag::new("float", FlagType::Float));
+
+        let app = App::new("test")
+            .author("Author <author@example.com>")
+            .description("This is a great tool.")
+            .usage("test [command] [arg]")
+            .version("0.0.1")
+            .command(c);
+
+        app.run(vec![
+            "test".to_string(),
+            "hello".to_string(),
+            "args".to_string(),
+            "--bool".to_string(),
+            "--string".to_string(),
+            "string".to_string(),
+            "--int".to_string(),
+            "100".to_string(),
+            "--float".to_string(),
+            "1.23".to_string(),
+        ]);
+
+        app.run(vec![
+            "test".to_string(),
+            "h".to_string(),
+            "args".to_string(),
+            "--bool".to_string(),
+            "--string".to_string(),
+            "string".to_string(),
+            "--int".to_string(),
+            "100".to_string(),
+            "--float".to_string(),
+            "1.23".to_string(),
+        ]);
+
+        assert_eq!(app.name, "test".to_string());
+        assert_eq!(app.usage, Some("test [command] [arg]".to_string()));
+        assert_eq!(app.author, Some("Author <author@example.com>".to_string()));
+        assert_eq!(app.description, Some("This is a great tool.".to_string()));
+        assert_eq!(app.version, Some("0.0.1".to_string()));
+    }
+
+    #[test]
+    fn single_app_test() {
+        let action: Action = Action::from(|c: &Context| {
+            assert!(c.bool_flag("bool"));
+            match c.string_flag("string") {
+                Ok(flag) => assert_eq!("string".to_string(), flag),
+                _ => panic!("string test false..."),
+            }
+            match c.int_flag("int") {
+                Ok(flag) => assert_eq!(100, flag),
+                _ => panic!("int test false..."),
+            }
+            match c.float_flag("float") {
+                Ok(flag) => assert_eq!(1.23, flag),
+                _ => panic!("float test false..."),
+            }
+        });
+
+        let app = App::new("test")
+            .author("Author <author@example.com>")
+            .description("This is a great tool.")
+            .usage("test [arg]")
+            .version("0.0.1")
+            .action(action)
+            .flag(Flag::new("bool", FlagType::Bool))
+            .flag(Flag::new("string", FlagType::String))
+            .flag(Flag::new("int", FlagType::Int))
+            .flag(Flag::new("float", FlagType::Float));
+
+        app.run(vec![
+            "test".to_string(),
+            "args".to_string(),
+            "--bool".to_string(),
+            "--string".to_string(),
+            "string".to_string(),
+            "--int".to_string(),
+            "100".to_string(),
+            "--float".to_string(),
+            "1.23".to_string(),
+        ]);
+
+        assert_eq!(app.name, "test".to_string());
+        assert_eq!(app.usage, Some("test [arg]".to_string()));
+        assert_eq!(app.author, Some("Author <author@example.com>".to_string()));
+        assert_eq!(app.description, Some("This is a great tool.".to_string()));
+        assert_eq!(app.version, Some("0.0.1".to_string()));
+    }
+
+    #[test]
+    fn flag_only_app_test() {
+        let action: Action = Action::from(|c: &Context| {
+            assert!(c.bool_flag("bool"));
+            match c.string_flag("string") {
+                Ok(flag) => assert_eq!("string".to_string(), flag),
+                _ => panic!("string test false..."),
+            }
+            match c.int_flag("int") {
+                Ok(flag) => assert_eq!(100, flag),
+                _ => panic!("int test false..."),
+            }
+            match c.float_flag("float") {
+                Ok(flag) => assert_eq!(1.23, flag),
+                _ => panic!("float test false..."),
+            }
+        });
+
+        let app = App::new("test")
+            .author("Author <author@example.com>")
+            .description("This is a great tool.")
+            .usage("test")
+            .version("0.0.1")
+            .action(action)
+            .flag(Flag::new("bool", FlagType::Bool))
+            .flag(Flag::new("string", FlagType::String))
+            .flag(Flag::new("int", FlagType::Int))
+            .flag(Flag::new("float", FlagType::Float));
+
+        app.run(vec![
+            "test".to_string(),
+            "--bool".to_string(),
+            "--string".to_string(),
+            "string".to_string(),
+            "--int".to_string(),
+            "100".to_string(),
+            "--float".to_string(),
+            "1.23".to_string(),
+        ]);
+
+        assert_eq!(app.name, "test".to_string());
+        assert_eq!(app.usage, Some("test".to_string()));
+        assert_eq!(app.author, Some("Author <author@example.com>".to_string()));
+        assert_eq!(app.description, Some("This is a great tool.".to_string()));
+        assert_eq!(app.version, Some("0.0.1".to_string()));
+    }
+
+    #[test]
+    fn single_app_equal_notation_test() {
+        let action: Action = Action::from(|c: &Context| {
+            assert!(c.bool_flag("bool"));
+            match c.string_flag("string") {
+                Ok(flag) => assert_eq!("str=ing".to_string(), flag),
+                _ => panic!("string test false..."),
+            }
+            match c.int_flag("int") {
+                Ok(flag) => assert_eq!(100, flag),
+                _ => panic!("int test false..."),
+            }
+            match c.float_flag("float") {
+                Ok(flag) => assert_eq!(1.23, flag),
+                _ => panic!("float test false..."),
+            }
+        });
+
+        let app = App::new("test")
+            .author("Author <author@example.com>")
+            .description("This is a great tool.")
+            .usage("test [arg]")
+            .version("0.0.1")
+            .action(action)
+            .flag(Flag::new("bool", FlagType::Bool))
+            .flag(Flag::new("string", FlagType::String))
+            .flag(Flag::new("int", FlagType::Int))
+            .flag(Flag::new("float", FlagType::Float).alias("f"));
+
+        app.run(vec![
+            "test".to_string(),
+            "args".to_string(),
+            "--bool".to_string(),
+            "--string=str=ing".to_string(),
+            "--int=100".to_string(),
+            "-f=1.23".to_string(),
+        ]);
+
+        assert_eq!(app.name, "test".to_string());
+        assert_eq!(app.usage, Some("test [arg]".to_string()));
+        assert_eq!(app.author, Some("Author <author@example.com>".to_string()));
+        assert_eq!(app.description, Some("This is a great tool.".to_string()));
+        assert_eq!(app.version, Some("0.0.1".to_string()));
+    }
+
+    #[test]
+    fn before_and_after_help_test() {
+        let app = App::new("test")
+            .usage("test [arg]")
+            .version("0.0.1")
+            .before_help("Banner\n  line")
+            .after_help("See https://example.com/docs for more");
+
+        assert_eq!(
+            app.help_text(),
+            "Banner\n  line\n\n\
+             Name:\n\ttest\n\n\
+             Usage:\n\ttest [arg]\n\n\
+             Flags:\n\t--help, -h    : Show help\n\t--version, -V : Show version\n\
+             \nVersion:\n\t0.0.1\n\
+             \nSee https://example.com/docs for more\n"
+        );
+    }
+
+    #[test]
+    fn display_order_test() {
+        fn positions(text: &str, words: &[&str]) -> Vec<usize> {
+            words.iter().map(|w| text.find(w).unwrap()).collect()
+        }
+
+        fn app() -> App {
+            App::new("test")
+                .flag(Flag::new("zeta", FlagType::Bool))
+                .flag(Flag::new("alpha", FlagType::Bool))
+                .command(Command::new("world"))
+                .command(Command::new("hello"))
+        }
+
+        // declaration order
+        let text = app().help_text();
+        let p = positions(&text, &["--zeta", "--alpha", "world", "hello"]);
+        assert!(p[0] < p[1] && p[2] < p[3]);
+
+        // explicit orders
+        let text = app()
+            .flag(Flag::new("first", FlagType::Bool).display_order(0))
+            .command(Command::new("bye").display_order(0))
+            .help_text();
+        let p = positions(&text, &["--first", "--zeta", "--alpha", "bye", "world"]);
+        assert!(p[0] < p[1] && p[1] < p[2] && p[3] < p[4]);
+
+        // alphabetical
+        let text = app()
+            .command(Command::new("bye").display_order(2000))
+            .sort_help_alphabetically()
+            .help_text();
+        let p = positions(&text, &["--alpha", "--zeta", "hello", "world", "bye"]);
+        assert!(p[0] < p[1] && p[2] < p[3] && p[3] < p[4]);
+
+        // as a setting, sorting the commands added before too
+        let top = Command::new("top")
+            .command(Command::new("zeta"))
+            .command(Command::new("alpha"));
+        let app = App::new("test")
+            .command(top)
+            .setting(AppSettings::SortHelpAlphabetically);
+        let text = app.commands()[0].render_help("test");
+        let p = positions(&text, &["alpha", "zeta"]);
+        assert!(p[0] < p[1]);
+    }
+
+    #[test]
+    fn version_text_test() {
+        let args = |arg: &str| vec!["cli".to_string(), arg.to_string()];
+
+        let app = App::new("cli").version("1.0.0");
+        assert_eq!(
+            app.version_text(&args("--version")),
+            Some("cli 1.0.0".to_string())
+        );
+        assert_eq!(app.version_text(&args("-V")), Some("cli 1.0.0".to_string()));
+        assert_eq!(app.version_text(&args("arg")), None);
+
+        let app = app.long_version("cli 1.0.0 (3a1b2c4 2024-01-15)");
+        assert_eq!(
+            app.version_text(&args("--version")),
+            Some("cli 1.0.0 (3a1b2c4 2024-01-15)".to_string())
+        );
+        assert_eq!(app.version_text(&args("-V")), Some("cli 1.0.0".to_string()));
+        assert!(app
+            .help_text()
+            .contains("Version:\n\tcli 1.0.0 (3a1b2c4 2024-01-15)"));
+        assert!(app.help_text().contains("--version, -V : Show version"));
+
+        let app = App::new("cli");
+        assert_eq!(app.version_text(&args("--version")), None);
+    }
+
+    #[test]
+    fn version_flag_test() {
+        let args = |a: &[&str]| {
+            let mut args = vec!["cli".to_string()];
+            args.extend(a.iter().map(|a| a.to_string()));
+            args
+        };
+        let app = App::new("cli")
+            .version("1.0.0")
+            .version_flag("release", Some('r'))
+            .flag(Flag::new("verbose-validation", FlagType::Bool).alias("V"))
+            .command(
+                Command::new("plugin")
+                    .version("0.4.1")
+                    .flag(Flag::new("version", FlagType::Bool)),
+            );
+        assert_eq!(
+            app.version_text(&args(&["--release"])),
+            Some("cli 1.0.0".to_string())
+        );
+        assert_eq!(
+            app.version_text(&args(&["-r"])),
+            Some("cli 1.0.0".to_string())
+        );
+        assert_eq!(app.version_text(&args(&["-V"])), None);
+        assert_eq!(app.version_text(&args(&["--version"])), None);
+        assert!(app
+            .help_text()
+            .contains("\t--release, -r            : Show version\n"));
+        let plugin = app
+            .help_path_text("cli", &args(&["plugin"])[1..])
+            .unwrap()
+            .0;
+        assert!(plugin.contains("\t--release, -r : Show version\n"));
+
+        // Without the short form
+        let app = App::new("cli")
+            .version("1.0.0")
+            .version_flag("version", None)
+            .flag(Flag::new("verbose-validation", FlagType::Bool).alias("V"));
+        assert_eq!(
+            app.version_text(&args(&["--version"])),
+            Some("cli 1.0.0".to_string())
+        );
+        assert_eq!(app.version_text(&args(&["-V"])), None);
+        assert!(app
+            .help_text()
+            .contains("\t--version                : Show version\n"));
+
+        // Disabled, `--version` is read as an unknown flag
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let app = App::new("cli")
+            .version("1.0.0")
+            .disable_version_flag()
+            .flag(Flag::new("verbose", FlagType::Bool))
+            .action(move |c: &Context| log.lock().unwrap().extend(c.args.clone()));
+        assert_eq!(app.version_text(&args(&["--version"])), None);
+        assert!(!app.help_text().contains("Show version"));
+        assert_eq!(app.try_run(args(&["--version"])), Ok(0));
+        assert_eq!(*seen.lock().unwrap(), vec!["--version"]);
+        let app = app.on_unknown_flag(|_: &str, _: &[String]| UnknownFlag::Error);
+        assert_eq!(
+            app.try_run(args(&["-V"])).map_err(|e| e.message),
+            Err(r#"Unknown flag "-V"."#.to_string())
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = r#"Flag "verbose-validation" conflicts with the version flag "--version, -V"."#
+    )]
+    fn version_flag_conflict_test() {
+        let _ = App::new("cli")
+            .flag(Flag::new("verbose-validation", FlagType::Bool).alias("V"))
+            .version("1.0.0");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Flag "V" conflicts with the version flag "--version, -V"."#)]
+    fn short_name_version_flag_conflict_test() {
+        let _ = App::new("cli")
+            .version("1.0.0")
+            .flag(Flag::new("V", FlagType::Bool));
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Flag "release" conflicts with the version flag "--release"."#)]
+    fn command_version_flag_conflict_test() {
+        let _ = App::new("cli")
+            .version("1.0.0")
+            .command(Command::new("build").flag(Flag::new("release", FlagType::Bool)))
+            .version_flag("release", None);
+    }
+
+    #[test]
+    fn path_help_test() {
+        let app = App::new("cli")
+            .flag(
+                Flag::new("config", FlagType::String)
+                    .description("Config file")
+                    .global(),
+            )
+            .flag(Flag::new("trace", FlagType::Bool).global().hidden())
+            .flag(Flag::new("local", FlagType::Bool))
+            .command(
+                Command::new("remote")
+                    .description("Manage remotes")
+                    .alias("r")
+                    .flag(Flag::new("verbose", FlagType::Bool))
+                    .command(
+                        Command::new("add")
+                            .description("Add a remote")
+                            .action(|_: &Context| {})
+                            .flag(Flag::new("name", FlagType::String)),
+                    )
+                    .command(Command::new("remove").description("Remove a remote")),
+            );
+
+        let top = app.help_path_text("cli", &[]).unwrap().0;
+        assert!(top.contains("Usage:\n\tcli <command> [flags]\n"));
+        assert!(top.contains("remote"));
+
+        let remote = app.help_path_text("cli", &["r".to_string()]).unwrap().0;
+        assert!(remote.starts_with("Description:\n\tManage remotes\n"));
+        assert!(remote.contains("Usage:\n\tcli remote <command> [flags]\n"));
+        assert!(remote.contains("--verbose"));
+
+        let add = app
+            .help_path_text("cli", &["remote".to_string(), "add".to_string()])
+            .unwrap()
+            .0;
+        assert!(add.starts_with("Description:\n\tAdd a remote\n"));
+        assert!(add.contains("Usage:\n\tcli remote add [flags] [args]\n"));
+        assert_eq!(
+            add,
+            "Description:\n\tAdd a remote\n\
+             \nUsage:\n\tcli remote add [flags] [args]\n\
+             \nFlags:\n\
+             \t--name <string>\n\
+             \t--help, -h        : Show help\n\
+             \nGlobal Flags:\n\
+             \t--config <string> : Config file\n"
+        );
+
+        // Running the command with the help flag shows the same help
+        #[derive(Clone, Default)]
+        struct Shared(Arc<Mutex<Vec<u8>>>);
+
+        impl Write for Shared {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let out = Shared::default();
+        let app = app.out(out.clone());
+        let line = ["cli", "remote", "add", "--help"];
+        assert_eq!(
+            app.try_run(line.iter().map(|a| a.to_string()).collect()),
+            Ok(0)
+        );
+        assert_eq!(
+            String::from_utf8(out.0.lock().unwrap().clone()).unwrap(),
+            format!("{}\n", add)
+        );
+
+        assert_eq!(
+            app.help_path_text("cli", &["remote".to_string(), "ad".to_string()])
+                .map_err(|e| e.message),
+            Err(r#"Unknown command "ad" in "cli remote". Did you mean "add"?"#.to_string())
+        );
+        assert_eq!(
+            app.help_path_text("cli", &["xyz".to_string()])
+                .map_err(|e| e.message),
+            Err(r#"Unknown command "xyz" in "cli"."#.to_string())
+        );
+    }
+
+    #[test]
+    fn alias_suggestion_test() {
+        let app = App::new("cli")
+            .command(Command::new("install").alias("in"))
+            .command(Command::new("remove").hidden_alias("rmv"))
+            .command(Command::new("search").alias("find").hidden());
+        let error = |name: &str| app.help_path_text("cli", &[name.to_string()]).unwrap_err();
+
+        let near_alias = error("inn");
+        assert_eq!(
+            near_alias.message,
+            r#"Unknown command "inn" in "cli". Did you mean "in" (alias of "install")?"#
+        );
+        assert_eq!(near_alias.suggestion, Some("in".to_string()));
+        assert_eq!(
+            error("instal").message,
+            r#"Unknown command "instal" in "cli". Did you mean "install"?"#
+        );
+        assert_eq!(error("rmx").suggestion, None);
+        assert_eq!(error("fnd").suggestion, None);
+
+        // Running the names suggests the same, at every level of commands
+        let app = app.command(
+            Command::new("remote")
+                .command(Command::new("add").alias("new").action(|_: &Context| {}))
+                .command(Command::new("prune").action(|_: &Context| {})),
+        );
+        let run = |args: &[&str]| {
+            app.try_run(args.iter().map(|a| a.to_string()).collect())
+                .unwrap_err()
+        };
+        for (args, suggestion) in &[
+            (&["cli", "inn"][..], Some("in")),
+            (&["cli", "instal", "x"], Some("install")),
+            (&["cli", "rmx"], None),
+            (&["cli", "remote", "ad"], Some("add")),
+            (&["cli", "remote", "neww"], Some("new")),
+            (&["cli", "remote", "prun", "--all"], Some("prune")),
+        ] {
+            let e = run(args);
+            assert_eq!(e.kind(), ErrorKind::UnknownCommand);
+            assert_eq!(e.suggestion.as_ref().map(String::as_str), *suggestion);
+        }
+        assert_eq!(run(&["cli", "inn"]).message, near_alias.message);
+        assert_eq!(
+            run(&["cli", "remote", "neww"]).message,
+            r#"Unknown command "neww" in "cli remote". Did you mean "new" (alias of "add")?"#
+        );
+        assert_eq!(
+            run(&["cli", "remote", "ad"]).usage,
+            Some("cli remote <command> [flags]".to_string())
+        );
+    }
+
+    #[test]
+    fn help_flag_test() {
+        let app = App::new("cli")
+            .help_flag("assist", Some('?'))
+            .flag(
+                Flag::new("host", FlagType::String)
+                    .description("Host")
+                    .alias("h"),
+            )
+            .command(
+                Command::new("serve")
+                    .flag(Flag::new("help", FlagType::Bool).description("Help topic"))
+                    .command(Command::new("tls").help_flag("help", None)),
+            );
+
+        assert!(app
+            .help_text()
+            .contains("Flags:\n\t--host, -h <string> : Host\n\t--assist, -?        : Show help\n"));
+        let serve = app.help_path_text("cli", &["serve".to_string()]).unwrap().0;
+        assert!(serve.contains("\t--help       : Help topic\n\t--assist, -? : Show help\n"));
+        let tls = app
+            .help_path_text("cli", &["serve".to_string(), "tls".to_string()])
+            .unwrap()
+            .0;
+        assert!(tls.contains("Flags:\n\t--help : Show help\n"));
+
+        assert!(HelpFlag::Inherit.matches("-h"));
+        assert!(app.help_flag.matches("-?"));
+        assert!(app.help_flag.matches("--assist"));
+        assert!(!app.help_flag.matches("-h"));
+        assert!(!app.help_flag.matches("--help"));
+    }
+
+    #[test]
+    fn disable_help_flag_test() {
+        let app = App::new("cli")
+            .usage("cli [args]")
+            .disable_help_flag()
+            .flag(Flag::new("help", FlagType::Bool));
+
+        assert!(!HelpFlag::Disabled.matches("--help"));
+        assert_eq!(
+            app.help_text(),
+            "Name:\n\tcli\n\nUsage:\n\tcli [args]\n\nFlags:\n\t--help\n"
+        );
+
+        let mut help = Vec::new();
+        app.write_help(&mut help).unwrap();
+        assert_eq!(String::from_utf8(help).unwrap(), app.help_text() + "\n");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Flag "host" conflicts with the help flag "--help, -h"."#)]
+    fn help_flag_conflict_test() {
+        let _ = App::new("cli").flag(Flag::new("host", FlagType::String).alias("h"));
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Flag "h" conflicts with the help flag "--help, -h"."#)]
+    fn short_name_help_flag_conflict_test() {
+        let _ = App::new("cli").flag(Flag::new("h", FlagType::String));
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Flag "assist" conflicts with the help flag "--assist, -?"."#)]
+    fn command_help_flag_conflict_test() {
+        let _ = App::new("cli")
+            .command(Command::new("serve").flag(Flag::new("assist", FlagType::Bool)))
+            .help_flag("assist", Some('?'));
+    }
+
+    #[test]
+    fn alias_conflict_test() {
+        let force = || Flag::new("force", FlagType::Bool).alias("f");
+        let error = |result: Result<App, String>| result.err();
+
+        // Alias of another flag
+        assert_eq!(
+            error(
+                App::new("cli")
+                    .flag(force())
+                    .try_flag(Flag::new("fix", FlagType::Bool).alias("f"))
+            ),
+            Some(r#"Alias "f" of flag "fix" is also used by flag "force"."#.to_string())
+        );
+        // Name of another flag, either way
+        assert_eq!(
+            error(
+                App::new("cli")
+                    .flag(force())
+                    .try_flag(Flag::new("f", FlagType::Bool))
+            ),
+            Some(r#"Alias "f" of flag "force" is also used by flag "f"."#.to_string())
+        );
+        assert_eq!(
+            error(
+                App::new("cli")
+                    .flag(Flag::new("fix", FlagType::Bool))
+                    .try_flag(Flag::new("force", FlagType::Bool).alias("fix"))
+            ),
+            Some(r#"Alias "fix" of flag "force" is also used by flag "fix"."#.to_string())
+        );
+        assert_eq!(
+            Command::new("rm")
+                .flag(force())
+                .try_flag(Flag::new("file", FlagType::String).alias("f"))
+                .err(),
+            Some(r#"Alias "f" of flag "file" is also used by flag "force"."#.to_string())
+        );
+
+        // Global flags of the app and flags of commands
+        assert_eq!(
+            error(
+                App::new("cli")
+                    .command(Command::new("rm").command(Command::new("all").flag(force())))
+                    .try_flag(Flag::new("file", FlagType::String).alias("f").global())
+            ),
+            Some(r#"Alias "f" of flag "file" is also used by flag "force"."#.to_string())
+        );
+        assert!(App::new("cli")
+            .command(Command::new("rm").flag(force()))
+            .try_flag(Flag::new("file", FlagType::String).alias("f"))
+            .is_ok());
+        assert!(App::new("cli")
+            .command(Command::new("rm").flag(force()))
+            .try_flag(Flag::new("force", FlagType::Bool).alias("f").global())
+            .is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Alias "f" of flag "file" is also used by flag "force"."#)]
+    fn global_alias_conflict_test() {
+        let _ = App::new("cli")
+            .flag(Flag::new("file", FlagType::String).alias("f").global())
+            .command(Command::new("rm").flag(Flag::new("force", FlagType::Bool).alias("f")));
+    }
+
+    #[test]
+    fn unknown_command_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let entry = |log: &Arc<Mutex<Vec<String>>>, prefix: &'static str| {
+            let log = log.clone();
+            move |c: &Context| {
+                log.lock()
+                    .unwrap()
+                    .push(format!("{} {}", prefix, c.args.join(" ")))
+            }
+        };
+        let handler_log = log.clone();
+        let app = App::new("cli")
+            .action(entry(&log, "app"))
+            .command(Command::new("remove").action(entry(&log, "remove")))
+            .on_unknown_command(move |name: &str, args: &[String]| {
+                handler_log
+                    .lock()
+                    .unwrap()
+                    .push(format!("unknown {} {}", name, args.join(" ")));
+                match name {
+                    "rm" => UnknownCommand::Retry("del".to_string()),
+                    "del" => UnknownCommand::Retry("remove".to_string()),
+                    "loop" => UnknownCommand::Retry("loop".to_string()),
+                    "stats" => UnknownCommand::Handled,
+                    _ => UnknownCommand::Fallthrough,
+                }
+            });
+        let run = |args: &[&str]| {
+            log.lock().unwrap().clear();
+            app.run(args.iter().map(|a| a.to_string()).collect());
+            log.lock().unwrap().clone()
+        };
+
+        assert_eq!(
+            run(&["cli", "rm", "a"]),
+            vec!["unknown rm a", "unknown del a", "remove a"]
+        );
+        assert_eq!(run(&["cli", "stats", "a"]), vec!["unknown stats a"]);
+        assert_eq!(run(&["cli", "x", "a"]), vec!["unknown x a", "app x a"]);
+        assert_eq!(
+            run(&["cli", "loop"]),
+            vec!["unknown loop "; 5]
+                .into_iter()
+                .chain(Some("app loop"))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(run(&["cli", "remove", "a"]), vec!["remove a"]);
+        assert_eq!(run(&["cli", "--flag"]), vec!["app --flag"]);
+    }
+
+    #[test]
+    fn unknown_flag_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let action_log = log.clone();
+        let app = App::new("cli")
+            .command(
+                Command::new("serve")
+                    .flag(Flag::new("port", FlagType::Uint))
+                    .action(move |c: &Context| {
+                        action_log.lock().unwrap().push(format!(
+                            "{:?} {:?}",
+                            c.uint_flag("port"),
+                            c.args
+                        ));
+                    }),
+            )
+            .on_unknown_flag(|flag: &str, path: &[String]| {
+                assert_eq!(path, ["cli".to_string(), "serve".to_string()]);
+                match flag {
+                    "--listen-port" => UnknownFlag::Replace("port".to_string()),
+                    _ => UnknownFlag::Ignore,
+                }
+            });
+        let run = |args: &[&str]| {
+            log.lock().unwrap().clear();
+            app.run(args.iter().map(|a| a.to_string()).collect());
+            log.lock().unwrap().clone()
+        };
+
+        assert_eq!(
+            run(&["cli", "serve", "--listen-port=8080", "--debug"]),
+            vec![r#"Ok(8080) ["--debug"]"#]
+        );
+        assert_eq!(
+            run(&["cli", "serve", "--debug", "--port", "80"]),
+            vec![r#"Ok(80) ["--debug"]"#]
+        );
+    }
+
+    #[test]
+    fn try_run_test() {
+        let app = App::new("cli")
+            .command(
+                Command::new("serve")
+                    .flag(Flag::new("port", FlagType::Int).alias("p"))
+                    .action(|_: &Context| {}),
+            )
+            .on_unknown_flag(|_: &str, _: &[String]| UnknownFlag::Error);
+        let error = |args: &[&str]| {
+            app.try_run(args.iter().map(|a| a.to_string()).collect())
+                .unwrap_err()
+        };
+
+        let e = error(&["cli", "serve", "--port", "http"]);
+        assert_eq!(e.kind, ErrorKind::InvalidValue);
+        assert_eq!(e.token, Some("http".to_string()));
+        assert_eq!(e.command, vec!["cli".to_string(), "serve".to_string()]);
+        assert_eq!(e.usage, Some("cli serve [flags] [args]".to_string()));
+        assert_eq!(e.help, Some("cli serve --help".to_string()));
+        assert_eq!(
+            e.to_string(),
+            "Invalid value \"http\" for flag \"port\", expected int\n\
+             Usage: cli serve [flags] [args]\n\
+             Try 'cli serve --help' for more information."
+        );
+
+        let e = error(&["cli", "serve", "--prot", "80"]);
+        assert_eq!(e.kind, ErrorKind::UnknownFlag);
+        assert_eq!(e.token, Some("--prot".to_string()));
+        assert_eq!(e.suggestion, Some("--port".to_string()));
+        assert_eq!(e.command, vec!["cli".to_string(), "serve".to_string()]);
+
+        let mut written = Vec::new();
+        ErrorHandler::default().handle(&e, &mut written);
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            "Unknown flag \"--prot\". Did you mean \"--port\"?\n  \
+             cli serve --prot 80\n            \
+             ^^^^^^\n\
+             Usage: cli serve [flags] [args]\n\
+             Try 'cli serve --help' for more information.\n"
+        );
+        assert!(app
+            .try_run(vec!["cli".to_string(), "serve".to_string()])
+            .is_ok());
+    }
+
+    #[test]
+    fn error_location_test() {
+        let app = App::new("cli").command(
+            Command::new("serve")
+                .action(|_: &Context| {})
+                .flag(Flag::new("host", FlagType::String))
+                .flag(Flag::new("port", FlagType::Int).alias("p")),
+        );
+        let error = |args: &[&str]| {
+            app.try_run(args.iter().map(|a| a.to_string()).collect())
+                .unwrap_err()
+        };
+        let render = |e: &Error| {
+            let mut written = Vec::new();
+            ErrorHandler::default().handle(e, &mut written);
+            String::from_utf8(written).unwrap()
+        };
+
+        let e = error(&["cli", "serve", "--host", "localhost", "-p", "http"]);
+        assert_eq!(e.index, Some(3));
+        assert_eq!(e.flag_index, Some(2));
+        assert_eq!(e.args[3], "http");
+        assert_eq!(
+            render(&e).lines().take(3).collect::<Vec<_>>(),
+            vec![
+                "Invalid value \"http\" for flag \"port\", expected int",
+                "  cli serve --host localhost -p http",
+                "                             ~~ ^^^^",
+            ]
+        );
+
+        let e = error(&["cli", "serve", "--port="]);
+        assert_eq!((e.index, e.flag_index), (Some(0), None));
+        assert!(render(&e).contains("\n  cli serve --port=\n            ^^^^^^^\n"));
+
+        let host = "h".repeat(80);
+        let e = error(&["cli", "serve", "--host", &host, "--port", "x"]);
+        assert_eq!(e.index, Some(3));
+        assert!(render(&e).contains("\n  at argument 4 \"x\"\n"));
+    }
+
+    #[test]
+    fn error_usage_test() {
+        let app = || {
+            App::new("cli").command(
+                Command::new("remote")
+                    .command(Command::new("add").action(|_: &Context| {}))
+                    .command(Command::new("prune").action(|_: &Context| {})),
+            )
+        };
+        let render = |app: App, args: &[&str]| {
+            let e = app
+                .try_run(args.iter().map(|a| a.to_string()).collect())
+                .unwrap_err();
+            let mut written = Vec::new();
+            ErrorHandler::default().handle(&e, &mut written);
+            String::from_utf8(written).unwrap()
+        };
+
+        assert_eq!(
+            render(app(), &["cli", "help", "remot"]),
+            "Unknown command \"remot\" in \"cli\". Did you mean \"remote\"?\n\
+             Usage: cli <command> [flags]\n\
+             Try 'cli --help' for more information.\n"
+        );
+        assert_eq!(
+            render(app(), &["cli", "help", "remote", "ad"]),
+            "Unknown command \"ad\" in \"cli remote\". Did you mean \"add\"?\n\
+             Usage: cli remote <command> [flags]\n\
+             Try 'cli remote --help' for more information.\n"
+        );
+        // Running an unknown command fails like asking for its help
+        assert_eq!(
+            render(app(), &["cli", "remot"]),
+            render(app(), &["cli", "help", "remot"])
+        );
+        let e = app()
+            .on_unknown_command(|_: &str, _: &[String]| UnknownCommand::Fallthrough)
+            .try_run(vec!["cli".to_string(), "remot".to_string()])
+            .unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::UnknownCommand);
+        assert_eq!(e.suggestion, Some("remote".to_string()));
+        let outcome = app().handle(vec!["cli".to_string(), "remot".to_string()]);
+        assert_eq!(outcome.exit_code(), 1);
+        assert_eq!(
+            render(
+                app()
+                    .on_unknown_flag(|_: &str, _: &[String]| UnknownFlag::Error)
+                    .help_flag("usage", None),
+                &["cli", "remote", "add", "--force"]
+            ),
+            "Unknown flag \"--force\".\n  \
+             cli remote add --force\n                 \
+             ^^^^^^^\n\
+             Usage: cli remote add [flags] [args]\n\
+             Try 'cli remote add --usage' for more information.\n"
+        );
+        assert_eq!(
+            render(app().disable_help_flag(), &["cli", "help", "remote", "ad"]),
+            "Unknown command \"ad\" in \"cli remote\". Did you mean \"add\"?\n\
+             Usage: cli remote <command> [flags]\n"
+        );
+        assert_eq!(
+            render(app().terse_errors(), &["cli", "help", "remote", "ad"]),
+            "Unknown command \"ad\" in \"cli remote\". Did you mean \"add\"?\n"
+        );
+    }
+
+    #[test]
+    fn command_abbreviation_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let entry = |name: &'static str| {
+            let log = log.clone();
+            move |_: &Context| log.lock().unwrap().push(name)
+        };
+        let app = App::new("cli")
+            .allow_command_abbreviation()
+            .command(Command::new("status").action(entry("status")))
+            .command(Command::new("stash").action(entry("stash")))
+            .command(
+                Command::new("remote")
+                    .command(Command::new("add").action(entry("remote add")))
+                    .action(entry("remote")),
+            );
+        let run = |args: &[&str]| {
+            log.lock().unwrap().clear();
+            app.try_run(args.iter().map(|a| a.to_string()).collect())
+                .map(|_| log.lock().unwrap().clone())
+        };
+
+        assert_eq!(run(&["cli", "stat"]), Ok(vec!["status"]));
+        assert_eq!(run(&["cli", "rem", "a"]), Ok(vec!["remote add"]));
+        let e = run(&["cli", "sta"]).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::AmbiguousCommand);
+        assert_eq!(e.token, Some("sta".to_string()));
+        assert_eq!(e.command, vec!["cli".to_string()]);
+        assert!(App::new("cli")
+            .command(Command::new("status"))
+            .select_command("stat")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn exit_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let entry = |name: &'static str| {
+            let log = log.clone();
+            move |c: &Context| {
+                log.lock()
+                    .unwrap()
+                    .push(format!("{} {:?}", name, c.exit_code()))
+            }
+        };
+        let (exit, after_exit) = (entry("deploy"), entry("command after"));
+        let app = App::new("cli")
+            .command(
+                Command::new("deploy")
+                    .action(move |c: &Context| {
+                        exit(c);
+                        if c.args.is_empty() {
+                            c.exit(3);
+                        }
+                    })
+                    .after_action(move |c: &Context| {
+                        after_exit(c);
+                        c.exit(4);
+                    }),
+            )
+            .after_action(entry("app after"));
+        let run = |args: &[&str]| {
+            log.lock().unwrap().clear();
+            app.try_run(args.iter().map(|a| a.to_string()).collect())
+                .map(|code| (code, log.lock().unwrap().clone()))
+        };
+
+        assert_eq!(
+            run(&["cli", "deploy"]),
+            Ok((
+                3,
+                vec![
+                    "deploy None".to_string(),
+                    "command after Some(3)".to_string(),
+                    "app after Some(3)".to_string()
+                ]
+            ))
+        );
+        assert_eq!(
+            run(&["cli", "deploy", "now"]),
+            Ok((
+                4,
+                vec![
+                    "deploy None".to_string(),
+                    "command after None".to_string(),
+                    "app after Some(4)".to_string()
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn bin_name_test() {
+        let app = || {
+            App::new("Remote manager")
+                .command(Command::new("add").min_args(1).action(|_: &Context| {}))
+        };
+        let usage = |app: &App, program: &str| {
+            app.try_run(vec![program.to_string(), "add".to_string()])
+                .unwrap_err()
+                .usage
+        };
+
+        let expected = |bin: &str| Some(format!("{} add [flags] [args]", bin));
+        assert_eq!(usage(&app(), "/usr/local/bin/remote"), expected("remote"));
+        assert_eq!(usage(&app(), "git-remote.exe"), expected("git-remote"));
+        assert_eq!(usage(&app(), ""), expected("Remote manager"));
+        assert_eq!(
+            usage(&app().bin_name("rm"), "/usr/local/bin/remote"),
+            expected("rm")
+        );
+        assert_eq!(app().usage_text("remote"), "remote <command> [flags]");
+        assert!(app()
+            .bin_name("rm")
+            .help_text()
+            .contains("Usage:\n\trm <command> [flags]"));
+    }
+
+    #[test]
+    fn mount_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let (checked, released, done) = (log.clone(), log.clone(), log.clone());
+        let fmt = App::new("rustfmt")
+            .description("Format sources")
+            .version("1.2.0")
+            .flag(Flag::new("edition", FlagType::String))
+            .command(
+                Command::new("check")
+                    .flag(Flag::new("edition", FlagType::String))
+                    .action(move |c: &Context| {
+                        checked.lock().unwrap().push(format!(
+                            "check {:?} {:?}",
+                            c.args,
+                            c.string_flag("edition")
+                        ))
+                    }),
+            )
+            .after_action(move |_: &Context| released.lock().unwrap().push("after".to_string()));
+        let app = App::new("devtool")
+            .mount("fmt", fmt)
+            .after_action(move |_: &Context| {
+                done.lock().unwrap().push("devtool after".to_string())
+            });
+
+        assert_eq!(
+            app.try_run(
+                ["devtool", "fmt", "check", "--edition", "2018", "src"]
+                    .iter()
+                    .map(|a| a.to_string())
+                    .collect()
+            ),
+            Ok(0)
+        );
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                r#"check ["src"] Ok("2018")"#.to_string(),
+                "after".to_string(),
+                "devtool after".to_string()
+            ]
+        );
+
+        let help = app
+            .help_path_text("devtool", &["fmt".to_string()])
+            .unwrap()
+            .0;
+        assert!(help.starts_with("Description:\n\tFormat sources\n"));
+        assert!(help.contains("Usage:\n\tdevtool fmt <command> [flags]\n"));
+        assert!(help.contains("\t--version, -V      : Show version\n"));
+        assert!(help.contains("\tcheck"));
+        assert!(help.contains("Version:\n\t1.2.0\n"));
+        let check = app
+            .help_path_text("devtool", &["fmt".to_string(), "check".to_string()])
+            .unwrap()
+            .0;
+        assert!(check.contains("Usage:\n\tdevtool fmt check [flags] [args]\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Command name "fmt" is already registered."#)]
+    fn mount_collision_test() {
+        let _ = App::new("devtool")
+            .command(Command::new("fmt"))
+            .mount("fmt", App::new("rustfmt"));
+    }
+
+    #[test]
+    fn pager_test() {
+        let paged = Arc::new(Mutex::new(Vec::new()));
+        let app = |use_pager: bool, height: Option<usize>| {
+            let log = paged.clone();
+            let pager = Pager::from(move |text: &str| {
+                log.lock()
+                    .unwrap()
+                    .push(text.lines().next().unwrap_or("").to_string());
+                Ok(())
+            })
+            .terminal(height);
+            App::new("cli")
+                .use_pager(use_pager)
+                .pager(pager)
+                .command(Command::new("add").action(|_: &Context| {}))
+        };
+        let run = |app: App, args: &[&str]| {
+            app.try_run(args.iter().map(|a| a.to_string()).collect())
+                .unwrap()
+        };
+
+        run(app(true, Some(1)), &["cli", "--help"]);
+        run(app(true, Some(1)), &["cli", "add", "--help"]);
+        run(app(true, Some(1)), &["cli", "help", "add"]);
+        assert_eq!(*paged.lock().unwrap(), vec!["Name:", "Usage:", "Usage:"]);
+
+        paged.lock().unwrap().clear();
+        run(app(true, Some(1)), &["cli", "--no-pager", "--help"]);
+        run(app(true, Some(1)), &["cli", "add", "--help", "--no-pager"]);
+        run(app(true, None), &["cli", "--help"]);
+        run(app(true, Some(100)), &["cli", "--help"]);
+        run(app(false, Some(1)), &["cli", "--help"]);
+        assert!(paged.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn global_flags_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let entry = |name: &'static str, log: &Arc<Mutex<Vec<String>>>| {
+            let log = log.clone();
+            move |c: &Context| {
+                log.lock().unwrap().push(format!(
+                    "{} verbose={} config={:?} args={:?}",
+                    name,
+                    c.count_flag("verbose"),
+                    c.string_flag("config").ok(),
+                    c.args
+                ))
+            }
+        };
+        let app = App::new("cli")
+            .action(entry("cli", &log))
+            .flag(Flag::verbose().global())
+            .flag(Flag::new("config", FlagType::String).global())
+            .flag(Flag::new("local", FlagType::Bool))
+            .command(Command::new("build").action(entry("build", &log)));
+        let run = |args: &[&str]| {
+            app.try_run(args.iter().map(|a| a.to_string()).collect())
+                .map_err(|e| e.message)
+        };
+
+        run(&["cli", "--verbose", "build", "x"]).unwrap();
+        run(&["cli", "build", "--verbose", "x"]).unwrap();
+        run(&["cli", "-v", "--config=a.toml", "build", "x"]).unwrap();
+        run(&["cli", "--config", "build", "build", "x"]).unwrap();
+        run(&["cli", "--config", "build"]).unwrap();
+        run(&["cli", "--verbose", "--", "build"]).unwrap();
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                r#"build verbose=1 config=None args=["x"]"#,
+                r#"build verbose=1 config=None args=["x"]"#,
+                r#"build verbose=1 config=Some("a.toml") args=["x"]"#,
+                r#"build verbose=0 config=Some("build") args=["x"]"#,
+                r#"cli verbose=0 config=Some("build") args=[]"#,
+                r#"cli verbose=1 config=None args=["build"]"#,
+            ]
+        );
+
+        let app = App::new("cli")
+            .flag(Flag::verbose().global())
+            .flag(Flag::new("local", FlagType::Bool))
+            .command(Command::new("build").action(|_: &Context| {}));
+        let run = |args: &[&str]| {
+            app.try_run(args.iter().map(|a| a.to_string()).collect())
+                .map_err(|e| e.message)
+        };
+        assert_eq!(run(&["cli", "-v", "build"]), Ok(0));
+        assert_eq!(
+            run(&["cli", "--bogus", "build"]),
+            Err(r#"Unknown flag "--bogus"."#.to_string())
+        );
+        assert_eq!(
+            run(&["cli", "--verbos", "build"]),
+            Err(r#"Unknown flag "--verbos". Did you mean "--verbose"?"#.to_string())
+        );
+        assert_eq!(run(&["cli", "--local", "build"]), Ok(0));
+    }
+
+    #[test]
+    fn slash_flags_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let app = |enabled: bool| {
+            let log = log.clone();
+            App::new("setup").allow_slash_flags(enabled).command(
+                Command::new("install")
+                    .flag(Flag::new("quiet", FlagType::Bool).alias("q"))
+                    .flag(Flag::new("out", FlagType::String))
+                    .action(move |c: &Context| {
+                        log.lock().unwrap().push(format!(
+                            "quiet={} out={:?} args={:?}",
+                            c.bool_flag("quiet"),
+                            c.string_flag("out").ok(),
+                            c.args
+                        ))
+                    }),
+            )
+        };
+        let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect();
+
+        app(true)
+            .try_run(args(&[
+                "setup",
+                "install",
+                "/q",
+                "/out:a.txt",
+                "/usr/bin/foo",
+            ]))
+            .unwrap();
+        app(false)
+            .try_run(args(&[
+                "setup",
+                "install",
+                "/q",
+                "/out:a.txt",
+                "/usr/bin/foo",
+            ]))
+            .unwrap();
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                r#"quiet=true out=Some("a.txt") args=["/usr/bin/foo"]"#,
+                r#"quiet=false out=None args=["/q", "/out:a.txt", "/usr/bin/foo"]"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn help_form_test() {
+        let shown = Arc::new(Mutex::new(Vec::new()));
+        let log = shown.clone();
+        let app = App::new("cli")
+            .after_help("Long only")
+            .use_pager(true)
+            .pager(
+                Pager::from(move |text: &str| {
+                    log.lock().unwrap().push(text.contains("Long only"));
+                    Ok(())
+                })
+                .terminal(Some(0)),
+            )
+            .command(
+                Command::new("build")
+                    .after_help("Long only")
+                    .action(|_: &Context| {}),
+            );
+        let runs: [&[&str]; 5] = [
+            &["cli", "-h"],
+            &["cli", "--help"],
+            &["cli", "build", "-h"],
+            &["cli", "build", "--help"],
+            &["cli", "help", "build"],
+        ];
+        for args in &runs {
+            app.try_run(args.iter().map(|a| a.to_string()).collect())
+                .unwrap();
+        }
+        assert_eq!(*shown.lock().unwrap(), vec![false, true, false, true, true]);
+    }
+
+    #[test]
+    fn command_list_test() {
+        let app = App::new("cli")
+            .command(Command::new("init").description("Create a repository"))
+            .command(
+                Command::new("remote-management")
+                    .alias("rm")
+                    .description("Add, rename and remove remotes of the repository")
+                    .category("Sync"),
+            )
+            .command(Command::new("push").category("Sync").description("Push"))
+            .command(Command::new("debug").hidden())
+            .command(Command::new("ls").description("List files"));
+
+        assert_eq!(
+            app.help_text(),
+            "Name:\n\tcli\n\n\
+             Usage:\n\tcli <command> [flags]\n\n\
+             Flags:\n\t--help, -h : Show help\n\
+             \nCommands:\n\
+             \tinit                          : Create a repository\n\
+             \tls                            : List files\n\
+             \nSync:\n\
+             \tremote-management (alias: rm) : Add, rename and remove remotes of the repository\n\
+             \tpush                          : Push\n\
+             \nSee 'cli help <command>' for more information.\n"
+        );
+
+        let commands = crate::help::visible(&app.commands, false);
+        assert_eq!(
+            crate::help::command_list(&commands, Some(70), &crate::DefaultMessages),
+            "\nCommands:\n\
+             \tinit                          : Create a repository\n\
+             \tls                            : List files\n\
+             \nSync:\n\
+             \tremote-management (alias: rm) : Add, rename and remove remotes\n\
+             \t                                of the repository\n\
+             \tpush                          : Push\n"
+        );
+        assert_eq!(
+            crate::help::command_list(
+                &[commands[0], commands[3]],
+                Some(10),
+                &crate::DefaultMessages
+            ),
+            "\nCommands:\n\tinit : Create a repository\n\tls   : List files\n"
+        );
+        assert_eq!(
+            crate::help::command_list(&[], Some(80), &crate::DefaultMessages),
+            ""
+        );
+    }
+
+    #[test]
+    fn env_prefix_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let action = {
+            let log = log.clone();
+            move |c: &Context| {
+                log.lock().unwrap().push(format!(
+                    "{:?} {:?} {:?} {:?}",
+                    c.string_flag("name").ok(),
+                    c.string_flag("url").ok(),
+                    c.string_flag("dry-run").ok(),
+                    c.string_flag("mode").ok(),
+                ))
+            }
+        };
+        let app = App::new("cli")
+            .env_prefix("seahorse_prefix_test")
+            .flag(Flag::new("name", FlagType::String))
+            .action(action.clone())
+            .command(
+                Command::new("remote").command(
+                    Command::new("add")
+                        .flag(Flag::new("name", FlagType::String).description("Remote name"))
+                        .flag(
+                            Flag::new("url", FlagType::String).env("SEAHORSE_PREFIX_TEST_URL_ENV"),
+                        )
+                        .flag(Flag::new("dry-run", FlagType::String))
+                        .flag(Flag::new("mode", FlagType::String).default_value("fast"))
+                        .action(action),
+                ),
+            );
+        let run = |args: &[&str]| app.try_run(args.iter().map(|a| a.to_string()).collect());
+
+        std::env::set_var("SEAHORSE_PREFIX_TEST_REMOTE_ADD_NAME", "scoped");
+        std::env::set_var("SEAHORSE_PREFIX_TEST_NAME", "app");
+        std::env::set_var("SEAHORSE_PREFIX_TEST_URL_ENV", "explicit");
+        std::env::set_var("SEAHORSE_PREFIX_TEST_REMOTE_ADD_URL", "prefixed");
+        std::env::set_var("SEAHORSE_PREFIX_TEST_DRY_RUN", "yes");
+        assert_eq!(run(&["cli", "remote", "add"]), Ok(0));
+        assert_eq!(run(&["cli", "remote", "add", "--name", "origin"]), Ok(0));
+        assert_eq!(run(&["cli"]), Ok(0));
+        std::env::remove_var("SEAHORSE_PREFIX_TEST_URL_ENV");
+        std::env::set_var("SEAHORSE_PREFIX_TEST_MODE", "slow");
+        assert_eq!(run(&["cli", "remote", "add"]), Ok(0));
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                r#"Some("scoped") Some("explicit") Some("yes") Some("fast")"#,
+                r#"Some("origin") Some("explicit") Some("yes") Some("fast")"#,
+                r#"Some("app") None None None"#,
+                r#"Some("scoped") Some("prefixed") Some("yes") Some("slow")"#,
+            ]
+        );
+
+        let help = app
+            .help_path_text("cli", &["remote".to_string(), "add".to_string()])
+            .unwrap()
+            .0;
+        assert!(help.contains(
+            "\t--name <string>    : Remote name \
+             [env: SEAHORSE_PREFIX_TEST_REMOTE_ADD_NAME, SEAHORSE_PREFIX_TEST_NAME]\n\
+             \t--url <string>     : [env: SEAHORSE_PREFIX_TEST_URL_ENV, \
+             SEAHORSE_PREFIX_TEST_REMOTE_ADD_URL, SEAHORSE_PREFIX_TEST_URL]\n"
+        ));
+        assert!(app
+            .help_text()
+            .contains("\t--name <string> : [env: SEAHORSE_PREFIX_TEST_NAME]\n"));
+    }
+
+    #[cfg(feature = "dotenv")]
+    #[test]
+    fn dotenv_test() {
+        let file = std::env::temp_dir().join("seahorse_dotenv_test.env");
+        std::fs::write(
+            &file,
+            "SEAHORSE_DOTENV_TEST_TOKEN=abc123\n\
+             export SEAHORSE_DOTENV_TEST_REGION=\"eu-west-1\"\n",
+        )
+        .unwrap();
+        std::env::set_var("SEAHORSE_DOTENV_TEST_REGION", "us-east-1");
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let action = {
+            let log = log.clone();
+            move |c: &Context| {
+                log.lock().unwrap().push(format!(
+                    "{:?} {:?}",
+                    c.string_flag("token").ok(),
+                    c.string_flag("region").ok()
+                ))
+            }
+        };
+        let app = App::new("cli")
+            .dotenv_file(&file)
+            .env_prefix("SEAHORSE_DOTENV_TEST")
+            .flag(Flag::new("token", FlagType::String))
+            .flag(Flag::new("region", FlagType::String).env("SEAHORSE_DOTENV_TEST_REGION"))
+            .action(action);
+        assert_eq!(app.try_run(vec!["cli".to_string()]), Ok(0));
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![r#"Some("abc123") Some("us-east-1")"#]
+        );
+    }
+
+    #[test]
+    fn value_source_test() {
+        let sources = Arc::new(Mutex::new(Vec::new()));
+        let action = {
+            let sources = sources.clone();
+            move |c: &Context| {
+                let names = ["region", "token", "user", "zone", "force", "other"];
+                sources.lock().unwrap().push(
+                    names
+                        .iter()
+                        .map(|name| c.value_source(name))
+                        .collect::<Vec<_>>(),
+                )
+            }
+        };
+        std::env::set_var("SEAHORSE_VALUE_SOURCE_TEST_TOKEN", "abc");
+        let app = App::new("cli")
+            .prompt(|_: &str| Ok("alice".to_string()))
+            .command(
+                Command::new("deploy")
+                    .flag(Flag::new("region", FlagType::String))
+                    .flag(
+                        Flag::new("token", FlagType::String)
+                            .env("SEAHORSE_VALUE_SOURCE_TEST_TOKEN"),
+                    )
+                    .flag(Flag::new("user", FlagType::String).prompt_if_missing())
+                    .flag(Flag::new("zone", FlagType::String).default_value("a"))
+                    .flag(Flag::new("force", FlagType::Bool))
+                    .action(action),
+            );
+        let run = |args: &[&str]| app.try_run(args.iter().map(|a| a.to_string()).collect());
+
+        assert_eq!(
+            run(&["cli", "deploy", "--region", "eu", "--zone=b", "--force"]),
+            Ok(0)
+        );
+        assert_eq!(run(&["cli", "deploy", "--token", "xyz"]), Ok(0));
+        assert_eq!(
+            *sources.lock().unwrap(),
+            vec![
+                vec![
+                    Some(ValueSource::CommandLine),
+                    Some(ValueSource::Environment(
+                        "SEAHORSE_VALUE_SOURCE_TEST_TOKEN".to_string()
+                    )),
+                    Some(ValueSource::Prompt),
+                    Some(ValueSource::CommandLine),
+                    Some(ValueSource::CommandLine),
+                    None,
+                ],
+                vec![
+                    None,
+                    Some(ValueSource::CommandLine),
+                    Some(ValueSource::Prompt),
+                    Some(ValueSource::Default),
+                    None,
+                    None,
+                ],
+            ]
+        );
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn config_value_source_test() {
+        let file = std::env::temp_dir().join("seahorse_value_source_test.toml");
+        std::fs::write(&file, "region = \"eu\"\n[deploy]\nzone = \"b\"\n").unwrap();
+        let sources = Arc::new(Mutex::new(Vec::new()));
+        let action = {
+            let sources = sources.clone();
+            move |c: &Context| {
+                let names = ["region", "zone", "verbose"];
+                sources.lock().unwrap().push(
+                    names
+                        .iter()
+                        .map(|name| c.value_source(name))
+                        .collect::<Vec<_>>(),
+                )
+            }
+        };
+        let app = App::new("cli")
+            .config_file(&file)
+            .flag(
+                Flag::new("verbose", FlagType::Bool)
+                    .global()
+                    .default_value("false"),
+            )
+            .command(
+                Command::new("deploy")
+                    .flag(Flag::new("region", FlagType::String).default_value("us"))
+                    .flag(Flag::new("zone", FlagType::String))
+                    .action(action),
+            );
+        let run = |args: &[&str]| app.try_run(args.iter().map(|a| a.to_string()).collect());
 
-        if let Some(commands) = &self.commands {
-            text += "\nCommands:\n";
+        assert_eq!(run(&["cli", "deploy"]), Ok(0));
+        assert_eq!(run(&["cli", "deploy", "--zone", "c"]), Ok(0));
+        std::fs::remove_file(&file).unwrap();
 
-            let name_max_len = &commands
-                .iter()
-                .map(|c| {
-                    if let Some(alias) = &c.alias {
-                        format!("{}, {}", alias.join(", "), c.name).len()
-                    } else {
-                        c.name.len()
-                    }
-                })
-                .max()
-                .unwrap();
+        let config = |key: &str| {
+            Some(ValueSource::ConfigFile {
+                file: file.display().to_string(),
+                key: key.to_string(),
+            })
+        };
+        assert_eq!(
+            *sources.lock().unwrap(),
+            vec![
+                vec![
+                    config("region"),
+                    config("deploy.zone"),
+                    Some(ValueSource::Default)
+                ],
+                vec![
+                    config("region"),
+                    Some(ValueSource::CommandLine),
+                    Some(ValueSource::Default)
+                ],
+            ]
+        );
+    }
 
-            for c in commands {
-                let command_name = if let Some(alias) = &c.alias {
-                    format!("{}, {}", alias.join(", "), c.name)
-                } else {
-                    c.name.clone()
-                };
+    #[test]
+    fn pass_through_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let app = {
+            let log = log.clone();
+            App::new("cli")
+                .version("1.0.0")
+                .add_color_flag()
+                .flag(Flag::new("verbose", FlagType::Bool).alias("v").global())
+                .flag(Flag::new("config", FlagType::String).global())
+                .on_unknown_flag(|_: &str, _: &[String]| UnknownFlag::Error)
+                .command(
+                    Command::new("exec")
+                        .alias("x")
+                        .pass_through()
+                        .flag(Flag::new("shell", FlagType::String))
+                        .command(Command::new("ls").action(|_: &Context| {}))
+                        .action(move |c: &Context| {
+                            log.lock().unwrap().push(format!(
+                                "{} {:?} {:?}",
+                                c.bool_flag("verbose"),
+                                c.string_flag("config").ok(),
+                                c.args
+                            ))
+                        }),
+                )
+        };
+        let run = |line: &[&str]| app.try_run(line.iter().map(|a| a.to_string()).collect());
 
-                let description = match &c.description {
-                    Some(description) => description,
-                    None => "",
-                };
+        assert_eq!(
+            run(&[
+                "cli",
+                "exec",
+                "ls",
+                "-la",
+                "--verbose",
+                "-v",
+                "--shell=sh",
+                "--",
+                "--help",
+                "--version",
+                "--color",
+                "rainbow",
+                "--no-input",
+                "--nope"
+            ]),
+            Ok(0)
+        );
+        assert_eq!(
+            run(&[
+                "cli",
+                "-v",
+                "--config=ci.toml",
+                "--no-input",
+                "x",
+                "--config",
+                "x"
+            ]),
+            Ok(0)
+        );
+        assert_eq!(run(&["cli", "--config", "exec", "x", "--verbose"]), Ok(0));
+        assert_eq!(run(&["cli", "exec"]), Ok(0));
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                r#"false None ["ls", "-la", "--verbose", "-v", "--shell=sh", "--", "--help", "--version", "--color", "rainbow", "--no-input", "--nope"]"#,
+                r#"true Some("ci.toml") ["--config", "x"]"#,
+                r#"false Some("exec") ["--verbose"]"#,
+                "false None []",
+            ]
+        );
+    }
 
-                text += &format!(
-                    "\t{} {}: {}\n",
-                    command_name,
-                    " ".repeat(name_max_len - command_name.len()),
-                    description
-                );
+    #[test]
+    fn messages_test() {
+        struct German;
+
+        impl Messages for German {
+            fn usage_heading(&self) -> String {
+                "Aufruf".to_string()
             }
-        }
 
-        text
-    }
-}
+            fn flags_heading(&self) -> String {
+                "Optionen".to_string()
+            }
 
-impl Help for App {
-    fn help_text(&self) -> String {
-        let mut text = String::new();
+            fn help_flag_description(&self) -> String {
+                "Hilfe anzeigen".to_string()
+            }
 
-        text += &format!("Name:\n\t{}\n\n", self.name);
+            fn unknown_flag(&self, flag: &str) -> String {
+                format!("Unbekannte Option \"{}\".", flag)
+            }
 
-        if let Some(author) = &self.author {
-            text += &format!("Author:\n\t{}\n\n", author);
-        }
+            fn did_you_mean(&self, suggestion: &str) -> String {
+                format!("Meinten Sie \"{}\"?", suggestion)
+            }
 
-        if let Some(description) = &self.description {
-            text += &format!("Description:\n\t{}\n\n", description);
-        }
+            fn usage_hint(&self, usage: &str) -> String {
+                format!("Aufruf: {}", usage)
+            }
 
-        if let Some(usage) = &self.usage {
-            text += &format!("Usage:\n\t{}\n\n", usage);
+            fn trailing_backslash(&self) -> String {
+                "Backslash am Zeilenende".to_string()
+            }
         }
 
-        text += &self.flag_help_text();
-        text += &self.command_help_text();
+        let app = App::new("cli")
+            .messages(German)
+            .on_unknown_flag(|_: &str, _: &[String]| UnknownFlag::Error)
+            .flag(Flag::new("verbose", FlagType::Bool).description("Verbose output"))
+            .action(|_: &Context| {});
+
+        let error = app.try_run_from_str("cli --verbos").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::UnknownFlag);
+        assert_eq!(
+            error.to_string(),
+            "Unbekannte Option \"--verbos\". Meinten Sie \"--verbose\"?\n\
+             Aufruf: cli [flags] [args]\n\
+             Try 'cli --help' for more information."
+        );
+        assert_eq!(
+            app.try_run_from_str("cli \\").unwrap_err().message,
+            "Backslash am Zeilenende"
+        );
+        assert_eq!(
+            app.help_text(),
+            "Name:\n\tcli\n\n\
+             Aufruf:\n\tcli [flags] [args]\n\n\
+             Optionen:\n\
+             \t--verbose  : Verbose output\n\
+             \t--help, -h : Hilfe anzeigen\n"
+        );
+    }
+
+    #[test]
+    fn builtin_precedence_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let push = |name: &'static str| {
+            let log = log.clone();
+            move |c: &Context| log.lock().unwrap().push(format!("{} {:?}", name, c.args))
+        };
+        let no_spaces = |v: &str| match v.contains(' ') {
+            true => Err("must not contain spaces".to_string()),
+            false => Ok(()),
+        };
+        let app = App::new("cli")
+            .version("1.0.0")
+            .action(push("cli"))
+            .flag(Flag::new("token", FlagType::String).required())
+            .flag(Flag::new("name", FlagType::String).validator(no_spaces))
+            .command(
+                Command::new("deploy")
+                    .min_args(1)
+                    .flag(Flag::new("region", FlagType::String).required())
+                    .flag(Flag::new("fast", FlagType::Bool))
+                    .flag(Flag::new("safe", FlagType::Bool))
+                    .flag_group(
+                        FlagGroup::new("mode")
+                            .member("fast")
+                            .member("safe")
+                            .exclusive(),
+                    )
+                    .action(push("deploy")),
+            );
+        let run = |args: &[&str]| app.try_run(args.iter().map(|a| a.to_string()).collect());
+        let kind = |args: &[&str]| run(args).map_err(|e| e.kind);
+
+        assert_eq!(kind(&["cli"]), Err(ErrorKind::MissingFlag));
+        assert_eq!(
+            kind(&["cli", "--token=t", "--name=a b"]),
+            Err(ErrorKind::InvalidValue)
+        );
+        assert_eq!(kind(&["cli", "deploy", "x"]), Err(ErrorKind::MissingFlag));
+        assert_eq!(
+            kind(&["cli", "deploy", "--region=eu", "--fast", "--safe", "x"]),
+            Err(ErrorKind::FlagGroup)
+        );
+        assert_eq!(
+            kind(&["cli", "deploy", "--region=eu"]),
+            Err(ErrorKind::WrongNumberOfArguments)
+        );
 
-        if let Some(version) = &self.version {
-            text += &format!("\nVersion:\n\t{}\n", version);
+        for args in &[
+            &["cli", "--help"][..],
+            &["cli", "--version"],
+            &["cli", "--name=a b", "-h"],
+            &["cli", "--name=a b", "--version"],
+            &["cli", "deploy", "--help"],
+            &["cli", "deploy", "-h"],
+            &["cli", "deploy", "--version"],
+            &["cli", "deploy", "--fast", "--safe", "--help"],
+            &["cli", "deploy", "--fast", "--safe", "--version"],
+        ] {
+            assert_eq!(run(args), Ok(0), "{:?}", args);
         }
+        assert!(log.lock().unwrap().is_empty());
 
-        text
+        assert_eq!(
+            run(&["cli", "deploy", "--region=eu", "x", "--", "--help"]),
+            Ok(0)
+        );
+        assert_eq!(*log.lock().unwrap(), vec![r#"deploy ["x", "--help"]"#]);
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::{Action, App, Command, Context, Flag, FlagType};
 
+    #[cfg(feature = "config")]
     #[test]
-    fn app_new_only_test() {
-        let app = App::new("cli");
-        app.run(vec!["cli".to_string()]);
+    fn config_precedence_test() {
+        let file = std::env::temp_dir().join("seahorse_config_precedence_test.toml");
+        std::fs::write(&file, "[deploy]\nregion = 1\n").unwrap();
+        let app = App::new("cli").version("1.0.0").config_file(&file).command(
+            Command::new("deploy")
+                .flag(Flag::new("region", FlagType::String))
+                .action(|_: &Context| {}),
+        );
+        let run = |args: &[&str]| app.try_run(args.iter().map(|a| a.to_string()).collect());
 
-        assert_eq!(app.name, "cli".to_string());
-        assert_eq!(app.usage, None);
-        assert_eq!(app.author, None);
-        assert_eq!(app.description, None);
-        assert_eq!(app.version, None);
+        assert_eq!(run(&["cli", "deploy", "--help"]), Ok(0));
+        assert_eq!(run(&["cli", "deploy", "--version"]), Ok(0));
+        assert_eq!(
+            run(&["cli", "deploy"]).map_err(|e| e.kind),
+            Err(ErrorKind::Config)
+        );
+        std::fs::remove_file(&file).unwrap();
     }
 
     #[test]
-    fn multiple_app_test() {
-        let a: Action = Action::from(|c: &Context| {
-            assert_eq!(true, c.bool_flag("bool"));
-            match c.string_flag("string") {
-                Ok(flag) => assert_eq!("string".to_string(), flag),
-                _ => assert!(false, "string test false..."),
+    fn run_from_str_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let action = {
+            let log = log.clone();
+            move |c: &Context| {
+                log.lock()
+                    .unwrap()
+                    .push(format!("{:?} {:?}", c.string_flag("name").ok(), c.args))
             }
-            match c.int_flag("int") {
-                Ok(flag) => assert_eq!(100, flag),
-                _ => assert!(false, "int test false..."),
+        };
+        let app = App::new("cli").command(
+            Command::new("add")
+                .flag(Flag::new("name", FlagType::String))
+                .action(action),
+        );
+
+        assert_eq!(
+            app.try_run_from_str(r#"cli add --name "Alice Smith" 'my file.txt'"#),
+            Ok(0)
+        );
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![r#"Some("Alice Smith") ["my file.txt"]"#]
+        );
+        let error = app
+            .try_run_from_str(r#"cli add --name "Alice"#)
+            .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Split);
+        assert_eq!(
+            error.message,
+            "Unterminated double quote starting at character 16"
+        );
+        assert_eq!(log.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn run_repl_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let push = |name: &'static str| {
+            let log = log.clone();
+            move |c: &Context| {
+                log.lock().unwrap().push(format!(
+                    "{} {:?} {:?}",
+                    name,
+                    c.int_flag("count").ok(),
+                    c.args
+                ))
             }
-            match c.float_flag("float") {
-                Ok(flag) => assert_eq!(1.23, flag),
-                _ => assert!(false, "float test false..."),
+        };
+        let app = App::new("cli")
+            .command(
+                Command::new("add")
+                    .flag(Flag::new("count", FlagType::Int))
+                    .action(push("add")),
+            )
+            .command(Command::new("list").action(push("list")));
+        let input = "add --count 2 \"a b\"\n\
+                     \n\
+                     add --count x\n\
+                     list 'a\n\
+                     list --help\n\
+                     list\n\
+                     quit\n\
+                     list\n";
+        let mut output = Vec::new();
+        app.run_repl_with("> ", Cursor::new(input), &mut output)
+            .unwrap();
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![r#"add Some(2) ["a b"]"#, r#"list None []"#]
+        );
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "> > > Invalid value \"x\" for flag \"count\", expected int\n  \
+             cli add --count x\n          ~~~~~~~ ^\n\
+             Usage: cli add [flags] [args]\n\
+             Try 'cli add --help' for more information.\n\
+             > Unterminated single quote starting at character 6\n\
+             > > > "
+        );
+
+        let mut output = Vec::new();
+        app.run_repl_with("> ", Cursor::new("list"), &mut output)
+            .unwrap();
+        assert_eq!(output, b"> > ");
+        assert_eq!(log.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn terminal_test() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let observe = {
+            let seen = seen.clone();
+            move |c: &Context| {
+                seen.lock().unwrap().push((
+                    c.stdout_is_tty(),
+                    c.stderr_is_tty(),
+                    c.terminal_width(),
+                ))
             }
-        });
-        let c = Command::new("hello")
-            .alias("h")
-            .description("hello command")
-            .usage("test hello(h) args")
-            .action(a)
-            .flag(Flag::new("bool", FlagType::Bool))
-            .flag(Flag::new("string", FlagType::String))
-            .flag(Flag::new("int", FlagType::Int))
-            .flag(Flag::new("float", FlagType::Float));
+        };
+        let app = |terminal: Terminal| {
+            App::new("cli")
+                .action(observe.clone())
+                .command(Command::new("table").action(observe.clone()))
+                .terminal(terminal)
+        };
+        let narrow = Terminal {
+            stdout_is_tty: true,
+            stderr_is_tty: false,
+            width: Some(40),
+        };
+        let piped = Terminal {
+            stdout_is_tty: false,
+            stderr_is_tty: true,
+            width: None,
+        };
+        assert_eq!(app(narrow).try_run(vec!["cli".to_string()]), Ok(0));
+        assert_eq!(
+            app(piped).try_run(vec!["cli".to_string(), "table".to_string()]),
+            Ok(0)
+        );
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![(true, false, Some(40)), (false, true, None)]
+        );
+    }
 
-        let app = App::new("test")
-            .author("Author <author@example.com>")
-            .description("This is a great tool.")
-            .usage("test [command] [arg]")
-            .version("0.0.1")
-            .command(c);
+    #[test]
+    fn try_run_returns_test() {
+        // Exiting would end the test binary before the assertions
+        let app = App::new("cli")
+            .version("1.0.0")
+            .action(|c: &Context| {
+                if c.args.is_empty() {
+                    c.exit(3)
+                }
+            })
+            .command(Command::new("remote").command(Command::new("add")))
+            .flag(Flag::new("count", FlagType::Int));
+        let run = |line: &[&str]| app.try_run(line.iter().map(|a| a.to_string()).collect());
 
-        app.run(vec![
-            "test".to_string(),
-            "hello".to_string(),
-            "args".to_string(),
-            "--bool".to_string(),
-            "--string".to_string(),
-            "string".to_string(),
-            "--int".to_string(),
-            "100".to_string(),
-            "--float".to_string(),
-            "1.23".to_string(),
-        ]);
+        assert_eq!(run(&["cli", "--help"]), Ok(0));
+        assert_eq!(run(&["cli", "remote", "add", "--help"]), Ok(0));
+        assert_eq!(run(&["cli", "help", "remote"]), Ok(0));
+        assert_eq!(run(&["cli", "--version"]), Ok(0));
+        assert_eq!(run(&["cli"]), Ok(3));
+        assert_eq!(run(&["cli", "x"]), Ok(0));
+        assert_eq!(
+            run(&["cli", "--count=x", "x"]).map_err(|e| e.kind()),
+            Err(ErrorKind::InvalidValue)
+        );
+        assert!(run(&["cli", "help", "nope"]).is_err());
+    }
 
-        app.run(vec![
-            "test".to_string(),
-            "h".to_string(),
-            "args".to_string(),
-            "--bool".to_string(),
-            "--string".to_string(),
-            "string".to_string(),
-            "--int".to_string(),
-            "100".to_string(),
-            "--float".to_string(),
-            "1.23".to_string(),
-        ]);
+    #[test]
+    fn handle_test() {
+        let app = App::new("cli")
+            .version("1.0.0")
+            .action(|_: &Context| {})
+            .command(
+                Command::new("remote")
+                    .alias("r")
+                    .command(Command::new("add").action(|c: &Context| c.exit(4)))
+                    .command(Command::new("list").action(|_: &Context| {})),
+            );
+        let args = |line: &[&str]| -> Vec<String> { line.iter().map(|a| a.to_string()).collect() };
+        let handle = |line: &[&str]| app.handle(args(line));
+        let help = |target: &[&str]| Outcome::HelpShown {
+            target: args(target),
+        };
+        let ran = |command: &[&str], exit_code: i32| Outcome::Ran {
+            command: args(command),
+            exit_code,
+        };
 
-        assert_eq!(app.name, "test".to_string());
-        assert_eq!(app.usage, Some("test [command] [arg]".to_string()));
-        assert_eq!(app.author, Some("Author <author@example.com>".to_string()));
-        assert_eq!(app.description, Some("This is a great tool.".to_string()));
-        assert_eq!(app.version, Some("0.0.1".to_string()));
+        assert_eq!(handle(&["cli", "--help"]), help(&["cli"]));
+        assert_eq!(handle(&["cli", "remote"]), help(&["cli", "remote"]));
+        assert_eq!(
+            handle(&["cli", "r", "add", "-h"]),
+            help(&["cli", "remote", "add"])
+        );
+        assert_eq!(
+            handle(&["cli", "help", "r", "list"]),
+            help(&["cli", "remote", "list"])
+        );
+        assert_eq!(handle(&["cli", "--version"]), Outcome::VersionShown);
+        assert_eq!(handle(&["cli", "a"]), ran(&["cli"], 0));
+        assert_eq!(
+            handle(&["cli", "r", "add"]),
+            ran(&["cli", "remote", "add"], 4)
+        );
+        assert_eq!(
+            handle(&["cli", "remote", "list", "x"]),
+            ran(&["cli", "remote", "list"], 0)
+        );
+        assert_eq!(
+            handle(&["cli", "__complete"]),
+            Outcome::Done { exit_code: 0 }
+        );
+        match handle(&["cli", "help", "nope"]) {
+            Outcome::Error(e) => assert_eq!(e.kind(), ErrorKind::UnknownCommand),
+            outcome => panic!("{:?}", outcome),
+        }
+        assert_eq!(handle(&["cli", "help", "nope"]).exit_code(), 1);
+        assert_eq!(app.try_run(args(&["cli", "r", "add"])), Ok(4));
     }
 
     #[test]
-    fn single_app_test() {
-        let action: Action = Action::from(|c: &Context| {
-            assert_eq!(true, c.bool_flag("bool"));
-            match c.string_flag("string") {
-                Ok(flag) => assert_eq!("string".to_string(), flag),
-                _ => assert!(false, "string test false..."),
-            }
-            match c.int_flag("int") {
-                Ok(flag) => assert_eq!(100, flag),
-                _ => assert!(false, "int test false..."),
-            }
-            match c.float_flag("float") {
-                Ok(flag) => assert_eq!(1.23, flag),
-                _ => assert!(false, "float test false..."),
+    fn multicall_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let push = |name: &'static str| {
+            let log = log.clone();
+            move |c: &Context| log.lock().unwrap().push(format!("{} {:?}", name, c.args))
+        };
+        let app = App::new("tools")
+            .multicall(true)
+            .multicall_name("myfmt", "fmt")
+            .action(push("tools"))
+            .command(Command::new("fmt").min_args(1).action(push("fmt")))
+            .command(Command::new("lint").action(push("lint")));
+        let args = |line: &[&str]| -> Vec<String> { line.iter().map(|a| a.to_string()).collect() };
+        let ran = |command: &[&str]| Outcome::Ran {
+            command: args(command),
+            exit_code: 0,
+        };
+        let usage = |line: &[&str]| match app.handle(args(line)) {
+            Outcome::Error(e) => e.usage,
+            outcome => panic!("{:?}", outcome),
+        };
+
+        assert_eq!(
+            app.handle(args(&["/usr/bin/tools", "fmt", "a.rs"])),
+            ran(&["tools", "fmt"])
+        );
+        assert_eq!(app.handle(args(&["/usr/bin/fmt", "a.rs"])), ran(&["fmt"]));
+        assert_eq!(app.handle(args(&["myfmt", "a.rs"])), ran(&["myfmt"]));
+        assert_eq!(app.handle(args(&["lint.exe"])), ran(&["lint"]));
+        assert_eq!(app.handle(args(&["other", "x"])), ran(&["other"]));
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                r#"fmt ["a.rs"]"#,
+                r#"fmt ["a.rs"]"#,
+                r#"fmt ["a.rs"]"#,
+                "lint []",
+                r#"tools ["x"]"#,
+            ]
+        );
+
+        assert_eq!(
+            usage(&["tools", "fmt"]),
+            Some("tools fmt [flags] [args]".to_string())
+        );
+        assert_eq!(
+            usage(&["/usr/bin/fmt"]),
+            Some("fmt [flags] [args]".to_string())
+        );
+        assert_eq!(usage(&["myfmt"]), Some("myfmt [flags] [args]".to_string()));
+        assert_eq!(
+            app.handle(args(&["myfmt", "--help"])),
+            Outcome::HelpShown {
+                target: args(&["myfmt"])
             }
-        });
+        );
+        assert_eq!(
+            app.multicall(false).handle(args(&["fmt", "a.rs"])),
+            ran(&["fmt"])
+        );
+        assert_eq!(
+            log.lock().unwrap().last().map(String::as_str),
+            Some(r#"tools ["a.rs"]"#)
+        );
+    }
 
-        let app = App::new("test")
-            .author("Author <author@example.com>")
-            .description("This is a great tool.")
-            .usage("test [arg]")
-            .version("0.0.1")
-            .action(action)
-            .flag(Flag::new("bool", FlagType::Bool))
-            .flag(Flag::new("string", FlagType::String))
-            .flag(Flag::new("int", FlagType::Int))
-            .flag(Flag::new("float", FlagType::Float));
+    #[test]
+    fn user_aliases_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let logged = log.clone();
+        let app = App::new("cli")
+            .flag(Flag::new("verbose", FlagType::Bool).alias("v").global())
+            .user_aliases(vec![
+                ("st", "status --short"),
+                ("s", "st -b"),
+                ("log", "status"),
+                ("loop", "again x"),
+                ("again", "loop"),
+                ("quote", "status 'a b"),
+            ])
+            .command(
+                Command::new("status")
+                    .flag(Flag::new("short", FlagType::Bool))
+                    .flag(Flag::new("branch", FlagType::Bool).alias("b"))
+                    .action(move |c: &Context| {
+                        logged.lock().unwrap().push(format!(
+                            "status {} {} {} {:?}",
+                            c.bool_flag("short"),
+                            c.bool_flag("branch"),
+                            c.bool_flag("verbose"),
+                            c.args
+                        ))
+                    }),
+            )
+            .command(Command::new("log").action(|c: &Context| c.exit(3)));
+        let args = |line: &[&str]| -> Vec<String> { line.iter().map(|a| a.to_string()).collect() };
+        let run = |line: &[&str]| app.try_run(args(line));
 
-        app.run(vec![
-            "test".to_string(),
-            "args".to_string(),
-            "--bool".to_string(),
-            "--string".to_string(),
-            "string".to_string(),
-            "--int".to_string(),
-            "100".to_string(),
-            "--float".to_string(),
-            "1.23".to_string(),
-        ]);
+        assert_eq!(run(&["cli", "st", "a.rs", "b.rs"]), Ok(0));
+        assert_eq!(run(&["cli", "-v", "s"]), Ok(0));
+        assert_eq!(run(&["cli", "log"]), Ok(3));
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                r#"status true false false ["a.rs", "b.rs"]"#,
+                "status true true true []",
+            ]
+        );
 
-        assert_eq!(app.name, "test".to_string());
-        assert_eq!(app.usage, Some("test [arg]".to_string()));
-        assert_eq!(app.author, Some("Author <author@example.com>".to_string()));
-        assert_eq!(app.description, Some("This is a great tool.".to_string()));
-        assert_eq!(app.version, Some("0.0.1".to_string()));
+        let error = run(&["cli", "loop", "y"]).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::RecursiveAlias);
+        assert_eq!(error.message, "Alias \"loop\" expands to itself");
+        assert_eq!(run(&["cli", "quote"]).unwrap_err().kind, ErrorKind::Split);
     }
 
     #[test]
-    fn flag_only_app_test() {
-        let action: Action = Action::from(|c: &Context| {
-            assert_eq!(true, c.bool_flag("bool"));
-            match c.string_flag("string") {
-                Ok(flag) => assert_eq!("string".to_string(), flag),
-                _ => assert!(false, "string test false..."),
-            }
-            match c.int_flag("int") {
-                Ok(flag) => assert_eq!(100, flag),
-                _ => assert!(false, "int test false..."),
-            }
-            match c.float_flag("float") {
-                Ok(flag) => assert_eq!(1.23, flag),
-                _ => assert!(false, "float test false..."),
-            }
-        });
+    fn shared_command_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let logged = log.clone();
+        let status = Command::new("status")
+            .flag(Flag::new("short", FlagType::Bool).alias("s"))
+            .command(Command::new("branch").action(|c: &Context| c.exit(2)))
+            .action(move |c: &Context| {
+                logged
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} {:?}", c.bool_flag("short"), c.args))
+            })
+            .shared();
+        let full = App::new("cli")
+            .command_arc(status.clone())
+            .command(Command::new("init"));
+        let shell = App::new("shell").command_arc(status.clone());
+        let sorted = App::new("sorted")
+            .sort_help_alphabetically()
+            .command_arc(status.clone());
+        let args = |line: &[&str]| -> Vec<String> { line.iter().map(|a| a.to_string()).collect() };
 
-        let app = App::new("test")
-            .author("Author <author@example.com>")
-            .description("This is a great tool.")
-            .usage("test")
-            .version("0.0.1")
-            .action(action)
-            .flag(Flag::new("bool", FlagType::Bool))
-            .flag(Flag::new("string", FlagType::String))
-            .flag(Flag::new("int", FlagType::Int))
-            .flag(Flag::new("float", FlagType::Float));
+        assert_eq!(full.try_run(args(&["cli", "status", "-s", "a.rs"])), Ok(0));
+        assert_eq!(shell.try_run(args(&["shell", "status", "b.rs"])), Ok(0));
+        assert_eq!(shell.try_run(args(&["shell", "status", "branch"])), Ok(2));
+        assert_eq!(
+            *log.lock().unwrap(),
+            [r#"true ["a.rs"]"#, r#"false ["b.rs"]"#]
+        );
 
-        app.run(vec![
-            "test".to_string(),
-            "--bool".to_string(),
-            "--string".to_string(),
-            "string".to_string(),
-            "--int".to_string(),
-            "100".to_string(),
-            "--float".to_string(),
-            "1.23".to_string(),
-        ]);
+        assert!(Arc::ptr_eq(&full.commands()[0], &status));
+        assert!(Arc::ptr_eq(&shell.commands()[0], &status));
+        assert!(!Arc::ptr_eq(&sorted.commands()[0], &status));
+        assert!(!status.sort_help_alphabetically);
+        assert!(sorted.commands()[0].subcommands()[0].sort_help_alphabetically);
+    }
 
-        assert_eq!(app.name, "test".to_string());
-        assert_eq!(app.usage, Some("test".to_string()));
-        assert_eq!(app.author, Some("Author <author@example.com>".to_string()));
-        assert_eq!(app.description, Some("This is a great tool.".to_string()));
-        assert_eq!(app.version, Some("0.0.1".to_string()));
+    #[test]
+    fn shadowed_global_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let push = |name: &'static str| {
+            let log = log.clone();
+            move |c: &Context| {
+                let global = match c.global_flag("output") {
+                    Ok(FlagValue::String(output)) => output,
+                    Ok(value) => format!("{:?}", value),
+                    Err(e) => e.to_string(),
+                };
+                log.lock().unwrap().push(format!(
+                    "{} {:?} {:?} {} {}",
+                    name,
+                    c.uint_flag("output").ok(),
+                    c.string_flag("output").ok(),
+                    global,
+                    c.bool_flag("verbose"),
+                ))
+            }
+        };
+        let app = App::new("cli")
+            .flag(
+                Flag::new("output", FlagType::String)
+                    .alias("o")
+                    .default_value("out.log")
+                    .global(),
+            )
+            .flag(Flag::new("verbose", FlagType::Bool).global())
+            .command(
+                Command::new("split")
+                    .flag(Flag::new("output", FlagType::Uint).alias("o"))
+                    .action(push("split")),
+            )
+            .command(Command::new("show").action(push("show")));
+        let args = |line: &[&str]| -> Vec<String> { line.iter().map(|a| a.to_string()).collect() };
+        let run = |line: &[&str]| app.try_run(args(line));
+
+        assert_eq!(
+            run(&["cli", "--output", "a.log", "--verbose", "split", "-o", "3"]),
+            Ok(0)
+        );
+        assert_eq!(run(&["cli", "-o", "b.log", "split"]), Ok(0));
+        assert_eq!(run(&["cli", "split", "--output", "2"]), Ok(0));
+        assert_eq!(run(&["cli", "--output", "c.log", "show"]), Ok(0));
+        assert_eq!(run(&["cli", "show"]), Ok(0));
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "split Some(3) None a.log true",
+                "split None None b.log false",
+                "split Some(2) None out.log false",
+                r#"show None Some("c.log") c.log false"#,
+                r#"show None Some("out.log") out.log false"#,
+            ]
+        );
+
+        let error = run(&["cli", "split", "--output", "a.log"]).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidValue);
     }
 
     #[test]
-    fn single_app_equal_notation_test() {
-        let action: Action = Action::from(|c: &Context| {
-            assert_eq!(true, c.bool_flag("bool"));
-            match c.string_flag("string") {
-                Ok(flag) => assert_eq!("str=ing".to_string(), flag),
-                _ => assert!(false, "string test false..."),
+    fn streams_test() {
+        #[derive(Clone, Default)]
+        struct Shared(Arc<Mutex<Vec<u8>>>);
+
+        impl Write for Shared {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
             }
-            match c.int_flag("int") {
-                Ok(flag) => assert_eq!(100, flag),
-                _ => assert!(false, "int test false..."),
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
             }
-            match c.float_flag("float") {
-                Ok(flag) => assert_eq!(1.23, flag),
-                _ => assert!(false, "float test false..."),
+        }
+
+        impl Shared {
+            fn take(&self) -> String {
+                String::from_utf8(std::mem::replace(&mut *self.0.lock().unwrap(), Vec::new()))
+                    .unwrap()
             }
-        });
+        }
 
-        let app = App::new("test")
-            .author("Author <author@example.com>")
-            .description("This is a great tool.")
-            .usage("test [arg]")
-            .version("0.0.1")
-            .action(action)
-            .flag(Flag::new("bool", FlagType::Bool))
-            .flag(Flag::new("string", FlagType::String))
-            .flag(Flag::new("int", FlagType::Int))
-            .flag(Flag::new("float", FlagType::Float).alias("f"));
+        let (out, err) = (Shared::default(), Shared::default());
+        let app = App::new("cli")
+            .version("1.0.0")
+            .out(out.clone())
+            .err(err.clone())
+            .command(Command::new("add").action(|c: &Context| c.help()));
+        let run = |line: &[&str]| app.try_run(line.iter().map(|a| a.to_string()).collect());
 
-        app.run(vec![
-            "test".to_string(),
-            "args".to_string(),
-            "--bool".to_string(),
-            "--string=str=ing".to_string(),
-            "--int=100".to_string(),
-            "-f=1.23".to_string(),
-        ]);
+        assert_eq!(run(&["cli", "--help"]), Ok(0));
+        assert!(out.take().starts_with("Name:\n\tcli\n"));
+        assert_eq!(run(&["cli", "--version"]), Ok(0));
+        assert_eq!(out.take(), "cli 1.0.0\n");
+        assert_eq!(run(&["cli", "add"]), Ok(0));
+        assert!(out.take().contains("cli add [flags]"));
+        assert_eq!(run(&["cli", "__tree"]), Ok(0));
+        assert_eq!(out.take(), "cli\n  add\n");
+        assert_eq!(err.take(), "");
 
-        assert_eq!(app.name, "test".to_string());
-        assert_eq!(app.usage, Some("test [arg]".to_string()));
-        assert_eq!(app.author, Some("Author <author@example.com>".to_string()));
-        assert_eq!(app.description, Some("This is a great tool.".to_string()));
-        assert_eq!(app.version, Some("0.0.1".to_string()));
+        let app = app.debug(true);
+        assert_eq!(
+            app.try_run(vec!["cli".to_string(), "add".to_string()]),
+            Ok(0)
+        );
+        assert!(err.take().starts_with("seahorse: parsing \"cli add\"\n"));
+    }
+
+    #[test]
+    fn send_sync_test() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<App>();
+        assert_send_sync::<Command>();
+        assert_send_sync::<Flag>();
+        assert_send_sync::<Action>();
+        assert_send_sync::<crate::CompiledApp>();
     }
 }
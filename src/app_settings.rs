@@ -0,0 +1,64 @@
+/// Parser behaviors toggled on apps and commands
+///
+/// Settings are set with `App::setting` and `Command::setting`. Inherited
+/// settings of an app apply to all of its commands, and those of a command
+/// to its subcommands, unless a command sets them itself with
+/// `Command::setting` or `Command::unset_setting`:
+///
+/// | Setting                    | Inherited | Commands |
+/// |----------------------------|-----------|----------|
+/// | `AllowCommandAbbreviation` | yes       | yes      |
+/// | `AllowSlashFlags`          | yes       | yes      |
+/// | `StopAtFirstPositional`    | yes       | yes      |
+/// | `ExcludeTrailingArgs`      | yes       | yes      |
+/// | `SortHelpAlphabetically`   | no        | yes      |
+/// | `AllowArgfile`             | no        | no       |
+/// | `TerseErrors`              | no        | no       |
+///
+/// Example
+///
+/// ```
+/// use seahorse::{App, AppSettings, Command};
+///
+/// let app = App::new("cli")
+///     .setting(AppSettings::AllowCommandAbbreviation)
+///     .setting(AppSettings::StopAtFirstPositional)
+///     .command(Command::new("status"))
+///     .command(Command::new("exec").unset_setting(AppSettings::StopAtFirstPositional));
+/// assert!(AppSettings::StopAtFirstPositional.is_inherited());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AppSettings {
+    /// Run commands named by a unique prefix, see `App::allow_command_abbreviation`
+    AllowCommandAbbreviation,
+    /// Read `/name` and `/name:value` as flags, see `App::allow_slash_flags`
+    AllowSlashFlags,
+    /// Read every argument after the first positional argument as positional,
+    /// see `Command::stop_at_first_positional`
+    StopAtFirstPositional,
+    /// Leave arguments after `--` out of the argument count, see
+    /// `Command::exclude_trailing_args`
+    ExcludeTrailingArgs,
+    /// Sort flags and commands alphabetically in help, see
+    /// `App::sort_help_alphabetically`
+    SortHelpAlphabetically,
+    /// Expand `@file` arguments, see `App::allow_argfile`
+    AllowArgfile,
+    /// Report errors with their message only, see `App::terse_errors`
+    TerseErrors,
+}
+
+impl AppSettings {
+    /// Whether commands inherit the setting from their parent
+    pub fn is_inherited(self) -> bool {
+        match self {
+            AppSettings::AllowCommandAbbreviation
+            | AppSettings::AllowSlashFlags
+            | AppSettings::StopAtFirstPositional
+            | AppSettings::ExcludeTrailingArgs => true,
+            AppSettings::SortHelpAlphabetically
+            | AppSettings::AllowArgfile
+            | AppSettings::TerseErrors => false,
+        }
+    }
+}
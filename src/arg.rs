@@ -0,0 +1,304 @@
+use crate::error::FlagError;
+use crate::{FlagType, FlagValue, Validator, ValueHint};
+
+/// `Arg` type.
+///
+/// Declared positional argument of a command
+#[derive(Clone, Debug)]
+pub struct Arg {
+    /// Argument name
+    pub name: String,
+    /// Argument description
+    pub description: Option<String>,
+    /// Argument type
+    pub arg_type: FlagType,
+    /// Argument default value
+    pub default_value: Option<String>,
+    /// Fail before the action runs when the argument is not given
+    pub required: bool,
+    /// Take the remaining arguments, for the last argument
+    pub variadic: bool,
+    /// Checks of the argument value, run in order
+    pub validators: Vec<Validator>,
+    /// Kind of value completion scripts complete the argument with
+    pub value_hint: Option<ValueHint>,
+    /// Expand glob patterns in the values to the paths they match
+    #[cfg(feature = "glob")]
+    pub glob_expand: bool,
+    /// Keep glob patterns matching no path instead of failing
+    #[cfg(feature = "glob")]
+    pub keep_unmatched_glob: bool,
+}
+
+impl Arg {
+    /// Create new instance of `Arg`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Arg, FlagType};
+    ///
+    /// let port = Arg::new("PORT", FlagType::Uint);
+    /// ```
+    pub fn new<T: Into<String>>(name: T, arg_type: FlagType) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            arg_type,
+            default_value: None,
+            required: false,
+            variadic: false,
+            validators: Vec::new(),
+            value_hint: None,
+            #[cfg(feature = "glob")]
+            glob_expand: false,
+            #[cfg(feature = "glob")]
+            keep_unmatched_glob: false,
+        }
+    }
+
+    /// Set description of the argument
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Arg, FlagType};
+    ///
+    /// let port = Arg::new("PORT", FlagType::Uint)
+    ///     .description("Port to listen on");
+    /// ```
+    pub fn description<T: Into<String>>(mut self, description: T) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set default value of the argument, used when it is not given
+    ///
+    /// The argument then reads as if given with the value, and
+    /// `Context::is_arg_defaulted` tells it was not.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Arg, FlagType};
+    ///
+    /// let port = Arg::new("PORT", FlagType::Uint)
+    ///     .default_value("8080");
+    /// ```
+    pub fn default_value<T: Into<String>>(mut self, value: T) -> Self {
+        self.default_value = Some(value.into());
+        self
+    }
+
+    /// Require the argument to be given
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Arg, FlagType};
+    ///
+    /// let src = Arg::new("SRC", FlagType::String)
+    ///     .required();
+    /// ```
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Take the remaining arguments, read with `Context::values`
+    ///
+    /// Only the last argument can be variadic. Each value is checked against
+    /// the type and the validators.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Arg, Command, Context, FlagType};
+    ///
+    /// let command = Command::new("cat")
+    ///     .arg(Arg::new("FILE", FlagType::String).required().variadic())
+    ///     .action(|c: &Context| println!("{:?}", c.values::<String>("FILE")));
+    /// ```
+    pub fn variadic(mut self) -> Self {
+        self.variadic = true;
+        self
+    }
+
+    /// Add check of the argument value
+    ///
+    /// Checks run in order after the type check, the first error
+    /// being reported before the action runs.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Arg, FlagType};
+    ///
+    /// let port = Arg::new("PORT", FlagType::Uint).validator(|v: &str| match v.parse::<u16>() {
+    ///     Ok(port) if port > 0 => Ok(()),
+    ///     _ => Err("must be between 1 and 65535".to_string()),
+    /// });
+    /// ```
+    pub fn validator(mut self, validator: impl Into<Validator>) -> Self {
+        self.validators.push(validator.into());
+        self
+    }
+
+    /// Set kind of value completion scripts complete the argument with
+    ///
+    /// Arguments of a command are completed with the hint of the first one
+    /// that has one, arguments expanding glob patterns completing paths
+    /// unless set.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Arg, FlagType, ValueHint};
+    ///
+    /// let src = Arg::new("SRC", FlagType::String).value_hint(ValueHint::FilePath);
+    /// ```
+    pub fn value_hint(mut self, hint: ValueHint) -> Self {
+        self.value_hint = Some(hint);
+        self
+    }
+
+    /// Hint completion scripts complete the argument with, paths for
+    /// arguments expanding glob patterns
+    pub(crate) fn completion_hint(&self) -> Option<ValueHint> {
+        #[cfg(feature = "glob")]
+        {
+            if self.glob_expand {
+                return Some(self.value_hint.unwrap_or(ValueHint::AnyPath));
+            }
+        }
+        self.value_hint
+    }
+
+    /// Expand glob patterns given as the argument to the paths they match,
+    /// for variadic arguments
+    ///
+    /// Patterns are expanded like the values of `Flag::glob_expand`, each
+    /// matching path counting as an argument.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Arg, Command, Context, FlagType};
+    ///
+    /// let command = Command::new("add")
+    ///     .arg(Arg::new("FILE", FlagType::String).variadic().glob_expand())
+    ///     .action(|c: &Context| println!("{:?}", c.values::<String>("FILE")));
+    /// ```
+    #[cfg(feature = "glob")]
+    pub fn glob_expand(mut self) -> Self {
+        self.glob_expand = true;
+        self
+    }
+
+    /// Keep glob patterns matching no path as the argument instead of failing
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Arg, FlagType};
+    ///
+    /// let files = Arg::new("FILE", FlagType::String)
+    ///     .variadic()
+    ///     .glob_expand()
+    ///     .keep_unmatched_glob();
+    /// ```
+    #[cfg(feature = "glob")]
+    pub fn keep_unmatched_glob(mut self) -> Self {
+        self.keep_unmatched_glob = true;
+        self
+    }
+
+    /// Argument as shown in usage, like `<SRC>`, `[EXTRA]...` or
+    /// `[DIR (default: .)]`
+    pub(crate) fn usage(&self) -> String {
+        match &self.default_value {
+            Some(default) => self.marked(&format!("[{} (default: {})]", self.name, default)),
+            None => self.name_text(),
+        }
+    }
+
+    /// Name with the markers of required and variadic arguments, like
+    /// `<SRC>` or `[EXTRA]...`
+    pub(crate) fn name_text(&self) -> String {
+        if self.required && self.default_value.is_none() {
+            self.marked(&format!("<{}>", self.name))
+        } else {
+            self.marked(&format!("[{}]", self.name))
+        }
+    }
+
+    fn marked(&self, name: &str) -> String {
+        if self.variadic {
+            format!("{}...", name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Value of the argument from the command line arguments from its
+    /// position on, a list of all of them for variadic arguments
+    pub(crate) fn values(&self, given: &[String]) -> Result<FlagValue, FlagError> {
+        if !self.variadic || given.is_empty() {
+            return self.value(given.first());
+        }
+        given
+            .iter()
+            .map(|v| self.arg_type.parse(v.to_owned()))
+            .collect::<Result<_, _>>()
+            .map(FlagValue::List)
+    }
+
+    /// Value of the argument from the command line argument, or the default
+    pub(crate) fn value(&self, v: Option<&String>) -> Result<FlagValue, FlagError> {
+        match v.or(self.default_value.as_ref()) {
+            Some(v) => self.arg_type.parse(v.to_owned()),
+            None => Err(FlagError::NotFound),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::FlagError;
+    use crate::{Arg, FlagType, FlagValue};
+
+    #[test]
+    fn value_test() {
+        let value =
+            |arg_type: FlagType, v: &str| Arg::new("A", arg_type).value(Some(&v.to_string()));
+
+        assert_eq!(value(FlagType::Bool, "true"), Ok(FlagValue::Bool(true)));
+        assert_eq!(
+            value(FlagType::String, "a"),
+            Ok(FlagValue::String("a".to_string()))
+        );
+        assert_eq!(value(FlagType::Int, "-1"), Ok(FlagValue::Int(-1)));
+        assert_eq!(value(FlagType::Uint, "1"), Ok(FlagValue::Uint(1)));
+        assert_eq!(value(FlagType::Float, "0.5"), Ok(FlagValue::Float(0.5)));
+        assert_eq!(value(FlagType::Uint, "-1"), Err(FlagError::ValueTypeError));
+        assert_eq!(value(FlagType::Bool, "yes"), Err(FlagError::ValueTypeError));
+    }
+
+    #[test]
+    fn default_value_test() {
+        let port = Arg::new("PORT", FlagType::Uint).default_value("8080");
+
+        assert_eq!(port.value(None), Ok(FlagValue::Uint(8080)));
+        assert_eq!(port.value(Some(&"80".to_string())), Ok(FlagValue::Uint(80)));
+        assert_eq!(
+            Arg::new("PORT", FlagType::Uint)
+                .default_value("http")
+                .value(None),
+            Err(FlagError::ValueTypeError)
+        );
+        assert_eq!(
+            Arg::new("PORT", FlagType::Uint).value(None),
+            Err(FlagError::NotFound)
+        );
+    }
+}
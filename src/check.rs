@@ -0,0 +1,495 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{App, Command, Flag, FlagType, HelpFlag};
+
+/// Problem in the definition of an app found by `App::check`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Problem {
+    /// Whether the problem is a mistake or a matter of style
+    pub severity: Severity,
+    /// What the problem is
+    pub kind: ProblemKind,
+    /// Path of the command the problem is in, starting with the app name
+    pub path: Vec<String>,
+    /// Name of the command, flag or help section the problem is about
+    pub item: String,
+    /// Description of the problem
+    pub message: String,
+}
+
+/// Severity of a `Problem`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Style issue, the app working as defined
+    Warning,
+    /// Mistake making part of the definition useless
+    Error,
+}
+
+/// Kind of a `Problem`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProblemKind {
+    /// Command with neither an action nor subcommands, only showing help
+    NoAction,
+    /// Visible flag without a description, or with a blank one
+    MissingDescription,
+    /// Required flag with a default value, which it never misses
+    RequiredWithDefault,
+    /// Alias that cannot be reached, being the name or an alias of
+    /// something matched instead
+    UnreachableAlias,
+    /// Choices set on a flag that is not a string flag
+    ChoicesOnNonString,
+    /// Help section listing a single flag
+    SingleUseHelpSection,
+    /// Command flag named like a global flag, which it hides in the command
+    ShadowedGlobal,
+}
+
+impl ProblemKind {
+    /// Severity of problems of the kind
+    pub fn severity(self) -> Severity {
+        match self {
+            ProblemKind::NoAction
+            | ProblemKind::RequiredWithDefault
+            | ProblemKind::UnreachableAlias => Severity::Error,
+            ProblemKind::MissingDescription
+            | ProblemKind::ChoicesOnNonString
+            | ProblemKind::SingleUseHelpSection
+            | ProblemKind::ShadowedGlobal => Severity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}: {}: {}", severity, self.path.join(" "), self.message)
+    }
+}
+
+/// Names of the hidden commands the app runs before looking up commands
+const RESERVED_COMMANDS: [&str; 2] = ["__complete", "__tree"];
+
+/// Problems of the app, in the order of the commands and flags they are about
+pub(crate) fn check_app(app: &App) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let path = vec![app.name.clone()];
+    if app.action.is_none() && app.commands.iter().flatten().next().is_none() {
+        push(
+            &mut problems,
+            ProblemKind::NoAction,
+            &path,
+            &app.name,
+            format!(r#"App "{}" has neither an action nor commands"#, app.name),
+        );
+    }
+    let flags = app.flags.as_ref().map_or(&[][..], Vec::as_slice);
+    check_flags(&path, flags, &[], &app.help_flag, &mut problems);
+    let globals: Vec<&Flag> = flags.iter().filter(|f| f.global).collect();
+    let commands = app.commands.as_ref().map_or(&[][..], Vec::as_slice);
+    check_aliases(&path, commands, &RESERVED_COMMANDS, &mut problems);
+    for command in commands {
+        check_command(&path, command, &globals, &app.help_flag, &mut problems);
+    }
+    problems
+}
+
+fn check_command(
+    parent_path: &[String],
+    command: &Command,
+    globals: &[&Flag],
+    parent_help_flag: &HelpFlag,
+    problems: &mut Vec<Problem>,
+) {
+    let path = command.path(parent_path);
+    let commands = command.commands.as_ref().map_or(&[][..], Vec::as_slice);
+    if command.action.is_none() && commands.is_empty() {
+        push(
+            problems,
+            ProblemKind::NoAction,
+            &path,
+            &command.name,
+            format!(
+                r#"Command "{}" has neither an action nor subcommands"#,
+                command.name
+            ),
+        );
+    }
+    let flags = command.flags.as_ref().map_or(&[][..], Vec::as_slice);
+    for flag in flags {
+        if globals.iter().any(|global| global.name == flag.name) {
+            push(
+                problems,
+                ProblemKind::ShadowedGlobal,
+                &path,
+                &flag.name,
+                format!(
+                    r#"Flag "{}" of command "{}" shadows the global flag of the same name"#,
+                    flag.name, command.name
+                ),
+            );
+        }
+    }
+    let inherited: Vec<&Flag> = globals
+        .iter()
+        .cloned()
+        .filter(|global| flags.iter().all(|flag| flag.name != global.name))
+        .collect();
+    let help_flag = command.help_flag.or(parent_help_flag);
+    check_flags(&path, flags, &inherited, help_flag, problems);
+    check_aliases(&path, commands, &[], problems);
+    for subcommand in commands {
+        check_command(&path, subcommand, globals, help_flag, problems);
+    }
+}
+
+/// Aliases of the commands that are their own name, repeated, or the name or
+/// an alias of a command listed before
+fn check_aliases(
+    path: &[String],
+    commands: &[Arc<Command>],
+    reserved: &[&str],
+    problems: &mut Vec<Problem>,
+) {
+    for (index, command) in commands.iter().enumerate() {
+        let earlier = &commands[..index];
+        for (i, alias) in command.all_aliases().enumerate() {
+            let owner =
+                if *alias == command.name || command.all_aliases().take(i).any(|a| a == alias) {
+                    Some(format!(r#"command "{}" itself"#, command.name))
+                } else if reserved.contains(&alias.as_str()) {
+                    Some(format!(r#"the built-in command "{}""#, alias))
+                } else {
+                    commands
+                        .iter()
+                        .find(|other| other.name == *alias)
+                        .or_else(|| {
+                            earlier
+                                .iter()
+                                .find(|other| other.all_aliases().any(|a| a == alias))
+                        })
+                        .map(|other| format!(r#"command "{}""#, other.name))
+                };
+            if let Some(owner) = owner {
+                push(
+                    problems,
+                    ProblemKind::UnreachableAlias,
+                    path,
+                    &command.name,
+                    format!(
+                        r#"Alias "{}" of command "{}" is already used by {}"#,
+                        alias, command.name, owner
+                    ),
+                );
+            }
+        }
+        if reserved.contains(&command.name.as_str()) {
+            push(
+                problems,
+                ProblemKind::UnreachableAlias,
+                path,
+                &command.name,
+                format!(
+                    r#"Command "{}" is shadowed by the built-in command of the same name"#,
+                    command.name
+                ),
+            );
+        }
+    }
+}
+
+/// Problems of the flags of a command, followed by the global flags it
+/// inherits
+fn check_flags(
+    path: &[String],
+    flags: &[Flag],
+    inherited: &[&Flag],
+    help_flag: &HelpFlag,
+    problems: &mut Vec<Problem>,
+) {
+    let all: Vec<&Flag> = flags.iter().chain(inherited.iter().cloned()).collect();
+    for (index, flag) in flags.iter().enumerate() {
+        let blank = flag
+            .description
+            .as_ref()
+            .map_or(true, |d| d.trim().is_empty());
+        if blank && !flag.hidden {
+            push(
+                problems,
+                ProblemKind::MissingDescription,
+                path,
+                &flag.name,
+                format!(r#"Flag "{}" has no description"#, flag.name),
+            );
+        }
+        if flag.required {
+            if let Some(default) = &flag.default_value {
+                push(
+                    problems,
+                    ProblemKind::RequiredWithDefault,
+                    path,
+                    &flag.name,
+                    format!(
+                        r#"Flag "{}" is required but has the default value "{}""#,
+                        flag.name, default
+                    ),
+                );
+            }
+        }
+        if flag.choices.is_some() && flag.flag_type != FlagType::String {
+            push(
+                problems,
+                ProblemKind::ChoicesOnNonString,
+                path,
+                &flag.name,
+                format!(
+                    r#"Flag "{}" has choices but takes {} values"#,
+                    flag.name,
+                    flag.flag_type.name()
+                ),
+            );
+        }
+
+        let help = help_flag.names();
+        for (i, alias) in flag.all_aliases().enumerate() {
+            let owner = if *alias == flag.name || flag.all_aliases().take(i).any(|a| a == alias) {
+                Some(format!(r#"flag "{}" itself"#, flag.name))
+            } else if help.map_or(false, |(name, short)| {
+                alias == name || short.map_or(false, |s| *alias == s.to_string())
+            }) {
+                Some("the help flag".to_string())
+            } else {
+                all.iter()
+                    .find(|other| other.name == *alias)
+                    .or_else(|| {
+                        all[..index]
+                            .iter()
+                            .chain(inherited)
+                            .find(|other| other.all_aliases().any(|a| a == alias))
+                    })
+                    .map(|other| format!(r#"flag "{}""#, other.name))
+            };
+            if let Some(owner) = owner {
+                push(
+                    problems,
+                    ProblemKind::UnreachableAlias,
+                    path,
+                    &flag.name,
+                    format!(
+                        r#"Alias "{}" of flag "{}" is already used by {}"#,
+                        alias, flag.name, owner
+                    ),
+                );
+            }
+        }
+    }
+
+    let mut sections: Vec<(&str, Vec<&Flag>)> = Vec::new();
+    for flag in flags {
+        if let Some(section) = &flag.help_section {
+            match sections.iter_mut().find(|(name, _)| name == section) {
+                Some((_, flags)) => flags.push(flag),
+                None => sections.push((section, vec![flag])),
+            }
+        }
+    }
+    for (section, flags) in sections {
+        if let [flag] = flags.as_slice() {
+            push(
+                problems,
+                ProblemKind::SingleUseHelpSection,
+                path,
+                section,
+                format!(
+                    r#"Help section "{}" lists only flag "{}""#,
+                    section, flag.name
+                ),
+            );
+        }
+    }
+}
+
+fn push(
+    problems: &mut Vec<Problem>,
+    kind: ProblemKind,
+    path: &[String],
+    item: &str,
+    message: String,
+) {
+    problems.push(Problem {
+        severity: kind.severity(),
+        kind,
+        path: path.to_vec(),
+        item: item.to_string(),
+        message,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProblemKind, Severity};
+    use crate::{App, Command, Context, Flag, FlagType};
+    use std::sync::Arc;
+
+    /// Kind, path and item of each problem of the app
+    fn problems(app: &App) -> Vec<(ProblemKind, String, String)> {
+        app.check()
+            .into_iter()
+            .map(|p| (p.kind, p.path.join(" "), p.item))
+            .collect()
+    }
+
+    fn problem(kind: ProblemKind, path: &str, item: &str) -> (ProblemKind, String, String) {
+        (kind, path.to_string(), item.to_string())
+    }
+
+    fn flag(name: &str) -> Flag {
+        Flag::new(name, FlagType::String).description("Described")
+    }
+
+    fn command(name: &str) -> Command {
+        Command::new(name).action(|_: &Context| {})
+    }
+
+    #[test]
+    fn clean_test() {
+        let app = App::new("cli")
+            .flag(flag("config").alias("c").global())
+            .command(
+                Command::new("remote")
+                    .alias("r")
+                    .command(command("add").alias("a").flag(flag("name").alias("n")))
+                    .command(command("remove").alias("rm")),
+            );
+        assert_eq!(problems(&app), vec![]);
+    }
+
+    #[test]
+    fn no_action_test() {
+        assert_eq!(
+            problems(&App::new("cli")),
+            vec![problem(ProblemKind::NoAction, "cli", "cli")]
+        );
+        let app = App::new("cli").command(
+            Command::new("remote")
+                .command(Command::new("add"))
+                .command(command("remove")),
+        );
+        assert_eq!(
+            problems(&app),
+            vec![problem(ProblemKind::NoAction, "cli remote add", "add")]
+        );
+    }
+
+    #[test]
+    fn flag_test() {
+        let app = App::new("cli").action(|_: &Context| {}).command(
+            command("serve")
+                .flag(Flag::new("port", FlagType::Uint))
+                .flag(flag("host").description("  "))
+                .flag(Flag::new("debug", FlagType::Bool).hidden())
+                .flag(flag("token").required().default_value("none"))
+                .flag(
+                    Flag::new("level", FlagType::Int)
+                        .description("Level")
+                        .choices(&["1", "2"]),
+                )
+                .flag(flag("format").choices(&["json", "yaml"])),
+        );
+        assert_eq!(
+            problems(&app),
+            vec![
+                problem(ProblemKind::MissingDescription, "cli serve", "port"),
+                problem(ProblemKind::MissingDescription, "cli serve", "host"),
+                problem(ProblemKind::RequiredWithDefault, "cli serve", "token"),
+                problem(ProblemKind::ChoicesOnNonString, "cli serve", "level"),
+            ]
+        );
+    }
+
+    #[test]
+    fn help_section_test() {
+        let app = App::new("cli")
+            .action(|_: &Context| {})
+            .flag(flag("host").help_section("Network"))
+            .flag(flag("port").help_section("Network"))
+            .flag(flag("token").help_section("Auth"));
+        assert_eq!(
+            problems(&app),
+            vec![problem(ProblemKind::SingleUseHelpSection, "cli", "Auth")]
+        );
+    }
+
+    #[test]
+    fn unreachable_alias_test() {
+        let mut name = flag("name");
+        name.alias = Some(vec!["name".to_string()]);
+        let mut app = App::new("cli")
+            .flag(flag("verbose").global())
+            .command(command("add").alias("add"))
+            .command(command("remove").alias("rm").hidden_alias("rm"))
+            .command(command("__tree"))
+            .command(command("list").alias("__complete"))
+            .command(command("show").flag(name));
+        // Fields bypass the checks of the builders
+        let commands = app.commands.as_mut().unwrap();
+        commands.push(command("delete").alias("rm").shared());
+        Arc::make_mut(&mut commands[4])
+            .flags
+            .as_mut()
+            .unwrap()
+            .push(flag("brief").alias("verbose"));
+        let problems = app.check();
+        let messages: Vec<&str> = problems.iter().map(|p| p.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                r#"Alias "add" of command "add" is already used by command "add" itself"#,
+                r#"Alias "rm" of command "remove" is already used by command "remove" itself"#,
+                r#"Command "__tree" is shadowed by the built-in command of the same name"#,
+                r#"Alias "__complete" of command "list" is already used by the built-in command "__complete""#,
+                r#"Alias "rm" of command "delete" is already used by command "remove""#,
+                r#"Alias "name" of flag "name" is already used by flag "name" itself"#,
+                r#"Alias "verbose" of flag "brief" is already used by flag "verbose""#,
+            ]
+        );
+        assert!(problems
+            .iter()
+            .all(|p| p.kind == ProblemKind::UnreachableAlias && p.severity == Severity::Error));
+        assert_eq!(problems[6].path, ["cli", "show"]);
+        assert_eq!(problems[6].item, "brief");
+    }
+
+    #[test]
+    fn shadowed_global_test() {
+        let app = App::new("cli")
+            .flag(flag("output").global())
+            .flag(flag("color"))
+            .command(
+                Command::new("remote")
+                    .command(command("add").flag(flag("output")).flag(flag("color"))),
+            );
+        let problems = app.check();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|p| (p.kind, p.path.join(" "), p.item.clone()))
+                .collect::<Vec<_>>(),
+            vec![problem(
+                ProblemKind::ShadowedGlobal,
+                "cli remote add",
+                "output"
+            )]
+        );
+        assert_eq!(problems[0].severity, Severity::Warning);
+        assert_eq!(
+            problems[0].message,
+            r#"Flag "output" of command "add" shadows the global flag of the same name"#
+        );
+    }
+}
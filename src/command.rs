@@ -1,21 +1,174 @@
-use crate::{Action, Context, Flag, FlagType, Help};
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::action::{AfterActions, Dispatch};
+use crate::app;
+use crate::compiled::Compiled;
+use crate::completion;
+use crate::config::{self, Config};
+use crate::error::{Error, ErrorHandler, ErrorKind};
+use crate::flag::{check_aliases, check_registered};
+use crate::help::HelpForm;
+use crate::messages::{DefaultMessages, Messages};
+use crate::pager::{self, Pager};
+use crate::parser::{FlagMatches, Kind};
+use crate::streams::{self, Streams};
+use crate::terminal;
+use crate::theme::{ColorChoice, Theme};
+use crate::{
+    help, parser, Action, App, AppSettings, Arg, Completer, Context, Flag, FlagGroup, FlagSet,
+    Help, HelpFlag, Prompt, Terminal, UnknownFlagHandler, VersionFlag,
+};
+
+/// Settings of the app applying to every command of a run
+#[derive(Clone, Copy)]
+pub(crate) struct Settings<'a> {
+    /// Flag defaults from the config file
+    pub(crate) config: Option<&'a Config>,
+    /// Prompt asking for missing flags
+    pub(crate) prompt: &'a Prompt,
+    /// Handler of flags matching no flag
+    pub(crate) unknown_flag: Option<&'a UnknownFlagHandler>,
+    /// Match commands by unique prefixes of their names
+    pub(crate) abbreviate_commands: bool,
+    /// Read `/name` and `/name:value` as flags
+    pub(crate) slash_flags: bool,
+    /// Read every argument after the first positional one as positional
+    pub(crate) stop_at_first_positional: bool,
+    /// Leave arguments after `--` out of the argument count
+    pub(crate) exclude_trailing_args: bool,
+    /// After actions of the app and of mounted apps, run after the action
+    /// of every command below them
+    pub(crate) after_actions: AfterActions<'a>,
+    /// Pager showing long help, unless help is printed as is
+    pub(crate) pager: Option<&'a Pager>,
+    /// Theme styling help, plain when `None`
+    pub(crate) theme: Option<&'a Theme>,
+    /// When help is styled
+    pub(crate) color: ColorChoice,
+    /// Flags of the app read by every command
+    pub(crate) global_flags: &'a [Flag],
+    /// App running the command, answering `--version` for commands without
+    /// a version
+    pub(crate) app: Option<&'a App>,
+    /// Write a parse trace to stderr
+    pub(crate) debug: bool,
+    /// Prefix of the environment variables named after flags
+    pub(crate) env_prefix: Option<&'a str>,
+    /// Parts of the app built by `App::compile`
+    pub(crate) compiled: Option<&'a Compiled>,
+    /// Catalog of the messages of errors raised while parsing
+    pub(crate) messages: &'a dyn Messages,
+    /// Arguments after the name of a pass-through command, as given
+    pub(crate) passed: &'a [String],
+    /// Global flags and their values given before the name of a command
+    /// declaring flags of the same name
+    pub(crate) shadowed_globals: &'a [String],
+    /// Terminal seen by actions, detected when the context is made if `None`
+    pub(crate) terminal: Option<Terminal>,
+    /// Writers of help and of diagnostics
+    pub(crate) streams: &'a Streams,
+}
+
+impl<'a> Settings<'a> {
+    /// Settings with the prompt only
+    pub(crate) fn new(prompt: &'a Prompt) -> Self {
+        Self {
+            config: None,
+            prompt,
+            unknown_flag: None,
+            abbreviate_commands: false,
+            slash_flags: false,
+            stop_at_first_positional: false,
+            exclude_trailing_args: false,
+            after_actions: AfterActions::default(),
+            pager: None,
+            theme: None,
+            color: ColorChoice::Auto,
+            global_flags: &[],
+            app: None,
+            debug: false,
+            env_prefix: None,
+            compiled: None,
+            messages: &DefaultMessages,
+            passed: &[],
+            shadowed_globals: &[],
+            terminal: None,
+            streams: &streams::STANDARD,
+        }
+    }
+}
 
 /// Application command type
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct Command {
     /// Command name
     pub name: String,
     /// Command description
     pub description: Option<String>,
+    /// Command description shown by `--help` instead of the description
+    pub long_description: Option<String>,
     /// Command usage
     pub usage: Option<String>,
     /// Command action
     pub action: Option<Action>,
+    /// Action run after the command action, even when it leaves with `Context::exit`
+    pub after_action: Option<Action>,
+    /// After action of the app the command was converted from, run after the
+    /// action of the command or of any subcommand
+    pub app_after_action: Option<Action>,
     /// Action flags
     pub flags: Option<Vec<Flag>>,
+    /// Groups of action flags checked together
+    pub flag_groups: Option<Vec<FlagGroup>>,
+    /// Declared positional arguments
+    pub args: Option<Vec<Arg>>,
     /// Command alias
     pub alias: Option<Vec<String>>,
-    pub commands: Option<Vec<Command>>,
+    /// Aliases matched like the others but left out of help, completions
+    /// and generated documents
+    pub hidden_alias: Option<Vec<String>>,
+    pub commands: Option<Vec<Arc<Command>>>,
+    /// Command category
+    pub category: Option<String>,
+    /// Hide the command from help and generated documents
+    pub hidden: bool,
+    /// Text shown before the help
+    pub before_help: Option<String>,
+    /// Text shown after the help
+    pub after_help: Option<String>,
+    /// Worked examples as description and command line
+    pub examples: Vec<(String, String)>,
+    /// Position of the command in help and generated documents
+    pub display_order: Option<u32>,
+    /// Sort help items alphabetically instead of by declaration order
+    pub sort_help_alphabetically: bool,
+    /// Completer of the command arguments
+    pub args_completer: Option<Completer>,
+    /// Minimum number of arguments
+    pub min_args: Option<usize>,
+    /// Maximum number of arguments
+    pub max_args: Option<usize>,
+    /// Leave arguments after `--` out of the argument count, inherited
+    /// from the parent unless set
+    pub exclude_trailing_args: Option<bool>,
+    /// Read every argument after the first positional argument as
+    /// positional, inherited from the parent unless set
+    pub stop_at_first_positional: Option<bool>,
+    /// Run subcommands named by a unique prefix, inherited from the parent
+    /// unless set
+    pub command_abbreviation: Option<bool>,
+    /// Give the arguments after the command name to the action as they are
+    pub pass_through: bool,
+    /// Read `/name` and `/name:value` as flags, inherited from the parent
+    /// unless set
+    pub slash_flags: Option<bool>,
+    /// Built-in help flag, inherited from the parent unless set
+    pub help_flag: HelpFlag,
+    /// Command version printed by `--version`
+    pub version: Option<String>,
+    /// Built-in version flag, that of the app the command is added to
+    pub version_flag: VersionFlag,
 }
 
 impl Command {
@@ -35,6 +188,25 @@ impl Command {
         }
     }
 
+    /// Create the `completions` command, whose `install <shell> [--force]`
+    /// subcommand installs the completion script of the app running it like
+    /// `App::install_completion`
+    ///
+    /// `--force` overwrites a script installed before. The app runs the
+    /// command itself, so it has no action, and the command shows its help
+    /// when run without an app or below another command.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command};
+    ///
+    /// let app = App::new("cli").command(Command::completions());
+    /// ```
+    pub fn completions() -> Self {
+        completion::install::command()
+    }
+
     /// Set description of the command
     ///
     /// Example
@@ -50,8 +222,31 @@ impl Command {
         self
     }
 
+    /// Set description of the command shown by `--help`, which may span lines
+    ///
+    /// `-h` shows the description set with `description` instead, and
+    /// `--help` falls back to it when no long description is set. Command
+    /// lists show the description.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("build")
+    ///     .description("Build the project")
+    ///     .long_description("Build the project\n\nArtifacts are written to target/.");
+    /// ```
+    pub fn long_description<T: Into<String>>(mut self, description: T) -> Self {
+        self.long_description = Some(description.into());
+        self
+    }
+
     /// Set usage of the command
     ///
+    /// The usage replaces the generated one in help, errors and generated
+    /// documents. Lines of a multi-line usage are kept as written.
+    ///
     /// Example
     ///
     /// ```
@@ -59,328 +254,1639 @@ impl Command {
     ///
     /// let command = Command::new("cmd")
     ///     .usage("cli cmd [arg]");
+    ///
+    /// let command = Command::new("sync")
+    ///     .usage("cli sync --push <REMOTE>\ncli sync --pull <REMOTE>");
     /// ```
     pub fn usage<T: Into<String>>(mut self, usage: T) -> Self {
         self.usage = Some(usage.into());
         self
     }
 
-    /// Set action of the command
+    /// Set action of the command, a closure, a function taking `&Context` or
+    /// an `Action` like `App::action`
     ///
     /// Example
     ///
     /// ```
-    /// use seahorse::{Command, Context, Action};
+    /// use seahorse::{Action, Command, Context};
     ///
-    /// let action: Action = |c: &Context| println!("{:?}", c.args);
-    /// let command = Command::new("cmd")
-    ///     .action(action);
+    /// fn list(c: &Context) {
+    ///     println!("{:?}", c.args);
+    /// }
+    ///
+    /// let command = Command::new("list").action(list);
+    /// let command = Command::new("cmd").action(|c: &Context| println!("{:?}", c.args));
+    /// let action = Action::from(list);
+    /// let command = Command::new("ls").action(action.clone());
     /// ```
     pub fn action(mut self, action: impl Into<Action>) -> Self {
         self.action = Some(action.into());
         self
     }
 
-    /// Set flag of the command
+    /// Set action run with the context of the command action after it,
+    /// even when it leaves with `Context::exit`
     ///
     /// Example
     ///
     /// ```
-    /// use seahorse::{Command, Flag, FlagType};
+    /// use seahorse::{Command, Context};
     ///
-    /// let command = Command::new("cmd")
-    ///     .flag(Flag::new("bool", FlagType::Bool))
-    ///     .flag(Flag::new("int", FlagType::Int));
+    /// let command = Command::new("deploy")
+    ///     .action(|c: &Context| c.exit(3))
+    ///     .after_action(|c: &Context| println!("releasing lock, status {:?}", c.exit_code()));
     /// ```
-    pub fn flag(mut self, flag: Flag) -> Self {
-        if let Some(ref mut flags) = self.flags {
-            (*flags).push(flag);
-        } else {
-            self.flags = Some(vec![flag]);
-        }
+    pub fn after_action(mut self, action: impl Into<Action>) -> Self {
+        self.after_action = Some(action.into());
         self
     }
 
-    /// Set alias of the command
+    /// Set completer of the command arguments
+    ///
+    /// The completer is called by the generated completion scripts.
     ///
     /// Example
     ///
     /// ```
-    /// use seahorse::Command;
+    /// use seahorse::{Command, Context};
     ///
-    /// let command = Command::new("cmd")
-    ///     .alias("c");
+    /// let command = Command::new("deploy")
+    ///     .complete_args_with(|_: &Context, _: &str| {
+    ///         vec!["staging".to_string(), "production".to_string()]
+    ///     });
     /// ```
-    pub fn alias<T: Into<String>>(mut self, name: T) -> Self {
-        if let Some(ref mut alias) = self.alias {
-            (*alias).push(name.into());
-        } else {
-            self.alias = Some(vec![name.into()]);
-        }
+    pub fn complete_args_with(mut self, completer: impl Into<Completer>) -> Self {
+        self.args_completer = Some(completer.into());
         self
     }
 
-    /// Set sub command of the command
+    /// Declare positional argument of the command
+    ///
+    /// Arguments are declared in command line order, and their values
+    /// are checked against the type before the action runs.
     ///
     /// Example
     ///
     /// ```
-    /// use seahorse::{App, Command};
-    ///
-    /// let sub_command = Command::new("world")
-    ///     .usage("cli hello world")
-    ///     .action(|_| println!("Hello world!"));
-    ///
-    /// let command = Command::new("hello")
-    ///     .usage("cli hello [arg]")
-    ///     .action(|c| println!("{:?}", c.args))
-    ///     .command(sub_command);
+    /// use seahorse::{Arg, Command, Context, FlagType};
     ///
-    /// let app = App::new("cli")
-    ///     .command(command);
+    /// let command = Command::new("serve")
+    ///     .arg(Arg::new("PORT", FlagType::Uint))
+    ///     .action(|c: &Context| println!("{:?}", c.uint_arg("PORT")));
     /// ```
     ///
     /// # Panics
     ///
-    /// You cannot set a command named as same as registered ones.
+    /// Arguments with a default value cannot precede a required argument,
+    /// and no argument can follow a variadic one.
     ///
     /// ```should_panic
-    /// use seahorse::{App, Command};
-    ///
-    /// let sub_command1 = Command::new("world")
-    ///     .usage("cli hello world")
-    ///     .action(|_| println!("Hello world!"));
-    ///
-    /// let sub_command2 = Command::new("world")
-    ///     .usage("cli hello world")
-    ///     .action(|_| println!("Hello world!"));
-    ///
-    /// let command = Command::new("hello")
-    ///     .usage("cli hello [arg]")
-    ///     .action(|c| println!("{:?}", c.args))
-    ///     .command(sub_command1)
-    ///     .command(sub_command2);
+    /// use seahorse::{Arg, Command, FlagType};
     ///
-    /// let app = App::new("cli")
-    ///     .command(command);
+    /// let command = Command::new("serve")
+    ///     .arg(Arg::new("DIR", FlagType::String).default_value("."))
+    ///     .arg(Arg::new("PORT", FlagType::Uint).required());
     /// ```
-    pub fn command(mut self, command: Command) -> Self {
-        if let Some(ref mut commands) = self.commands {
-            if commands
+    pub fn arg(mut self, arg: Arg) -> Self {
+        if let Some(variadic) = self
+            .args
+            .iter()
+            .flatten()
+            .find(|declared| declared.variadic)
+        {
+            panic!(
+                r#"Argument "{}" cannot follow the variadic argument "{}"."#,
+                arg.name, variadic.name
+            );
+        }
+        if arg.required {
+            if let Some(defaulted) = self
+                .args
                 .iter()
-                .any(|registered| registered.name == command.name)
+                .flatten()
+                .find(|declared| declared.default_value.is_some())
             {
-                panic!(r#"Command name "{}" is already registered."#, command.name);
+                panic!(
+                    r#"Argument "{}" with a default value cannot precede the required argument "{}"."#,
+                    defaulted.name, arg.name
+                );
             }
-            (*commands).push(command);
+        }
+        if let Some(ref mut args) = self.args {
+            (*args).push(arg);
         } else {
-            self.commands = Some(vec![command]);
+            self.args = Some(vec![arg]);
         }
         self
     }
 
-    fn select_command(&self, cmd: &str) -> Option<&Command> {
-        match &self.commands {
-            Some(commands) => commands.iter().find(|command| match &command.alias {
-                Some(alias) => command.name == cmd || alias.iter().any(|a| a == cmd),
-                None => command.name == cmd,
-            }),
-            None => None,
-        }
+    /// Set minimum number of arguments
+    ///
+    /// Flags and their values do not count as arguments, arguments after `--` do.
+    /// Running the command with fewer arguments prints an error and the usage.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("greet")
+    ///     .min_args(1);
+    /// ```
+    pub fn min_args(mut self, min: usize) -> Self {
+        self.min_args = Some(min);
+        self
     }
 
-    fn normalized_args(raw_args: Vec<String>) -> Vec<String> {
-        raw_args.iter().fold(Vec::<String>::new(), |mut acc, cur| {
-            if cur.starts_with('-') && cur.contains('=') {
-                let mut splitted_flag: Vec<String> =
-                    cur.splitn(2, '=').map(|s| s.to_owned()).collect();
-                acc.append(&mut splitted_flag);
-            } else {
-                acc.push(cur.to_owned());
-            }
-            acc
-        })
+    /// Set maximum number of arguments
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("greet")
+    ///     .max_args(2);
+    /// ```
+    pub fn max_args(mut self, max: usize) -> Self {
+        self.max_args = Some(max);
+        self
     }
 
-    /// Run command
-    /// Call this function only from `App`
-    pub fn run(&self, args: Vec<String>) {
-        let args = Self::normalized_args(args);
-
-        match args.split_first() {
-            Some((cmd, args_v)) => match self.select_command(cmd) {
-                Some(command) => command.run(args_v.to_vec()),
-                None => match &self.action {
-                    Some(action) => {
-                        if args.contains(&"-h".to_string()) || args.contains(&"--help".to_string())
-                        {
-                            self.help();
-                            return;
-                        }
-                        action.run(&Context::new(
-                            args.to_vec(),
-                            self.flags.clone(),
-                            self.help_text(),
-                        ));
-                    }
-                    None => self.help(),
-                },
-            },
-            None => match &self.action {
-                Some(action) => {
-                    if args.contains(&"-h".to_string()) || args.contains(&"--help".to_string()) {
-                        self.help();
-                        return;
-                    }
-                    action.run(&Context::new(
-                        args.to_vec(),
-                        self.flags.clone(),
-                        self.help_text(),
-                    ));
-                }
-                None => self.help(),
-            },
-        }
+    /// Leave arguments after `--` out of the `min_args` and `max_args` count
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("exec")
+    ///     .max_args(1)
+    ///     .exclude_trailing_args();
+    /// ```
+    pub fn exclude_trailing_args(self) -> Self {
+        self.setting(AppSettings::ExcludeTrailingArgs)
     }
 
-    fn flag_help_text(&self) -> String {
-        let mut text = String::new();
-        text += "Flags:\n";
-        let help_flag = "-h, --help";
-
-        if let Some(flags) = &self.flags {
-            let int_val = "<int>";
-            let float_val = "<float>";
-            let string_val = "<string>";
-
-            let flag_helps = &flags.iter().map(|f| {
-                let alias = match &f.alias {
-                    Some(alias) => alias
-                        .iter()
-                        .map(|a| format!("-{}", a))
-                        .collect::<Vec<String>>()
-                        .join(", "),
-                    None => String::new(),
-                };
-                let val = match f.flag_type {
-                    FlagType::Int => int_val,
-                    FlagType::Float => float_val,
-                    FlagType::String => string_val,
-                    _ => "",
-                };
-
-                let help = if alias.is_empty() {
-                    format!("--{} {}", f.name, val)
-                } else {
-                    format!("{}, --{} {}", alias, f.name, val)
-                };
-
-                (help, f.description.clone())
-            });
-
-            let flag_name_max_len = flag_helps
-                .clone()
-                .map(|h| h.0.len())
-                .chain(vec![help_flag.len()].into_iter())
-                .max()
-                .unwrap();
+    /// Read every argument after the first positional argument as positional
+    ///
+    /// Flags are otherwise read wherever they appear among positionals, so
+    /// `cp a --force b` is `cp --force a b`. With this set, `run echo --force`
+    /// gives `echo` and `--force` to the action as arguments.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("run")
+    ///     .stop_at_first_positional();
+    /// ```
+    pub fn stop_at_first_positional(self) -> Self {
+        self.setting(AppSettings::StopAtFirstPositional)
+    }
 
-            for flag_help in flag_helps.clone() {
-                text += &format!("\t{}", flag_help.0);
+    /// Give every argument after the command name to the action as it is,
+    /// in `Context::args`
+    ///
+    /// The arguments are not read as flags, `--` is kept among them, and
+    /// none is read as help, version or subcommand, so `exec ls --help`
+    /// gives `ls` and `--help` to the action. Global flags given before the
+    /// command name are still read.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::new("verbose", FlagType::Bool).global())
+    ///     .command(
+    ///         Command::new("exec")
+    ///             .pass_through()
+    ///             .action(|c: &Context| {
+    ///                 assert!(c.bool_flag("verbose"));
+    ///                 assert_eq!(c.args, ["ls", "-la", "--verbose"]);
+    ///             }),
+    ///     );
+    /// let args = ["cli", "--verbose", "exec", "ls", "-la", "--verbose"];
+    /// app.run(args.iter().map(|a| a.to_string()).collect());
+    /// ```
+    pub fn pass_through(mut self) -> Self {
+        self.pass_through = true;
+        self
+    }
 
-                if let Some(usage) = &flag_help.1 {
-                    let flag_name_len = flag_help.0.len();
-                    text += &format!(
-                        "{} : {}\n",
-                        " ".repeat(flag_name_max_len - flag_name_len),
-                        usage
-                    );
-                } else {
-                    text += "\n";
-                }
-            }
+    /// Turn on the parser behavior for the command and, for inherited
+    /// settings, its subcommands unless they unset it
+    ///
+    /// Panics for settings of the app only, see [`AppSettings`].
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{AppSettings, Command};
+    ///
+    /// let command = Command::new("exec").setting(AppSettings::StopAtFirstPositional);
+    /// ```
+    pub fn setting(self, setting: AppSettings) -> Self {
+        self.set(setting, true)
+    }
 
-            text += &format!(
-                "\t{}{} : Show help\n",
-                help_flag,
-                " ".repeat(flag_name_max_len - help_flag.len())
+    /// Turn off the inherited parser behavior for the command and its
+    /// subcommands unless they set it again
+    ///
+    /// Panics for settings commands don't inherit, see [`AppSettings`].
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, AppSettings, Command};
+    ///
+    /// let app = App::new("cli")
+    ///     .setting(AppSettings::AllowCommandAbbreviation)
+    ///     .command(
+    ///         Command::new("remote")
+    ///             .unset_setting(AppSettings::AllowCommandAbbreviation)
+    ///             .command(Command::new("remove")),
+    ///     );
+    /// ```
+    pub fn unset_setting(self, setting: AppSettings) -> Self {
+        if !setting.is_inherited() {
+            panic!(
+                "{:?} is not inherited by commands and cannot be unset",
+                setting
             );
-        } else {
-            text += &format!("\t{} : Show help\n", help_flag);
         }
-
-        text
+        self.set(setting, false)
     }
 
-    fn command_help_text(&self) -> String {
-        let mut text = String::new();
-
-        if let Some(commands) = &self.commands {
-            text += "\nCommands:\n";
-
-            let name_max_len = &commands
-                .iter()
-                .map(|c| {
-                    if let Some(alias) = &c.alias {
-                        format!("{}, {}", alias.join(", "), c.name).len()
-                    } else {
-                        c.name.len()
-                    }
-                })
-                .max()
-                .unwrap();
-
-            for c in commands {
-                let command_name = if let Some(alias) = &c.alias {
-                    format!("{}, {}", alias.join(", "), c.name)
-                } else {
-                    c.name.clone()
-                };
-
-                let description = match &c.description {
-                    Some(description) => description,
-                    None => "",
-                };
-
-                text += &format!(
-                    "\t{} {}: {}\n",
-                    command_name,
-                    " ".repeat(name_max_len - command_name.len()),
-                    description
-                );
+    fn set(mut self, setting: AppSettings, enabled: bool) -> Self {
+        match setting {
+            AppSettings::AllowCommandAbbreviation => self.command_abbreviation = Some(enabled),
+            AppSettings::AllowSlashFlags => self.slash_flags = Some(enabled),
+            AppSettings::StopAtFirstPositional => self.stop_at_first_positional = Some(enabled),
+            AppSettings::ExcludeTrailingArgs => self.exclude_trailing_args = Some(enabled),
+            AppSettings::SortHelpAlphabetically => self.sort_help_alphabetically = enabled,
+            AppSettings::AllowArgfile | AppSettings::TerseErrors => {
+                panic!("{:?} is a setting of the app only", setting)
             }
         }
-
-        text
+        self
     }
-}
-
-impl Help for Command {
-    fn help_text(&self) -> String {
-        let mut text = String::new();
 
-        if let Some(description) = &self.description {
-            text += &format!("Description:\n\t{}\n\n", description);
+    /// Set flag of the command
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Command, Flag, FlagType};
+    ///
+    /// let command = Command::new("cmd")
+    ///     .flag(Flag::new("bool", FlagType::Bool))
+    ///     .flag(Flag::new("int", FlagType::Int));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Aliases of the flag cannot be the name or an alias of another flag.
+    ///
+    /// ```should_panic
+    /// use seahorse::{Command, Flag, FlagType};
+    ///
+    /// let command = Command::new("cmd")
+    ///     .flag(Flag::new("force", FlagType::Bool).alias("f"))
+    ///     .flag(Flag::new("fix", FlagType::Bool).alias("f"));
+    /// ```
+    pub fn flag(self, flag: Flag) -> Self {
+        match self.try_flag(flag) {
+            Ok(command) => command,
+            Err(e) => panic!("{}", e),
         }
+    }
 
-        if let Some(usage) = &self.usage {
-            text += &format!("Usage:\n\t{}\n\n", usage);
+    /// Set flag of the command like `flag`, returning why the flag conflicts
+    /// with another flag instead of panicking
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Command, Flag, FlagType};
+    ///
+    /// let command = Command::new("cmd").flag(Flag::new("force", FlagType::Bool).alias("f"));
+    /// assert_eq!(
+    ///     command
+    ///         .try_flag(Flag::new("fix", FlagType::Bool).alias("f"))
+    ///         .err(),
+    ///     Some(r#"Alias "f" of flag "fix" is also used by flag "force"."#.to_string())
+    /// );
+    /// ```
+    pub fn try_flag(mut self, flag: Flag) -> Result<Self, String> {
+        if self.help_flag != HelpFlag::Inherit {
+            self.help_flag.try_check(Some(&flag))?;
         }
+        check_registered(&flag, self.flags.iter().flatten())?;
+        check_aliases(&flag, self.flags.iter().flatten())?;
+        if let Some(ref mut flags) = self.flags {
+            (*flags).push(flag);
+        } else {
+            self.flags = Some(vec![flag]);
+        }
+        Ok(self)
+    }
 
-        text += &self.flag_help_text();
-        text += &self.command_help_text();
-
-        text
+    /// Set flags of the command in order, like `flag` for each
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Command, Flag, FlagType};
+    ///
+    /// let command = Command::new("list").with_flags(vec![
+    ///     Flag::new("format", FlagType::String),
+    ///     Flag::new("no-header", FlagType::Bool),
+    /// ]);
+    /// ```
+    pub fn with_flags(self, flags: impl IntoIterator<Item = Flag>) -> Self {
+        flags
+            .into_iter()
+            .fold(self, |command, flag| command.flag(flag))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::{Action, Command, Context, Flag, FlagType};
+    /// Set a copy of the flags of the set, like `flag` for each, listed in
+    /// help in a section of their own when the set is a section
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Command, Flag, FlagSet, FlagType};
+    ///
+    /// let output = FlagSet::new("Output").flag(Flag::new("format", FlagType::String));
+    /// let command = Command::new("list").flag_set(&output);
+    /// ```
+    pub fn flag_set(self, set: &FlagSet) -> Self {
+        self.with_flags(set.to_flags())
+    }
 
-    #[test]
-    fn command_test() {
-        let a: Action = Action::from(|c: &Context| println!("Hello, {:?}", c.args));
+    /// Set flag group of the command
+    ///
+    /// Groups are checked after parsing, before the action runs.
+    /// A flag can belong to several groups.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Command, Flag, FlagGroup, FlagType};
+    ///
+    /// let command = Command::new("fetch")
+    ///     .flag(Flag::new("file", FlagType::String))
+    ///     .flag(Flag::new("url", FlagType::String))
+    ///     .flag(Flag::new("stdin", FlagType::Bool))
+    ///     .flag_group(
+    ///         FlagGroup::new("input")
+    ///             .member("file")
+    ///             .member("url")
+    ///             .member("stdin")
+    ///             .required(),
+    ///     );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Members of the group must be registered flags, and the default
+    /// member must be a bool member.
+    ///
+    /// ```should_panic
+    /// use seahorse::{Command, Flag, FlagGroup, FlagType};
+    ///
+    /// let command = Command::new("fetch")
+    ///     .flag(Flag::new("file", FlagType::String))
+    ///     .flag_group(FlagGroup::new("input").member("file").member("url"));
+    /// ```
+    pub fn flag_group(mut self, group: FlagGroup) -> Self {
+        group.check_members(&self.flags);
+
+        if let Some(ref mut flag_groups) = self.flag_groups {
+            (*flag_groups).push(group);
+        } else {
+            self.flag_groups = Some(vec![group]);
+        }
+        self
+    }
+
+    /// Set help flag of the command and its sub commands
+    ///
+    /// The short form is left out when `short` is `None`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Command, Flag, FlagType};
+    ///
+    /// let command = Command::new("serve")
+    ///     .help_flag("help", None)
+    ///     .flag(Flag::new("host", FlagType::String).alias("h"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// The help flag cannot be named as a registered flag or its alias.
+    ///
+    /// ```should_panic
+    /// use seahorse::{Command, Flag, FlagType};
+    ///
+    /// let command = Command::new("serve")
+    ///     .flag(Flag::new("assist", FlagType::Bool))
+    ///     .help_flag("assist", Some('?'));
+    /// ```
+    pub fn help_flag<T: Into<String>>(mut self, name: T, short: Option<char>) -> Self {
+        self.help_flag = HelpFlag::Enabled {
+            name: name.into(),
+            short,
+        };
+        self.check_help_flag(&HelpFlag::Inherit);
+        self
+    }
+
+    /// Disable built-in help flag of the command and its sub commands
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Command, Flag, FlagType};
+    ///
+    /// let command = Command::new("serve")
+    ///     .disable_help_flag()
+    ///     .flag(Flag::new("help", FlagType::Bool));
+    /// ```
+    pub fn disable_help_flag(mut self) -> Self {
+        self.help_flag = HelpFlag::Disabled;
+        self
+    }
+
+    /// Panic when a flag of the command or its sub commands conflicts
+    /// with the help flag applying to it
+    pub(crate) fn check_help_flag(&self, parent: &HelpFlag) {
+        let help_flag = self.help_flag.or(parent);
+        help_flag.check(self.flags.iter().flatten());
+        for command in self.commands.iter().flatten() {
+            command.check_help_flag(help_flag);
+        }
+    }
+
+    /// Panic when a flag of the command or its sub commands conflicts with the
+    /// version flag, for those with a version or under an app with one
+    pub(crate) fn check_version_flag(&self, app_version: bool) {
+        if app_version || self.version.is_some() {
+            self.version_flag.check(self.flags.iter().flatten());
+        }
+        for command in self.commands.iter().flatten() {
+            command.check_version_flag(app_version);
+        }
+    }
+
+    /// Error for a flag of the command or its sub commands sharing an alias with
+    /// the global flag of the app
+    pub(crate) fn check_global_aliases(&self, global: &Flag) -> Result<(), String> {
+        check_aliases(global, self.flags.iter().flatten())?;
+        for command in self.commands.iter().flatten() {
+            command.check_global_aliases(global)?;
+        }
+        Ok(())
+    }
+
+    /// Set version flag of the command and its sub commands to that of the app
+    pub(crate) fn set_version_flag(&mut self, version_flag: &VersionFlag) {
+        self.version_flag = version_flag.clone();
+        for command in self.commands.iter_mut().flatten() {
+            set_shared_version_flag(command, version_flag);
+        }
+    }
+
+    /// Whether the command and its sub commands have the version flag
+    fn has_version_flag(&self, version_flag: &VersionFlag) -> bool {
+        self.version_flag == *version_flag
+            && self
+                .commands
+                .iter()
+                .flatten()
+                .all(|command| command.has_version_flag(version_flag))
+    }
+
+    /// Set standard verbosity flags of the command
+    ///
+    /// Adds a repeatable `--verbose`/`-v` flag and a `--quiet`/`-q` flag,
+    /// read back with `Context::log_level`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Command, Context};
+    ///
+    /// let command = Command::new("cmd")
+    ///     .verbosity_flags()
+    ///     .action(|c: &Context| log::set_max_level(c.log_level()));
+    /// ```
+    #[cfg(feature = "log")]
+    pub fn verbosity_flags(self) -> Self {
+        Flag::verbosity()
+            .into_iter()
+            .fold(self, |command, flag| command.flag(flag))
+    }
+
+    /// Set alias of the command
+    ///
+    /// Aliases are listed after the name in the commands of help, like
+    /// `build (alias: b)`, and under their own heading in the help of the
+    /// command.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("cmd")
+    ///     .alias("c");
+    /// ```
+    pub fn alias<T: Into<String>>(mut self, name: T) -> Self {
+        if let Some(ref mut alias) = self.alias {
+            (*alias).push(name.into());
+        } else {
+            self.alias = Some(vec![name.into()]);
+        }
+        self
+    }
+
+    /// Set alias of the command run like the others but left out of help,
+    /// completions, generated documents and suggestions, for old names of
+    /// renamed commands
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("remove").alias("rm").hidden_alias("delete");
+    /// assert_eq!(command.aliases(), ["rm".to_string()]);
+    /// assert_eq!(command.hidden_aliases(), ["delete".to_string()]);
+    /// ```
+    pub fn hidden_alias<T: Into<String>>(mut self, name: T) -> Self {
+        self.hidden_alias
+            .get_or_insert_with(Vec::new)
+            .push(name.into());
+        self
+    }
+
+    /// Set category of the command
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("cmd")
+    ///     .category("Utilities");
+    /// ```
+    pub fn category<T: Into<String>>(mut self, category: T) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Hide the command from help and generated documents
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("cmd")
+    ///     .hidden();
+    /// ```
+    pub fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    /// Set text shown before the help of the command
+    ///
+    /// The text is rendered verbatim above the generated help.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("cmd")
+    ///     .before_help("cli - a great tool");
+    /// ```
+    pub fn before_help<T: Into<String>>(mut self, text: T) -> Self {
+        self.before_help = Some(text.into());
+        self
+    }
+
+    /// Set text shown after the help of the command
+    ///
+    /// The text is rendered verbatim below the generated help.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("cmd")
+    ///     .after_help("See https://example.com/docs for more");
+    /// ```
+    pub fn after_help<T: Into<String>>(mut self, text: T) -> Self {
+        self.after_help = Some(text.into());
+        self
+    }
+
+    /// Add worked example of the command, shown by `--help` and in generated
+    /// documents as the description with the command line beneath it
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("cp")
+    ///     .example("Copy a file", "cli cp src.txt dest.txt")
+    ///     .example("Copy into a directory", "cli cp src.txt backup/");
+    /// ```
+    pub fn example<D: Into<String>, L: Into<String>>(
+        mut self,
+        description: D,
+        command_line: L,
+    ) -> Self {
+        self.examples
+            .push((description.into(), command_line.into()));
+        self
+    }
+
+    /// Set version of the command, printed with the command path like
+    /// `cli plugin 1.2.0` by `--version` instead of the app version
+    /// and `-V` and shown in help
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("fmt")
+    ///     .version("1.2.0");
+    /// ```
+    pub fn version<T: Into<String>>(mut self, version: T) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Set display order of the command
+    ///
+    /// Commands are listed in ascending order, commands without one come after
+    /// those with a lower order. Ties keep the declaration order.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("cmd")
+    ///     .display_order(1);
+    /// ```
+    pub fn display_order(mut self, order: u32) -> Self {
+        self.display_order = Some(order);
+        self
+    }
+
+    /// Set sub command of the command
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context};
+    ///
+    /// let sub_command = Command::new("world")
+    ///     .usage("cli hello world")
+    ///     .action(|_: &Context| println!("Hello world!"));
+    ///
+    /// let command = Command::new("hello")
+    ///     .usage("cli hello [arg]")
+    ///     .action(|c: &Context| println!("{:?}", c.args))
+    ///     .command(sub_command);
+    ///
+    /// let app = App::new("cli")
+    ///     .command(command);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// You cannot set a command named as same as registered ones.
+    ///
+    /// ```should_panic
+    /// use seahorse::{App, Command, Context};
+    ///
+    /// let sub_command1 = Command::new("world")
+    ///     .usage("cli hello world")
+    ///     .action(|_: &Context| println!("Hello world!"));
+    ///
+    /// let sub_command2 = Command::new("world")
+    ///     .usage("cli hello world")
+    ///     .action(|_: &Context| println!("Hello world!"));
+    ///
+    /// let command = Command::new("hello")
+    ///     .usage("cli hello [arg]")
+    ///     .action(|c: &Context| println!("{:?}", c.args))
+    ///     .command(sub_command1)
+    ///     .command(sub_command2);
+    ///
+    /// let app = App::new("cli")
+    ///     .command(command);
+    /// ```
+    pub fn command(self, command: Command) -> Self {
+        self.command_arc(Arc::new(command))
+    }
+
+    /// Set sub command shared with other commands or apps, made with
+    /// `Command::shared`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Command, Context};
+    ///
+    /// let list = Command::new("list")
+    ///     .action(|_: &Context| println!("listing"))
+    ///     .shared();
+    ///
+    /// let remote = Command::new("remote").command_arc(list.clone());
+    /// let branch = Command::new("branch").command_arc(list);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// You cannot set a command named as same as registered ones.
+    pub fn command_arc(mut self, command: Arc<Command>) -> Self {
+        if self.help_flag != HelpFlag::Inherit {
+            command.check_help_flag(&self.help_flag);
+        }
+        if let Some(ref mut commands) = self.commands {
+            if let Err(e) = check_command(&command, commands.iter().map(|c| &**c)) {
+                panic!("{}", e);
+            }
+            (*commands).push(command);
+        } else {
+            self.commands = Some(vec![command]);
+        }
+        self
+    }
+
+    /// Share the command, to set it in several apps or commands with
+    /// `App::command_arc` or `Command::command_arc` without copying it
+    ///
+    /// The shared command cannot be changed by its builders any more, so it
+    /// stays the same wherever it is set.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use seahorse::{App, Command, Context};
+    ///
+    /// let status = Command::new("status")
+    ///     .action(|_: &Context| println!("clean"))
+    ///     .shared();
+    ///
+    /// let full = App::new("cli").command_arc(Arc::clone(&status));
+    /// let shell = App::new("shell").command_arc(status);
+    /// assert!(Arc::ptr_eq(&full.commands()[0], &shell.commands()[0]));
+    /// ```
+    pub fn shared(self) -> Arc<Command> {
+        Arc::new(self)
+    }
+
+    /// Sort help items of the command and its sub commands alphabetically
+    pub(crate) fn set_sort_help_alphabetically(&mut self) {
+        self.sort_help_alphabetically = true;
+        for command in self.commands.iter_mut().flatten() {
+            sort_shared_help_alphabetically(command);
+        }
+    }
+
+    /// Whether help items of the command and its sub commands are sorted
+    /// alphabetically
+    fn sorts_help_alphabetically(&self) -> bool {
+        self.sort_help_alphabetically
+            && self
+                .commands
+                .iter()
+                .flatten()
+                .all(|command| command.sorts_help_alphabetically())
+    }
+
+    /// Name of the command
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Aliases of the command, in registration order
+    pub fn aliases(&self) -> &[String] {
+        self.alias.as_ref().map_or(&[], Vec::as_slice)
+    }
+
+    /// Hidden aliases of the command, set with `hidden_alias`
+    pub fn hidden_aliases(&self) -> &[String] {
+        self.hidden_alias.as_ref().map_or(&[], Vec::as_slice)
+    }
+
+    /// Aliases the command is run by, hidden ones included
+    pub(crate) fn all_aliases(&self) -> impl Iterator<Item = &String> {
+        self.alias
+            .iter()
+            .flatten()
+            .chain(self.hidden_alias.iter().flatten())
+    }
+
+    /// Description of the command, if set
+    pub fn get_description(&self) -> Option<&str> {
+        self.description.as_ref().map(String::as_str)
+    }
+
+    /// Category the command is listed under in help, if set
+    pub fn get_category(&self) -> Option<&str> {
+        self.category.as_ref().map(String::as_str)
+    }
+
+    /// Whether the command is hidden from help and generated documents
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Flags of the command, in registration order
+    pub fn flags(&self) -> &[Flag] {
+        self.flags.as_ref().map_or(&[], Vec::as_slice)
+    }
+
+    /// Declared positional arguments of the command, in order
+    pub fn args(&self) -> &[Arg] {
+        self.args.as_ref().map_or(&[], Vec::as_slice)
+    }
+
+    /// Sub commands of the command, in registration order
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let remote = Command::new("remote").command(Command::new("add").alias("a"));
+    /// let add = &remote.subcommands()[0];
+    /// assert_eq!(add.name(), "add");
+    /// assert_eq!(add.aliases(), ["a".to_string()]);
+    /// ```
+    pub fn subcommands(&self) -> &[Arc<Command>] {
+        self.commands.as_ref().map_or(&[], Vec::as_slice)
+    }
+
+    /// Render help of the command as `bin name --help` prints it, without
+    /// styles or pager
+    ///
+    /// The text suits snapshot tests of the help. Commands below other
+    /// commands are rendered with the binary name followed by the names of
+    /// their parents, like `cli remote`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("status").usage("cli status");
+    /// assert_eq!(
+    ///     command.render_help("cli"),
+    ///     "Usage:\n\tcli status\n\nFlags:\n\t--help, -h : Show help\n\n"
+    /// );
+    /// ```
+    pub fn render_help(&self, bin: &str) -> String {
+        let mut path: Vec<String> = bin.split_whitespace().map(String::from).collect();
+        path.push(self.name.clone());
+        help::render(&self.help_text_at(&path, &self.help_flag, &[], None, &DefaultMessages))
+    }
+
+    /// Run command
+    /// Call this function only from `App`
+    pub fn run(&self, args: Vec<String>) {
+        let result = self
+            .run_with_path(
+                &[],
+                args,
+                0,
+                &HelpFlag::Inherit,
+                &Settings::new(&Prompt::stdio()),
+            )
+            .map(Dispatch::run);
+        let code = match result {
+            Ok(outcome) => outcome.exit_code(),
+            Err(e) => {
+                ErrorHandler::default().handle(&e, &mut std::io::stderr());
+                1
+            }
+        };
+        app::exit_process(code);
+    }
+
+    /// Settings of the app with those the command sets itself and the after
+    /// action of a mounted app
+    fn settings<'a>(&'a self, settings: &'a Settings<'a>) -> Settings<'a> {
+        Settings {
+            after_actions: match &self.app_after_action {
+                Some(action) => settings.after_actions.inner(action),
+                None => settings.after_actions,
+            },
+            abbreviate_commands: self
+                .command_abbreviation
+                .unwrap_or(settings.abbreviate_commands),
+            slash_flags: self.slash_flags.unwrap_or(settings.slash_flags),
+            stop_at_first_positional: self
+                .stop_at_first_positional
+                .unwrap_or(settings.stop_at_first_positional),
+            exclude_trailing_args: self
+                .exclude_trailing_args
+                .unwrap_or(settings.exclude_trailing_args),
+            ..*settings
+        }
+    }
+
+    /// Run command under the path of its parent commands, with the
+    /// arguments of the command starting at `start`,
+    /// the help flag of the parent and the settings of the app, returning the
+    /// status requested with `Context::exit` or failing before the action runs
+    /// on errors of the command line
+    pub(crate) fn run_with_path(
+        &self,
+        parent_path: &[String],
+        args: Vec<String>,
+        start: usize,
+        parent_help_flag: &HelpFlag,
+        settings: &Settings,
+    ) -> Result<Dispatch, Error> {
+        let path = self.path(parent_path);
+        self.run_at(&path, args, start, parent_help_flag, settings)
+    }
+
+    /// Run the command under the path, which ends with the name the command
+    /// is shown with
+    pub(crate) fn run_at(
+        &self,
+        path: &[String],
+        args: Vec<String>,
+        start: usize,
+        parent_help_flag: &HelpFlag,
+        settings: &Settings,
+    ) -> Result<Dispatch, Error> {
+        let settings = &self.settings(settings);
+        let help_flag = self.help_flag.or(parent_help_flag);
+
+        let command = match args.get(start) {
+            Some(_) if self.pass_through => None,
+            Some(cmd) => parser::select_command(&self.commands, cmd, settings.abbreviate_commands)
+                .map_err(|e| e.within(path, self.usage_text(path), help_flag))?,
+            None => None,
+        };
+        match command {
+            Some(command) => command.run_with_path(path, args, start + 1, help_flag, settings),
+            None => self.run_action(path, args, start, help_flag, settings),
+        }
+    }
+
+    fn run_action(
+        &self,
+        path: &[String],
+        mut args: Vec<String>,
+        start: usize,
+        help_flag: &HelpFlag,
+        settings: &Settings,
+    ) -> Result<Dispatch, Error> {
+        let version = self.version_text(path, &args[start..], settings);
+        if let Some(version) = version {
+            settings.streams.out(&format!("{}\n", version));
+            return Ok(Dispatch::Version);
+        }
+
+        let form = help_flag.form(&args[start..]);
+        let within = |e: Error| e.within(path, self.usage_text(path), help_flag);
+
+        // The ready-made `completions install` command has no action, the app
+        // running it installing the completion script instead
+        let install = match settings.app {
+            Some(app) if self.action.is_none() && completion::install::mounted(path) => Some(app),
+            _ => None,
+        };
+        match &self.action {
+            action if action.is_some() || install.is_some() => {
+                if let Some(form) = form {
+                    let help_text = self.help_text_in(
+                        path,
+                        help_flag,
+                        settings.global_flags,
+                        form,
+                        settings.env_prefix,
+                        settings.messages,
+                    );
+                    pager::show(
+                        settings.pager,
+                        settings.theme,
+                        settings.color,
+                        settings.messages,
+                        settings.streams,
+                        &help_text,
+                    );
+                    return Ok(Dispatch::Help(path.to_vec()));
+                }
+                let config = match settings.app {
+                    Some(app) => app.load_config().map_err(within)?,
+                    None => None,
+                };
+                let settings = &Settings {
+                    config: config.as_ref().or(settings.config),
+                    ..*settings
+                };
+                let compiled = settings.compiled;
+                let help_text = match compiled.and_then(|c| c.help_text(path)) {
+                    Some(help_text) => help_text,
+                    None => self
+                        .help_text_at(
+                            path,
+                            help_flag,
+                            settings.global_flags,
+                            settings.env_prefix,
+                            settings.messages,
+                        )
+                        .into(),
+                };
+                let flags = match compiled.and_then(|c| c.flags(path)) {
+                    Some(flags) if settings.config.is_none() => Cow::Borrowed(flags),
+                    _ => {
+                        let flags =
+                            config::apply(settings.config, path, &self.flags).map_err(within)?;
+                        with_globals(flags, settings.global_flags)
+                    }
+                };
+                args.drain(..start);
+                if self.pass_through {
+                    // After `--`, so that only global flags before the
+                    // command name are read
+                    args.push("--".to_string());
+                    args.extend(settings.passed.iter().cloned());
+                }
+                let context = self
+                    .context(path, args, &flags, help_text, settings)
+                    .map_err(within)?
+                    .with_globals(
+                        self.flags(),
+                        settings.global_flags,
+                        settings.shadowed_globals,
+                    );
+                if let Some(app) = install {
+                    return completion::install::run(app, &context).map_err(within);
+                }
+                let actions = [action.as_ref(), self.after_action.as_ref()];
+                Ok(Dispatch::actions(
+                    &actions,
+                    &settings.after_actions,
+                    context,
+                ))
+            }
+            _ => {
+                if let Some(cmd) = args.get(start) {
+                    if self.commands.is_some() && !cmd.starts_with('-') {
+                        let error =
+                            parser::unknown_command_error(&self.commands, cmd, &path.join(" "));
+                        return Err(within(error));
+                    }
+                }
+                let form = form.unwrap_or(HelpForm::Long);
+                let help_text = self.help_text_in(
+                    path,
+                    help_flag,
+                    settings.global_flags,
+                    form,
+                    settings.env_prefix,
+                    settings.messages,
+                );
+                pager::show(
+                    settings.pager,
+                    settings.theme,
+                    settings.color,
+                    settings.messages,
+                    settings.streams,
+                    &help_text,
+                );
+                Ok(Dispatch::Help(path.to_vec()))
+            }
+        }
+    }
+
+    /// Context of the action, resolving unknown flags with the handler and
+    /// asking for missing flags with the prompt of the settings, failing on
+    /// unknown flags, missing or invalid flag values or argument count
+    fn context<T: Into<Arc<str>>>(
+        &self,
+        path: &[String],
+        args: Vec<String>,
+        flags: &[Flag],
+        help_text: T,
+        settings: &Settings,
+    ) -> Result<Context, Error> {
+        let args = if settings.slash_flags {
+            parser::slash_flags(args, flags)
+        } else {
+            args
+        };
+        let index = settings.compiled.and_then(|c| c.index(path));
+        let mut matches =
+            FlagMatches::parse_indexed(args, flags, index, settings.stop_at_first_positional);
+        if let Some(handler) = settings.unknown_flag {
+            matches = matches.resolve_unknown(flags, handler, path)?;
+        }
+        if settings.debug {
+            settings.streams.err(&matches.trace_args(flags, path));
+        }
+        matches.fill_missing(flags, settings.prompt, settings.messages, |flag| {
+            flag.env_names(settings.env_prefix, path.get(1..).unwrap_or_default())
+        })?;
+        matches.apply_overrides(flags);
+        matches.read_files(flags)?;
+        let declared = self.args.as_ref().map_or(&[][..], Vec::as_slice);
+        matches.normalize_values(flags);
+        #[cfg(feature = "glob")]
+        matches.expand_globs(flags, declared)?;
+        matches.check_values(flags)?;
+        let own_flags = self.flags();
+        let configured = |flag: &Flag| {
+            if own_flags.iter().any(|own| own.name == flag.name) {
+                config::source(settings.config, path, flag)
+            } else {
+                None
+            }
+        };
+        if settings.debug {
+            settings
+                .streams
+                .err(&matches.trace_flags(flags, configured));
+        }
+        for group in self.flag_groups.iter().flatten() {
+            group.check(flags, &mut matches)?;
+        }
+
+        let values =
+            || {
+                matches.tokens.iter().enumerate().filter(|(_, token)| {
+                    token.kind == Kind::Positional || token.kind == Kind::Trailing
+                })
+            };
+        let counted = || {
+            values().filter(|(_, token)| {
+                !(settings.exclude_trailing_args && token.kind == Kind::Trailing)
+            })
+        };
+        let count = counted().count();
+        match (self.min_args, self.max_args) {
+            (Some(min), _) if count < min => {
+                return Err(Error::new(ErrorKind::WrongNumberOfArguments, move |m| {
+                    m.too_few_args(min)
+                }));
+            }
+            (_, Some(max)) if count > max => {
+                let error = Error::new(ErrorKind::WrongNumberOfArguments, move |m| {
+                    m.too_many_args(max)
+                });
+                return Err(match counted().nth(max) {
+                    Some((position, token)) => {
+                        matches.locate(error.token(token.arg.as_str()), position)
+                    }
+                    None => error,
+                });
+            }
+            _ => {}
+        }
+
+        let missing: Vec<String> = declared
+            .iter()
+            .skip(values().count())
+            .filter(|arg| arg.required && arg.default_value.is_none())
+            .map(|arg| format!("<{}>", arg.name))
+            .collect();
+        if !missing.is_empty() {
+            let first = missing[0].clone();
+            return Err(Error::new(ErrorKind::MissingArgument, move |m| {
+                m.missing_args(&missing)
+            })
+            .token(first));
+        }
+
+        // The last argument takes the remaining values when variadic
+        let declared_values = || {
+            values().enumerate().filter_map(move |(index, value)| {
+                declared
+                    .get(index)
+                    .or_else(|| declared.last().filter(|arg| arg.variadic))
+                    .map(|arg| (arg, value))
+            })
+        };
+        for (arg, (position, token)) in declared_values() {
+            if arg.value(Some(&token.arg)).is_err() {
+                let (value, name, expected) =
+                    (token.arg.clone(), arg.name.clone(), arg.arg_type.name());
+                return Err(matches.locate(
+                    Error::new(ErrorKind::InvalidValue, move |m| {
+                        m.invalid_arg_value(&value, &name, expected)
+                    })
+                    .token(token.arg.as_str()),
+                    position,
+                ));
+            }
+        }
+
+        for (arg, (position, token)) in declared_values() {
+            for validator in &arg.validators {
+                if let Err(e) = validator.validate(&token.arg) {
+                    let (value, name, reason) =
+                        (token.arg.clone(), arg.name.clone(), e.to_string());
+                    return Err(matches.locate(
+                        Error::new(ErrorKind::InvalidValue, move |m| {
+                            m.rejected_arg_value(&value, &name, &reason)
+                        })
+                        .token(token.arg.as_str()),
+                        position,
+                    ));
+                }
+            }
+        }
+
+        let mut context = Context::from_matches(matches, flags, help_text, configured)
+            .with_prompt(settings.prompt.clone())
+            .with_terminal(settings.terminal.unwrap_or_else(Terminal::detect))
+            .with_streams(settings.streams.clone())
+            .within(path);
+        context.declare_args(declared);
+        Ok(context)
+    }
+
+    /// Full path of the command from the path of its parent commands
+    pub(crate) fn path(&self, parent_path: &[String]) -> Vec<String> {
+        let mut path = parent_path.to_vec();
+        path.push(self.name.clone());
+        path
+    }
+
+    /// Add the entries of the command and of its subcommands with an action
+    /// to the compiled app, with the path of the parent and the help flag
+    /// and global flags of the run
+    pub(crate) fn compile_into(
+        &self,
+        parent_path: &[String],
+        parent_help_flag: &HelpFlag,
+        global_flags: &[Flag],
+        env_prefix: Option<&str>,
+        messages: &dyn Messages,
+        compiled: &mut Compiled,
+    ) {
+        let path = self.path(parent_path);
+        let help_flag = self.help_flag.or(parent_help_flag);
+        if self.action.is_some() {
+            let help_text = if compiled.keeps_help_texts() {
+                self.help_text_at(&path, help_flag, global_flags, env_prefix, messages)
+            } else {
+                String::new()
+            };
+            let flags = with_globals(Cow::Borrowed(self.flags()), global_flags).into_owned();
+            compiled.insert(&path, help_text, flags);
+        }
+        for command in self.commands.iter().flatten() {
+            command.compile_into(
+                &path,
+                help_flag,
+                global_flags,
+                env_prefix,
+                messages,
+                compiled,
+            );
+        }
+    }
+
+    /// Help text of the command at the full path, listing the help flag
+    /// applying to it, the global flags it doesn't declare and the
+    /// environment variables named with the prefix, with the headings of the
+    /// catalog
+    pub(crate) fn help_text_at(
+        &self,
+        path: &[String],
+        help_flag: &HelpFlag,
+        global_flags: &[Flag],
+        env_prefix: Option<&str>,
+        messages: &dyn Messages,
+    ) -> String {
+        self.help_text_in(
+            path,
+            help_flag,
+            global_flags,
+            HelpForm::Long,
+            env_prefix,
+            messages,
+        )
+    }
+
+    /// Version line requested by the arguments, of the command when it has a
+    /// version and of the app otherwise
+    fn version_text(
+        &self,
+        path: &[String],
+        args: &[String],
+        settings: &Settings,
+    ) -> Option<String> {
+        let args = args.iter().take_while(|a| *a != "--");
+        match (&self.version, settings.app) {
+            (Some(version), _) if args.clone().any(|a| self.version_flag.matches(a)) => {
+                Some(format!("{} {}", path.join(" "), version))
+            }
+            (None, Some(app)) => app.version_text(args),
+            _ => None,
+        }
+    }
+
+    /// Help of the command in the form, the short form leaving out the text
+    /// before and after help and the version
+    pub(crate) fn help_text_in(
+        &self,
+        path: &[String],
+        help_flag: &HelpFlag,
+        global_flags: &[Flag],
+        form: HelpForm,
+        env_prefix: Option<&str>,
+        messages: &dyn Messages,
+    ) -> String {
+        let mut text = String::new();
+        let long = form == HelpForm::Long;
+
+        if let (true, Some(before_help)) = (long, &self.before_help) {
+            text += &format!("{}\n\n", before_help);
+        }
+
+        if let Some(description) = form.description(&self.description, &self.long_description) {
+            text += &format!(
+                "{}:\n\t{}\n\n",
+                messages.description_heading(),
+                help::indent(description)
+            );
+        }
+
+        text += &format!(
+            "{}:\n\t{}\n\n",
+            messages.usage_heading(),
+            help::indent(&self.usage_text(path))
+        );
+        if let Some(alias) = &self.alias {
+            text += &format!(
+                "{}:\n\t{}\n\n",
+                messages.aliases_heading(),
+                alias.join(", ")
+            );
+        }
+        text += &help::arg_list(self.args.as_ref().map_or(&[][..], Vec::as_slice), messages);
+        text += &self.flag_help_text(help_flag, global_flags, form, messages, |flag| {
+            // Global flags are read from the variables of the app
+            let commands = if self.flags().iter().any(|f| f.name == flag.name) {
+                path.get(1..).unwrap_or_default()
+            } else {
+                &[]
+            };
+            flag.env_names(env_prefix, commands)
+        });
+        text += &self.command_help_text(messages);
+
+        if long && !self.examples.is_empty() {
+            text += &format!("\n{}:\n", messages.examples_heading());
+            for (description, command_line) in &self.examples {
+                text += &format!("\t{}\n\t\t{}\n", description, command_line);
+            }
+        }
+
+        if let (true, Some(version)) = (long, &self.version) {
+            text += &format!("\n{}:\n\t{}\n", messages.version_heading(), version);
+        }
+
+        if let (true, Some(after_help)) = (long, &self.after_help) {
+            text += &format!("\n{}\n", after_help);
+        }
+
+        text
+    }
+
+    /// Usage line of the command, generated from the full path unless set
+    pub(crate) fn usage_text(&self, path: &[String]) -> String {
+        if let Some(usage) = &self.usage {
+            return usage.clone();
+        }
+
+        let mut usage = path.join(" ");
+        if !help::visible(&self.commands, false).is_empty() {
+            usage += if self.action.is_some() {
+                " [command]"
+            } else {
+                " <command>"
+            };
+        }
+        let flags = self.flags.as_ref().map_or(&[][..], Vec::as_slice);
+        for group in self.flag_groups.iter().flatten() {
+            if let Some(group_usage) = group.usage_text(flags) {
+                usage += &format!(" {}", group_usage);
+            }
+        }
+        usage += " [flags]";
+        match &self.args {
+            Some(args) if !args.is_empty() => {
+                for arg in args {
+                    usage += &format!(" {}", arg.usage());
+                }
+            }
+            _ if self.action.is_some() => usage += " [args]",
+            _ => {}
+        }
+        usage
+    }
+
+    fn flag_help_text(
+        &self,
+        help_flag: &HelpFlag,
+        global_flags: &[Flag],
+        form: HelpForm,
+        messages: &dyn Messages,
+        env_names: impl Fn(&Flag) -> Vec<String>,
+    ) -> String {
+        let mut builtins = Vec::new();
+        if let Some(help_flag) = help_flag.usage() {
+            builtins.push((help_flag, messages.help_flag_description()));
+        }
+        if let (Some(_), Some(version_flag)) = (&self.version, self.version_flag.usage()) {
+            builtins.push((version_flag, messages.version_flag_description()));
+        }
+
+        // Global flags of the app the command reads too, in a section of their
+        // own after those of the command
+        let heading = messages.global_flags_heading();
+        let globals: Vec<Flag> = inherited_globals(self.flags(), global_flags)
+            .filter(|global| !global.hidden)
+            .map(|global| Flag {
+                help_section: Some(heading.clone()),
+                ..global.clone()
+            })
+            .collect();
+        let mut flags = help::visible(&self.flags, self.sort_help_alphabetically);
+        flags.extend(&globals);
+        help::flag_list(&flags, &builtins, form, messages, env_names)
+    }
+
+    fn command_help_text(&self, messages: &dyn Messages) -> String {
+        let commands = help::visible(&self.commands, self.sort_help_alphabetically);
+        help::command_list(&commands, terminal::width(), messages)
+    }
+}
+
+/// Set the version flag of the shared command and its sub commands, copying
+/// the commands it changes when they are shared
+pub(crate) fn set_shared_version_flag(command: &mut Arc<Command>, version_flag: &VersionFlag) {
+    if !command.has_version_flag(version_flag) {
+        Arc::make_mut(command).set_version_flag(version_flag);
+    }
+}
+
+/// Sort help items of the shared command and its sub commands alphabetically,
+/// copying the commands it changes when they are shared
+pub(crate) fn sort_shared_help_alphabetically(command: &mut Arc<Command>) {
+    if !command.sorts_help_alphabetically() {
+        Arc::make_mut(command).set_sort_help_alphabetically();
+    }
+}
+
+/// Global flags of the app the command doesn't declare
+fn inherited_globals<'a>(flags: &'a [Flag], globals: &'a [Flag]) -> impl Iterator<Item = &'a Flag> {
+    globals
+        .iter()
+        .filter(move |global| global.global && flags.iter().all(|flag| flag.name != global.name))
+}
+
+/// Flags of the command followed by the global flags it doesn't declare
+fn with_globals<'a>(flags: Cow<'a, [Flag]>, globals: &[Flag]) -> Cow<'a, [Flag]> {
+    let inherited: Vec<Flag> = inherited_globals(&flags, globals).cloned().collect();
+    if inherited.is_empty() {
+        return flags;
+    }
+    let mut flags = flags.into_owned();
+    flags.extend(inherited);
+    Cow::Owned(flags)
+}
+
+/// Command running the app, its commands becoming subcommands
+///
+/// Help texts, flags, actions, after action and version of the app are kept.
+/// Settings of the run like the prompt, handlers and config file come from
+/// the app the command is added to.
+///
+/// Example
+///
+/// ```
+/// use seahorse::{App, Command};
+///
+/// let fmt = App::new("fmt").version("1.2.0").command(Command::new("check"));
+/// let command = Command::from(fmt);
+/// assert_eq!(command.subcommands()[0].name(), "check");
+/// ```
+impl From<App> for Command {
+    fn from(app: App) -> Self {
+        Self {
+            name: app.name,
+            description: app.description,
+            usage: app.usage,
+            action: app.action,
+            app_after_action: app.after_action,
+            flags: app.flags,
+            commands: app.commands,
+            before_help: app.before_help,
+            after_help: app.after_help,
+            sort_help_alphabetically: app.sort_help_alphabetically,
+            exclude_trailing_args: Some(app.exclude_trailing_args).filter(|&set| set),
+            stop_at_first_positional: Some(app.stop_at_first_positional).filter(|&set| set),
+            command_abbreviation: Some(app.command_abbreviation).filter(|&set| set),
+            slash_flags: Some(app.slash_flags).filter(|&set| set),
+            args_completer: app.args_completer,
+            help_flag: app.help_flag,
+            version: app.version,
+            version_flag: app.version_flag,
+            ..Self::default()
+        }
+    }
+}
+
+impl Help for Command {
+    fn help_text(&self) -> String {
+        self.help_text_at(
+            std::slice::from_ref(&self.name),
+            &self.help_flag,
+            &[],
+            None,
+            &DefaultMessages,
+        )
+    }
+}
+
+/// Error for a command named as one of the registered commands, or sharing
+/// an alias with one of them
+pub(crate) fn check_command<'a>(
+    command: &Command,
+    registered: impl IntoIterator<Item = &'a Command>,
+) -> Result<(), String> {
+    for other in registered {
+        if other.name == command.name {
+            return Err(format!(
+                r#"Command name "{}" is already registered."#,
+                command.name
+            ));
+        }
+        let shared = command
+            .all_aliases()
+            .find(|alias| **alias == other.name || other.all_aliases().any(|a| a == *alias))
+            .map(|alias| (command, alias, other))
+            .or_else(|| {
+                other
+                    .all_aliases()
+                    .find(|alias| **alias == command.name)
+                    .map(|alias| (other, alias, command))
+            });
+        if let Some((owner, alias, user)) = shared {
+            return Err(format!(
+                r#"Alias "{}" of command "{}" is also used by command "{}"."#,
+                alias, owner.name, user.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Settings;
+    use crate::error::FlagError;
+    use crate::help::HelpForm;
+    use crate::{
+        Action, App, AppSettings, Arg, Command, Context, DefaultMessages, Flag, FlagGroup,
+        FlagType, Help, HelpFlag, Prompt,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn command_test() {
+        let a: Action = Action::from(|c: &Context| println!("Hello, {:?}", c.args));
         let c = Command::new("hello")
             .description("usre command")
             .usage("test hello user")
@@ -412,4 +1918,946 @@ mod tests {
         assert_eq!(c.name, "hello".to_string());
         assert_eq!(c.usage, Some("test hello user".to_string()));
     }
+
+    #[test]
+    fn before_and_after_help_test() {
+        let c = Command::new("hello")
+            .usage("test hello user")
+            .before_help("Banner\n  line")
+            .after_help("See https://example.com/docs for more");
+
+        assert_eq!(
+            c.help_text(),
+            "Banner\n  line\n\n\
+             Usage:\n\ttest hello user\n\n\
+             Flags:\n\t--help, -h : Show help\n\
+             \nSee https://example.com/docs for more\n"
+        );
+    }
+
+    #[test]
+    fn number_of_values_help_test() {
+        let c = Command::new("draw").usage("test draw").flag(
+            Flag::new("point", FlagType::Int)
+                .description("Point to draw")
+                .alias("p")
+                .number_of_values(2),
+        );
+
+        assert!(c
+            .help_text()
+            .contains("\t--point, -p <int> <int> : Point to draw\n"));
+    }
+
+    #[test]
+    fn flag_usage_help_test() {
+        let c = Command::new("build")
+            .usage("test build")
+            .flag(
+                Flag::new("define", FlagType::String)
+                    .usage("-D, --define <KEY=VALUE>")
+                    .description("Define a variable"),
+            )
+            .flag(Flag::new("jobs", FlagType::Int).usage("-j, --jobs <N>"));
+
+        assert_eq!(
+            c.flag_help_text(
+                &HelpFlag::Inherit,
+                &[],
+                HelpForm::Long,
+                &DefaultMessages,
+                |_| { Vec::new() }
+            ),
+            "Flags:\n\
+             \t-D, --define <KEY=VALUE> : Define a variable\n\
+             \t-j, --jobs <N>\n\
+             \t--help, -h               : Show help\n"
+        );
+    }
+
+    #[test]
+    fn short_and_long_help_test() {
+        let c = Command::new("build")
+            .description("Build the project")
+            .long_description("Build the project\n\nArtifacts are written to target/.")
+            .version("1.2.0")
+            .before_help("Banner")
+            .after_help("See https://example.com/build")
+            .flag(
+                Flag::new("jobs", FlagType::Uint)
+                    .alias("j")
+                    .description("Parallel jobs")
+                    .long_description("Parallel jobs\nDefaults to the number of CPUs"),
+            )
+            .flag(Flag::new("release", FlagType::Bool).description("Optimize\nfor speed"));
+        let path = ["cli".to_string(), "build".to_string()];
+
+        assert_eq!(
+            c.help_text_in(
+                &path,
+                &HelpFlag::Inherit,
+                &[],
+                HelpForm::Short,
+                None,
+                &DefaultMessages
+            ),
+            "Description:\n\tBuild the project\n\n\
+             Usage:\n\tcli build [flags]\n\n\
+             Flags:\n\
+             \t--jobs, -j <uint> : Parallel jobs\n\
+             \t--release         : Optimize\n\
+             \t--help, -h        : Show help\n\
+             \t--version, -V     : Show version\n"
+        );
+        assert_eq!(
+            c.help_text_in(
+                &path,
+                &HelpFlag::Inherit,
+                &[],
+                HelpForm::Long,
+                None,
+                &DefaultMessages
+            ),
+            "Banner\n\n\
+             Description:\n\tBuild the project\n\t\n\tArtifacts are written to target/.\n\n\
+             Usage:\n\tcli build [flags]\n\n\
+             Flags:\n\
+             \t--jobs, -j <uint> : Parallel jobs\n\
+             \t                    Defaults to the number of CPUs\n\
+             \t--release         : Optimize\n\
+             \t                    for speed\n\
+             \t--help, -h        : Show help\n\
+             \t--version, -V     : Show version\n\
+             \nVersion:\n\t1.2.0\n\
+             \nSee https://example.com/build\n"
+        );
+        assert_eq!(
+            c.help_text(),
+            c.help_text_in(
+                &path[1..],
+                &HelpFlag::Inherit,
+                &[],
+                HelpForm::Long,
+                None,
+                &DefaultMessages
+            )
+        );
+    }
+
+    #[test]
+    fn examples_help_test() {
+        let c = Command::new("cp")
+            .action(|_: &Context| {})
+            .example("Copy a file", "cli cp src.txt dest.txt")
+            .example("Copy into a directory", "cli cp src.txt backup/")
+            .after_help("See also: mv");
+        let path = ["cli".to_string(), "cp".to_string()];
+
+        assert_eq!(
+            c.help_text_in(
+                &path,
+                &HelpFlag::Inherit,
+                &[],
+                HelpForm::Long,
+                None,
+                &DefaultMessages
+            ),
+            "Usage:\n\tcli cp [flags] [args]\n\n\
+             Flags:\n\
+             \t--help, -h : Show help\n\
+             \nExamples:\n\
+             \tCopy a file\n\
+             \t\tcli cp src.txt dest.txt\n\
+             \tCopy into a directory\n\
+             \t\tcli cp src.txt backup/\n\
+             \nSee also: mv\n"
+        );
+        assert_eq!(
+            c.help_text_in(
+                &path,
+                &HelpFlag::Inherit,
+                &[],
+                HelpForm::Short,
+                None,
+                &DefaultMessages
+            ),
+            "Usage:\n\tcli cp [flags] [args]\n\n\
+             Flags:\n\
+             \t--help, -h : Show help\n"
+        );
+    }
+
+    #[test]
+    fn version_text_test() {
+        let app = App::new("cli").version("2.0.0");
+        let prompt = Prompt::non_interactive();
+        let settings = Settings {
+            app: Some(&app),
+            ..Settings::new(&prompt)
+        };
+        let path = ["cli".to_string(), "plugin".to_string()];
+        let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+
+        let plugin = Command::new("plugin").version("0.4.1");
+        assert_eq!(
+            plugin.version_text(&path, &args(&["--version"]), &settings),
+            Some("cli plugin 0.4.1".to_string())
+        );
+        assert_eq!(
+            plugin.version_text(&path, &args(&["a", "-V"]), &settings),
+            Some("cli plugin 0.4.1".to_string())
+        );
+        assert_eq!(
+            plugin.version_text(&path, &args(&["--", "--version"]), &settings),
+            None
+        );
+
+        let build = Command::new("build");
+        assert_eq!(
+            build.version_text(&path, &args(&["--version"]), &settings),
+            Some("cli 2.0.0".to_string())
+        );
+        assert_eq!(build.version_text(&path, &args(&["a"]), &settings), None);
+        assert_eq!(
+            build.version_text(&path, &args(&["--version"]), &Settings::new(&prompt)),
+            None
+        );
+        assert!(plugin.help_text().contains("\nVersion:\n\t0.4.1\n"));
+    }
+
+    #[test]
+    fn settings_inheritance_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let push = |name: &'static str| {
+            let log = log.clone();
+            move |c: &Context| {
+                log.lock()
+                    .unwrap()
+                    .push(format!("{} {:?} {}", name, c.args, c.bool_flag("force")))
+            }
+        };
+        let force = || Flag::new("force", FlagType::Bool).alias("f");
+        let app = App::new("cli")
+            .setting(AppSettings::StopAtFirstPositional)
+            .setting(AppSettings::AllowCommandAbbreviation)
+            .command(Command::new("exec").action(push("exec")).flag(force()))
+            .command(
+                Command::new("remote")
+                    .unset_setting(AppSettings::StopAtFirstPositional)
+                    .command(Command::new("prune").action(push("prune")).flag(force()))
+                    .command(
+                        Command::new("branch")
+                            .setting(AppSettings::StopAtFirstPositional)
+                            .unset_setting(AppSettings::AllowCommandAbbreviation)
+                            .command(Command::new("add").action(push("add")).flag(force()))
+                            .command(Command::new("again").action(push("again"))),
+                    ),
+            );
+        let run = |args: &[&str]| {
+            let mut args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            args.insert(0, "cli".to_string());
+            app.try_run(args).map_err(|e| e.to_string())
+        };
+
+        assert_eq!(run(&["ex", "a", "-f"]), Ok(0));
+        assert_eq!(run(&["rem", "pr", "a", "-f"]), Ok(0));
+        assert_eq!(run(&["remote", "branch", "add", "a", "-f"]), Ok(0));
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                r#"exec ["a", "-f"] false"#,
+                r#"prune ["a"] true"#,
+                r#"add ["a", "-f"] false"#,
+            ]
+        );
+        // Not abbreviated below the command unsetting it
+        let _ = run(&["remote", "branch", "ad", "a"]);
+        assert_eq!(log.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "TerseErrors is a setting of the app only")]
+    fn app_only_setting_test() {
+        Command::new("exec").setting(AppSettings::TerseErrors);
+    }
+
+    #[test]
+    fn arg_count_test() {
+        let c = Command::new("greet")
+            .action(|_: &Context| {})
+            .min_args(1)
+            .max_args(2)
+            .flag(Flag::new("name", FlagType::String));
+        let path = ["cli".to_string(), "greet".to_string()];
+        let error = |c: &Command, args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            let flags = c.flags.clone().unwrap_or_default();
+            c.context(
+                &path,
+                args,
+                &flags,
+                String::new(),
+                &c.settings(&Settings::new(&Prompt::non_interactive())),
+            )
+            .err()
+            .map(|e| e.to_string())
+        };
+
+        assert_eq!(
+            error(&c, &["--name", "x"]),
+            Some("Expected at least 1 argument".to_string())
+        );
+        assert_eq!(error(&c, &["a"]), None);
+        assert_eq!(error(&c, &["a", "--name", "x", "b"]), None);
+        assert_eq!(
+            error(&c, &["a", "b", "c"]),
+            Some("Expected at most 2 arguments".to_string())
+        );
+        assert_eq!(
+            error(&c, &["a", "--", "b", "c"]),
+            Some("Expected at most 2 arguments".to_string())
+        );
+
+        let c = c.exclude_trailing_args();
+        assert_eq!(error(&c, &["a", "--", "b", "c"]), None);
+        assert!(error(&c, &["--", "a"]).is_some());
+    }
+
+    #[test]
+    fn interleaved_flags_test() {
+        let c = Command::new("cp")
+            .action(|_: &Context| {})
+            .flag(Flag::new("force", FlagType::Bool).alias("f"))
+            .flag(Flag::new("output", FlagType::String).alias("o"))
+            .flag(Flag::new("mode", FlagType::Uint).number_of_values(2));
+        let path = ["cli".to_string(), "cp".to_string()];
+        let parse = |c: &Command, args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            let flags = c.flags.clone().unwrap_or_default();
+            let context = c
+                .context(
+                    &path,
+                    args,
+                    &flags,
+                    String::new(),
+                    &c.settings(&Settings::new(&Prompt::non_interactive())),
+                )
+                .unwrap();
+            format!(
+                "force={} output={:?} mode={:?} args={:?}",
+                context.bool_flag("force"),
+                context.string_flag("output").ok(),
+                context.values::<usize>("mode").ok(),
+                context.args
+            )
+        };
+
+        let expected = r#"force=true output=Some("dest.txt") mode=None args=["a", "b", "c"]"#;
+        assert_eq!(
+            parse(&c, &["a", "-f", "b", "-o", "dest.txt", "c"]),
+            expected
+        );
+        assert_eq!(
+            parse(&c, &["-o", "dest.txt", "a", "b", "--force", "c"]),
+            expected
+        );
+        assert_eq!(
+            parse(&c, &["a", "--output=dest.txt", "b", "c", "-f"]),
+            expected
+        );
+        assert_eq!(
+            parse(&c, &["a", "--mode", "6", "4", "b", "-f", "c"]),
+            r#"force=true output=None mode=Some([6, 4]) args=["a", "b", "c"]"#
+        );
+        assert_eq!(
+            parse(&c, &["--output", "dest.txt", "src.txt"]),
+            r#"force=false output=Some("dest.txt") mode=None args=["src.txt"]"#
+        );
+
+        let c = c.stop_at_first_positional();
+        assert_eq!(
+            parse(&c, &["-f", "a", "-o", "dest.txt", "b", "--", "c"]),
+            r#"force=true output=None mode=None args=["a", "-o", "dest.txt", "b", "c"]"#
+        );
+        assert_eq!(
+            parse(&c, &["a", "--output=", "b"]),
+            r#"force=false output=None mode=None args=["a", "--output=", "b"]"#
+        );
+    }
+
+    #[test]
+    fn hidden_alias_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let push = log.clone();
+        let app = App::new("cli").command(
+            Command::new("remove")
+                .alias("rm")
+                .hidden_alias("delete")
+                .action(move |c: &Context| {
+                    push.lock()
+                        .unwrap()
+                        .push(format!("{:?}", c.string_flag("color")))
+                })
+                .flag(Flag::new("color", FlagType::String).hidden_alias("colour")),
+        );
+        let run = |args: &[&str]| app.try_run(args.iter().map(|a| a.to_string()).collect());
+
+        assert_eq!(run(&["cli", "delete", "-colour", "red"]), Ok(0));
+        assert_eq!(run(&["cli", "rm", "--color=blue"]), Ok(0));
+        assert_eq!(*log.lock().unwrap(), vec![r#"Ok("red")"#, r#"Ok("blue")"#]);
+
+        let mut text = app.help_text();
+        text += &app.commands()[0].render_help("cli");
+        let mut script = Vec::new();
+        app.generate_completion(crate::Shell::Bash, &mut script)
+            .unwrap();
+        text += &String::from_utf8(script).unwrap();
+        assert!(text.contains("rm"));
+        assert!(!text.contains("delete"));
+        assert!(!text.contains("colour"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = r#"Alias "delete" of command "remove" is also used by command "delete"."#
+    )]
+    fn hidden_alias_conflict_test() {
+        let _ = Command::new("cli")
+            .command(Command::new("remove").hidden_alias("delete"))
+            .command(Command::new("delete"));
+    }
+
+    #[test]
+    fn hidden_flag_alias_conflict_test() {
+        let command =
+            Command::new("paint").flag(Flag::new("color", FlagType::String).hidden_alias("c"));
+        assert_eq!(
+            command
+                .try_flag(Flag::new("count", FlagType::Int).alias("c"))
+                .err(),
+            Some(r#"Alias "c" of flag "count" is also used by flag "color"."#.to_string())
+        );
+    }
+
+    #[test]
+    fn typed_args_test() {
+        let c = Command::new("serve")
+            .action(|_: &Context| {})
+            .arg(Arg::new("HOST", FlagType::String))
+            .arg(Arg::new("PORT", FlagType::Uint))
+            .arg(Arg::new("RATIO", FlagType::Float).default_value("0.5"))
+            .arg(Arg::new("OFFSET", FlagType::Int))
+            .arg(Arg::new("TLS", FlagType::Bool))
+            .flag(Flag::new("force", FlagType::Bool));
+        let path = ["cli".to_string(), "serve".to_string()];
+        let context = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            let flags = c.flags.clone().unwrap_or_default();
+            c.context(
+                &path,
+                args,
+                &flags,
+                String::new(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+        };
+
+        let all = context(&["localhost", "--force", "80", "0.25", "-3", "true"]).unwrap();
+        assert_eq!(all.string_arg("HOST"), Ok("localhost".to_string()));
+        assert_eq!(all.uint_arg("PORT"), Ok(80));
+        assert_eq!(all.float_arg("RATIO"), Ok(0.25));
+        assert_eq!(all.int_arg("OFFSET"), Ok(-3));
+        assert_eq!(all.bool_arg("TLS"), Ok(true));
+        assert_eq!(all.int_arg("PORT"), Err(FlagError::TypeError));
+        assert_eq!(all.string_arg("OTHER"), Err(FlagError::Undefined));
+
+        let some = context(&["localhost"]).unwrap();
+        assert_eq!(some.uint_arg("PORT"), Err(FlagError::NotFound));
+        assert_eq!(some.float_arg("RATIO"), Ok(0.5));
+
+        assert_eq!(
+            context(&["localhost", "http"]).err().map(|e| e.to_string()),
+            Some("Invalid value \"http\" for argument \"PORT\", expected uint".to_string())
+        );
+    }
+
+    #[test]
+    fn default_args_test() {
+        let c = Command::new("serve")
+            .action(|_: &Context| {})
+            .arg(Arg::new("HOST", FlagType::String).required())
+            .arg(Arg::new("DIR", FlagType::String).default_value("."))
+            .arg(Arg::new("PORT", FlagType::Uint))
+            .min_args(1);
+        let path = ["cli".to_string(), "serve".to_string()];
+        let context = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            c.context(
+                &path,
+                args,
+                &[],
+                String::new(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+        };
+
+        let defaulted = context(&["localhost"]).unwrap();
+        assert_eq!(defaulted.string_arg("DIR"), Ok(".".to_string()));
+        assert_eq!(defaulted.value_of("DIR"), Some("."));
+        assert!(defaulted.is_arg_defaulted("DIR"));
+        assert!(!defaulted.is_arg_defaulted("HOST"));
+        assert!(!defaulted.is_arg_defaulted("PORT"));
+        assert_eq!(defaulted.args, vec!["localhost"]);
+
+        let given = context(&["localhost", "www"]).unwrap();
+        assert_eq!(given.string_arg("DIR"), Ok("www".to_string()));
+        assert!(!given.is_arg_defaulted("DIR"));
+
+        assert_eq!(
+            c.usage_text(&path),
+            "cli serve [flags] <HOST> [DIR (default: .)] [PORT]"
+        );
+
+        // A required argument with a default is never missing
+        let c = Command::new("serve").action(|_: &Context| {}).arg(
+            Arg::new("DIR", FlagType::String)
+                .required()
+                .default_value("."),
+        );
+        let context = c
+            .context(
+                &path,
+                Vec::new(),
+                &[],
+                String::new(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+            .unwrap();
+        assert_eq!(context.string_arg("DIR"), Ok(".".to_string()));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = r#"Argument "DIR" with a default value cannot precede the required argument "PORT"."#
+    )]
+    fn default_before_required_arg_test() {
+        let _ = Command::new("serve")
+            .arg(Arg::new("DIR", FlagType::String).default_value("."))
+            .arg(Arg::new("PORT", FlagType::Uint).required());
+    }
+
+    #[test]
+    fn arg_help_test() {
+        let c = Command::new("cp")
+            .description("Copy files")
+            .action(|_: &Context| {})
+            .arg(
+                Arg::new("SRC", FlagType::String)
+                    .description("File to copy")
+                    .required(),
+            )
+            .arg(
+                Arg::new("DEST", FlagType::String)
+                    .description("Directory to copy to")
+                    .default_value("."),
+            )
+            .arg(
+                Arg::new("EXTRA", FlagType::Uint)
+                    .description("Extra modes")
+                    .variadic(),
+            )
+            .flag(Flag::new("force", FlagType::Bool).description("Overwrite files"));
+        let path = ["cli".to_string(), "cp".to_string()];
+
+        assert_eq!(
+            c.help_text_in(
+                &path,
+                &HelpFlag::Inherit,
+                &[],
+                HelpForm::Long,
+                None,
+                &DefaultMessages
+            ),
+            "Description:\n\tCopy files\n\n\
+             Usage:\n\tcli cp [flags] <SRC> [DEST (default: .)] [EXTRA]...\n\n\
+             Args:\n\
+             \t<SRC>               : File to copy\n\
+             \t[DEST (default: .)] : Directory to copy to\n\
+             \t[EXTRA]...          : Extra modes\n\n\
+             Flags:\n\
+             \t--force    : Overwrite files\n\
+             \t--help, -h : Show help\n"
+        );
+
+        let context = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            c.context(
+                &path,
+                args,
+                &[],
+                String::new(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+            .map_err(|e| e.to_string())
+        };
+        let all = context(&["a", "b", "1", "2"]).unwrap();
+        assert_eq!(all.string_arg("DEST"), Ok("b".to_string()));
+        assert_eq!(all.values::<usize>("EXTRA"), Ok(vec![1, 2]));
+        let one = context(&["a"]).unwrap();
+        assert_eq!(one.values::<usize>("EXTRA"), Err(FlagError::NotFound));
+        assert_eq!(
+            context(&["a", "b", "1", "x"]).err(),
+            Some("Invalid value \"x\" for argument \"EXTRA\", expected uint".to_string())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Argument "MODE" cannot follow the variadic argument "FILE"."#)]
+    fn after_variadic_arg_test() {
+        let _ = Command::new("cat")
+            .arg(Arg::new("FILE", FlagType::String).variadic())
+            .arg(Arg::new("MODE", FlagType::String));
+    }
+
+    #[test]
+    fn required_args_test() {
+        let c = Command::new("cp")
+            .usage("cli cp <SRC> <DEST> <MODE> [flags]")
+            .action(|_: &Context| {})
+            .arg(Arg::new("SRC", FlagType::String).required())
+            .arg(Arg::new("DEST", FlagType::String).required())
+            .arg(Arg::new("MODE", FlagType::Uint).required())
+            .arg(Arg::new("EXTRA", FlagType::String))
+            .flag(Flag::new("force", FlagType::Bool));
+        let path = ["cli".to_string(), "cp".to_string()];
+        let error = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            let flags = c.flags.clone().unwrap_or_default();
+            c.context(
+                &path,
+                args,
+                &flags,
+                String::new(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+            .err()
+            .map(|e| e.to_string())
+        };
+
+        assert_eq!(
+            error(&["a.txt", "--force"]),
+            Some("Missing required arguments: <DEST>, <MODE>".to_string())
+        );
+        assert_eq!(
+            error(&["a.txt", "b.txt"]),
+            Some("Missing required argument: <MODE>".to_string())
+        );
+        assert_eq!(error(&["a.txt", "b.txt", "644"]), None);
+    }
+
+    #[test]
+    fn flag_group_test() {
+        let c = Command::new("fetch")
+            .action(|_: &Context| {})
+            .flag(Flag::new("file", FlagType::String).alias("f"))
+            .flag(Flag::new("url", FlagType::String))
+            .flag(Flag::new("stdin", FlagType::Bool))
+            .flag(Flag::new("force", FlagType::Bool))
+            .flag_group(
+                FlagGroup::new("input")
+                    .member("file")
+                    .member("url")
+                    .member("stdin")
+                    .required(),
+            )
+            .flag_group(FlagGroup::new("remote").member("url").member("force"));
+        let path = ["cli".to_string(), "fetch".to_string()];
+        let error = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            let flags = c.flags.clone().unwrap_or_default();
+            c.context(
+                &path,
+                args,
+                &flags,
+                String::new(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+            .err()
+            .map(|e| e.to_string())
+        };
+
+        assert_eq!(
+            error(&["--force", "a"]),
+            Some("Flag group \"input\" requires one of --file, --url, --stdin".to_string())
+        );
+        assert_eq!(error(&["-f", "a.txt"]), None);
+        assert_eq!(error(&["--url", "https://example.com", "--stdin"]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Flag "url" of flag group "input" is not registered."#)]
+    fn flag_group_member_test() {
+        let _ = Command::new("fetch")
+            .flag(Flag::new("file", FlagType::String))
+            .flag_group(FlagGroup::new("input").member("file").member("url"));
+    }
+
+    #[test]
+    fn exclusive_flag_group_test() {
+        let c = Command::new("show")
+            .action(|_: &Context| {})
+            .flag(Flag::new("json", FlagType::Bool).alias("j"))
+            .flag(Flag::new("yaml", FlagType::Bool))
+            .flag(Flag::new("table", FlagType::Bool))
+            .flag_group(
+                FlagGroup::new("format")
+                    .member("json")
+                    .member("yaml")
+                    .member("table")
+                    .exclusive()
+                    .default_member("table"),
+            );
+        let path = ["cli".to_string(), "show".to_string()];
+        let context = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            let flags = c.flags.clone().unwrap_or_default();
+            c.context(
+                &path,
+                args,
+                &flags,
+                String::new(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+        };
+
+        let none = context(&[]).unwrap();
+        assert!(none.bool_flag("table"));
+        assert!(!none.bool_flag("json"));
+
+        let one = context(&["-j"]).unwrap();
+        assert!(one.bool_flag("json"));
+        assert!(!one.bool_flag("table"));
+
+        assert_eq!(
+            context(&["-j", "--yaml"]).err().map(|e| e.to_string()),
+            Some(
+                "Flags --json, --yaml of flag group \"format\" cannot be used together".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn overrides_with_test() {
+        let c = Command::new("list")
+            .action(|_: &Context| {})
+            .flag(
+                Flag::new("all", FlagType::Bool)
+                    .alias("a")
+                    .overrides_with("mine"),
+            )
+            .flag(Flag::new("mine", FlagType::Bool))
+            .flag(Flag::new("owner", FlagType::Int))
+            .flag(Flag::new("sort", FlagType::String).overrides_with("owner"))
+            .flag_group(
+                FlagGroup::new("scope")
+                    .member("all")
+                    .member("mine")
+                    .exclusive()
+                    .required(),
+            );
+        let path = ["cli".to_string(), "list".to_string()];
+        let context = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            let flags = c.flags.clone().unwrap_or_default();
+            c.context(
+                &path,
+                args,
+                &flags,
+                String::new(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+        };
+        let present = |args: &[&str]| {
+            let c = context(args).unwrap();
+            ["all", "mine", "owner", "sort"]
+                .iter()
+                .filter(|name| c.is_present(name))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(present(&["--all", "--mine"]), ["mine"]);
+        assert_eq!(present(&["--mine", "-a"]), ["all"]);
+        assert_eq!(present(&["--mine", "--mine"]), ["mine"]);
+
+        let c = context(&["--mine", "--owner", "x", "--sort", "name"]).unwrap();
+        assert_eq!(c.string_flag("sort"), Ok("name".to_string()));
+        assert!(c.int_flag("owner").is_err());
+        assert!(!c.args.contains(&"x".to_string()));
+        let c = context(&["--mine", "--sort", "name", "--owner", "3"]).unwrap();
+        assert_eq!(c.int_flag("owner"), Ok(3));
+        assert!(!c.is_present("sort"));
+
+        assert_eq!(
+            context(&["--owner", "3"]).err().map(|e| e.to_string()),
+            Some("Flag group \"scope\" requires one of --all, --mine".to_string())
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = r#"Default member "json" of flag group "format" is not a bool flag."#
+    )]
+    fn default_member_test() {
+        let _ = Command::new("show")
+            .flag(Flag::new("json", FlagType::String))
+            .flag_group(
+                FlagGroup::new("format")
+                    .member("json")
+                    .default_member("json"),
+            );
+    }
+
+    #[test]
+    fn usage_override_test() {
+        let usage = "cli sync --push <REMOTE>\ncli sync --pull <REMOTE>\n    [--force]";
+        let c = Command::new("sync")
+            .usage(usage)
+            .action(|_: &Context| {})
+            .arg(Arg::new("REMOTE", FlagType::String).required())
+            .flag(Flag::new("push", FlagType::Bool))
+            .flag(Flag::new("pull", FlagType::Bool));
+        let path = ["cli".to_string(), "sync".to_string()];
+
+        assert!(c.help_text_at(&path, &HelpFlag::Inherit, &[], None, &DefaultMessages).starts_with(
+            "Usage:\n\tcli sync --push <REMOTE>\n\tcli sync --pull <REMOTE>\n\t    [--force]\n\n"
+        ));
+        assert_eq!(
+            c.run_with_path(
+                &path[..1],
+                vec!["cli".to_string(), "sync".to_string(), "--push".to_string()],
+                2,
+                &HelpFlag::Inherit,
+                &Settings::new(&Prompt::non_interactive())
+            )
+            .err()
+            .map(|e| e.to_string()),
+            Some(format!(
+                "Missing required argument: <REMOTE>\nUsage: {}\n\
+                 Try 'cli sync --help' for more information.",
+                usage
+            ))
+        );
+    }
+
+    #[test]
+    fn arg_validator_test() {
+        let c = Command::new("serve")
+            .usage("cli serve <PORT>")
+            .action(|_: &Context| {})
+            .arg(
+                Arg::new("PORT", FlagType::Uint)
+                    .validator(|v: &str| match v.parse::<usize>() {
+                        Ok(port) if port > 0 => Ok(()),
+                        _ => Err("must be at least 1".to_string()),
+                    })
+                    .validator(|v: &str| match v.parse::<usize>() {
+                        Ok(port) if port <= 65535 => Ok(()),
+                        _ => Err("must be at most 65535".to_string()),
+                    }),
+            );
+        let path = ["cli".to_string(), "serve".to_string()];
+        let error = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            c.context(
+                &path,
+                args,
+                &[],
+                String::new(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+            .err()
+            .map(|e| e.to_string())
+        };
+
+        assert_eq!(error(&["8080"]), None);
+        assert_eq!(
+            error(&["0"]),
+            Some("Invalid value \"0\" for argument \"PORT\": must be at least 1".to_string())
+        );
+        assert_eq!(
+            error(&["70000"]),
+            Some(
+                "Invalid value \"70000\" for argument \"PORT\": must be at most 65535".to_string()
+            )
+        );
+        assert_eq!(
+            error(&["http"]),
+            Some("Invalid value \"http\" for argument \"PORT\", expected uint".to_string())
+        );
+    }
+
+    #[cfg(feature = "glob")]
+    #[test]
+    fn glob_expand_test() {
+        let dir = std::env::temp_dir().join("seahorse_glob_expand_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in &["a.log", "b.log", "c.txt", "[1].log"] {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+        let path_of = |name: &str| dir.join(name).to_string_lossy().into_owned();
+
+        let c = Command::new("add")
+            .action(|_: &Context| {})
+            .arg(Arg::new("FILE", FlagType::String).variadic().glob_expand());
+        let flags = vec![Flag::new("exclude", FlagType::String)
+            .glob_expand()
+            .keep_unmatched_glob()];
+        let path = ["cli".to_string(), "add".to_string()];
+        let context = |args: &[String]| {
+            c.context(
+                &path,
+                args.to_vec(),
+                &flags,
+                String::new(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+        };
+
+        let logs = vec![path_of("[1].log"), path_of("a.log"), path_of("b.log")];
+        let expanded = context(&[path_of("*.log"), path_of("c.txt")]).unwrap();
+        assert_eq!(
+            expanded.values::<String>("FILE"),
+            Ok(vec![
+                logs[0].clone(),
+                logs[1].clone(),
+                logs[2].clone(),
+                path_of("c.txt")
+            ])
+        );
+        let literal = context(&[path_of("[1].log")]).unwrap();
+        assert_eq!(
+            literal.values::<String>("FILE"),
+            Ok(vec![path_of("[1].log")])
+        );
+
+        let exclude = |pattern: &str| {
+            context(&["--exclude".to_string(), path_of(pattern)])
+                .unwrap()
+                .values::<String>("exclude")
+        };
+        assert_eq!(exclude("*.log"), Ok(logs));
+        assert_eq!(exclude("*.tmp"), Ok(vec![path_of("*.tmp")]));
+
+        assert_eq!(
+            context(&[path_of("*.tmp")]).err().map(|e| e.message),
+            Some(format!(
+                "Pattern \"{}\" for argument \"FILE\" matches no path",
+                path_of("*.tmp")
+            ))
+        );
+        assert!(context(&[path_of("a[")])
+            .err()
+            .map_or(false, |e| e.message.starts_with("Invalid pattern")));
+    }
 }
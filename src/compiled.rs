@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::parser::FlagIndex;
+use crate::terminal::{self, Stream};
+use crate::{App, Flag, Outcome};
+
+/// `CompiledApp` type.
+///
+/// App made with `App::compile`, running command lines like the app does
+/// with the flag lookup tables of its commands, the flags they inherit and
+/// their help texts built once instead of on every run. The app cannot be
+/// changed while compiled, `into_app` gives it back to add to it and compile
+/// it again.
+///
+/// Help texts are kept only while stdout is not a terminal, since on a
+/// terminal they are wrapped to its width, which may change between runs.
+///
+/// Example
+///
+/// ```
+/// use seahorse::{App, Command, Context, Flag, FlagType};
+///
+/// let app = App::new("cli")
+///     .command(
+///         Command::new("add")
+///             .flag(Flag::new("name", FlagType::String))
+///             .action(|c: &Context| assert!(c.string_flag("name").is_ok())),
+///     )
+///     .compile();
+///
+/// for name in &["a", "b", "c"] {
+///     let args = vec!["cli".to_string(), "add".to_string(), format!("--name={}", name)];
+///     assert_eq!(app.try_run(args), Ok(0));
+/// }
+///
+/// let app = app.into_app().command(Command::new("remove")).compile();
+/// ```
+pub struct CompiledApp {
+    app: App,
+    compiled: Compiled,
+}
+
+impl CompiledApp {
+    pub(crate) fn new(app: App) -> Self {
+        let compiled = Compiled::new(&app);
+        Self { app, compiled }
+    }
+
+    /// App the compiled app runs
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    /// App the compiled app runs, to change and compile again
+    pub fn into_app(self) -> App {
+        self.app
+    }
+
+    /// Run app like `App::run`
+    pub fn run(&self, args: Vec<String>) {
+        let color = self.app.color_choice(&args).unwrap_or(self.app.color);
+        self.app.exit(self.try_run(args), color);
+    }
+
+    /// Run app like `App::try_run`
+    pub fn try_run(&self, args: Vec<String>) -> Result<i32, Error> {
+        self.app.try_run_compiled(args, Some(&self.compiled))
+    }
+
+    /// Run app like `App::handle`
+    pub fn handle(&self, args: Vec<String>) -> Outcome {
+        self.app.handle_compiled(args, Some(&self.compiled))
+    }
+}
+
+/// Parts of the app built by `App::compile`, by command path
+pub(crate) struct Compiled {
+    /// Name of the app in the paths the help texts are written for
+    bin: String,
+    /// Whether help texts are kept, written when stdout was not a terminal
+    help_texts: bool,
+    /// Entries of the app action and of the commands with an action, by
+    /// path after the app name
+    entries: HashMap<Vec<String>, Entry>,
+}
+
+struct Entry {
+    /// Long help text of the command
+    help_text: Arc<str>,
+    /// Flags of the command followed by the global flags it inherits
+    flags: Vec<Flag>,
+    /// Positions of those flags by name and alias
+    index: FlagIndex<String>,
+}
+
+impl Compiled {
+    fn new(app: &App) -> Self {
+        let mut compiled = Self {
+            bin: app.display_name().to_string(),
+            help_texts: !terminal::is_terminal(Stream::Stdout),
+            entries: HashMap::new(),
+        };
+        app.compile_into(&mut compiled);
+        compiled
+    }
+
+    /// Path of the app, the start of the command paths
+    pub(crate) fn bin(&self) -> &str {
+        &self.bin
+    }
+
+    /// Whether help texts are kept, so that help texts of the entries need
+    /// not be written
+    pub(crate) fn keeps_help_texts(&self) -> bool {
+        self.help_texts
+    }
+
+    /// Add the entry of the command at the path, starting with the app name
+    pub(crate) fn insert(&mut self, path: &[String], help_text: String, flags: Vec<Flag>) {
+        let entry = Entry {
+            help_text: help_text.into(),
+            index: FlagIndex::owned(&flags),
+            flags,
+        };
+        self.entries.insert(path[1..].to_vec(), entry);
+    }
+
+    fn entry(&self, path: &[String]) -> Option<&Entry> {
+        self.entries.get(path.get(1..)?)
+    }
+
+    /// Long help text of the command at the path, when it can be shown
+    /// as kept
+    pub(crate) fn help_text(&self, path: &[String]) -> Option<Arc<str>> {
+        if !self.help_texts
+            || path.first() != Some(&self.bin)
+            || terminal::is_terminal(Stream::Stdout)
+        {
+            return None;
+        }
+        self.entry(path).map(|entry| entry.help_text.clone())
+    }
+
+    /// Flags of the command at the path followed by the global flags it
+    /// inherits
+    pub(crate) fn flags(&self, path: &[String]) -> Option<&[Flag]> {
+        self.entry(path).map(|entry| entry.flags.as_slice())
+    }
+
+    /// Positions of the flags of the command at the path by name and alias,
+    /// which config files leave as they are
+    pub(crate) fn index(&self, path: &[String]) -> Option<&FlagIndex<String>> {
+        self.entry(path).map(|entry| &entry.index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Compiled;
+    use crate::terminal::{self, Stream};
+    use crate::{App, Command, Context, DefaultMessages, Flag, FlagType};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn compile_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let push = |name: &'static str| {
+            let log = log.clone();
+            move |c: &Context| {
+                log.lock().unwrap().push(format!(
+                    "{} {:?} {} {:?}",
+                    name,
+                    c.string_flag("name").ok(),
+                    c.bool_flag("verbose"),
+                    c.args
+                ))
+            }
+        };
+        let app = || {
+            App::new("cli")
+                .flag(Flag::new("verbose", FlagType::Bool).alias("v").global())
+                .action(push("cli"))
+                .command(
+                    Command::new("remote").command(
+                        Command::new("add")
+                            .alias("a")
+                            .flag(Flag::new("name", FlagType::String).alias("n"))
+                            .action(push("add")),
+                    ),
+                )
+        };
+        let lines: &[&[&str]] = &[
+            &["cli", "-v", "x"],
+            &["cli", "remote", "add", "-n", "origin", "-v", "url"],
+            &["cli", "remote", "a", "--name=upstream"],
+            &["cli", "remote", "add", "--nme", "x"],
+            &["cli", "remote", "nope"],
+            &["cli", "remote", "add", "--help"],
+        ];
+        let args = |line: &[&str]| line.iter().map(|a| a.to_string()).collect();
+
+        let expected: Vec<_> = lines.iter().map(|line| app().try_run(args(line))).collect();
+        let expected_log = std::mem::replace(&mut *log.lock().unwrap(), Vec::new());
+        let compiled = app().compile();
+        for _ in 0..2 {
+            let results: Vec<_> = lines
+                .iter()
+                .map(|line| compiled.try_run(args(line)))
+                .collect();
+            assert_eq!(results, expected);
+            assert_eq!(
+                std::mem::replace(&mut *log.lock().unwrap(), Vec::new()),
+                expected_log
+            );
+        }
+        assert_eq!(
+            expected_log,
+            vec![
+                r#"cli None true ["x"]"#,
+                r#"add Some("origin") true ["url"]"#,
+                r#"add Some("upstream") false []"#,
+                r#"add None false ["--nme", "x"]"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn help_text_test() {
+        let app = App::new("cli").command(
+            Command::new("add")
+                .description("Add a remote")
+                .flag(Flag::new("name", FlagType::String))
+                .action(|_: &Context| {}),
+        );
+        let compiled = Compiled::new(&app);
+        let path = |line: &[&str]| -> Vec<String> { line.iter().map(|a| a.to_string()).collect() };
+
+        let expected = app.commands()[0].help_text_at(
+            &path(&["cli", "add"]),
+            &app.help_flag,
+            app.flags(),
+            None,
+            &DefaultMessages,
+        );
+        if compiled.keeps_help_texts() && !terminal::is_terminal(Stream::Stdout) {
+            assert_eq!(
+                compiled
+                    .help_text(&path(&["cli", "add"]))
+                    .as_ref()
+                    .map(|text| &**text),
+                Some(expected.as_str())
+            );
+        }
+        assert_eq!(compiled.help_text(&path(&["other", "add"])), None);
+        assert_eq!(compiled.help_text(&path(&["cli", "remove"])), None);
+        assert_eq!(
+            compiled.flags(&path(&["cli", "add"])).map(<[Flag]>::len),
+            Some(1)
+        );
+    }
+}
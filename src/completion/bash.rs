@@ -0,0 +1,152 @@
+use std::io::{self, Write};
+
+use super::{escape, ident, quote, CommandSpec, ValueHint};
+
+/// Candidates printed by the `__complete` command of the app
+const DYNAMIC: &str =
+    "$(\"${COMP_WORDS[0]}\" __complete \"$((COMP_CWORD - 1))\" \"${COMP_WORDS[@]:1}\")";
+
+/// Write the bash completion script
+pub(crate) fn write<W: Write + ?Sized>(spec: &CommandSpec, w: &mut W) -> io::Result<()> {
+    let root = ident(&spec.path);
+
+    writeln!(w, "_{}() {{", root)?;
+    writeln!(w, "    local i cur prev cmd opts")?;
+    writeln!(w, "    COMPREPLY=()")?;
+    writeln!(w, "    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"")?;
+    writeln!(w, "    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"")?;
+    writeln!(w, "    cmd=\"{}\"", root)?;
+    writeln!(w)?;
+    // `--name=value` is split into `--name`, `=` and the value
+    writeln!(w, "    if [[ \"${{cur}}\" == \"=\" ]]; then")?;
+    writeln!(w, "        cur=\"\"")?;
+    writeln!(
+        w,
+        "    elif [[ \"${{prev}}\" == \"=\" && ${{COMP_CWORD}} -gt 1 ]]; then"
+    )?;
+    writeln!(w, "        prev=\"${{COMP_WORDS[COMP_CWORD-2]}}\"")?;
+    writeln!(w, "    fi")?;
+    writeln!(w)?;
+    writeln!(w, "    for ((i = 1; i < COMP_CWORD; i++)); do")?;
+    writeln!(w, "        case \"${{cmd}},${{COMP_WORDS[i]}}\" in")?;
+    for command in spec.walk() {
+        let parent = ident(&command.path);
+        for sub in &command.commands {
+            let patterns = sub
+                .names
+                .iter()
+                .map(|n| quote(&format!("{},{}", parent, n)))
+                .collect::<Vec<String>>()
+                .join(" | ");
+            writeln!(w, "            {})", patterns)?;
+            writeln!(w, "                cmd=\"{}\"", ident(&sub.path))?;
+            writeln!(w, "                ;;")?;
+        }
+    }
+    writeln!(w, "        esac")?;
+    writeln!(w, "    done")?;
+    writeln!(w)?;
+    writeln!(w, "    case \"${{cmd}}\" in")?;
+    for command in spec.walk() {
+        write_command(command, w)?;
+    }
+    writeln!(w, "    esac")?;
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+    writeln!(w, "complete -F _{} {}", root, quote(&spec.path[0]))
+}
+
+fn write_command<W: Write + ?Sized>(command: &CommandSpec, w: &mut W) -> io::Result<()> {
+    let mut words: Vec<String> = Vec::new();
+    for sub in &command.commands {
+        words.extend(sub.names.iter().cloned());
+    }
+    for flag in &command.flags {
+        words.push(format!("--{}", flag.name));
+        words.extend(flag.alias.iter().map(|a| format!("-{}", a)));
+    }
+
+    writeln!(w, "        {})", ident(&command.path))?;
+    writeln!(w, "            opts={}", quote(&words.join(" ")))?;
+
+    let value_flags: Vec<_> = command.flags.iter().filter(|f| f.takes_value).collect();
+    if !value_flags.is_empty() {
+        writeln!(w, "            case \"${{prev}}\" in")?;
+        for flag in value_flags {
+            let patterns = std::iter::once(format!("--{}", flag.name))
+                .chain(flag.alias.iter().map(|a| format!("-{}", a)))
+                .map(|p| quote(&p))
+                .collect::<Vec<String>>()
+                .join(" | ");
+            writeln!(w, "                {})", patterns)?;
+            if flag.choices.iter().any(|c| escape(c) != *c) {
+                // Read the choices a line each, escaping them again once matched
+                let choices: Vec<String> = flag.choices.iter().map(|c| quote(c)).collect();
+                writeln!(w, "                    local IFS=$'\\n'")?;
+                writeln!(
+                    w,
+                    "                    COMPREPLY=($(compgen -W \"$(printf '%q\\n' {})\" -- \"${{cur}}\"))",
+                    choices.join(" ")
+                )?;
+                writeln!(
+                    w,
+                    "                    [[ ${{#COMPREPLY[@]}} -gt 0 ]] && COMPREPLY=($(printf '%q\\n' \"${{COMPREPLY[@]}}\"))"
+                )?;
+            } else if !flag.choices.is_empty() {
+                writeln!(
+                    w,
+                    "                    COMPREPLY=($(compgen -W {} -- \"${{cur}}\"))",
+                    quote(&flag.choices.join(" "))
+                )?;
+            } else if flag.dynamic {
+                writeln!(w, "                    COMPREPLY=({})", DYNAMIC)?;
+            } else if let Some(hint) = flag.hint {
+                for line in hint_lines(hint) {
+                    writeln!(w, "                    {}", line)?;
+                }
+            }
+            writeln!(w, "                    return 0")?;
+            writeln!(w, "                    ;;")?;
+        }
+        writeln!(w, "            esac")?;
+    }
+
+    if command.dynamic_args {
+        writeln!(w, "            COMPREPLY=({})", DYNAMIC)?;
+    } else if let (Some(hint), true) = (command.args_hint, command.commands.is_empty()) {
+        // Flags are still completed after a dash
+        writeln!(w, "            if [[ \"${{cur}}\" == -* ]]; then")?;
+        writeln!(
+            w,
+            "                COMPREPLY=($(compgen -W \"${{opts}}\" -- \"${{cur}}\"))"
+        )?;
+        writeln!(w, "            else")?;
+        for line in hint_lines(hint) {
+            writeln!(w, "                {}", line)?;
+        }
+        writeln!(w, "            fi")?;
+    } else {
+        writeln!(
+            w,
+            "            COMPREPLY=($(compgen -W \"${{opts}}\" -- \"${{cur}}\"))"
+        )?;
+    }
+    writeln!(w, "            ;;")
+}
+
+/// Lines completing a value of the hint, files being completed by readline
+/// itself for any path
+fn hint_lines(hint: ValueHint) -> &'static [&'static str] {
+    match hint {
+        ValueHint::AnyPath => &["compopt -o default 2>/dev/null", "COMPREPLY=()"],
+        ValueHint::FilePath | ValueHint::ExecutablePath => &[
+            "compopt -o filenames 2>/dev/null",
+            "COMPREPLY=($(compgen -f -- \"${cur}\"))",
+        ],
+        ValueHint::DirPath => &[
+            "compopt -o filenames 2>/dev/null",
+            "COMPREPLY=($(compgen -d -- \"${cur}\"))",
+        ],
+        ValueHint::Other => &["COMPREPLY=()"],
+    }
+}
@@ -0,0 +1,262 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{help, App, Command, Context, Flag, FlagType, HelpFlag, VersionFlag};
+
+/// Callback supplying completion candidates known only at runtime
+///
+/// It receives the context parsed from the words before the cursor
+/// and the prefix being completed.
+///
+/// Example
+///
+/// ```
+/// use seahorse::{Completer, Context};
+///
+/// let completer = Completer::from(|_: &Context, prefix: &str| {
+///     vec!["alpha".to_string(), "beta".to_string()]
+/// });
+/// ```
+#[derive(Clone)]
+pub struct Completer {
+    inner: Arc<CompleterFn>,
+}
+
+type CompleterFn = dyn Fn(&Context, &str) -> Vec<String> + Send + Sync;
+
+impl Completer {
+    pub fn complete(&self, context: &Context, prefix: &str) -> Vec<String> {
+        (self.inner)(context, prefix)
+    }
+}
+
+impl fmt::Debug for Completer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Completer")
+    }
+}
+
+impl<F> From<F> for Completer
+where
+    for<'a, 'b> F: Fn(&'a Context, &'b str) -> Vec<String> + Send + Sync + 'static,
+{
+    fn from(value: F) -> Self {
+        Self {
+            inner: Arc::new(value),
+        }
+    }
+}
+
+/// Flags, sub commands and argument completer of the app or a command
+struct Level<'a> {
+    flags: &'a Option<Vec<Flag>>,
+    commands: &'a Option<Vec<Arc<Command>>>,
+    args_completer: &'a Option<Completer>,
+    alphabetical: bool,
+    /// Version flag of the app, `None` when it has no version
+    version_flag: Option<&'a VersionFlag>,
+    help_flag: &'a HelpFlag,
+}
+
+impl<'a> Level<'a> {
+    fn find_command(&self, word: &str) -> Option<&'a Command> {
+        self.commands
+            .iter()
+            .flatten()
+            .find(|c| c.name == word || c.all_aliases().any(|a| a == word))
+            .map(|c| &**c)
+    }
+
+    fn find_flag(&self, word: &str) -> Option<&'a Flag> {
+        self.flags.iter().flatten().find(|f| f.is_named_by(word))
+    }
+
+    fn flag_words(&self) -> Vec<String> {
+        let mut words = Vec::new();
+        for flag in help::visible(self.flags, self.alphabetical) {
+            words.push(format!("--{}", flag.name));
+            if flag.is_short_name() {
+                words.push(format!("-{}", flag.name));
+            }
+            words.extend(flag.alias.iter().flatten().map(|a| format!("-{}", a)));
+        }
+        words.extend(self.help_flag.words());
+        words.extend(self.version_flag.iter().flat_map(|flag| flag.words()));
+        words
+    }
+
+    fn command_words(&self) -> Vec<String> {
+        let mut words = Vec::new();
+        for command in help::visible(self.commands, self.alphabetical) {
+            words.push(command.name.clone());
+            words.extend(command.alias.iter().flatten().cloned());
+        }
+        words
+    }
+}
+
+/// Completion candidates for the word at `position` among the words
+/// following the app name, as printed by the `__complete` command
+pub(crate) fn candidates(app: &App, position: usize, words: &[String]) -> Vec<String> {
+    let position = position.min(words.len());
+    let current = words.get(position).map_or("", String::as_str);
+
+    let mut level = Level {
+        flags: &app.flags,
+        commands: &app.commands,
+        args_completer: &app.args_completer,
+        alphabetical: app.sort_help_alphabetically,
+        version_flag: Some(&app.version_flag)
+            .filter(|_| app.version.is_some() || app.long_version.is_some()),
+        help_flag: &app.help_flag,
+    };
+    let mut level_words: Vec<String> = Vec::new();
+    let mut pending: Option<&Flag> = None;
+
+    for word in &words[..position] {
+        if pending.take().is_some() {
+            level_words.push(word.clone());
+            continue;
+        }
+
+        if level_words.is_empty() {
+            if let Some(command) = level.find_command(word) {
+                level = Level {
+                    flags: &command.flags,
+                    commands: &command.commands,
+                    args_completer: &command.args_completer,
+                    alphabetical: command.sort_help_alphabetically,
+                    version_flag: None,
+                    help_flag: command.help_flag.or(level.help_flag),
+                };
+                continue;
+            }
+        }
+
+        if let Some(flag) = level.find_flag(word) {
+            if flag.flag_type != FlagType::Bool {
+                pending = Some(flag);
+            }
+        }
+        level_words.push(word.clone());
+    }
+
+    let flags = level.flags.as_ref().map_or(&[][..], Vec::as_slice);
+    let context = || Context::with_flags(level_words.clone(), flags, String::new());
+
+    if let Some(flag) = pending {
+        return values(flag, &context(), current);
+    }
+
+    if current.starts_with("--") && current.contains('=') {
+        let mut split = current.splitn(2, '=');
+        let name = split.next().unwrap_or_default();
+        let prefix = split.next().unwrap_or_default();
+        return match level.find_flag(name) {
+            Some(flag) if flag.flag_type != FlagType::Bool => values(flag, &context(), prefix)
+                .into_iter()
+                .map(|value| format!("{}={}", name, value))
+                .collect(),
+            _ => Vec::new(),
+        };
+    }
+
+    let mut candidates = if current.starts_with('-') {
+        level.flag_words()
+    } else {
+        let mut words = if level_words.is_empty() {
+            level.command_words()
+        } else {
+            Vec::new()
+        };
+        if let Some(completer) = level.args_completer {
+            words.extend(completer.complete(&context(), current));
+        }
+        words
+    };
+
+    candidates.retain(|c| c.starts_with(current));
+    candidates
+}
+
+/// Values of the flag starting with the prefix
+fn values(flag: &Flag, context: &Context, prefix: &str) -> Vec<String> {
+    let mut values = match (&flag.choices, &flag.completer) {
+        (Some(choices), _) => choices.clone(),
+        (None, Some(completer)) => completer.complete(context, prefix),
+        (None, None) => Vec::new(),
+    };
+    values.retain(|v| v.starts_with(prefix));
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::candidates;
+    use crate::{App, Command, Context, Flag, FlagType};
+
+    fn app() -> App {
+        App::new("cli")
+            .version("1.0.0")
+            .flag(Flag::new("verbose", FlagType::Bool).alias("v"))
+            .flag(Flag::new("debug", FlagType::Bool).hidden())
+            .command(
+                Command::new("deploy")
+                    .alias("d")
+                    .flag(
+                        Flag::new("project", FlagType::String)
+                            .alias("p")
+                            .complete_with(|_: &Context, _: &str| {
+                                vec!["api".to_string(), "app".to_string(), "web".to_string()]
+                            }),
+                    )
+                    .flag(Flag::new("format", FlagType::String).choices(&["json", "yaml"]))
+                    .flag(Flag::new("force", FlagType::Bool))
+                    .complete_args_with(|c: &Context, _: &str| {
+                        if c.bool_flag("force") {
+                            vec!["production".to_string()]
+                        } else {
+                            vec!["staging".to_string(), "production".to_string()]
+                        }
+                    }),
+            )
+            .command(Command::new("destroy"))
+            .command(Command::new("secret").hidden())
+    }
+
+    fn complete(position: usize, words: &[&str]) -> Vec<String> {
+        let words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        candidates(&app(), position, &words)
+    }
+
+    #[test]
+    fn command_candidates_test() {
+        assert_eq!(complete(0, &[]), vec!["deploy", "d", "destroy"]);
+        assert_eq!(complete(0, &["de"]), vec!["deploy", "destroy"]);
+        assert_eq!(
+            complete(0, &["-"]),
+            vec!["--verbose", "-v", "--help", "-h", "--version", "-V"]
+        );
+        assert_eq!(complete(1, &["deploy"]), vec!["staging", "production"]);
+        assert!(complete(1, &["destroy"]).is_empty());
+    }
+
+    #[test]
+    fn flag_candidates_test() {
+        assert_eq!(
+            complete(1, &["d", "--"]),
+            vec!["--project", "--format", "--force", "--help"]
+        );
+        assert_eq!(complete(2, &["deploy", "-p", "a"]), vec!["api", "app"]);
+        assert_eq!(complete(2, &["deploy", "--format"]), vec!["json", "yaml"]);
+        assert_eq!(
+            complete(1, &["deploy", "--format=y"]),
+            vec!["--format=yaml"]
+        );
+        assert_eq!(complete(3, &["deploy", "--force", "p"]), vec!["production"]);
+        assert_eq!(
+            complete(3, &["deploy", "-p", "api", "s", "ignored"]),
+            vec!["staging"]
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use std::io::{self, Write};
+
+use super::{escape, ident, quote, CommandSpec, ValueHint};
+
+/// Write the fish completion script
+pub(crate) fn write<W: Write + ?Sized>(spec: &CommandSpec, w: &mut W) -> io::Result<()> {
+    if spec.is_dynamic() {
+        writeln!(w, "function {}", dynamic(spec))?;
+        writeln!(w, "    set -l tokens (commandline -opc)")?;
+        writeln!(w, "    set -e tokens[1]")?;
+        writeln!(
+            w,
+            "    {} __complete (count $tokens) $tokens (commandline -ct)",
+            quote(&spec.path[0])
+        )?;
+        writeln!(w, "end")?;
+        writeln!(w)?;
+    }
+
+    write_command(spec, spec, &[], w)
+}
+
+/// Name of the function completing with the `__complete` command
+fn dynamic(spec: &CommandSpec) -> String {
+    format!("__fish_{}_complete", ident(&spec.path))
+}
+
+/// Write completions of the command, `ancestors` being the names of the
+/// commands between the app and this command
+fn write_command<W: Write + ?Sized>(
+    spec: &CommandSpec,
+    command: &CommandSpec,
+    ancestors: &[&[String]],
+    w: &mut W,
+) -> io::Result<()> {
+    let bin = &spec.path[0];
+    let dynamic = quote(&format!("({})", dynamic(spec)));
+    let condition = condition(command, ancestors);
+    let prefix = match &condition {
+        Some(condition) => format!("complete -c {} -n {}", quote(bin), quote(condition)),
+        None => format!("complete -c {}", quote(bin)),
+    };
+
+    for sub in &command.commands {
+        for name in &sub.names {
+            let mut line = format!("{} -f -a {}", prefix, quote(name));
+            if let Some(description) = &sub.description {
+                line += &format!(" -d {}", quote_escaped(description));
+            }
+            writeln!(w, "{}", line)?;
+        }
+    }
+
+    for flag in &command.flags {
+        let mut line = format!("{} -l {}", prefix, quote(&flag.name));
+        for alias in &flag.alias {
+            if alias.chars().count() == 1 {
+                line += &format!(" -s {}", quote(alias));
+            } else {
+                line += &format!(" -o {}", quote(alias));
+            }
+        }
+        if flag.takes_value {
+            line += " -r";
+        }
+        if !flag.choices.is_empty() {
+            let choices: Vec<String> = flag.choices.iter().map(|c| escape(c)).collect();
+            line += &format!(" -f -a {}", quote_escaped(&choices.join(" ")));
+        } else if flag.dynamic {
+            line += &format!(" -f -a {}", dynamic);
+        } else if let Some(hint) = flag.hint {
+            line += hint_options(hint);
+        }
+        if let Some(description) = &flag.description {
+            line += &format!(" -d {}", quote_escaped(description));
+        }
+        writeln!(w, "{}", line)?;
+    }
+
+    if command.dynamic_args {
+        writeln!(w, "{} -f -a {}", prefix, dynamic)?;
+    } else if let (Some(hint), true) = (command.args_hint, command.commands.is_empty()) {
+        writeln!(w, "{}{}", prefix, hint_options(hint))?;
+    }
+
+    let mut ancestors = ancestors.to_vec();
+    if command.path.len() > 1 {
+        ancestors.push(&command.names);
+    }
+    for sub in &command.commands {
+        write_command(spec, sub, &ancestors, w)?;
+    }
+
+    Ok(())
+}
+
+/// Options of `complete` completing a value of the hint
+fn hint_options(hint: ValueHint) -> &'static str {
+    match hint {
+        ValueHint::AnyPath | ValueHint::FilePath | ValueHint::ExecutablePath => " --force-files",
+        ValueHint::DirPath => " -f -a '(__fish_complete_directories)'",
+        ValueHint::Other => " -f",
+    }
+}
+
+/// Quote a word with single quotes, fish reading `\\` and `\'` in them as
+/// escapes
+fn quote_escaped(word: &str) -> String {
+    format!("'{}'", word.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Condition under which the command is the one being completed
+fn condition(command: &CommandSpec, ancestors: &[&[String]]) -> Option<String> {
+    let mut conditions: Vec<String> = ancestors
+        .iter()
+        .map(|names| format!("__fish_seen_subcommand_from {}", names.join(" ")))
+        .collect();
+
+    if command.path.len() > 1 {
+        conditions.push(format!(
+            "__fish_seen_subcommand_from {}",
+            command.names.join(" ")
+        ));
+    }
+
+    if !command.commands.is_empty() {
+        if conditions.is_empty() {
+            conditions.push("__fish_use_subcommand".to_string());
+        } else {
+            let names: Vec<String> = command
+                .commands
+                .iter()
+                .flat_map(|c| c.names.iter().cloned())
+                .collect();
+            conditions.push(format!(
+                "not __fish_seen_subcommand_from {}",
+                names.join(" ")
+            ));
+        }
+    }
+
+    if conditions.is_empty() {
+        None
+    } else {
+        Some(conditions.join("; and "))
+    }
+}
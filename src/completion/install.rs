@@ -0,0 +1,126 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use super::Shell;
+use crate::action::Dispatch;
+use crate::error::{Error, ErrorKind};
+use crate::{App, Arg, Command, Context, Flag, FlagType};
+
+/// Per-user file the shell loads the completion script of the binary from,
+/// `None` for PowerShell, which has no such directory, or without `HOME`
+pub(crate) fn path(shell: Shell, bin: &str) -> Option<PathBuf> {
+    let var = |name: &str| {
+        env::var_os(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+    let home = || var("HOME");
+    let xdg = |name: &str, default: &str| var(name).or_else(|| Some(home()?.join(default)));
+    match shell {
+        Shell::Bash => xdg("XDG_DATA_HOME", ".local/share")
+            .map(|dir| dir.join("bash-completion").join("completions").join(bin)),
+        Shell::Zsh => home().map(|dir| dir.join(".zfunc").join(format!("_{}", bin))),
+        Shell::Fish => xdg("XDG_CONFIG_HOME", ".config").map(|dir| {
+            dir.join("fish")
+                .join("completions")
+                .join(format!("{}.fish", bin))
+        }),
+        Shell::PowerShell => None,
+    }
+}
+
+/// Write the completion script of the app to the file of the shell, making
+/// its directory, over a script already there only when `force` is set
+pub(crate) fn install(app: &App, shell: Shell, force: bool) -> Result<PathBuf, Error> {
+    let path = match path(shell, app.display_name()) {
+        Some(path) => path,
+        None => {
+            let name = shell.to_string();
+            return Err(Error::new(ErrorKind::Completion, move |m| {
+                m.no_completion_dir(&name)
+            }));
+        }
+    };
+    let file = path.display().to_string();
+    let failed = |e: io::Error| {
+        let (path, reason) = (file.clone(), e.to_string());
+        Error::new(ErrorKind::Completion, move |m| {
+            m.completion_failed(&path, &reason)
+        })
+        .token(file.as_str())
+        .cause(e)
+    };
+
+    let mut script = Vec::new();
+    app.generate_completion(shell, &mut script)
+        .map_err(&failed)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(&failed)?;
+    }
+    let mut options = OpenOptions::new();
+    if force {
+        options.write(true).create(true).truncate(true);
+    } else {
+        options.write(true).create_new(true);
+    }
+    let mut out = options.open(&path).map_err(|e| match e.kind() {
+        io::ErrorKind::AlreadyExists => {
+            let path = file.clone();
+            Error::new(ErrorKind::Completion, move |m| m.completion_exists(&path))
+                .token(file.as_str())
+        }
+        _ => failed(e),
+    })?;
+    out.write_all(&script).map_err(&failed)?;
+    Ok(path)
+}
+
+/// `completions` command with the `install <shell> [--force]` subcommand
+pub(crate) fn command() -> Command {
+    let shells = |value: &str| value.parse::<Shell>().map(|_| ());
+    Command::new("completions")
+        .description("Manage shell completions")
+        .command(
+            Command::new("install")
+                .description("Install the completion script for the shell")
+                .arg(
+                    Arg::new("shell", FlagType::String)
+                        .description("bash, zsh or fish")
+                        .required()
+                        .validator(shells),
+                )
+                .flag(
+                    Flag::new("force", FlagType::Bool).description("Overwrite an installed script"),
+                ),
+        )
+}
+
+/// Whether the command at the path is the `install` subcommand of a
+/// `completions` command of the app, which the app runs when it has no action
+pub(crate) fn mounted(path: &[String]) -> bool {
+    match path {
+        [_, completions, install] => completions == "completions" && install == "install",
+        _ => false,
+    }
+}
+
+/// Install the completion script for the shell the context of the install
+/// command names, telling where it went
+pub(crate) fn run(app: &App, context: &Context) -> Result<Dispatch, Error> {
+    // Checked by the validator of the argument
+    let shell = match context.string_arg("shell").map(|shell| shell.parse()) {
+        Ok(Ok(shell)) => shell,
+        _ => return Ok(Dispatch::Done(1)),
+    };
+    let path = install(app, shell, context.bool_flag("force"))?;
+    let messages = app.message_catalog();
+    let mut text = messages.completion_installed(&path.display().to_string());
+    if shell == Shell::Zsh {
+        text += "\n";
+        text += &messages.zsh_fpath_hint();
+    }
+    context.streams().out(&(text + "\n"));
+    Ok(Dispatch::Done(0))
+}
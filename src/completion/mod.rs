@@ -0,0 +1,310 @@
+mod bash;
+mod dynamic;
+mod fish;
+pub(crate) mod install;
+mod powershell;
+mod zsh;
+
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::Arc;
+
+pub(crate) use self::dynamic::candidates;
+pub use self::dynamic::Completer;
+
+use crate::messages::Messages;
+use crate::{help, App, Arg, Command, Flag, FlagType, HelpFlag};
+
+/// Shell type for completion scripts
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    PowerShell,
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+            Shell::PowerShell => "powershell",
+        })
+    }
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            "powershell" => Ok(Shell::PowerShell),
+            _ => Err(format!(
+                "expected bash, zsh, fish or powershell, got \"{}\"",
+                s
+            )),
+        }
+    }
+}
+
+/// Kind of value a flag or argument takes, telling completion scripts what
+/// to complete it with
+///
+/// PowerShell falls back to its own path completion for flags with a path
+/// hint, without telling files and directories apart.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ValueHint {
+    /// Path to a file or a directory
+    AnyPath,
+    /// Path to a file
+    FilePath,
+    /// Path to a directory
+    DirPath,
+    /// Path to an executable file
+    ExecutablePath,
+    /// Value completed with nothing, paths included
+    Other,
+}
+
+/// Command model shared by the completion backends
+pub(crate) struct CommandSpec {
+    /// Command path from the app name
+    pub path: Vec<String>,
+    /// Command name followed by the command alias
+    pub names: Vec<String>,
+    /// Description on one line
+    pub description: Option<String>,
+    pub flags: Vec<FlagSpec>,
+    pub commands: Vec<CommandSpec>,
+    /// Arguments are completed by the `__complete` command
+    pub dynamic_args: bool,
+    /// Hint of the first declared argument with one, completing arguments
+    pub args_hint: Option<ValueHint>,
+}
+
+/// Flag model shared by the completion backends
+pub(crate) struct FlagSpec {
+    pub name: String,
+    pub alias: Vec<String>,
+    /// Description, or the usage of flags without one, on one line
+    pub description: Option<String>,
+    pub takes_value: bool,
+    pub choices: Vec<String>,
+    /// Values are completed by the `__complete` command
+    pub dynamic: bool,
+    /// Hint of values without choices completed by the shell
+    pub hint: Option<ValueHint>,
+}
+
+impl CommandSpec {
+    fn from_app(app: &App) -> Self {
+        let path = vec![app.display_name().to_string()];
+        let messages = app.message_catalog();
+        let mut flags = FlagSpec::from_flags(
+            &app.flags,
+            app.sort_help_alphabetically,
+            &app.help_flag,
+            messages,
+        );
+        if let (true, Some((name, short))) = (
+            app.version.is_some() || app.long_version.is_some(),
+            app.version_flag.names(),
+        ) {
+            flags.push(FlagSpec {
+                name: name.to_string(),
+                alias: short.iter().map(char::to_string).collect(),
+                description: Some(messages.version_flag_description()),
+                takes_value: false,
+                choices: Vec::new(),
+                dynamic: false,
+                hint: None,
+            });
+        }
+
+        Self {
+            commands: Self::from_commands(
+                &path,
+                &app.commands,
+                app.sort_help_alphabetically,
+                &app.help_flag,
+                messages,
+            ),
+            names: path.clone(),
+            path,
+            description: app.description.as_ref().map(|d| one_line(d)),
+            flags,
+            dynamic_args: app.args_completer.is_some(),
+            args_hint: None,
+        }
+    }
+
+    fn from_command(
+        parent_path: &[String],
+        command: &Command,
+        parent_help_flag: &HelpFlag,
+        messages: &dyn Messages,
+    ) -> Self {
+        let help_flag = command.help_flag.or(parent_help_flag);
+        let mut path = parent_path.to_vec();
+        path.push(command.name.clone());
+
+        let mut names = vec![command.name.clone()];
+        if let Some(alias) = &command.alias {
+            names.extend(alias.iter().cloned());
+        }
+
+        Self {
+            commands: Self::from_commands(
+                &path,
+                &command.commands,
+                command.sort_help_alphabetically,
+                help_flag,
+                messages,
+            ),
+            path,
+            names,
+            description: command.description.as_ref().map(|d| one_line(d)),
+            flags: FlagSpec::from_flags(
+                &command.flags,
+                command.sort_help_alphabetically,
+                help_flag,
+                messages,
+            ),
+            dynamic_args: command.args_completer.is_some(),
+            args_hint: command
+                .args
+                .iter()
+                .flatten()
+                .filter_map(Arg::completion_hint)
+                .next(),
+        }
+    }
+
+    fn from_commands(
+        parent_path: &[String],
+        commands: &Option<Vec<Arc<Command>>>,
+        alphabetical: bool,
+        help_flag: &HelpFlag,
+        messages: &dyn Messages,
+    ) -> Vec<Self> {
+        help::visible(commands, alphabetical)
+            .into_iter()
+            .map(|c| Self::from_command(parent_path, c, help_flag, messages))
+            .collect()
+    }
+
+    /// Whether any command or flag is completed by the `__complete` command
+    pub fn is_dynamic(&self) -> bool {
+        self.walk()
+            .iter()
+            .any(|c| c.dynamic_args || c.flags.iter().any(|f| f.dynamic))
+    }
+
+    /// Iterate over this command and all of its sub commands, depth first
+    pub fn walk(&self) -> Vec<&CommandSpec> {
+        let mut specs = vec![self];
+        for command in &self.commands {
+            specs.extend(command.walk());
+        }
+        specs
+    }
+}
+
+impl FlagSpec {
+    fn from_flags(
+        flags: &Option<Vec<Flag>>,
+        alphabetical: bool,
+        help_flag: &HelpFlag,
+        messages: &dyn Messages,
+    ) -> Vec<Self> {
+        let mut specs: Vec<Self> = help::visible(flags, alphabetical)
+            .into_iter()
+            .map(|f| Self {
+                name: f.name.clone(),
+                alias: if f.is_short_name() {
+                    std::iter::once(f.name.clone())
+                        .chain(f.alias.iter().flatten().cloned())
+                        .collect()
+                } else {
+                    f.alias.clone().unwrap_or_default()
+                },
+                description: f
+                    .description
+                    .as_ref()
+                    .or(f.usage.as_ref())
+                    .map(|d| one_line(d)),
+                takes_value: f.flag_type != FlagType::Bool,
+                choices: f.choices.clone().unwrap_or_default(),
+                dynamic: f.choices.is_none() && f.completer.is_some(),
+                hint: f.completion_hint(),
+            })
+            .collect();
+
+        if let Some((name, short)) = help_flag.names() {
+            specs.push(Self {
+                name: name.to_string(),
+                alias: short.iter().map(char::to_string).collect(),
+                description: Some(messages.help_flag_description()),
+                takes_value: false,
+                choices: Vec::new(),
+                dynamic: false,
+                hint: None,
+            });
+        }
+
+        specs
+    }
+}
+
+/// Write the completion script of the app for the shell
+pub(crate) fn write_app<W: Write + ?Sized>(app: &App, shell: Shell, w: &mut W) -> io::Result<()> {
+    let spec = CommandSpec::from_app(app);
+    match shell {
+        Shell::Bash => bash::write(&spec, w),
+        Shell::Zsh => zsh::write(&spec, w),
+        Shell::Fish => fish::write(&spec, w),
+        Shell::PowerShell => powershell::write(&spec, w),
+    }
+}
+
+/// Quote a word with single quotes
+pub(crate) fn quote(word: &str) -> String {
+    format!("'{}'", word.replace('\'', r"'\''"))
+}
+
+/// Escape the characters of a word the shell would split or expand with
+/// backslashes, for word lists expanded by the shell
+pub(crate) fn escape(word: &str) -> String {
+    let mut escaped = String::with_capacity(word.len());
+    for c in word.chars() {
+        if !(c.is_alphanumeric() || "-_./=:@+,%".contains(c)) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Join the lines of a description with spaces, completion menus showing
+/// a single line per candidate
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Make a shell identifier from a command path
+pub(crate) fn ident(path: &[String]) -> String {
+    path.iter()
+        .map(|p| {
+            p.chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("__")
+}
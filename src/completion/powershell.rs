@@ -0,0 +1,190 @@
+use std::io::{self, Write};
+
+use super::{CommandSpec, FlagSpec, ValueHint};
+
+/// Write the PowerShell completion script
+pub(crate) fn write<W: Write + ?Sized>(spec: &CommandSpec, w: &mut W) -> io::Result<()> {
+    let bin = &spec.path[0];
+
+    writeln!(w, "using namespace System.Management.Automation")?;
+    writeln!(w, "using namespace System.Management.Automation.Language")?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "Register-ArgumentCompleter -Native -CommandName {} -ScriptBlock {{",
+        quote(bin)
+    )?;
+    writeln!(
+        w,
+        "    param($wordToComplete, $commandAst, $cursorPosition)"
+    )?;
+    writeln!(w)?;
+    writeln!(w, "    $commandElements = $commandAst.CommandElements")?;
+    writeln!(w, "    $command = @(")?;
+    writeln!(w, "        {}", quote(bin))?;
+    writeln!(
+        w,
+        "        for ($i = 1; $i -lt $commandElements.Count; $i++) {{"
+    )?;
+    writeln!(w, "            $element = $commandElements[$i]")?;
+    writeln!(
+        w,
+        "            if ($element -isnot [StringConstantExpressionAst] -or"
+    )?;
+    writeln!(
+        w,
+        "                $element.StringConstantType -ne [StringConstantType]::BareWord -or"
+    )?;
+    writeln!(w, "                $element.Value.StartsWith('-') -or")?;
+    writeln!(w, "                $element.Value -eq $wordToComplete) {{")?;
+    writeln!(w, "                break")?;
+    writeln!(w, "            }}")?;
+    writeln!(w, "            $element.Value")?;
+    writeln!(w, "        }}) -join ';'")?;
+    writeln!(w)?;
+    writeln!(w, "    $index = $commandElements.Count - 1")?;
+    writeln!(w, "    if ($wordToComplete -ne '') {{ $index-- }}")?;
+    writeln!(w, "    $previous = $commandElements[$index].ToString()")?;
+    if spec.is_dynamic() {
+        writeln!(
+            w,
+            "    $words = @($commandElements | Select-Object -Skip 1 | ForEach-Object {{ $_.ToString() }})"
+        )?;
+    }
+    writeln!(w)?;
+    writeln!(w, "    $completions = @(switch ($command) {{")?;
+    for command in spec.walk() {
+        for key in keys(spec, &command.path) {
+            write_command(spec, command, &key, w)?;
+        }
+    }
+    writeln!(w, "    }})")?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "    $completions.Where{{ $_.CompletionText -like \"$wordToComplete*\" }} |"
+    )?;
+    writeln!(w, "        Sort-Object -Property ListItemText")?;
+    writeln!(w, "}}")
+}
+
+fn write_command<W: Write + ?Sized>(
+    spec: &CommandSpec,
+    command: &CommandSpec,
+    key: &str,
+    w: &mut W,
+) -> io::Result<()> {
+    let dynamic = format!(
+        "& {} __complete $index @words | ForEach-Object {{ {} }}",
+        quote(&spec.path[0]),
+        "[CompletionResult]::new($_, $_, [CompletionResultType]::ParameterValue, $_)"
+    );
+    writeln!(w, "        {} {{", quote(key))?;
+
+    if command.dynamic_args {
+        writeln!(w, "            {}", dynamic)?;
+        writeln!(w, "            break")?;
+        return writeln!(w, "        }}");
+    }
+
+    // Flags completing paths complete nothing, leaving paths to PowerShell
+    for flag in command
+        .flags
+        .iter()
+        .filter(|f| !f.choices.is_empty() || f.dynamic || completes_paths(f))
+    {
+        let condition = flag_words(flag)
+            .iter()
+            .map(|word| format!("$previous -eq {}", quote(word)))
+            .collect::<Vec<String>>()
+            .join(" -or ");
+        writeln!(w, "            if ({}) {{", condition)?;
+        if flag.dynamic {
+            writeln!(w, "                {}", dynamic)?;
+        }
+        for choice in &flag.choices {
+            writeln!(
+                w,
+                "                {}",
+                result(choice, choice, "ParameterValue", choice)
+            )?;
+        }
+        writeln!(w, "                break")?;
+        writeln!(w, "            }}")?;
+    }
+
+    for sub in &command.commands {
+        let tooltip = sub.description.as_ref().unwrap_or(&sub.names[0]);
+        for name in &sub.names {
+            writeln!(
+                w,
+                "            {}",
+                result(name, name, "ParameterValue", tooltip)
+            )?;
+        }
+    }
+
+    for flag in &command.flags {
+        let tooltip = flag.description.as_ref().unwrap_or(&flag.name);
+        for word in flag_words(flag) {
+            let list_item = word.trim_start_matches('-');
+            writeln!(
+                w,
+                "            {}",
+                result(&word, list_item, "ParameterName", tooltip)
+            )?;
+        }
+    }
+
+    writeln!(w, "            break")?;
+    writeln!(w, "        }}")
+}
+
+/// Whether the values of the flag are paths
+fn completes_paths(flag: &FlagSpec) -> bool {
+    match flag.hint {
+        Some(ValueHint::Other) | None => false,
+        Some(_) => true,
+    }
+}
+
+/// Keys of the `switch` statement matching the command, one per
+/// combination of command names and aliases along the path
+fn keys(spec: &CommandSpec, path: &[String]) -> Vec<String> {
+    let mut keys = vec![spec.path[0].clone()];
+    let mut current = spec;
+
+    for name in &path[1..] {
+        current = match current.commands.iter().find(|c| &c.names[0] == name) {
+            Some(command) => command,
+            None => break,
+        };
+        keys = keys
+            .iter()
+            .flat_map(|key| current.names.iter().map(move |n| format!("{};{}", key, n)))
+            .collect();
+    }
+
+    keys
+}
+
+fn flag_words(flag: &FlagSpec) -> Vec<String> {
+    std::iter::once(format!("--{}", flag.name))
+        .chain(flag.alias.iter().map(|a| format!("-{}", a)))
+        .collect()
+}
+
+fn result(text: &str, list_item: &str, result_type: &str, tooltip: &str) -> String {
+    format!(
+        "[CompletionResult]::new({}, {}, [CompletionResultType]::{}, {})",
+        quote(text),
+        quote(list_item),
+        result_type,
+        quote(tooltip)
+    )
+}
+
+/// Quote a word with PowerShell single quotes
+fn quote(word: &str) -> String {
+    format!("'{}'", word.replace('\'', "''"))
+}
@@ -0,0 +1,203 @@
+use std::io::{self, Write};
+
+use super::{escape, ident, quote, CommandSpec, FlagSpec, ValueHint};
+
+/// Write the zsh completion script
+pub(crate) fn write<W: Write + ?Sized>(spec: &CommandSpec, w: &mut W) -> io::Result<()> {
+    let root = ident(&spec.path);
+
+    writeln!(w, "#compdef {}", spec.path[0])?;
+
+    for command in spec.walk() {
+        writeln!(w)?;
+        write_command(spec, command, w)?;
+    }
+
+    if spec.is_dynamic() {
+        writeln!(w)?;
+        writeln!(w, "{}() {{", dynamic(spec))?;
+        writeln!(w, "    local -a candidates")?;
+        writeln!(
+            w,
+            "    candidates=(${{(f)\"$(${{__{r}_words[1]}} __complete $__{r}_position \"${{(@)__{r}_words[2,-1]}}\")\"}})",
+            r = root
+        )?;
+        writeln!(w, "    compadd -a candidates")?;
+        writeln!(w, "}}")?;
+    }
+
+    writeln!(w)?;
+    writeln!(w, "if [ \"$funcstack[1]\" = \"_{}\" ]; then", root)?;
+    writeln!(w, "    _{} \"$@\"", root)?;
+    writeln!(w, "else")?;
+    writeln!(w, "    compdef _{} {}", root, quote(&spec.path[0]))?;
+    writeln!(w, "fi")
+}
+
+/// Name of the function completing with the `__complete` command
+fn dynamic(spec: &CommandSpec) -> String {
+    format!("__{}_complete", ident(&spec.path))
+}
+
+fn write_command<W: Write + ?Sized>(
+    spec: &CommandSpec,
+    command: &CommandSpec,
+    w: &mut W,
+) -> io::Result<()> {
+    let mut specs: Vec<String> = command
+        .flags
+        .iter()
+        .map(|f| flag_spec(f, &dynamic(spec)))
+        .collect();
+    if !command.commands.is_empty() {
+        specs.push(quote("1: :->commands"));
+        specs.push(quote("*:: :->args"));
+    } else if command.dynamic_args {
+        specs.push(quote(&format!("*: :{}", dynamic(spec))));
+    } else if let Some(hint) = command.args_hint {
+        specs.push(quote(&format!("*: :{}", action(hint))));
+    }
+
+    writeln!(w, "_{}() {{", ident(&command.path))?;
+    if command.path.len() == 1 && spec.is_dynamic() {
+        let root = ident(&spec.path);
+        writeln!(w, "    local -a __{}_words", root)?;
+        writeln!(w, "    __{}_words=(\"${{words[@]}}\")", root)?;
+        writeln!(w, "    local __{}_position=$((CURRENT - 2))", root)?;
+        writeln!(w)?;
+    }
+    if command.commands.is_empty() {
+        writeln!(w, "    _arguments \\")?;
+    } else {
+        writeln!(w, "    local curcontext=\"$curcontext\" state line")?;
+        writeln!(w, "    typeset -A opt_args")?;
+        writeln!(w)?;
+        writeln!(w, "    _arguments -C \\")?;
+    }
+    let last = specs.len() - 1;
+    for (i, spec) in specs.iter().enumerate() {
+        if i == last {
+            writeln!(w, "        {}", spec)?;
+        } else {
+            writeln!(w, "        {} \\", spec)?;
+        }
+    }
+
+    if !command.commands.is_empty() {
+        writeln!(w)?;
+        writeln!(w, "    case $state in")?;
+        writeln!(w, "        commands)")?;
+        writeln!(w, "            local -a commands")?;
+        writeln!(w, "            commands=(")?;
+        for sub in &command.commands {
+            for name in &sub.names {
+                let entry = match &sub.description {
+                    Some(description) => format!("{}:{}", escape_name(name), description),
+                    None => escape_name(name),
+                };
+                writeln!(w, "                {}", quote(&entry))?;
+            }
+        }
+        writeln!(w, "            )")?;
+        writeln!(
+            w,
+            "            _describe -t commands {} commands",
+            quote(&format!("{} commands", command.path.join(" ")))
+        )?;
+        writeln!(w, "            ;;")?;
+        writeln!(w, "        args)")?;
+        writeln!(w, "            case $line[1] in")?;
+        for sub in &command.commands {
+            let patterns = sub
+                .names
+                .iter()
+                .map(|n| quote(n))
+                .collect::<Vec<String>>()
+                .join("|");
+            writeln!(w, "                {})", patterns)?;
+            writeln!(w, "                    _{}", ident(&sub.path))?;
+            writeln!(w, "                    ;;")?;
+        }
+        writeln!(w, "            esac")?;
+        writeln!(w, "            ;;")?;
+        writeln!(w, "    esac")?;
+    }
+
+    writeln!(w, "}}")
+}
+
+/// Make an `_arguments` spec of the flag
+///
+/// Long names of flags taking a value end with `=`, so that the value is
+/// completed after `--name=` as well as after `--name`.
+fn flag_spec(flag: &FlagSpec, dynamic: &str) -> String {
+    let names: Vec<String> = std::iter::once(format!("--{}", flag.name))
+        .chain(flag.alias.iter().map(|a| format!("-{}", a)))
+        .collect();
+    let mut matched = names.clone();
+    if flag.takes_value {
+        matched[0].push('=');
+    }
+
+    let mut rest = String::new();
+    if let Some(description) = &flag.description {
+        rest += &format!("[{}]", describe(description));
+    }
+    if flag.takes_value {
+        if flag.dynamic {
+            rest += &format!(":{}:{}", flag.name, dynamic);
+        } else if flag.choices.is_empty() {
+            rest += &format!(":{}:{}", flag.name, flag.hint.map_or(" ", action));
+        } else {
+            let choices: Vec<String> = flag.choices.iter().map(|c| escape(c)).collect();
+            rest += &format!(
+                ":{}:_values {} {}",
+                flag.name,
+                escape(&flag.name),
+                choices.join(" ")
+            );
+        }
+    }
+
+    if names.len() == 1 {
+        quote(&format!("{}{}", matched[0], rest))
+    } else {
+        format!(
+            "{}{{{}}}{}",
+            quote(&format!("({})", names.join(" "))),
+            matched.join(","),
+            quote(&rest)
+        )
+    }
+}
+
+/// Action of an `_arguments` spec completing a value of the hint
+fn action(hint: ValueHint) -> &'static str {
+    match hint {
+        ValueHint::AnyPath | ValueHint::FilePath => "_files",
+        ValueHint::DirPath => "_directories",
+        ValueHint::ExecutablePath => "_files -g \"*(-*)\"",
+        ValueHint::Other => " ",
+    }
+}
+
+/// Escape a command name for `_describe`, which reads the first
+/// unescaped `:` as the start of the description, any later one being part
+/// of it
+fn escape_name(name: &str) -> String {
+    name.replace('\\', "\\\\").replace(':', "\\:")
+}
+
+/// Escape a flag description for `_arguments`, which reads backslashes as
+/// escapes and `[`, `]` and `:` as the end of the description or the start
+/// of the next part of the spec
+fn describe(description: &str) -> String {
+    let mut escaped = String::with_capacity(description.len());
+    for c in description.chars() {
+        if "\\[]:".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,294 @@
+use std::borrow::Cow;
+
+use crate::error::Error;
+#[cfg(feature = "config")]
+use crate::error::ErrorKind;
+#[cfg(feature = "config")]
+use crate::FlagType;
+use crate::{Flag, ValueSource};
+
+/// Flag defaults loaded from a TOML config file
+///
+/// Top-level keys set flags of the app and of every command, and tables
+/// named after commands set flags of that command only:
+///
+/// ```toml
+/// region = "eu-west-1"
+///
+/// [remote.add]
+/// force = true
+/// ```
+#[cfg(feature = "config")]
+pub(crate) struct Config {
+    file: String,
+    table: toml::Table,
+}
+
+/// Never constructed without the `config` feature
+#[cfg(not(feature = "config"))]
+pub(crate) enum Config {}
+
+#[cfg(feature = "config")]
+impl Config {
+    /// Load the config file, a missing file being an empty config
+    pub(crate) fn load(path: &std::path::Path) -> Result<Option<Self>, Error> {
+        let file = path.display().to_string();
+        match std::fs::read_to_string(path) {
+            Ok(content) => Self::parse(file, &content).map(Some),
+            Err(ref e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => {
+                let (name, reason) = (file.clone(), e.to_string());
+                Err(
+                    Error::new(ErrorKind::Config, move |m| m.config_failed(&name, &reason))
+                        .token(file)
+                        .cause(e),
+                )
+            }
+        }
+    }
+
+    pub(crate) fn parse(file: String, content: &str) -> Result<Self, Error> {
+        match content.parse::<toml::Table>() {
+            Ok(table) => Ok(Self { file, table }),
+            Err(e) => {
+                let (name, reason) = (file.clone(), e.message().to_string());
+                let error = Error::new(ErrorKind::Config, move |m| m.config_failed(&name, &reason));
+                Err(error.token(file).cause(e))
+            }
+        }
+    }
+
+    /// Value of the flag at the deepest table along the command path,
+    /// with its dotted key
+    fn lookup(&self, path: &[String], name: &str) -> Option<(String, &toml::Value)> {
+        let mut tables = vec![(String::new(), &self.table)];
+        for command in path {
+            let (prefix, table) = &tables[tables.len() - 1];
+            match table.get(command) {
+                Some(toml::Value::Table(table)) => {
+                    let prefix = format!("{}{}.", prefix, command);
+                    tables.push((prefix, table))
+                }
+                _ => break,
+            }
+        }
+
+        tables
+            .iter()
+            .rev()
+            .find_map(|(prefix, table)| match table.get(name) {
+                Some(toml::Value::Table(_)) | None => None,
+                Some(value) => Some((format!("{}{}", prefix, name), value)),
+            })
+    }
+
+    fn apply(&self, path: &[String], flag: &mut Flag) -> Result<(), Error> {
+        let (key, value) = match self.lookup(path, &flag.name) {
+            Some(found) => found,
+            None => return Ok(()),
+        };
+
+        let value = match (&flag.flag_type, value) {
+            (FlagType::Bool, toml::Value::Boolean(b)) => Some(b.to_string()),
+            (FlagType::String, toml::Value::String(s))
+            | (FlagType::Secret, toml::Value::String(s))
+            | (FlagType::DateTime, toml::Value::String(s)) => Some(s.clone()),
+            #[cfg(feature = "url")]
+            (FlagType::Url, toml::Value::String(s)) => Some(s.clone()),
+            #[cfg(feature = "uuid")]
+            (FlagType::Uuid, toml::Value::String(s)) => Some(s.clone()),
+            #[cfg(feature = "regex")]
+            (FlagType::Regex, toml::Value::String(s)) => Some(s.clone()),
+            (FlagType::DateTime, toml::Value::Datetime(d)) => Some(d.to_string()),
+            (FlagType::Int, toml::Value::Integer(i))
+            | (FlagType::Uint, toml::Value::Integer(i))
+            | (FlagType::Float, toml::Value::Integer(i)) => Some(i.to_string()),
+            (FlagType::Float, toml::Value::Float(f)) => Some(f.to_string()),
+            _ => None,
+        };
+
+        let mut configured = flag.clone();
+        configured.default_value = value;
+        match configured.default_flag_value() {
+            Some(Ok(_)) => {
+                *flag = configured;
+                Ok(())
+            }
+            _ => {
+                let (file, name, expected) =
+                    (self.file.clone(), key.clone(), flag.flag_type.name());
+                Err(Error::new(ErrorKind::Config, move |m| {
+                    m.invalid_config_value(&file, &name, expected)
+                })
+                .token(key)
+                .flag(flag.name.as_str()))
+            }
+        }
+    }
+}
+
+/// Flags with defaults replaced by the values of the config file,
+/// `path` being the full command path starting with the app name
+pub(crate) fn apply<'a>(
+    config: Option<&Config>,
+    path: &[String],
+    flags: &'a Option<Vec<Flag>>,
+) -> Result<Cow<'a, [Flag]>, Error> {
+    let flags: &'a [Flag] = flags.as_ref().map_or(&[], Vec::as_slice);
+    let config = match config {
+        Some(config) => config,
+        None => return Ok(Cow::Borrowed(flags)),
+    };
+
+    #[cfg(feature = "config")]
+    {
+        let mut flags = flags.to_vec();
+        for flag in flags.iter_mut() {
+            config.apply(&path[1..], flag)?;
+        }
+        Ok(Cow::Owned(flags))
+    }
+
+    #[cfg(not(feature = "config"))]
+    {
+        let _ = path;
+        match *config {}
+    }
+}
+
+/// Source of the default of the flag when the config file sets it,
+/// `path` being the full command path starting with the app name
+pub(crate) fn source(config: Option<&Config>, path: &[String], flag: &Flag) -> Option<ValueSource> {
+    let config = config?;
+
+    #[cfg(feature = "config")]
+    {
+        config
+            .lookup(&path[1..], &flag.name)
+            .map(|(key, _)| ValueSource::ConfigFile {
+                file: config.file.clone(),
+                key,
+            })
+    }
+
+    #[cfg(not(feature = "config"))]
+    {
+        let _ = (path, flag);
+        match *config {}
+    }
+}
+
+#[cfg(all(test, feature = "config"))]
+mod tests {
+    use super::{apply, Config};
+    use crate::{Context, Flag, FlagType};
+
+    fn config(content: &str) -> Config {
+        Config::parse(".clirc".to_string(), content).unwrap()
+    }
+
+    fn path(path: &[&str]) -> Vec<String> {
+        path.iter().map(|p| p.to_string()).collect()
+    }
+
+    fn region(config_file: Option<&Config>, args: &[&str], default: Option<&str>) -> String {
+        let mut flag = Flag::new("region", FlagType::String);
+        if let Some(default) = default {
+            flag = flag.default_value(default);
+        }
+        let flags = Some(vec![flag]);
+        let flags = apply(config_file, &path(&["cli"]), &flags).unwrap();
+        let args = args.iter().map(|a| a.to_string()).collect();
+        Context::with_flags(args, &flags, String::new())
+            .string_flag("region")
+            .unwrap_or_else(|e| e.to_string())
+    }
+
+    #[test]
+    fn precedence_test() {
+        let config = config(r#"region = "config""#);
+        let cli = ["--region", "cli"];
+
+        assert_eq!(region(Some(&config), &cli, Some("default")), "cli");
+        assert_eq!(region(Some(&config), &[], Some("default")), "config");
+        assert_eq!(region(Some(&config), &[], None), "config");
+        assert_eq!(region(None, &cli, Some("default")), "cli");
+        assert_eq!(region(None, &[], Some("default")), "default");
+        assert_eq!(region(None, &[], None), "NotFound");
+    }
+
+    #[test]
+    fn namespace_test() {
+        let config = config(
+            r#"
+            count = 1
+            [remote]
+            count = 2
+            [remote.add]
+            count = 3
+            "#,
+        );
+        let count = |p: &[&str]| {
+            let flags = Some(vec![Flag::new("count", FlagType::Int)]);
+            apply(Some(&config), &path(p), &flags).unwrap()[0]
+                .default_value
+                .clone()
+        };
+
+        assert_eq!(count(&["cli"]), Some("1".to_string()));
+        assert_eq!(count(&["cli", "remote"]), Some("2".to_string()));
+        assert_eq!(count(&["cli", "remote", "add"]), Some("3".to_string()));
+        assert_eq!(count(&["cli", "remote", "remove"]), Some("2".to_string()));
+        assert_eq!(count(&["cli", "other"]), Some("1".to_string()));
+    }
+
+    #[test]
+    fn type_error_test() {
+        let config = config(
+            r#"
+            [hello]
+            count = "three"
+            size = -1
+            lang = "de"
+            "#,
+        );
+        let error = |flag: Flag| {
+            apply(Some(&config), &path(&["cli", "hello"]), &Some(vec![flag]))
+                .err()
+                .map(|e| e.to_string())
+        };
+
+        assert_eq!(
+            error(Flag::new("count", FlagType::Int)),
+            Some(r#".clirc: "hello.count" must be a valid int value"#.to_string())
+        );
+        assert_eq!(
+            error(Flag::new("size", FlagType::Uint)),
+            Some(r#".clirc: "hello.size" must be a valid uint value"#.to_string())
+        );
+        assert_eq!(
+            error(Flag::new("lang", FlagType::String).choices(&["en", "ja"])),
+            Some(r#".clirc: "hello.lang" must be a valid string value"#.to_string())
+        );
+        assert!(error(Flag::new("size", FlagType::Float)).is_none());
+    }
+
+    #[test]
+    fn load_test() {
+        let file = std::env::temp_dir().join("seahorse_config_load_test.toml");
+        std::fs::write(&file, "verbose = true\n").unwrap();
+        let config = Config::load(&file).unwrap().unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        let flags = Some(vec![Flag::new("verbose", FlagType::Bool)]);
+        let flags = apply(Some(&config), &path(&["cli"]), &flags).unwrap();
+        assert!(Context::with_flags(Vec::new(), &flags, String::new()).bool_flag("verbose"));
+
+        assert!(Config::load(&file).unwrap().is_none());
+        assert!(Config::parse(".clirc".to_string(), "region = ")
+            .err()
+            .map(|e| e.to_string())
+            .unwrap()
+            .starts_with(".clirc: "));
+    }
+}
@@ -1,72 +1,565 @@
-use crate::error::FlagError;
-use crate::{Flag, FlagType, FlagValue};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::ops::Index;
+use std::panic;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::action::Exit;
+use crate::command::Settings;
+use crate::config;
+use crate::error::{Error, FlagError};
+#[cfg(feature = "interrupt")]
+use crate::interrupt::Interrupt;
+use crate::parser::{self, FlagMatches};
+use crate::streams::Streams;
+use crate::terminal::{self, Stream};
+use crate::{Arg, DateTime, Flag, FlagType, FlagValue, FromContext, Prompt, Secret, Terminal};
 
 /// `Context` type
 ///
 /// This type is used only for `Action` arguments
 pub struct Context {
-    /// `Vec<String>` with flags and flag values ​​removed from command line arguments
+    /// Positional arguments in command line order, with flags and the values
+    /// they take removed, followed by the arguments after `--`
     pub args: Vec<String>,
-    /// `Vec` that stores flag name and flag value as tuple
-    flags: Option<Vec<(String, Result<FlagValue, FlagError>)>>,
-    help_text: String,
+    /// Flag values by flag name
+    flags: HashMap<String, Result<FlagValue, FlagError>>,
+    /// Flags in declaration order
+    declared: Vec<Declared>,
+    /// Number of occurrences of each specified flag
+    counts: HashMap<String, usize>,
+    /// Values of each occurrence of flags given more than once
+    groups: HashMap<String, Vec<FlagValue>>,
+    /// Values of declared positional arguments by argument name
+    positionals: HashMap<String, Result<FlagValue, FlagError>>,
+    /// Names of declared positional arguments not given, read from their default
+    defaulted_args: Vec<String>,
+    /// Values of the global flags shadowed by flags of the command, from the
+    /// arguments given before the command name
+    globals: HashMap<String, Result<FlagValue, FlagError>>,
+    help_text: Arc<str>,
+    /// Prompt asking for confirmations, reading from stdin when not set
+    prompt: Option<Prompt>,
+    /// Terminal the app runs in, detected when asked for when not set
+    terminal: Option<Terminal>,
+    /// Writers of the app, `help` writing to its output stream
+    streams: Streams,
+    /// Status requested with `exit`
+    exit_code: Cell<Option<i32>>,
+    /// Path of the command running the action, starting with the app name
+    path: Vec<String>,
+    /// Handling of interrupts of the action, set when the app handles them
+    #[cfg(feature = "interrupt")]
+    interrupt: Option<Interrupt>,
+}
+
+/// Where the value of a flag comes from, as told by `Context::value_source`
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValueSource {
+    /// Given on the command line
+    CommandLine,
+    /// Read from the environment variable of the name
+    Environment(String),
+    /// Answered at the prompt
+    Prompt,
+    /// Read from the config file
+    ConfigFile {
+        /// Path of the config file
+        file: String,
+        /// Dotted key of the value, like `remote.add.force`
+        key: String,
+    },
+    /// Default value of the flag
+    Default,
+}
+
+/// Source as shown in parse traces
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValueSource::CommandLine => f.write_str("command line"),
+            ValueSource::Environment(name) => write!(f, "env {}", name),
+            ValueSource::Prompt => f.write_str("prompt"),
+            ValueSource::ConfigFile { file, key } => write!(f, "config {} in {}", key, file),
+            ValueSource::Default => f.write_str("default"),
+        }
+    }
+}
+
+/// Flag of a context, in the order the flags are declared
+struct Declared {
+    name: String,
+    /// Bool flag, `false` when not given
+    bool_flag: bool,
+    /// Where the value comes from, `None` when the flag has no value
+    source: Option<ValueSource>,
 }
 
+/// Value of bool flags that are not given
+static NOT_GIVEN: FlagValue = FlagValue::Bool(false);
+
 impl Context {
     /// Create new instance of `Context`
     /// Parse processing using `Vec<String>` command line argument and `Vec<Flag>` as arguments
     pub fn new(args: Vec<String>, flags: Option<Vec<Flag>>, help_text: String) -> Self {
-        let mut v = Vec::new();
-        let mut parsed_args = args;
-        let flags_val = match flags {
-            Some(flags) => {
-                for flag in flags {
-                    if let Some(index) = flag.option_index(&parsed_args) {
-                        parsed_args.remove(index);
-
-                        let val = if flag.flag_type != FlagType::Bool {
-                            if parsed_args.len() <= index {
-                                None
-                            } else {
-                                Some(parsed_args.remove(index))
-                            }
-                        } else {
-                            None
-                        };
-                        v.push((flag.name.to_string(), flag.value(val)))
-                    } else {
-                        v.push((flag.name.to_string(), Err(FlagError::NotFound)))
-                    }
+        Self::with_flags(args, flags.as_ref().map_or(&[], Vec::as_slice), help_text)
+    }
+
+    /// Create new instance of `Context` parsing the arguments it takes ownership of
+    /// against borrowed flags
+    pub(crate) fn with_flags(args: Vec<String>, flags: &[Flag], help_text: String) -> Self {
+        Self::from_matches(FlagMatches::parse(args, flags), flags, help_text, |_| None)
+    }
+
+    /// Create new instance of `Context` for running the action at the path,
+    /// resolving unknown flags with the handler and asking for missing flags with
+    /// the prompt of the settings, failing on unknown flags, missing required flags
+    /// or flag values that cannot be read or are not valid numbers of the flag type
+    pub(crate) fn for_action<T: Into<Arc<str>>>(
+        path: &[String],
+        args: Vec<String>,
+        flags: &[Flag],
+        help_text: T,
+        settings: &Settings,
+    ) -> Result<Self, Error> {
+        let args = if settings.slash_flags {
+            parser::slash_flags(args, flags)
+        } else {
+            args
+        };
+        let index = settings.compiled.and_then(|c| c.index(path));
+        let mut matches =
+            FlagMatches::parse_indexed(args, flags, index, settings.stop_at_first_positional);
+        if let Some(handler) = settings.unknown_flag {
+            matches = matches.resolve_unknown(flags, handler, path)?;
+        }
+        if settings.debug {
+            settings.streams.err(&matches.trace_args(flags, path));
+        }
+        matches.fill_missing(flags, settings.prompt, settings.messages, |flag| {
+            flag.env_names(settings.env_prefix, path.get(1..).unwrap_or_default())
+        })?;
+        matches.apply_overrides(flags);
+        matches.read_files(flags)?;
+        matches.normalize_values(flags);
+        #[cfg(feature = "glob")]
+        matches.expand_globs(flags, &[])?;
+        matches.check_values(flags)?;
+        let configured = |flag: &Flag| config::source(settings.config, path, flag);
+        if settings.debug {
+            settings
+                .streams
+                .err(&matches.trace_flags(flags, configured));
+        }
+        Ok(Self::from_matches(matches, flags, help_text, configured)
+            .with_prompt(settings.prompt.clone())
+            .with_terminal(settings.terminal.unwrap_or_else(Terminal::detect))
+            .with_streams(settings.streams.clone())
+            .within(path))
+    }
+
+    /// Create new instance of `Context` from the parsed flags, `configured`
+    /// naming the key of the config file for flags whose default it sets
+    pub(crate) fn from_matches<T, F>(
+        matches: FlagMatches,
+        flags: &[Flag],
+        help_text: T,
+        configured: F,
+    ) -> Self
+    where
+        T: Into<Arc<str>>,
+        F: Fn(&Flag) -> Option<ValueSource>,
+    {
+        let mut values = HashMap::with_capacity(flags.len());
+        let mut declared = Vec::with_capacity(flags.len());
+        let mut occurrences = HashMap::new();
+        let mut groups = HashMap::new();
+        for (index, flag) in flags.iter().enumerate() {
+            let count = matches.occurrences[index].count;
+            let value = if count == 0 {
+                match flag.default_flag_value() {
+                    Some(default) => default,
+                    None => Err(FlagError::NotFound),
                 }
-                Some(v)
+            } else if flag.flag_type == FlagType::Bool {
+                occurrences.insert(flag.name.clone(), count);
+                flag.value(None)
+            } else {
+                occurrences.insert(flag.name.clone(), count);
+                let positions = matches.groups(index);
+                if positions.len() == 1 {
+                    group_value(flag, &matches, &positions[0])
+                } else {
+                    let all: Result<Vec<FlagValue>, FlagError> = positions
+                        .iter()
+                        .map(|group| group_value(flag, &matches, group))
+                        .collect();
+                    all.map(|all| {
+                        let first = all[0].clone();
+                        groups.insert(flag.name.clone(), all);
+                        first
+                    })
+                }
+            };
+            if !values.contains_key(&flag.name) {
+                declared.push(Declared {
+                    name: flag.name.clone(),
+                    bool_flag: flag.flag_type == FlagType::Bool,
+                    source: matches.source(index, flag, &configured),
+                });
+                values.insert(flag.name.clone(), value);
             }
-            None => None,
-        };
+        }
 
         Self {
-            args: parsed_args,
-            flags: flags_val,
-            help_text,
+            args: matches.into_args(),
+            flags: values,
+            declared,
+            counts: occurrences,
+            groups,
+            positionals: HashMap::new(),
+            defaulted_args: Vec::new(),
+            globals: HashMap::new(),
+            help_text: help_text.into(),
+            prompt: None,
+            terminal: None,
+            streams: Streams::default(),
+            exit_code: Cell::new(None),
+            path: Vec::new(),
+            #[cfg(feature = "interrupt")]
+            interrupt: None,
         }
     }
 
-    /// Get flag value
-    fn result_flag_value(&self, name: &str) -> Result<FlagValue, FlagError> {
-        let flag = self
-            .flags
+    /// Leave the action, exiting with the status after the after actions ran
+    ///
+    /// The action unwinds back to the app, which runs the after actions of the
+    /// command and of the app before `App::run` exits with the status, or
+    /// `App::try_run` returns it. After actions calling `exit` again leave
+    /// themselves, keeping the first status. Leaving the action needs unwinding,
+    /// so builds aborting on panic exit without running the after actions.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Context};
+    ///
+    /// let app = App::new("cli")
+    ///     .action(|c: &Context| {
+    ///         if c.args.is_empty() {
+    ///             c.exit(2);
+    ///         }
+    ///     })
+    ///     .after_action(|c: &Context| println!("exiting with {:?}", c.exit_code()));
+    /// assert_eq!(app.try_run(vec!["cli".to_string()]), Ok(2));
+    /// ```
+    pub fn exit(&self, code: i32) -> ! {
+        if self.exit_code.get().is_none() {
+            self.exit_code.set(Some(code));
+        }
+        panic::resume_unwind(Box::new(Exit))
+    }
+
+    /// Status requested with [`Context::exit`], if any
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code.get()
+    }
+
+    /// Request the status unless one already is, without leaving the action
+    #[cfg(feature = "interrupt")]
+    pub(crate) fn set_exit_code(&self, code: i32) {
+        if self.exit_code.get().is_none() {
+            self.exit_code.set(Some(code));
+        }
+    }
+
+    /// Whether Ctrl-C was pressed while the action runs, always false unless
+    /// the app sets `App::on_interrupt`
+    ///
+    /// Long running sync actions check this to stop early, the interrupt
+    /// action and the after actions running once they return.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Context};
+    ///
+    /// let app = App::new("watch")
+    ///     .action(|c: &Context| {
+    ///         while !c.interrupted() {
+    ///             // Watch for changes
+    ///             # break;
+    ///         }
+    ///     })
+    ///     .on_interrupt(|_: &Context| println!("stopped watching"));
+    /// ```
+    #[cfg(feature = "interrupt")]
+    pub fn interrupted(&self) -> bool {
+        self.interrupt
             .as_ref()
-            .and_then(|flags| flags.iter().find(|flag| flag.0 == name));
+            .map_or(false, |interrupt| interrupt.source.interrupted())
+    }
+
+    /// Handling of interrupts of the action, if the app handles them
+    #[cfg(feature = "interrupt")]
+    pub(crate) fn interrupt(&self) -> Option<&Interrupt> {
+        self.interrupt.as_ref()
+    }
+
+    /// Context handling interrupts of the action as set on the app
+    #[cfg(feature = "interrupt")]
+    pub(crate) fn with_interrupt(mut self, interrupt: &Interrupt) -> Self {
+        if interrupt.action.is_some() {
+            self.interrupt = Some(interrupt.clone());
+        }
+        self
+    }
+
+    /// Set prompt asking for confirmations, used to test actions
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Context, Prompt};
+    ///
+    /// let context = Context::new(Vec::new(), None, String::new())
+    ///     .with_prompt(|_: &str| Ok("y".to_string()));
+    /// assert!(context.confirm("Delete all files?"));
+    /// ```
+    pub fn with_prompt(mut self, prompt: impl Into<Prompt>) -> Self {
+        self.prompt = Some(prompt.into());
+        self
+    }
+
+    /// Set terminal the action sees instead of detecting it, used to test
+    /// actions
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Context, Terminal};
+    ///
+    /// let context = Context::new(Vec::new(), None, String::new()).with_terminal(Terminal {
+    ///     stdout_is_tty: false,
+    ///     stderr_is_tty: false,
+    ///     width: None,
+    /// });
+    /// assert!(!context.stdout_is_tty());
+    /// ```
+    pub fn with_terminal(mut self, terminal: Terminal) -> Self {
+        self.terminal = Some(terminal);
+        self
+    }
+
+    /// Set writers of the app the action writes help and errors to
+    pub(crate) fn with_streams(mut self, streams: Streams) -> Self {
+        self.streams = streams;
+        self
+    }
+
+    /// Read the global flags the flags of the command shadow from the
+    /// arguments given for them before the command name
+    pub(crate) fn with_globals(
+        mut self,
+        flags: &[Flag],
+        globals: &[Flag],
+        args: &[String],
+    ) -> Self {
+        let shadowed: Vec<Flag> = globals
+            .iter()
+            .filter(|global| global.global && flags.iter().any(|flag| flag.name == global.name))
+            .cloned()
+            .collect();
+        if !shadowed.is_empty() {
+            self.globals = Context::with_flags(args.to_vec(), &shadowed, String::new()).flags;
+        }
+        self
+    }
+
+    /// Writers of the app the action writes help and errors to
+    pub(crate) fn streams(&self) -> &Streams {
+        &self.streams
+    }
+
+    /// Whether stdout is a terminal, for leaving out colors when piped
+    pub fn stdout_is_tty(&self) -> bool {
+        match self.terminal {
+            Some(terminal) => terminal.stdout_is_tty,
+            None => terminal::is_terminal(Stream::Stdout),
+        }
+    }
+
+    /// Whether stderr is a terminal
+    pub fn stderr_is_tty(&self) -> bool {
+        match self.terminal {
+            Some(terminal) => terminal.stderr_is_tty,
+            None => terminal::is_terminal(Stream::Stderr),
+        }
+    }
+
+    /// Number of columns of the terminal stdout is, `None` when stdout is not
+    /// a terminal
+    ///
+    /// The width is read from `COLUMNS` or `stty size` like help does, once
+    /// when the command line is dispatched, so it does not follow resizes of
+    /// the terminal while the action runs.
+    pub fn terminal_width(&self) -> Option<usize> {
+        match self.terminal {
+            Some(terminal) => terminal.width,
+            None => terminal::width(),
+        }
+    }
+
+    /// Ask for confirmation, `no` being the default answer
+    ///
+    /// The question is written to stderr followed by `[y/N]`, and the answer is
+    /// read from stdin, accepting y, yes, n and no in any case. The default is
+    /// returned on an empty answer or without asking when stdin is not a terminal
+    /// or the app runs with `--no-input`, and `true` is returned without asking
+    /// when the bool flag named by [`Prompt::yes_flag`], `yes` by default, is given.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Context, Flag, FlagType};
+    ///
+    /// let yes = Flag::new("yes", FlagType::Bool).alias("y");
+    /// let context = Context::new(vec!["-y".to_string()], Some(vec![yes]), String::new());
+    /// assert!(context.confirm("Delete all files?"));
+    /// ```
+    pub fn confirm(&self, message: &str) -> bool {
+        self.confirm_with_default(message, false)
+    }
+
+    /// Ask for confirmation like [`Context::confirm`], `yes` being the default answer
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Context, Prompt};
+    ///
+    /// let context = Context::new(Vec::new(), None, String::new())
+    ///     .with_prompt(|_: &str| Ok(String::new()));
+    /// assert!(context.confirm_default_yes("Continue?"));
+    /// ```
+    pub fn confirm_default_yes(&self, message: &str) -> bool {
+        self.confirm_with_default(message, true)
+    }
+
+    fn confirm_with_default(&self, message: &str, default: bool) -> bool {
+        let stdio;
+        let prompt = match &self.prompt {
+            Some(prompt) => prompt,
+            None => {
+                stdio = Prompt::stdio();
+                &stdio
+            }
+        };
+        self.bool_flag(prompt.yes_flag_name()) || prompt.confirm(message, default)
+    }
+
+    /// Context of the action of the command at the path
+    pub(crate) fn within(mut self, path: &[String]) -> Self {
+        self.path = path.to_vec();
+        self
+    }
+
+    /// Path of the command running the action, starting with the app name
+    pub(crate) fn into_path(self) -> Vec<String> {
+        self.path
+    }
+
+    /// Resolve the arguments to the declared positional arguments, in order
+    pub(crate) fn declare_args(&mut self, declared: &[Arg]) {
+        for (index, arg) in declared.iter().enumerate() {
+            let given = self.args.get(index..).unwrap_or_default();
+            if given.is_empty() && arg.default_value.is_some() {
+                self.defaulted_args.push(arg.name.clone());
+            }
+            let value = arg.values(given);
+            self.positionals.entry(arg.name.clone()).or_insert(value);
+        }
+    }
+
+    /// Whether the declared positional argument was not given and has the
+    /// value of its default
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     if c.is_arg_defaulted("DIR") {
+    ///         println!("Serving the current directory");
+    ///     }
+    /// }
+    /// ```
+    pub fn is_arg_defaulted(&self, name: &str) -> bool {
+        self.defaulted_args.iter().any(|arg| arg == name)
+    }
+
+    /// Get value of declared positional argument
+    fn result_arg_value(&self, name: &str) -> Result<FlagValue, FlagError> {
+        match self.positionals.get(name) {
+            Some(Ok(val)) => Ok(val.to_owned()),
+            Some(Err(e)) => Err(e.to_owned()),
+            None => Err(FlagError::Undefined),
+        }
+    }
 
-        match flag {
-            Some(f) => match &f.1 {
-                Ok(val) => Ok(val.to_owned()),
-                Err(e) => Err(e.to_owned()),
-            },
+    /// Get flag value
+    fn result_flag_value(&self, name: &str) -> Result<FlagValue, FlagError> {
+        match self.flags.get(name) {
+            Some(Ok(val)) => Ok(val.to_owned()),
+            Some(Err(e)) => Err(e.to_owned()),
             None => Err(FlagError::Undefined),
         }
     }
 
-    /// Get bool flag
+    /// Get the value of a global flag the command shadows with a flag of the
+    /// same name, given before the command name
+    ///
+    /// Within a command declaring a flag named like a global flag, the flag
+    /// of the command is the one parsed, shown in help and read by the other
+    /// accessors. The global flag keeps its own type, its value or default
+    /// being read here. Flags shadowing no global flag are read like with
+    /// any accessor.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context, Flag, FlagType, FlagValue};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::new("output", FlagType::String).global())
+    ///     .command(
+    ///         Command::new("split")
+    ///             .flag(Flag::new("output", FlagType::Uint))
+    ///             .action(|c: &Context| {
+    ///                 assert_eq!(c.uint_flag("output"), Ok(3));
+    ///                 match c.global_flag("output") {
+    ///                     Ok(FlagValue::String(log)) => assert_eq!(log, "log.txt"),
+    ///                     _ => panic!("global output missing"),
+    ///                 }
+    ///             }),
+    ///     );
+    /// let args = ["cli", "--output", "log.txt", "split", "--output", "3"];
+    /// assert_eq!(app.try_run(args.iter().map(|a| a.to_string()).collect()), Ok(0));
+    /// ```
+    pub fn global_flag(&self, name: &str) -> Result<FlagValue, FlagError> {
+        match self.globals.get(name) {
+            Some(value) => value.clone(),
+            None => self.result_flag_value(name),
+        }
+    }
+
+    /// Get bool flag, `false` when the flag is absent
     ///
     /// Example
     ///
@@ -111,6 +604,131 @@ impl Context {
         }
     }
 
+    /// Get secret flag
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     match c.secret_flag("token") {
+    ///         Ok(token) => println!("{:?}", token),
+    ///         Err(e) => println!("{}", e)
+    ///     }
+    /// }
+    /// ```
+    pub fn secret_flag(&self, name: &str) -> Result<Secret, FlagError> {
+        let r = self.result_flag_value(name)?;
+        match r {
+            FlagValue::Secret(val) => Ok(val),
+            _ => Err(FlagError::TypeError),
+        }
+    }
+
+    /// Get string flag without copying its value
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     match c.str_flag("string") {
+    ///         Ok(s) => println!("{}", s),
+    ///         Err(e) => println!("{}", e)
+    ///     }
+    /// }
+    /// ```
+    pub fn str_flag(&self, name: &str) -> Result<&str, FlagError> {
+        match self.flags.get(name) {
+            Some(Ok(FlagValue::String(val))) => Ok(val),
+            Some(Ok(_)) => Err(FlagError::TypeError),
+            Some(Err(e)) => Err(e.to_owned()),
+            None => Err(FlagError::Undefined),
+        }
+    }
+
+    /// Value of the string flag or positional argument, borrowed from the
+    /// context, `None` when it's absent, invalid or not a string
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     if c.value_of("format") == Some("json") {
+    ///         println!("{{}}");
+    ///     }
+    /// }
+    /// ```
+    pub fn value_of(&self, name: &str) -> Option<&str> {
+        match self.flags.get(name).or_else(|| self.positionals.get(name)) {
+            Some(Ok(FlagValue::String(val))) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Value of the string flag or positional argument as a path, borrowed
+    /// from the context
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     if let Some(path) = c.path_of("config") {
+    ///         println!("{}", path.display());
+    ///     }
+    /// }
+    /// ```
+    pub fn path_of(&self, name: &str) -> Option<&Path> {
+        self.value_of(name).map(Path::new)
+    }
+
+    /// Open the file named by the string flag for reading, stdin when the value is `-`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    /// use std::io::Read;
+    ///
+    /// fn action(c: &Context) {
+    ///     let mut input = String::new();
+    ///     match c.reader_for_flag("input") {
+    ///         Ok(mut reader) => reader.read_to_string(&mut input).unwrap(),
+    ///         Err(e) => return eprintln!("{}", e),
+    ///     };
+    /// }
+    /// ```
+    pub fn reader_for_flag(&self, name: &str) -> io::Result<Box<dyn Read>> {
+        match self.str_flag(name) {
+            Ok("-") => Ok(Box::new(io::stdin())),
+            Ok(path) => Ok(Box::new(File::open(path)?)),
+            Err(e) => Err(io::Error::new(io::ErrorKind::InvalidInput, e)),
+        }
+    }
+
+    /// Get the argument at the index without copying it
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     if let Some(name) = c.arg(0) {
+    ///         println!("Hello, {}", name);
+    ///     }
+    /// }
+    /// ```
+    pub fn arg(&self, index: usize) -> Option<&str> {
+        self.args.get(index).map(String::as_str)
+    }
+
     /// Get int flag
     ///
     /// Example
@@ -125,7 +743,7 @@ impl Context {
     ///     }
     /// }
     /// ```
-    pub fn int_flag(&self, name: &str) -> Result<isize, FlagError> {
+    pub fn int_flag(&self, name: &str) -> Result<i64, FlagError> {
         let r = self.result_flag_value(name)?;
         match r {
             FlagValue::Int(val) => Ok(val),
@@ -147,7 +765,7 @@ impl Context {
     ///     }
     /// }
     /// ```
-    pub fn uint_flag(&self, name: &str) -> Result<usize, FlagError> {
+    pub fn uint_flag(&self, name: &str) -> Result<u64, FlagError> {
         let r = self.result_flag_value(name)?;
         match r {
             FlagValue::Uint(val) => Ok(val),
@@ -177,7 +795,7 @@ impl Context {
         }
     }
 
-    /// Display help
+    /// Get datetime flag
     ///
     /// Example
     ///
@@ -185,21 +803,651 @@ impl Context {
     /// use seahorse::Context;
     ///
     /// fn action(c: &Context) {
-    ///     c.help();
+    ///     match c.datetime_flag("since") {
+    ///         Ok(since) => println!("since {}", since),
+    ///         Err(e) => println!("{}", e)
+    ///     }
     /// }
     /// ```
-    pub fn help(&self) {
-        println!("{}", self.help_text);
+    pub fn datetime_flag(&self, name: &str) -> Result<DateTime, FlagError> {
+        let r = self.result_flag_value(name)?;
+        match r {
+            FlagValue::DateTime(val) => Ok(val),
+            _ => Err(FlagError::TypeError),
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::error::FlagError;
-    use crate::{Context, Flag, FlagType};
+    /// Get uuid flag
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     match c.uuid_flag("id") {
+    ///         Ok(id) => println!("{}", id),
+    ///         Err(e) => println!("{}", e)
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "uuid")]
+    pub fn uuid_flag(&self, name: &str) -> Result<uuid::Uuid, FlagError> {
+        let r = self.result_flag_value(name)?;
+        match r {
+            FlagValue::Uuid(val) => Ok(val),
+            _ => Err(FlagError::TypeError),
+        }
+    }
 
-    #[test]
-    fn context_test() {
+    /// Get url flag
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     match c.url_flag("endpoint") {
+    ///         Ok(url) => println!("{}", url.host_str().unwrap_or("")),
+    ///         Err(e) => println!("{}", e)
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "url")]
+    pub fn url_flag(&self, name: &str) -> Result<url::Url, FlagError> {
+        let r = self.result_flag_value(name)?;
+        match r {
+            FlagValue::Url(val) => Ok(val),
+            _ => Err(FlagError::TypeError),
+        }
+    }
+
+    /// Get regex flag, compiled before the action runs
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     if let Ok(filter) = c.regex_flag("filter") {
+    ///         for line in ["alpha", "beta"].iter().filter(|l| filter.is_match(l)) {
+    ///             println!("{}", line);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn regex_flag(&self, name: &str) -> Result<regex::Regex, FlagError> {
+        let r = self.result_flag_value(name)?;
+        match r {
+            FlagValue::Regex(val) => Ok(val),
+            _ => Err(FlagError::TypeError),
+        }
+    }
+
+    /// Get bool positional argument
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     match c.bool_arg("FORCE") {
+    ///         Ok(v) => println!("{}", v),
+    ///         Err(e) => println!("{}", e)
+    ///     }
+    /// }
+    /// ```
+    pub fn bool_arg(&self, name: &str) -> Result<bool, FlagError> {
+        match self.result_arg_value(name)? {
+            FlagValue::Bool(val) => Ok(val),
+            _ => Err(FlagError::TypeError),
+        }
+    }
+
+    /// Get string positional argument
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     match c.string_arg("NAME") {
+    ///         Ok(v) => println!("{}", v),
+    ///         Err(e) => println!("{}", e)
+    ///     }
+    /// }
+    /// ```
+    pub fn string_arg(&self, name: &str) -> Result<String, FlagError> {
+        match self.result_arg_value(name)? {
+            FlagValue::String(val) => Ok(val),
+            _ => Err(FlagError::TypeError),
+        }
+    }
+
+    /// Get int positional argument
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     match c.int_arg("OFFSET") {
+    ///         Ok(v) => println!("{}", v),
+    ///         Err(e) => println!("{}", e)
+    ///     }
+    /// }
+    /// ```
+    pub fn int_arg(&self, name: &str) -> Result<i64, FlagError> {
+        match self.result_arg_value(name)? {
+            FlagValue::Int(val) => Ok(val),
+            _ => Err(FlagError::TypeError),
+        }
+    }
+
+    /// Get uint positional argument
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     match c.uint_arg("PORT") {
+    ///         Ok(v) => println!("{}", v),
+    ///         Err(e) => println!("{}", e)
+    ///     }
+    /// }
+    /// ```
+    pub fn uint_arg(&self, name: &str) -> Result<u64, FlagError> {
+        match self.result_arg_value(name)? {
+            FlagValue::Uint(val) => Ok(val),
+            _ => Err(FlagError::TypeError),
+        }
+    }
+
+    /// Get float positional argument
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     match c.float_arg("RATIO") {
+    ///         Ok(v) => println!("{}", v),
+    ///         Err(e) => println!("{}", e)
+    ///     }
+    /// }
+    /// ```
+    pub fn float_arg(&self, name: &str) -> Result<f64, FlagError> {
+        match self.result_arg_value(name)? {
+            FlagValue::Float(val) => Ok(val),
+            _ => Err(FlagError::TypeError),
+        }
+    }
+
+    /// Get flag value as any type parsed from the value
+    ///
+    /// Values of every flag type can be read, so the same getter works
+    /// for `bool`, `String`, `i64`, `u64` and `f64` flags as well as any
+    /// type implementing `FromStr` read from a string flag. A value the type
+    /// cannot be parsed from is a `FlagError::ParseError` with the message of
+    /// the type's error.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Context, Flag, FlagType};
+    ///
+    /// let args = vec!["--port".to_string(), "8080".to_string()];
+    /// let flags = vec![Flag::new("port", FlagType::String)];
+    /// let c = Context::new(args, Some(flags), "".to_string());
+    /// assert_eq!(c.get::<u16>("port"), Ok(8080));
+    /// assert!(c.get::<u8>("port").is_err());
+    /// ```
+    ///
+    /// A type of the app parsed from a flag
+    ///
+    /// ```
+    /// use seahorse::{Context, Flag, FlagType};
+    /// use std::str::FromStr;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Size(u32, u32);
+    ///
+    /// impl FromStr for Size {
+    ///     type Err = String;
+    ///
+    ///     fn from_str(s: &str) -> Result<Self, String> {
+    ///         let (w, h) = match s.find('x') {
+    ///             Some(i) => (&s[..i], &s[i + 1..]),
+    ///             None => return Err(format!("expected WxH, found {}", s)),
+    ///         };
+    ///         Ok(Size(w.parse().map_err(|_| "bad width")?, h.parse().map_err(|_| "bad height")?))
+    ///     }
+    /// }
+    ///
+    /// let args = vec!["--size".to_string(), "640x480".to_string()];
+    /// let c = Context::new(args, Some(vec![Flag::new("size", FlagType::String)]), "".to_string());
+    /// assert_eq!(c.get::<Size>("size"), Ok(Size(640, 480)));
+    /// ```
+    pub fn get<T>(&self, name: &str) -> Result<T, FlagError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let value = value_text(self.result_flag_value(name)?).ok_or(FlagError::TypeError)?;
+        value.parse().map_err(|e: T::Err| FlagError::ParseError {
+            name: name.to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Get flag value like `get`, `None` when the flag is not specified
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     if let Ok(Some(port)) = c.get_opt::<u16>("port") {
+    ///         println!("{}", port);
+    ///     }
+    /// }
+    /// ```
+    pub fn get_opt<T>(&self, name: &str) -> Result<Option<T>, FlagError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        match self.get(name) {
+            Ok(value) => Ok(Some(value)),
+            Err(FlagError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Get values of the first occurrence of a flag taking several values,
+    /// or of a variadic positional argument
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     if let Ok(point) = c.values::<i64>("point") {
+    ///         println!("x: {}, y: {}", point[0], point[1]);
+    ///     }
+    /// }
+    /// ```
+    pub fn values<T: FromStr>(&self, name: &str) -> Result<Vec<T>, FlagError> {
+        if self.flags.contains_key(name) {
+            parse_values(self.result_flag_value(name)?)
+        } else {
+            parse_values(self.result_arg_value(name)?)
+        }
+    }
+
+    /// Get values of each occurrence of a repeatable flag
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     for point in c.value_groups::<i64>("point").unwrap_or_default() {
+    ///         println!("{:?}", point);
+    ///     }
+    /// }
+    /// ```
+    pub fn value_groups<T: FromStr>(&self, name: &str) -> Result<Vec<Vec<T>>, FlagError> {
+        let first = self.result_flag_value(name)?;
+        match self.groups.get(name) {
+            Some(groups) => groups.iter().cloned().map(parse_values).collect(),
+            None => Ok(vec![parse_values(first)?]),
+        }
+    }
+
+    /// Get flag value like `get`, or the default when the flag is not specified
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     let out = c.get_or::<String>("out", "a.out");
+    /// }
+    /// ```
+    pub fn get_or<T>(&self, name: &str, default: impl Into<T>) -> Result<T, FlagError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        Ok(self.get_opt(name)?.unwrap_or_else(|| default.into()))
+    }
+
+    /// Parse the flags and arguments into a type implementing `FromContext`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::error::FlagError;
+    /// use seahorse::{Context, FromContext};
+    ///
+    /// struct Opts {
+    ///     out: String,
+    /// }
+    ///
+    /// impl FromContext for Opts {
+    ///     fn from_context(c: &Context) -> Result<Self, FlagError> {
+    ///         Ok(Self { out: c.get_or("out", "a.out")? })
+    ///     }
+    /// }
+    ///
+    /// fn action(c: &Context) {
+    ///     if let Ok(opts) = c.parse::<Opts>() {
+    ///         println!("{}", opts.out);
+    ///     }
+    /// }
+    /// ```
+    pub fn parse<T: FromContext>(&self) -> Result<T, FlagError> {
+        T::from_context(self)
+    }
+
+    /// Get where the value of a flag comes from
+    ///
+    /// `None` for flags without a value, bool flags not given included, and
+    /// for names of no flag.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Context, ValueSource};
+    ///
+    /// fn action(c: &Context) {
+    ///     if let Some(ValueSource::Environment(name)) = c.value_source("region") {
+    ///         eprintln!("region read from {}", name);
+    ///     }
+    /// }
+    /// ```
+    pub fn value_source(&self, name: &str) -> Option<ValueSource> {
+        self.declared
+            .iter()
+            .find(|declared| declared.name == name)
+            .and_then(|declared| declared.source.clone())
+    }
+
+    /// Get number of occurrences of a flag
+    ///
+    /// Every occurrence of a repeatable flag is counted,
+    /// other flags count once at most.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     println!("verbosity: {}", c.count_flag("verbose"));
+    /// }
+    /// ```
+    pub fn count_flag(&self, name: &str) -> usize {
+        self.counts.get(name).cloned().unwrap_or(0)
+    }
+
+    /// Whether a flag was given, even with an empty value like `--label=`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     if c.is_present("label") && c.str_flag("label") == Ok("") {
+    ///         println!("label cleared");
+    ///     }
+    /// }
+    /// ```
+    pub fn is_present(&self, name: &str) -> bool {
+        self.count_flag(name) > 0
+    }
+
+    /// Whether the string flag or positional argument has a value, so that
+    /// `c[name]` reads it
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     if c.contains("name") {
+    ///         println!("Hello, {}", &c["name"]);
+    ///     }
+    /// }
+    /// ```
+    pub fn contains(&self, name: &str) -> bool {
+        self.value_of(name).is_some()
+    }
+
+    /// Iterate over the flags with a value, in declaration order
+    ///
+    /// Values read from defaults, the environment or a prompt are included and
+    /// bool flags not given are `false`. Secret flags are left out so that the
+    /// values can be logged.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Context, Flag, FlagType, FlagValue};
+    ///
+    /// let args = vec!["--name".to_string(), "alice".to_string()];
+    /// let flags = vec![
+    ///     Flag::new("name", FlagType::String),
+    ///     Flag::new("force", FlagType::Bool),
+    /// ];
+    /// let c = Context::new(args, Some(flags), "".to_string());
+    /// let names: Vec<&str> = c.flags().map(|(name, _)| name).collect();
+    /// assert_eq!(names, vec!["name", "force"]);
+    /// ```
+    pub fn flags(&self) -> impl Iterator<Item = (&str, &FlagValue)> + '_ {
+        self.declared
+            .iter()
+            .filter_map(move |declared| self.resolved(declared))
+    }
+
+    /// Iterate over the flags given on the command line, in declaration order
+    ///
+    /// Like `flags`, leaving out values from defaults, the environment or a
+    /// prompt, and secret flags.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     for (name, value) in c.set_flags() {
+    ///         eprintln!("{} = {:?}", name, value);
+    ///     }
+    /// }
+    /// ```
+    pub fn set_flags(&self) -> impl Iterator<Item = (&str, &FlagValue)> + '_ {
+        self.declared
+            .iter()
+            .filter(|declared| declared.source == Some(ValueSource::CommandLine))
+            .filter_map(move |declared| self.resolved(declared))
+    }
+
+    /// Name and value of the declared flag, unless it is a secret or has no value
+    fn resolved<'a>(&'a self, declared: &'a Declared) -> Option<(&'a str, &'a FlagValue)> {
+        let value = match self.flags.get(&declared.name)? {
+            Ok(FlagValue::Secret(_)) => return None,
+            Ok(value) => value,
+            Err(FlagError::NotFound) if declared.bool_flag => &NOT_GIVEN,
+            Err(_) => return None,
+        };
+        Some((&declared.name, value))
+    }
+
+    /// Get log level from the verbosity flags
+    ///
+    /// `--quiet` gives `Error`, otherwise the level is `Warn` and each
+    /// `--verbose` raises it through `Info`, `Debug` and `Trace`.
+    ///
+    /// Example
+    ///
+    /// ```ignore
+    /// use seahorse::{App, Context};
+    ///
+    /// let app = App::new("cli")
+    ///     .verbosity_flags()
+    ///     .action(|c: &Context| {
+    ///         env_logger::Builder::new().filter_level(c.log_level()).init();
+    ///         log::info!("shown with -v");
+    ///     });
+    /// ```
+    #[cfg(feature = "log")]
+    pub fn log_level(&self) -> log::LevelFilter {
+        if self.bool_flag("quiet") {
+            return log::LevelFilter::Error;
+        }
+
+        match self.count_flag("verbose") {
+            0 => log::LevelFilter::Warn,
+            1 => log::LevelFilter::Info,
+            2 => log::LevelFilter::Debug,
+            _ => log::LevelFilter::Trace,
+        }
+    }
+
+    /// Display help
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     c.help();
+    /// }
+    /// ```
+    pub fn help(&self) {
+        self.streams.out(&format!("{}\n", self.help_text));
+    }
+}
+
+/// Value of the string flag or positional argument, like `value_of`
+///
+/// Panics when the flag has no value, naming the flag and the command. Flags
+/// users may leave out are better read with `value_of` or the other checked
+/// getters, indexing suits values that are always there like required flags
+/// or flags with a default value.
+///
+/// Example
+///
+/// ```
+/// use seahorse::{Context, Flag, FlagType};
+///
+/// let args = vec!["--name".to_string(), "alice".to_string()];
+/// let flags = vec![Flag::new("name", FlagType::String)];
+/// let c = Context::new(args, Some(flags), "".to_string());
+/// assert_eq!(&c["name"], "alice");
+/// ```
+impl Index<&str> for Context {
+    type Output = str;
+
+    fn index(&self, name: &str) -> &str {
+        match self.value_of(name) {
+            Some(value) => value,
+            None if self.path.is_empty() => panic!(r#"Flag "{}" has no string value"#, name),
+            None => panic!(
+                r#"Flag "{}" has no string value in command "{}""#,
+                name,
+                self.path.join(" ")
+            ),
+        }
+    }
+}
+
+/// Value of an occurrence of a flag that is not a bool flag
+fn group_value(
+    flag: &Flag,
+    matches: &FlagMatches,
+    group: &[usize],
+) -> Result<FlagValue, FlagError> {
+    let value = |position: &usize| flag.value(Some(matches.arg(*position).to_string()));
+    match flag.number_of_values {
+        Some(n) if group.len() < n => Err(FlagError::ArgumentError),
+        Some(_) => group
+            .iter()
+            .map(value)
+            .collect::<Result<Vec<FlagValue>, FlagError>>()
+            .map(FlagValue::List),
+        // Values expanded from a glob pattern
+        None if group.len() > 1 => group
+            .iter()
+            .map(value)
+            .collect::<Result<Vec<FlagValue>, FlagError>>()
+            .map(FlagValue::List),
+        None => match group.first() {
+            Some(position) => value(position),
+            None => flag.value(None),
+        },
+    }
+}
+
+/// Text of a flag value, `None` for the values of a flag taking several
+fn value_text(value: FlagValue) -> Option<String> {
+    match value {
+        FlagValue::Bool(val) => Some(val.to_string()),
+        FlagValue::String(val) => Some(val),
+        FlagValue::Int(val) => Some(val.to_string()),
+        FlagValue::Uint(val) => Some(val.to_string()),
+        FlagValue::Float(val) => Some(val.to_string()),
+        FlagValue::Secret(val) => Some(val.into_inner()),
+        FlagValue::DateTime(val) => Some(val.to_string()),
+        #[cfg(feature = "uuid")]
+        FlagValue::Uuid(val) => Some(val.to_string()),
+        #[cfg(feature = "url")]
+        FlagValue::Url(val) => Some(val.to_string()),
+        #[cfg(feature = "regex")]
+        FlagValue::Regex(val) => Some(val.as_str().to_string()),
+        FlagValue::List(_) => None,
+    }
+}
+
+fn parse_value<T: FromStr>(value: FlagValue) -> Result<T, FlagError> {
+    let value = value_text(value).ok_or(FlagError::TypeError)?;
+    value.parse().map_err(|_| FlagError::TypeError)
+}
+
+fn parse_values<T: FromStr>(value: FlagValue) -> Result<Vec<T>, FlagError> {
+    match value {
+        FlagValue::List(values) => values.into_iter().map(parse_value).collect(),
+        value => Ok(vec![parse_value(value)?]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::Settings;
+    use crate::error::FlagError;
+    use crate::{Arg, Context, Flag, FlagType, FlagValue, Prompt, Terminal};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn context_test() {
         let args = vec![
             "cli".to_string(),
             "command".to_string(),
@@ -227,8 +1475,12 @@ mod tests {
         ];
         let context = Context::new(args, Some(flags), "".to_string());
 
-        assert_eq!(context.bool_flag("bool"), true);
+        assert!(context.bool_flag("bool"));
         assert_eq!(context.string_flag("string"), Ok("test".to_string()));
+        assert_eq!(context.str_flag("string"), Ok("test"));
+        assert_eq!(context.str_flag("int"), Err(FlagError::TypeError));
+        assert_eq!(context.arg(2), Some("args"));
+        assert_eq!(context.arg(3), None);
         assert_eq!(context.int_flag("int"), Ok(100));
         assert_eq!(context.uint_flag("uint"), Ok(1234567654321));
         assert_eq!(context.float_flag("float"), Ok(1.23));
@@ -253,4 +1505,733 @@ mod tests {
             Err(FlagError::NotFound)
         );
     }
+
+    #[test]
+    fn wide_int_test() {
+        let args = vec![
+            "cli".to_string(),
+            "--int".to_string(),
+            "-5000000000".to_string(),
+            "--uint".to_string(),
+            "18000000000000000000".to_string(),
+            "--too-big".to_string(),
+            "9223372036854775808".to_string(),
+        ];
+        let flags = vec![
+            Flag::new("int", FlagType::Int),
+            Flag::new("uint", FlagType::Uint),
+            Flag::new("too-big", FlagType::Int),
+        ];
+        let context = Context::new(args, Some(flags), "".to_string());
+
+        assert_eq!(context.int_flag("int"), Ok(-5_000_000_000));
+        assert_eq!(context.uint_flag("uint"), Ok(18_000_000_000_000_000_000));
+        assert_eq!(context.int_flag("too-big"), Err(FlagError::ValueTypeError));
+        assert_eq!(context.get::<i64>("int"), Ok(-5_000_000_000));
+    }
+
+    #[test]
+    fn count_flag_test() {
+        let args = ["-v", "arg", "--verbose", "-vv", "-q", "-vx"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        let flags = vec![
+            Flag::new("verbose", FlagType::Bool).alias("v").repeatable(),
+            Flag::new("quiet", FlagType::Bool).alias("q"),
+            Flag::new("force", FlagType::Bool),
+        ];
+        let context = Context::new(args, Some(flags), "".to_string());
+
+        assert!(context.bool_flag("verbose"));
+        assert_eq!(context.count_flag("verbose"), 4);
+        assert_eq!(context.count_flag("quiet"), 1);
+        assert_eq!(context.count_flag("force"), 0);
+        assert_eq!(context.count_flag("not_registered"), 0);
+        assert_eq!(context.args, vec!["arg".to_string(), "-vx".to_string()]);
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn log_level_test() {
+        use log::LevelFilter;
+
+        let level = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            Context::new(args, Some(Flag::verbosity()), "".to_string()).log_level()
+        };
+
+        assert_eq!(level(&[]), LevelFilter::Warn);
+        assert_eq!(level(&["-q"]), LevelFilter::Error);
+        assert_eq!(level(&["-v"]), LevelFilter::Info);
+        assert_eq!(level(&["-vv"]), LevelFilter::Debug);
+        assert_eq!(level(&["-v", "--verbose", "-v"]), LevelFilter::Trace);
+        assert_eq!(level(&["-vvvv"]), LevelFilter::Trace);
+        assert_eq!(level(&["-vv", "--quiet"]), LevelFilter::Error);
+    }
+
+    #[test]
+    fn get_test() {
+        let args = ["--jobs", "4", "--ratio", "0.5", "--name", "x", "--dry"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        let flags = vec![
+            Flag::new("jobs", FlagType::Int),
+            Flag::new("ratio", FlagType::Float),
+            Flag::new("name", FlagType::String),
+            Flag::new("dry", FlagType::Bool),
+            Flag::new("out", FlagType::String),
+        ];
+        let context = Context::new(args, Some(flags), "".to_string());
+
+        assert_eq!(context.get::<isize>("jobs"), Ok(4));
+        assert_eq!(context.get::<u8>("jobs"), Ok(4));
+        assert_eq!(context.get::<f64>("ratio"), Ok(0.5));
+        assert_eq!(context.get::<String>("name"), Ok("x".to_string()));
+        assert_eq!(context.get::<bool>("dry"), Ok(true));
+        let invalid = Err(FlagError::ParseError {
+            name: "ratio".to_string(),
+            message: "invalid digit found in string".to_string(),
+        });
+        assert_eq!(context.get::<isize>("ratio"), invalid);
+        assert_eq!(
+            invalid.clone().unwrap_err().to_string(),
+            "Invalid value for flag \"ratio\": invalid digit found in string"
+        );
+        assert_eq!(context.get::<String>("out"), Err(FlagError::NotFound));
+        assert_eq!(context.get::<String>("unknown"), Err(FlagError::Undefined));
+
+        assert_eq!(
+            context.get_or::<String>("out", "a.out"),
+            Ok("a.out".to_string())
+        );
+        assert_eq!(context.get_or::<isize>("jobs", 1isize), Ok(4));
+        assert_eq!(context.get_or::<isize>("ratio", 1isize), invalid);
+
+        assert_eq!(context.get_opt::<u16>("jobs"), Ok(Some(4)));
+        assert_eq!(context.get_opt::<u16>("out"), Ok(None));
+        assert_eq!(context.get_opt::<u16>("unknown"), Err(FlagError::Undefined));
+        assert!(context.get_opt::<u16>("ratio").is_err());
+    }
+
+    #[test]
+    fn alias_lookup_test() {
+        let flags = || {
+            Some(vec![
+                Flag::new("name", FlagType::String).alias("n").alias("nm"),
+                Flag::new("count", FlagType::Int).alias("c"),
+                Flag::new("force", FlagType::Bool).alias("f"),
+            ])
+        };
+        let long = [
+            "--name", "a", "arg", "--count", "3", "--force", "--name", "b",
+        ];
+        let short = ["-nm", "a", "arg", "-c", "3", "-f", "-n", "b"];
+        let long = Context::new(
+            long.iter().map(|a| a.to_string()).collect(),
+            flags(),
+            "".to_string(),
+        );
+        let short = Context::new(
+            short.iter().map(|a| a.to_string()).collect(),
+            flags(),
+            "".to_string(),
+        );
+
+        for context in &[&long, &short] {
+            assert_eq!(context.string_flag("name"), Ok("a".to_string()));
+            assert_eq!(context.int_flag("count"), Ok(3));
+            assert!(context.bool_flag("force"));
+        }
+        assert_eq!(long.args, vec!["arg", "--name", "b"]);
+        assert_eq!(short.args, vec!["arg", "-n", "b"]);
+    }
+
+    #[test]
+    fn invalid_number_test() {
+        let flags = vec![
+            Flag::new("port", FlagType::Int).alias("p"),
+            Flag::new("ratio", FlagType::Float),
+        ];
+        let error = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            Context::for_action(
+                &[],
+                args,
+                &flags,
+                "".to_string(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+            .err()
+            .map(|e| e.to_string())
+        };
+
+        assert_eq!(
+            error(&["-p", "http"]),
+            Some("Invalid value \"http\" for flag \"port\", expected int".to_string())
+        );
+        assert_eq!(
+            error(&["--port", "80", "--ratio", "half"]),
+            Some("Invalid value \"half\" for flag \"ratio\", expected float".to_string())
+        );
+        assert_eq!(error(&["--port", "80", "--ratio", "0.5"]), None);
+    }
+
+    #[test]
+    fn normalize_values_test() {
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let flags = vec![
+            Flag::new("format", FlagType::String)
+                .choices(&["json", "yaml"])
+                .trim()
+                .to_lowercase()
+                .validator(move |v: &str| {
+                    log.lock().unwrap().push(v.to_string());
+                    Ok(())
+                }),
+            Flag::new("name", FlagType::String).trim(),
+            Flag::new("token", FlagType::Secret).trim(),
+            Flag::new("word", FlagType::String).validator(|v: &str| {
+                if v.contains(' ') {
+                    Err("must not contain spaces".to_string())
+                } else {
+                    Ok(())
+                }
+            }),
+        ];
+        let context = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            Context::for_action(
+                &[],
+                args,
+                &flags,
+                "".to_string(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+            .map_err(|e| e.to_string())
+        };
+
+        let c = context(&[
+            "--format", " JSON\n", "--name", " Alice ", "--token", " t0k ",
+        ])
+        .unwrap();
+        assert_eq!(c.string_flag("format"), Ok("json".to_string()));
+        assert_eq!(*seen.lock().unwrap(), vec!["json".to_string()]);
+        assert_eq!(c.string_flag("name"), Ok("Alice".to_string()));
+        assert_eq!(
+            c.secret_flag("token").map(|t| t.expose().to_string()),
+            Ok(" t0k ".to_string())
+        );
+
+        assert_eq!(
+            context(&["--word", "a b"]).err(),
+            Some("Invalid value \"a b\" for flag \"word\": must not contain spaces".to_string())
+        );
+        assert!(context(&["--word", "ab"]).is_ok());
+    }
+
+    #[test]
+    fn positional_args_test() {
+        let flags = vec![
+            Flag::new("name", FlagType::String).alias("n"),
+            Flag::new("point", FlagType::Int).number_of_values(2),
+            Flag::new("force", FlagType::Bool).alias("f"),
+        ];
+        let args = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            Context::for_action(
+                &[],
+                args,
+                &flags,
+                "".to_string(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+            .unwrap()
+            .args
+        };
+
+        assert_eq!(args(&["--name", "Alice", "Bob"]), vec!["Bob"]);
+        assert_eq!(args(&["Bob", "--name", "Alice"]), vec!["Bob"]);
+        assert_eq!(args(&["a", "--name=Alice", "b"]), vec!["a", "b"]);
+        assert_eq!(args(&["a", "-n=Alice", "b"]), vec!["a", "b"]);
+        assert_eq!(
+            args(&["a", "--point", "1", "2", "b", "-f", "c"]),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(args(&["--point=1", "2", "a"]), vec!["a"]);
+        assert_eq!(args(&["a", "--", "--name", "b"]), vec!["a", "--name", "b"]);
+    }
+
+    #[test]
+    fn empty_value_test() {
+        let flags = vec![
+            Flag::new("label", FlagType::String).alias("l"),
+            Flag::new("token", FlagType::Secret),
+            Flag::new("port", FlagType::Int),
+            Flag::new("ratio", FlagType::Float),
+            Flag::new("force", FlagType::Bool),
+            Flag::new("verbose", FlagType::Bool).alias("v").repeatable(),
+            Flag::new("other", FlagType::String),
+        ];
+        let context = |args: &[&str]| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            Context::for_action(
+                &[],
+                args,
+                &flags,
+                "".to_string(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+            .map_err(|e| e.to_string())
+        };
+
+        let c = context(&["--label=", "-l=", "--token=", "x"]).unwrap();
+        assert_eq!(c.string_flag("label"), Ok("".to_string()));
+        assert!(c.is_present("label"));
+        assert_eq!(c.secret_flag("token").map(|t| t.expose().len()), Ok(0));
+        assert_eq!(c.string_flag("other"), Err(FlagError::NotFound));
+        assert!(!c.is_present("other"));
+        assert_eq!(c.args, vec!["-l=".to_string(), "x".to_string()]);
+
+        assert_eq!(
+            context(&["--port="]).err(),
+            Some("Empty value for flag \"port\", expected int".to_string())
+        );
+        assert_eq!(
+            context(&["--ratio="]).err(),
+            Some("Empty value for flag \"ratio\", expected float".to_string())
+        );
+        assert_eq!(
+            context(&["--force="]).err(),
+            Some("Flag \"force\" takes no value".to_string())
+        );
+        assert_eq!(
+            context(&["-v", "-v="]).err(),
+            Some("Flag \"verbose\" takes no value".to_string())
+        );
+        assert_eq!(
+            context(&["--", "--force="]).map(|c| c.args),
+            Ok(vec!["--force=".to_string()])
+        );
+    }
+
+    #[test]
+    fn bool_absent_test() {
+        let args = vec!["--given".to_string()];
+        let flags = vec![
+            Flag::new("given", FlagType::Bool),
+            Flag::new("absent", FlagType::Bool),
+            Flag::new("off", FlagType::Bool).default_value("false"),
+        ];
+        let context = Context::new(args, Some(flags), "".to_string());
+
+        assert_eq!(context.get::<bool>("given"), Ok(true));
+        assert_eq!(context.get::<bool>("absent"), Err(FlagError::NotFound));
+        assert_eq!(context.get::<bool>("off"), Ok(false));
+        assert!(context.bool_flag("given"));
+        assert!(!context.bool_flag("absent"));
+        assert!(!context.bool_flag("off"));
+        assert_eq!(context.count_flag("absent"), 0);
+    }
+
+    #[test]
+    fn reader_for_flag_test() {
+        use std::io::Read;
+
+        let file = std::env::temp_dir().join("seahorse_reader_for_flag_test.txt");
+        std::fs::write(&file, "content").unwrap();
+        let args = vec![
+            "--input".to_string(),
+            file.to_string_lossy().into_owned(),
+            "-".to_string(),
+        ];
+        let flags = vec![
+            Flag::new("input", FlagType::String),
+            Flag::new("output", FlagType::String),
+        ];
+        let context = Context::new(args, Some(flags), "".to_string());
+
+        let mut content = String::new();
+        let mut reader = context.reader_for_flag("input").unwrap();
+        reader.read_to_string(&mut content).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(content, "content");
+        assert_eq!(context.args, vec!["-".to_string()]);
+        assert_eq!(
+            context
+                .reader_for_flag("output")
+                .err()
+                .map(|e| e.to_string())
+                .unwrap()
+                .to_string(),
+            "NotFound"
+        );
+        assert!(context.reader_for_flag("input").is_err());
+    }
+
+    #[test]
+    fn values_test() {
+        let args = [
+            "--point", "1", "2", "--point", "3", "4", "--size", "5", "--tag", "a",
+        ]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+        let flags = vec![
+            Flag::new("point", FlagType::Int)
+                .number_of_values(2)
+                .repeatable(),
+            Flag::new("size", FlagType::Uint).number_of_values(2),
+            Flag::new("tag", FlagType::String),
+            Flag::new("empty", FlagType::String).number_of_values(2),
+        ];
+        let context = Context::new(args, Some(flags), "".to_string());
+
+        assert_eq!(context.values::<isize>("point"), Ok(vec![1, 2]));
+        assert_eq!(
+            context.value_groups::<isize>("point"),
+            Ok(vec![vec![1, 2], vec![3, 4]])
+        );
+        assert_eq!(
+            context.result_flag_value("point"),
+            Ok(FlagValue::List(vec![FlagValue::Int(1), FlagValue::Int(2)]))
+        );
+        assert_eq!(context.count_flag("point"), 2);
+        assert_eq!(
+            context.values::<usize>("size"),
+            Err(FlagError::ArgumentError)
+        );
+        assert_eq!(context.values::<String>("tag"), Ok(vec!["a".to_string()]));
+        assert_eq!(
+            context.value_groups::<String>("tag"),
+            Ok(vec![vec!["a".to_string()]])
+        );
+        assert_eq!(context.values::<String>("empty"), Err(FlagError::NotFound));
+        assert_eq!(context.get::<isize>("point"), Err(FlagError::TypeError));
+    }
+
+    #[test]
+    fn secret_flag_test() {
+        std::env::set_var("SEAHORSE_SECRET_FLAG_TEST", "hunter2");
+        let flags = vec![
+            Flag::new("token", FlagType::Secret).env("SEAHORSE_SECRET_FLAG_TEST"),
+            Flag::new("key", FlagType::Secret),
+            Flag::new("name", FlagType::String),
+        ];
+        let args = vec!["--name".to_string(), "alice".to_string()];
+        let context = Context::for_action(
+            &[],
+            args,
+            &flags,
+            "".to_string(),
+            &Settings::new(&Prompt::non_interactive()),
+        )
+        .unwrap();
+
+        let token = context.secret_flag("token").unwrap();
+        assert_eq!(token.expose(), "hunter2");
+        assert_eq!(format!("{:?}", token), "Secret(****)");
+        assert_eq!(context.secret_flag("key"), Err(FlagError::NotFound));
+        assert_eq!(context.secret_flag("name"), Err(FlagError::TypeError));
+    }
+
+    #[test]
+    fn flags_test() {
+        std::env::set_var("SEAHORSE_FLAGS_TEST", "eu");
+        let flags = vec![
+            Flag::new("name", FlagType::String),
+            Flag::new("force", FlagType::Bool),
+            Flag::new("verbose", FlagType::Bool).repeatable(),
+            Flag::new("depth", FlagType::Int).default_value("1"),
+            Flag::new("region", FlagType::String).env("SEAHORSE_FLAGS_TEST"),
+            Flag::new("token", FlagType::Secret),
+            Flag::new("label", FlagType::String),
+        ];
+        let args = [
+            "a",
+            "--token",
+            "t",
+            "--verbose",
+            "--name",
+            "alice",
+            "-verbose",
+        ];
+        let context = Context::for_action(
+            &[],
+            args.iter().map(|a| a.to_string()).collect(),
+            &flags,
+            "".to_string(),
+            &Settings::new(&Prompt::non_interactive()),
+        )
+        .unwrap();
+
+        let all: BTreeMap<&str, FlagValue> = context
+            .flags()
+            .map(|(name, value)| (name, value.clone()))
+            .collect();
+        let expected: BTreeMap<&str, FlagValue> = vec![
+            ("name", FlagValue::String("alice".to_string())),
+            ("force", FlagValue::Bool(false)),
+            ("verbose", FlagValue::Bool(true)),
+            ("depth", FlagValue::Int(1)),
+            ("region", FlagValue::String("eu".to_string())),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(all, expected);
+        let names: Vec<&str> = context.flags().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["name", "force", "verbose", "depth", "region"]);
+
+        let set: BTreeMap<&str, &FlagValue> = context.set_flags().collect();
+        assert_eq!(set.keys().collect::<Vec<_>>(), vec![&"name", &"verbose"]);
+    }
+
+    #[test]
+    fn datetime_flag_test() {
+        let flags = vec![
+            Flag::new("since", FlagType::DateTime),
+            Flag::new("until", FlagType::DateTime),
+        ];
+        let context = |args: &[&str]| {
+            Context::for_action(
+                &[],
+                args.iter().map(|a| a.to_string()).collect(),
+                &flags,
+                "".to_string(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+        };
+
+        let c = context(&[
+            "--since",
+            "2024-01-15",
+            "--until",
+            "2024-03-01T13:00:00+01:00",
+        ])
+        .unwrap();
+        let since = c.datetime_flag("since").unwrap();
+        let until = c.datetime_flag("until").unwrap();
+        assert_eq!(since.to_string(), "2024-01-15T00:00:00Z");
+        assert_eq!(until.unix_timestamp(), 1_709_294_400);
+        assert_eq!(until.offset_minutes(), 60);
+        assert_eq!(
+            c.get::<String>("until"),
+            Ok("2024-03-01T13:00:00+01:00".to_string())
+        );
+        assert_eq!(c.string_flag("since"), Err(FlagError::TypeError));
+
+        assert_eq!(
+            context(&["--since", "yesterday"])
+                .err()
+                .map(|e| e.to_string()),
+            Some(
+                "Invalid value \"yesterday\" for flag \"since\", expected datetime \
+                 like 2024-01-15 or 2024-03-01T12:00:00Z"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn uuid_flag_test() {
+        let flags = vec![Flag::new("id", FlagType::Uuid)];
+        let context = |args: &[&str]| {
+            Context::for_action(
+                &[],
+                args.iter().map(|a| a.to_string()).collect(),
+                &flags,
+                "".to_string(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+        };
+
+        let c = context(&["--id", "67E5504410B1426F9247BB680E5FE0C8"]).unwrap();
+        let id = c.uuid_flag("id").unwrap();
+        assert_eq!(id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+        assert_eq!(
+            context(&["--id", "67e55044-10b1-426f-9247-bb680e5fe0c8"])
+                .unwrap()
+                .uuid_flag("id"),
+            Ok(id)
+        );
+        assert_eq!(c.string_flag("id"), Err(FlagError::TypeError));
+
+        assert_eq!(
+            context(&["--id", "67e55044-10b1"])
+                .err()
+                .map(|e| e.to_string()),
+            Some(
+                "Invalid value \"67e55044-10b1\" for flag \"id\", expected uuid \
+                 like xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "url")]
+    fn url_flag_test() {
+        let flags = vec![Flag::new("endpoint", FlagType::Url).allowed_schemes(&["http", "HTTPS"])];
+        let context = |args: &[&str]| {
+            Context::for_action(
+                &[],
+                args.iter().map(|a| a.to_string()).collect(),
+                &flags,
+                "".to_string(),
+                &Settings::new(&Prompt::non_interactive()),
+            )
+        };
+        let error = |args: &[&str]| context(args).err().map(|e| e.to_string());
+
+        let c = context(&["--endpoint", "https://api.example.com:8443/v1"]).unwrap();
+        let url = c.url_flag("endpoint").unwrap();
+        assert_eq!(url.host_str(), Some("api.example.com"));
+        assert_eq!(url.port(), Some(8443));
+        assert_eq!(
+            c.get::<String>("endpoint"),
+            Ok("https://api.example.com:8443/v1".to_string())
+        );
+        assert_eq!(c.string_flag("endpoint"), Err(FlagError::TypeError));
+
+        assert_eq!(
+            error(&["--endpoint", "api/v1"]),
+            Some(
+                "Invalid value \"api/v1\" for flag \"endpoint\", expected url \
+                 like https://example.com"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            error(&["--endpoint", "ftp://example.com"]),
+            Some(
+                "Scheme \"ftp\" of flag \"endpoint\" is not allowed, expected http or https"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn confirm_test() {
+        let flags = || {
+            Some(vec![
+                Flag::new("yes", FlagType::Bool).alias("y"),
+                Flag::new("force", FlagType::Bool),
+            ])
+        };
+        let context = |args: &[&str], prompt: Prompt| {
+            let args = args.iter().map(|a| a.to_string()).collect();
+            Context::new(args, flags(), "".to_string()).with_prompt(prompt)
+        };
+        let no = || Prompt::from(|_: &str| Ok("n".to_string()));
+
+        assert!(!context(&[], no()).confirm("Delete?"));
+        assert!(context(&["-y"], no()).confirm("Delete?"));
+        assert!(context(&["--yes"], no()).confirm_default_yes("Delete?"));
+        assert!(!context(&["--force"], no()).confirm("Delete?"));
+        assert!(context(&["--force"], no().yes_flag("force")).confirm("Delete?"));
+        assert!(!context(&["-y"], no().yes_flag("force")).confirm("Delete?"));
+        assert!(!context(&[], Prompt::non_interactive()).confirm("Delete?"));
+        assert!(context(&[], Prompt::non_interactive()).confirm_default_yes("Delete?"));
+    }
+
+    #[test]
+    fn terminal_test() {
+        let wide = Terminal {
+            stdout_is_tty: true,
+            stderr_is_tty: true,
+            width: Some(120),
+        };
+        let context = Context::new(Vec::new(), None, "".to_string()).with_terminal(wide);
+        assert!(context.stdout_is_tty());
+        assert!(context.stderr_is_tty());
+        assert_eq!(context.terminal_width(), Some(120));
+
+        let context = Context::new(Vec::new(), None, "".to_string()).with_terminal(Terminal {
+            stderr_is_tty: false,
+            width: None,
+            ..wide
+        });
+        assert!(context.stdout_is_tty());
+        assert!(!context.stderr_is_tty());
+        assert_eq!(context.terminal_width(), None);
+    }
+
+    #[test]
+    fn borrowed_values_test() {
+        let flags = vec![
+            Flag::new("from", FlagType::String),
+            Flag::new("to", FlagType::String),
+            Flag::new("jobs", FlagType::Uint),
+        ];
+        let args = [
+            "--from",
+            "a.txt",
+            "--to",
+            "out/b.txt",
+            "--jobs",
+            "2",
+            "NAME",
+        ];
+        let mut context = Context::new(
+            args.iter().map(|a| a.to_string()).collect(),
+            Some(flags),
+            "".to_string(),
+        );
+        context.declare_args(&[Arg::new("name", FlagType::String)]);
+
+        let (from, to, name) = (
+            context.value_of("from"),
+            context.value_of("to"),
+            context.value_of("name"),
+        );
+        assert_eq!(from, Some("a.txt"));
+        assert_eq!(to, Some("out/b.txt"));
+        assert_eq!(name, Some("NAME"));
+        assert_eq!(
+            context.path_of("to").and_then(|p| p.parent()),
+            Some(std::path::Path::new("out"))
+        );
+        assert_eq!(context.value_of("jobs"), None);
+        assert_eq!(context.value_of("missing"), None);
+    }
+
+    #[test]
+    fn index_test() {
+        let flags = vec![
+            Flag::new("name", FlagType::String),
+            Flag::new("jobs", FlagType::Int),
+            Flag::new("out", FlagType::String),
+        ];
+        let args = vec![
+            "--name".to_string(),
+            "alice".to_string(),
+            "--jobs".to_string(),
+            "2".to_string(),
+        ];
+        let path = ["cli".to_string(), "add".to_string()];
+        let context = Context::for_action(
+            &path,
+            args,
+            &flags,
+            "".to_string(),
+            &Settings::new(&Prompt::non_interactive()),
+        )
+        .unwrap();
+
+        assert_eq!(&context["name"], "alice");
+        assert!(context.contains("name"));
+        assert!(!context.contains("jobs"));
+        assert!(!context.contains("out"));
+
+        let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| context["out"].len()))
+            .unwrap_err();
+        assert_eq!(
+            panic.downcast_ref::<String>().map(String::as_str),
+            Some(r#"Flag "out" has no string value in command "cli add""#)
+        );
+    }
 }
@@ -0,0 +1,308 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Value of a datetime flag, a point in time with the offset it was given in
+///
+/// Values are RFC 3339 timestamps like `2024-03-01T12:00:00Z` or
+/// `2024-03-01T13:00:00.5+01:00`, or dates like `2024-01-15` read as
+/// midnight UTC. Relative values like `yesterday` are not accepted.
+///
+/// Example
+///
+/// ```
+/// use seahorse::DateTime;
+///
+/// let since: DateTime = "2024-01-15".parse().unwrap();
+/// assert_eq!(since.unix_timestamp(), 1_705_276_800);
+/// assert_eq!(since.to_string(), "2024-01-15T00:00:00Z");
+///
+/// let until: DateTime = "2024-03-01T13:00:00+01:00".parse().unwrap();
+/// assert_eq!(until.offset_minutes(), 60);
+/// assert!(since < until);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DateTime {
+    seconds: i64,
+    nanosecond: u32,
+    offset: i32,
+}
+
+/// Forms of datetime values, shown in errors
+pub(crate) const FORMATS: [&str; 2] = ["2024-01-15", "2024-03-01T12:00:00Z"];
+
+impl DateTime {
+    /// Seconds since 1970-01-01T00:00:00Z
+    pub fn unix_timestamp(&self) -> i64 {
+        self.seconds
+    }
+
+    /// Nanoseconds within the second
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    /// Offset from UTC the value was given in, in minutes east
+    pub fn offset_minutes(&self) -> i32 {
+        self.offset
+    }
+}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DateTime {
+    /// Points in time are compared whatever their offsets
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.seconds, self.nanosecond).cmp(&(other.seconds, other.nanosecond))
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).ok_or_else(|| {
+            format!(
+                "expected a datetime like {}, got \"{}\"",
+                FORMATS.join(" or "),
+                s
+            )
+        })
+    }
+}
+
+/// RFC 3339 form in the offset of the value
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let local = self.seconds + i64::from(self.offset) * 60;
+        let (days, time) = floor_div(local, 86_400);
+        let (year, month, day) = civil_from_days(days);
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            time / 3600,
+            time / 60 % 60,
+            time % 60
+        )?;
+        if self.nanosecond > 0 {
+            let fraction = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        match self.offset {
+            0 => f.write_str("Z"),
+            offset => write!(
+                f,
+                "{}{:02}:{:02}",
+                if offset < 0 { '-' } else { '+' },
+                offset.abs() / 60,
+                offset.abs() % 60
+            ),
+        }
+    }
+}
+
+/// Datetime written as a date or an RFC 3339 timestamp
+fn parse(s: &str) -> Option<DateTime> {
+    let bytes = s.as_bytes();
+    if !s.is_ascii() || bytes.len() < 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+    let year = number(&s[0..4])?;
+    let month = number(&s[5..7])?;
+    let day = number(&s[8..10])?;
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    if bytes.len() == 10 {
+        return Some(DateTime {
+            seconds: days * 86_400,
+            nanosecond: 0,
+            offset: 0,
+        });
+    }
+
+    let time = &s[11..];
+    match bytes[10] {
+        b'T' | b't' | b' ' => {}
+        _ => return None,
+    }
+    let tb = time.as_bytes();
+    if tb.len() < 9 || tb[2] != b':' || tb[5] != b':' {
+        return None;
+    }
+    let hour = number(&time[0..2])?;
+    let minute = number(&time[3..5])?;
+    let second = number(&time[6..8])?;
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    let mut rest = &time[8..];
+    let mut nanosecond = 0;
+    if rest.starts_with('.') {
+        let digits = rest[1..].bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        let fraction = &rest[1..=digits];
+        nanosecond = fraction
+            .bytes()
+            .chain(std::iter::repeat(b'0'))
+            .take(9)
+            .fold(0, |n, digit| n * 10 + u32::from(digit - b'0'));
+        rest = &rest[digits + 1..];
+    }
+
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ => {
+            let ob = rest.as_bytes();
+            if ob.len() != 6 || ob[3] != b':' {
+                return None;
+            }
+            let sign = match ob[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let hours = number(&rest[1..3])?;
+            let minutes = number(&rest[4..6])?;
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            sign * (hours * 60 + minutes) as i32
+        }
+    };
+
+    let local = days * 86_400 + hour * 3600 + minute * 60 + second;
+    Some(DateTime {
+        seconds: local - i64::from(offset) * 60,
+        nanosecond,
+        offset,
+    })
+}
+
+/// Number written with ASCII digits only
+fn number(s: &str) -> Option<i64> {
+    if s.bytes().all(|b| b.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Quotient rounded down and remainder of the division by a positive divisor
+fn floor_div(n: i64, divisor: i64) -> (i64, i64) {
+    let remainder = n % divisor;
+    if remainder < 0 {
+        (n / divisor - 1, remainder + divisor)
+    } else {
+        (n / divisor, remainder)
+    }
+}
+
+/// Days since 1970-01-01 of the date in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = floor_div(year, 400).0;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Date of the day since 1970-01-01, the inverse of `days_from_civil`
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = floor_div(days, 146_097).0;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DateTime;
+
+    fn parse(s: &str) -> Option<DateTime> {
+        s.parse().ok()
+    }
+
+    #[test]
+    fn date_test() {
+        let date = parse("2024-01-15").unwrap();
+        assert_eq!(date.unix_timestamp(), 1_705_276_800);
+        assert_eq!(date.offset_minutes(), 0);
+        assert_eq!(date.to_string(), "2024-01-15T00:00:00Z");
+        assert_eq!(parse("1970-01-01").unwrap().unix_timestamp(), 0);
+        assert_eq!(parse("1969-12-31").unwrap().unix_timestamp(), -86_400);
+        assert!(parse("2024-02-29").is_some());
+        assert!(parse("2023-02-29").is_none());
+    }
+
+    #[test]
+    fn timestamp_test() {
+        let utc = parse("2024-03-01T12:00:00Z").unwrap();
+        assert_eq!(utc.unix_timestamp(), 1_709_294_400);
+
+        let offset = parse("2024-03-01T13:30:00.25+01:30").unwrap();
+        assert_eq!(offset.unix_timestamp(), utc.unix_timestamp());
+        assert_eq!(offset.nanosecond(), 250_000_000);
+        assert_eq!(offset.offset_minutes(), 90);
+        assert_eq!(offset.to_string(), "2024-03-01T13:30:00.25+01:30");
+        assert!(utc < offset);
+
+        let west = parse("2024-03-01t04:00:00-08:00").unwrap();
+        assert_eq!(west.unix_timestamp(), utc.unix_timestamp());
+        assert_eq!(west.to_string(), "2024-03-01T04:00:00-08:00");
+    }
+
+    #[test]
+    fn invalid_test() {
+        for value in &[
+            "yesterday",
+            "",
+            "2024-1-15",
+            "2024-13-01",
+            "2024-01-15T12:00",
+            "2024-01-15T12:00:00",
+            "2024-01-15T24:00:00Z",
+            "2024-01-15T12:00:00.Z",
+            "2024-01-15T12:00:00+0100",
+            "2024-01-15T12:00:00+01:00x",
+            "+024-01-15",
+            "2024-01-15é",
+        ] {
+            assert_eq!(parse(value), None, "{}", value);
+        }
+        assert_eq!(
+            "yesterday".parse::<DateTime>(),
+            Err(
+                "expected a datetime like 2024-01-15 or 2024-03-01T12:00:00Z, got \"yesterday\""
+                    .to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::streams::Streams;
+
+/// Set the variables of the dotenv file missing from the environment,
+/// writing a warning to the error stream for each malformed line
+///
+/// A missing file sets nothing. Variables already set, even to an empty
+/// value, keep their value.
+pub(crate) fn load(path: &Path, streams: &Streams) {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return,
+        Err(e) => return streams.err(&format!("warning: {}: {}\n", path.display(), e)),
+    };
+    for (number, line) in content.lines().enumerate() {
+        match parse_line(line) {
+            Ok(Some((key, value))) => {
+                if env::var_os(&key).is_none() {
+                    env::set_var(key, value);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => streams.err(&format!(
+                "warning: {}:{}: {}\n",
+                path.display(),
+                number + 1,
+                e
+            )),
+        }
+    }
+}
+
+/// Variable of the line, `None` for blank lines and comments
+///
+/// Lines are `KEY=value`, optionally starting with `export `. Values in
+/// single quotes are taken as is, values in double quotes read `\n`, `\"`
+/// and `\\` escapes, and other values are trimmed, a ` #` starting a comment.
+fn parse_line(line: &str) -> Result<Option<(String, String)>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let line = if line.starts_with("export ") {
+        line["export ".len()..].trim_start()
+    } else {
+        line
+    };
+    let index = match line.find('=') {
+        Some(index) => index,
+        None => return Err(format!("expected KEY=value, got \"{}\"", line)),
+    };
+    let key = line[..index].trim_end();
+    let mut chars = key.chars();
+    let valid = chars
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    if !valid {
+        return Err(format!("invalid variable name \"{}\"", key));
+    }
+
+    let rest = line[index + 1..].trim_start();
+    let value = if rest.starts_with('\'') {
+        match rest[1..].find('\'') {
+            Some(end) => rest[1..=end].to_string(),
+            None => return Err(format!("unterminated quote in value of \"{}\"", key)),
+        }
+    } else if rest.starts_with('"') {
+        let mut value = String::new();
+        let mut chars = rest[1..].chars();
+        loop {
+            match chars.next() {
+                Some('"') => break,
+                Some('\\') => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some(c) => value.push(c),
+                    None => return Err(format!("unterminated quote in value of \"{}\"", key)),
+                },
+                Some(c) => value.push(c),
+                None => return Err(format!("unterminated quote in value of \"{}\"", key)),
+            }
+        }
+        value
+    } else {
+        match rest.find(" #") {
+            Some(comment) => rest[..comment].trim_end().to_string(),
+            None => rest.to_string(),
+        }
+    };
+    Ok(Some((key.to_string(), value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load, parse_line};
+    use crate::streams::STANDARD;
+
+    fn entry(key: &str, value: &str) -> Result<Option<(String, String)>, String> {
+        Ok(Some((key.to_string(), value.to_string())))
+    }
+
+    #[test]
+    fn parse_line_test() {
+        assert_eq!(parse_line("TOKEN=abc"), entry("TOKEN", "abc"));
+        assert_eq!(
+            parse_line("export REGION = eu-west-1 # eu"),
+            entry("REGION", "eu-west-1")
+        );
+        assert_eq!(parse_line("NAME='a # b'"), entry("NAME", "a # b"));
+        assert_eq!(
+            parse_line(r#"GREETING="hi\n\"you\"""#),
+            entry("GREETING", "hi\n\"you\"")
+        );
+        assert_eq!(parse_line("EMPTY="), entry("EMPTY", ""));
+        assert_eq!(parse_line("  # comment"), Ok(None));
+        assert_eq!(parse_line(""), Ok(None));
+
+        assert_eq!(
+            parse_line("TOKEN"),
+            Err("expected KEY=value, got \"TOKEN\"".to_string())
+        );
+        assert_eq!(
+            parse_line("1TOKEN=x"),
+            Err("invalid variable name \"1TOKEN\"".to_string())
+        );
+        assert_eq!(
+            parse_line("TOKEN=\"abc"),
+            Err("unterminated quote in value of \"TOKEN\"".to_string())
+        );
+    }
+
+    #[test]
+    fn load_test() {
+        let file = std::env::temp_dir().join("seahorse_dotenv_load_test.env");
+        std::fs::write(
+            &file,
+            "SEAHORSE_DOTENV_LOAD_TEST_A=from file\n\
+             not a variable\n\
+             SEAHORSE_DOTENV_LOAD_TEST_B=from file\n",
+        )
+        .unwrap();
+        std::env::set_var("SEAHORSE_DOTENV_LOAD_TEST_B", "from env");
+        load(&file, &STANDARD);
+        std::fs::remove_file(&file).unwrap();
+        load(&file, &STANDARD);
+
+        assert_eq!(
+            std::env::var("SEAHORSE_DOTENV_LOAD_TEST_A"),
+            Ok("from file".to_string())
+        );
+        assert_eq!(
+            std::env::var("SEAHORSE_DOTENV_LOAD_TEST_B"),
+            Ok("from env".to_string())
+        );
+    }
+}
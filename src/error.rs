@@ -1,5 +1,10 @@
 use std::error;
 use std::fmt;
+use std::io::Write;
+use std::sync::Arc;
+
+use crate::messages::{DefaultMessages, Messages};
+use crate::HelpFlag;
 
 #[derive(PartialEq, Clone, Debug)]
 pub enum FlagError {
@@ -8,6 +13,13 @@ pub enum FlagError {
     TypeError,
     ValueTypeError,
     ArgumentError,
+    /// Value that the type asked for cannot be parsed from
+    ParseError {
+        /// Name of the flag
+        name: String,
+        /// Error of the type parsing the value
+        message: String,
+    },
 }
 
 impl fmt::Display for FlagError {
@@ -18,6 +30,10 @@ impl fmt::Display for FlagError {
             FlagError::TypeError => f.write_str("TypeError"),
             FlagError::ValueTypeError => f.write_str("ValueTypeError"),
             FlagError::ArgumentError => f.write_str("ArgumentError"),
+            FlagError::ParseError {
+                ref name,
+                ref message,
+            } => write!(f, "Invalid value for flag \"{}\": {}", name, message),
         }
     }
 }
@@ -30,6 +46,384 @@ impl error::Error for FlagError {
             FlagError::TypeError => "Flag type mismatch",
             FlagError::ValueTypeError => "Value type mismatch",
             FlagError::ArgumentError => "Illegal argument",
+            FlagError::ParseError { .. } => "Value cannot be parsed",
+        }
+    }
+}
+
+/// Kind of a command line error
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ErrorKind {
+    /// Command given to `help` matching no command
+    UnknownCommand,
+    /// Abbreviated command matching several commands
+    AmbiguousCommand,
+    /// Flag matching no flag, rejected by the unknown flag handler
+    UnknownFlag,
+    /// Required flag not given
+    MissingFlag,
+    /// Flag or argument value that is not valid
+    InvalidValue,
+    /// Flag occurrence missing some of its values
+    WrongNumberOfValues,
+    /// Required argument not given
+    MissingArgument,
+    /// Fewer or more arguments than the command takes
+    WrongNumberOfArguments,
+    /// Flags breaking the rules of their flag group
+    FlagGroup,
+    /// Flag value, argument file or answer that cannot be read
+    Io,
+    /// Config file that cannot be loaded or applied
+    Config,
+    /// Command line given as a string that cannot be split into arguments
+    Split,
+    /// User alias expanding to itself
+    RecursiveAlias,
+    /// Completion script that cannot be installed
+    Completion,
+}
+
+/// Command line error reported before an action runs
+///
+/// `Display` writes the message followed by the usage line and a hint to run
+/// the help flag, the way `App::run` reports errors unless an error handler
+/// is set, which also echoes the command line when the error happened at
+/// one of its arguments:
+///
+/// ```text
+/// Invalid value "http" for flag "port", expected int
+///   cli serve --port http
+///             ~~~~~~ ^^^^
+/// ```
+#[derive(PartialEq, Clone, Debug)]
+pub struct Error {
+    /// Kind of the error
+    pub kind: ErrorKind,
+    /// Message describing the error, without the usage line
+    pub message: String,
+    /// Argument, flag or file causing the error
+    pub token: Option<String>,
+    /// Name of the flag the error is about, without dashes
+    pub flag: Option<String>,
+    /// Index in `args` of the argument causing the error
+    pub index: Option<usize>,
+    /// Index in `args` of the flag the argument causing the error is a value of
+    pub flag_index: Option<usize>,
+    /// Arguments following the command path, as parsed, when the error
+    /// happened at one of them
+    pub args: Vec<String>,
+    /// Path of the command the error happened in, starting with the app name
+    pub command: Vec<String>,
+    /// Known name close to the token
+    pub suggestion: Option<String>,
+    /// Usage of the command the error happened in, or of the app when no
+    /// command was identified
+    pub usage: Option<String>,
+    /// Command line showing help of that command, when it has a help flag
+    pub help: Option<String>,
+    /// Cause and catalog of the error, boxed to keep errors small
+    details: Box<Details>,
+}
+
+/// Underlying error of an `Error`, errors being equal when their messages are
+#[derive(Clone)]
+struct Cause(Arc<dyn error::Error + Send + Sync>);
+
+impl PartialEq for Cause {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_string() == other.0.to_string()
+    }
+}
+
+impl fmt::Debug for Cause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+/// Parts of an `Error` not shown to error handlers, errors being equal when
+/// their causes are
+#[derive(Clone)]
+struct Details {
+    /// Error the error was caused by, returned by `source`
+    cause: Option<Cause>,
+    /// Message of the error written with a catalog
+    message: Arc<MessageFn>,
+    /// Catalog of the app reporting the error, writing the usage line and
+    /// help hint, English when `None`
+    messages: Option<Arc<dyn Messages>>,
+}
+
+type MessageFn = dyn Fn(&dyn Messages) -> String + Send + Sync;
+
+impl PartialEq for Details {
+    fn eq(&self, other: &Self) -> bool {
+        self.cause == other.cause
+    }
+}
+
+impl fmt::Debug for Details {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.cause, f)
+    }
+}
+
+impl Error {
+    /// Error with the message written by the catalog, in English until the
+    /// app reporting it writes it with its own
+    pub(crate) fn new<F>(kind: ErrorKind, message: F) -> Self
+    where
+        F: Fn(&dyn Messages) -> String + Send + Sync + 'static,
+    {
+        Self {
+            kind,
+            message: message(&DefaultMessages),
+            token: None,
+            flag: None,
+            index: None,
+            flag_index: None,
+            args: Vec::new(),
+            command: Vec::new(),
+            suggestion: None,
+            usage: None,
+            help: None,
+            details: Box::new(Details {
+                cause: None,
+                message: Arc::new(message),
+                messages: None,
+            }),
+        }
+    }
+
+    /// Kind of the error
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::error::ErrorKind;
+    /// use seahorse::{App, Context, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::new("port", FlagType::Int))
+    ///     .action(|_: &Context| {});
+    /// let args = vec!["cli".to_string(), "--port=http".to_string()];
+    /// let error = app.try_run(args).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::InvalidValue);
+    /// assert_eq!(error.flag.as_ref().map(String::as_str), Some("port"));
+    /// assert_eq!(error.token.as_ref().map(String::as_str), Some("http"));
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    pub(crate) fn token<T: Into<String>>(mut self, token: T) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Error at the argument at the index, a value of the flag at the flag
+    /// index when given
+    pub(crate) fn at(mut self, args: Vec<String>, index: usize, flag_index: Option<usize>) -> Self {
+        self.args = args;
+        self.index = Some(index);
+        self.flag_index = flag_index;
+        self
+    }
+
+    pub(crate) fn flag<T: Into<String>>(mut self, name: T) -> Self {
+        self.flag = Some(name.into());
+        self
+    }
+
+    /// Error caused by the error, returned by `source`
+    pub(crate) fn cause<E: error::Error + Send + Sync + 'static>(mut self, cause: E) -> Self {
+        self.details.cause = Some(Cause(Arc::new(cause)));
+        self
+    }
+
+    pub(crate) fn suggestion<T: Into<String>>(mut self, suggestion: T) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    /// Error at the command path with the usage and help flag of the
+    /// command, unless a command below already set them
+    pub(crate) fn within(mut self, path: &[String], usage: String, help_flag: &HelpFlag) -> Self {
+        if !self.command.is_empty() {
+            return self;
+        }
+        self.command = path.to_vec();
+        if self.usage.is_none() {
+            self.usage = Some(usage);
+        }
+        self.help = help_flag
+            .names()
+            .map(|(name, _)| format!("{} --{}", path.join(" "), name));
+        self
+    }
+
+    /// Error written with the catalog, its message and the lines after it
+    pub(crate) fn localize(mut self, messages: &Arc<dyn Messages>) -> Self {
+        self.message = (self.details.message)(&**messages);
+        self.details.messages = Some(messages.clone());
+        self
+    }
+
+    /// Error without the usage line and help hint
+    pub(crate) fn terse(self) -> Self {
+        Self {
+            usage: None,
+            help: None,
+            ..self
+        }
+    }
+}
+
+/// Longest command line echoed with the argument causing an error underlined
+const MAX_ANNOTATED_WIDTH: usize = 80;
+
+impl Error {
+    /// Command line with the argument causing the error underlined with `^`
+    /// and its flag with `~`, or the argument named when the line is too long
+    fn write_location(&self, f: &mut fmt::Formatter, messages: &dyn Messages) -> fmt::Result {
+        let index = match self.index {
+            Some(index) if index < self.args.len() => index,
+            _ => return Ok(()),
+        };
+        let words: Vec<&str> = self
+            .command
+            .iter()
+            .chain(&self.args)
+            .map(String::as_str)
+            .collect();
+        let line = words.join(" ");
+        if line.chars().count() > MAX_ANNOTATED_WIDTH {
+            return write!(
+                f,
+                "\n  {}",
+                messages.at_argument(index + 1, &self.args[index])
+            );
+        }
+
+        let at = self.command.len() + index;
+        let flag_at = self.flag_index.map(|index| self.command.len() + index);
+        let mut underline = String::with_capacity(line.len());
+        for (i, word) in words.iter().enumerate().take(at + 1) {
+            let mark = if i == at {
+                '^'
+            } else if Some(i) == flag_at {
+                '~'
+            } else {
+                ' '
+            };
+            underline.extend(std::iter::repeat(mark).take(word.chars().count().max(1)));
+            underline.push(' ');
+        }
+        write!(f, "\n  {}\n  {}", line, underline.trim_end())
+    }
+}
+
+impl Error {
+    fn write(&self, f: &mut fmt::Formatter, location: bool) -> fmt::Result {
+        let messages: &dyn Messages = match &self.details.messages {
+            Some(messages) => &**messages,
+            None => &DefaultMessages,
+        };
+        f.write_str(&self.message)?;
+        if location {
+            self.write_location(f, messages)?;
+        }
+        if let Some(usage) = &self.usage {
+            write!(f, "\n{}", messages.usage_hint(usage))?;
+        }
+        if let Some(help) = &self.help {
+            write!(f, "\n{}", messages.help_hint(help))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, false)
+    }
+}
+
+/// Error displayed with the command line, as written by the default handler
+struct Located<'a>(&'a Error);
+
+impl<'a> fmt::Display for Located<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.write(f, true)
+    }
+}
+
+/// The source is the error reading a file, prompt or argument file, the
+/// error of the config file or the error splitting a command line given as
+/// a string
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.details
+            .cause
+            .as_ref()
+            .map(|cause| &*cause.0 as &(dyn error::Error + 'static))
+    }
+}
+
+/// Writer of command line errors
+///
+/// The default handler writes the error and a newline, echoing the command
+/// line under the message with the argument causing the error underlined.
+///
+/// Example
+///
+/// ```
+/// use seahorse::error::{ErrorHandler, ErrorKind};
+/// use seahorse::Error;
+/// use std::io::Write;
+///
+/// let handler = ErrorHandler::from(|e: &Error, w: &mut dyn Write| match e.kind {
+///     ErrorKind::UnknownFlag => {
+///         let _ = writeln!(w, "{}\nSee https://example.com/help", e);
+///     }
+///     _ => ErrorHandler::default().handle(e, w),
+/// });
+/// ```
+#[derive(Clone)]
+pub struct ErrorHandler {
+    inner: Arc<ErrorHandlerFn>,
+}
+
+type ErrorHandlerFn = dyn Fn(&Error, &mut dyn Write) + Send + Sync;
+
+impl ErrorHandler {
+    pub fn handle(&self, error: &Error, w: &mut dyn Write) {
+        (self.inner)(error, w)
+    }
+}
+
+impl Default for ErrorHandler {
+    fn default() -> Self {
+        Self::from(|error: &Error, w: &mut dyn Write| {
+            let _ = writeln!(w, "{}", Located(error));
+        })
+    }
+}
+
+impl fmt::Debug for ErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ErrorHandler")
+    }
+}
+
+impl<F> From<F> for ErrorHandler
+where
+    for<'a, 'b> F: Fn(&'a Error, &'b mut dyn Write) + Send + Sync + 'static,
+{
+    fn from(value: F) -> Self {
+        Self {
+            inner: Arc::new(value),
         }
     }
 }
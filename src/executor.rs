@@ -0,0 +1,316 @@
+// The `async` feature needs Rust 1.51 for `Wake` and async blocks, above the
+// version the rest of the crate supports
+#![allow(clippy::incompatible_msrv)]
+
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use crate::action::{ActionFn, Dispatch, Exit};
+#[cfg(feature = "interrupt")]
+use crate::interrupt::Interrupt;
+use crate::{Action, App, Context, Error, Outcome};
+
+/// Future of an async action, borrowing the context
+pub(crate) type ActionFuture<'a> = Pin<Box<dyn Future<Output = ()> + 'a>>;
+
+pub(crate) type AsyncActionFn = dyn for<'a> Fn(&'a Context) -> ActionFuture<'a> + Send + Sync;
+
+/// Future of the action, sync actions running when first polled, ending
+/// like a finished action when left with `Context::exit`
+fn action_future<'a>(action: &'a Action, context: &'a Context) -> CatchExit<'a> {
+    CatchExit(match &action.inner {
+        ActionFn::Sync(action) => Box::pin(async move { action(context) }),
+        ActionFn::Async(action) => action(context),
+    })
+}
+
+struct CatchExit<'a>(ActionFuture<'a>);
+
+impl<'a> Future for CatchExit<'a> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext) -> Poll<()> {
+        let future = &mut self.0;
+        match panic::catch_unwind(AssertUnwindSafe(|| future.as_mut().poll(cx))) {
+            Ok(poll) => poll,
+            Err(payload) if payload.is::<Exit>() => Poll::Ready(()),
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
+
+/// Future of the action, dropped when the action is interrupted
+#[cfg(feature = "interrupt")]
+struct Cancel<'a> {
+    future: Option<CatchExit<'a>>,
+    context: &'a Context,
+}
+
+#[cfg(feature = "interrupt")]
+impl<'a> Future for Cancel<'a> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext) -> Poll<()> {
+        if self.context.interrupted() {
+            self.future = None;
+        }
+        let poll = match &mut self.future {
+            Some(future) => Pin::new(future).poll(cx),
+            None => return Poll::Ready(()),
+        };
+        if poll.is_pending() {
+            if let Some(interrupt) = self.context.interrupt() {
+                interrupt.source.wake_on_interrupt(cx.waker());
+            }
+        }
+        poll
+    }
+}
+
+impl Dispatch {
+    /// Await the actions in the order `run` runs them
+    pub(crate) async fn run_async(self) -> Outcome {
+        let (actions, context) = match self {
+            Dispatch::Help(target) => return Outcome::HelpShown { target },
+            Dispatch::Version => return Outcome::VersionShown,
+            Dispatch::Done(exit_code) => return Outcome::Done { exit_code },
+            Dispatch::Run(actions, context) => (actions, context),
+        };
+        #[cfg(feature = "interrupt")]
+        Interrupt::install(&context);
+        let mut actions = actions.iter();
+        if let Some(action) = actions.next() {
+            #[cfg(feature = "interrupt")]
+            Cancel {
+                future: Some(action_future(action, &context)),
+                context: &context,
+            }
+            .await;
+            #[cfg(not(feature = "interrupt"))]
+            action_future(action, &context).await;
+            #[cfg(feature = "interrupt")]
+            Interrupt::handle(&context);
+        }
+        for action in actions {
+            action_future(action, &context).await;
+        }
+        Outcome::Ran {
+            exit_code: context.exit_code().unwrap_or(0),
+            command: context.into_path(),
+        }
+    }
+}
+
+/// Run the app like `try_run`, awaiting the actions
+pub(crate) async fn try_run(app: &App, args: Vec<String>) -> Result<i32, Error> {
+    match app.parse_args(args, None) {
+        Ok(dispatch) => dispatch.run_async().await.into_result(),
+        Err(e) => Err(e),
+    }
+}
+
+/// Run the app like `run`, awaiting the actions
+pub(crate) async fn run(app: &App, args: Vec<String>) {
+    let color = app.color_choice(&args).unwrap_or(app.color);
+    app.exit(try_run(app, args).await, color);
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Run the future to completion on the current thread
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = TaskContext::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::block_on;
+    use crate::{Action, App, Command, Context};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context as TaskContext, Poll};
+
+    /// Future pending once, so that actions are awaited past a wake up
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn run_async_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let sync = |name: &'static str| {
+            let log = log.clone();
+            move |c: &Context| {
+                log.lock()
+                    .unwrap()
+                    .push(format!("{} {:?}", name, c.exit_code()))
+            }
+        };
+        let deploy = {
+            let log = log.clone();
+            Action::from_async(move |c: &Context| {
+                let log = log.clone();
+                Box::pin(async move {
+                    YieldOnce(false).await;
+                    log.lock().unwrap().push(format!("deploy {:?}", c.args));
+                    if c.args.is_empty() {
+                        c.exit(3);
+                    }
+                    log.lock().unwrap().push("deploy done".to_string());
+                })
+            })
+        };
+        let app = App::new("cli")
+            .command(
+                Command::new("deploy")
+                    .action(deploy)
+                    .after_action(sync("command after")),
+            )
+            .command(Command::new("status").action(sync("status")))
+            .after_action(sync("app after"));
+        let run = |args: &[&str], run_async: bool| {
+            log.lock().unwrap().clear();
+            let args = args.iter().map(|a| a.to_string()).collect();
+            let code = if run_async {
+                block_on(app.try_run_async(args))
+            } else {
+                app.try_run(args)
+            };
+            (code, log.lock().unwrap().clone())
+        };
+
+        for args in [
+            &["cli", "deploy"][..],
+            &["cli", "deploy", "now"],
+            &["cli", "status"],
+        ]
+        .iter()
+        {
+            assert_eq!(run(args, true), run(args, false));
+        }
+        assert_eq!(
+            run(&["cli", "deploy"], true),
+            (
+                Ok(3),
+                vec![
+                    "deploy []".to_string(),
+                    "command after Some(3)".to_string(),
+                    "app after Some(3)".to_string(),
+                ]
+            )
+        );
+        assert_eq!(
+            run(&["cli", "deploy", "now"], true).1,
+            vec![
+                "deploy [\"now\"]",
+                "deploy done",
+                "command after None",
+                "app after None"
+            ]
+        );
+    }
+
+    #[cfg(feature = "interrupt")]
+    #[test]
+    fn cancel_test() {
+        use crate::SignalSource;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::task::Waker;
+
+        /// Source interrupting once checked a number of times, waking the
+        /// action right away
+        struct After(AtomicUsize);
+
+        impl SignalSource for After {
+            fn interrupted(&self) -> bool {
+                let left = self.0.load(Ordering::SeqCst);
+                self.0.store(left.saturating_sub(1), Ordering::SeqCst);
+                left == 0
+            }
+
+            fn wake_on_interrupt(&self, waker: &Waker) {
+                waker.wake_by_ref();
+            }
+        }
+
+        /// Logs when the future of the action is dropped
+        struct Dropped(Arc<Mutex<Vec<String>>>);
+
+        impl Drop for Dropped {
+            fn drop(&mut self) {
+                self.0.lock().unwrap().push("dropped".to_string());
+            }
+        }
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let serve = {
+            let log = log.clone();
+            Action::from_async(move |_: &Context| {
+                let log = log.clone();
+                Box::pin(async move {
+                    let _guard = Dropped(log.clone());
+                    loop {
+                        YieldOnce(false).await;
+                        log.lock().unwrap().push("served".to_string());
+                    }
+                })
+            })
+        };
+        let after = {
+            let log = log.clone();
+            move |c: &Context| {
+                log.lock()
+                    .unwrap()
+                    .push(format!("after {:?}", c.exit_code()))
+            }
+        };
+        let interrupted = {
+            let log = log.clone();
+            move |_: &Context| log.lock().unwrap().push("interrupted".to_string())
+        };
+        let app = App::new("cli")
+            .action(serve)
+            .on_interrupt(interrupted)
+            .after_action(after)
+            .signal_source(After(AtomicUsize::new(2)));
+
+        assert_eq!(
+            block_on(app.try_run_async(vec!["cli".to_string()])),
+            Ok(130)
+        );
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["served", "dropped", "interrupted", "after Some(130)"]
+        );
+    }
+}
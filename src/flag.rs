@@ -1,4 +1,5 @@
 use crate::error::FlagError;
+use crate::{Completer, DateTime, Secret, Validator, ValueHint};
 
 /// `Flag` type.
 ///
@@ -9,160 +10,1284 @@ pub struct Flag {
     pub name: String,
     /// Flag description
     pub description: Option<String>,
+    /// Flag description shown by `--help` instead of the description
+    pub long_description: Option<String>,
+    /// Flag syntax shown instead of the generated one
+    pub usage: Option<String>,
     /// Flag type
     pub flag_type: FlagType,
     /// Flag alias
     pub alias: Option<Vec<String>>,
+    /// Aliases matched like the others but left out of help, completions
+    /// and generated documents
+    pub hidden_alias: Option<Vec<String>>,
+    /// Flag default value
+    pub default_value: Option<String>,
+    /// Hide the flag from help and generated documents
+    pub hidden: bool,
+    /// Values accepted by the flag
+    pub choices: Option<Vec<String>>,
+    /// Schemes accepted by the url flag, any scheme when not set
+    #[cfg(feature = "url")]
+    pub allowed_schemes: Option<Vec<String>>,
+    /// Position of the flag in help and generated documents
+    pub display_order: Option<u32>,
+    /// Count each occurrence of the bool flag, or keep the values of each occurrence
+    pub repeatable: bool,
+    /// Number of values taken by each occurrence of the flag, one if not set
+    pub number_of_values: Option<usize>,
+    /// Argument ending the values of each occurrence of the flag
+    pub value_terminator: Option<String>,
+    /// Take the next arguments as values even when they look like flags
+    pub allow_hyphen_values: bool,
+    /// Completer of the flag value
+    pub completer: Option<Completer>,
+    /// Read values given as `@path` from the file
+    pub from_file: bool,
+    /// Fail before the action runs when the flag is not given
+    pub required: bool,
+    /// Ask for the value when the flag is not given
+    pub prompt: bool,
+    /// Environment variable read when the flag is not given
+    pub env: Option<String>,
+    /// Remove leading and trailing whitespace of string values
+    pub trim: bool,
+    /// Lowercase string values
+    pub lowercase: bool,
+    /// Checks of the flag values, run in order
+    pub validators: Vec<Validator>,
+    /// Accept underscores between digits of number values
+    pub digit_separators: bool,
+    /// Accept a comma as decimal separator of float values
+    pub decimal_comma: bool,
+    /// Make the app flag available to every command, before or after its name
+    pub global: bool,
+    /// Section of help listing the flag, `Flags:` when not set
+    pub help_section: Option<String>,
+    /// Kind of value completion scripts complete the value with
+    pub value_hint: Option<ValueHint>,
+    /// Names of the flags the flag overrides, the one given last counting
+    /// as given
+    pub overrides: Vec<String>,
+    /// Expand glob patterns in the values to the paths they match
+    #[cfg(feature = "glob")]
+    pub glob_expand: bool,
+    /// Keep glob patterns matching no path instead of failing
+    #[cfg(feature = "glob")]
+    pub keep_unmatched_glob: bool,
 }
 
 /// `FlagType` enum
+///
+/// Values are given as `--name value` or `--name=value`. An empty value
+/// written `--name=` is an empty string for string and secret flags, and an
+/// error for number, datetime, uuid and url flags and for bool flags, which
+/// take no value.
 #[derive(PartialEq, Clone, Debug)]
 pub enum FlagType {
     Bool,
     String,
+    /// Signed integer read into an `i64`, with the same range on every target
     Int,
+    /// Unsigned integer read into a `u64`, with the same range on every target
     Uint,
     Float,
+    /// String value kept out of debug output, asked without echo when prompted
+    Secret,
+    /// RFC 3339 timestamp like `2024-03-01T12:00:00Z`, or date like
+    /// `2024-01-15` read as midnight UTC
+    DateTime,
+    /// Uuid written hyphenated like `67e55044-10b1-426f-9247-bb680e5fe0c8`
+    /// or as 32 hex digits
+    #[cfg(feature = "uuid")]
+    Uuid,
+    /// Absolute URL like `https://example.com/api`
+    #[cfg(feature = "url")]
+    Url,
+    /// Regular expression, compiled before the action runs
+    #[cfg(feature = "regex")]
+    Regex,
+}
+
+impl FlagType {
+    /// Name of the value type used in help and generated documents
+    pub fn name(&self) -> &'static str {
+        match self {
+            FlagType::Bool => "bool",
+            FlagType::String => "string",
+            FlagType::Int => "int",
+            FlagType::Uint => "uint",
+            FlagType::Float => "float",
+            FlagType::Secret => "secret",
+            FlagType::DateTime => "datetime",
+            #[cfg(feature = "uuid")]
+            FlagType::Uuid => "uuid",
+            #[cfg(feature = "url")]
+            FlagType::Url => "url",
+            #[cfg(feature = "regex")]
+            FlagType::Regex => "regex",
+        }
+    }
+
+    /// Parse a value of the type
+    pub(crate) fn parse(&self, v: String) -> Result<FlagValue, FlagError> {
+        let value = match self {
+            FlagType::Bool => v.parse().ok().map(FlagValue::Bool),
+            FlagType::String => Some(FlagValue::String(v)),
+            FlagType::Int => v.parse().ok().map(FlagValue::Int),
+            FlagType::Uint => v.parse().ok().map(FlagValue::Uint),
+            FlagType::Float => v.parse().ok().map(FlagValue::Float),
+            FlagType::Secret => Some(FlagValue::Secret(Secret::new(v))),
+            FlagType::DateTime => v.parse().ok().map(FlagValue::DateTime),
+            #[cfg(feature = "uuid")]
+            FlagType::Uuid => v.parse().ok().map(FlagValue::Uuid),
+            #[cfg(feature = "url")]
+            FlagType::Url => v.parse().ok().map(FlagValue::Url),
+            #[cfg(feature = "regex")]
+            FlagType::Regex => regex::Regex::new(&v).ok().map(FlagValue::Regex),
+        };
+        value.ok_or(FlagError::ValueTypeError)
+    }
+}
+
+/// `FlagValue` enum
+///
+/// Integers are `i64` and `u64` rather than `isize` and `usize`, so a value
+/// like `5000000000` is accepted on 32-bit targets too. Code matching on
+/// `FlagValue::Int(n)` or `FlagValue::Uint(n)` that needs a pointer-sized
+/// integer converts with `usize::try_from(n)` or `n as isize`.
+#[derive(Clone, Debug)]
+pub enum FlagValue {
+    Bool(bool),
+    String(String),
+    Int(i64),
+    Uint(u64),
+    Float(f64),
+    /// Values of a flag taking several values
+    List(Vec<FlagValue>),
+    Secret(Secret),
+    DateTime(DateTime),
+    #[cfg(feature = "uuid")]
+    Uuid(uuid::Uuid),
+    #[cfg(feature = "url")]
+    Url(url::Url),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
 }
 
-/// `FlagValue` enum
-#[derive(PartialEq, Clone, Debug)]
-pub enum FlagValue {
-    Bool(bool),
-    String(String),
-    Int(isize),
-    Uint(usize),
-    Float(f64),
-}
+/// Regular expressions are equal when written the same
+impl PartialEq for FlagValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (FlagValue::Bool(a), FlagValue::Bool(b)) => a == b,
+            (FlagValue::String(a), FlagValue::String(b)) => a == b,
+            (FlagValue::Int(a), FlagValue::Int(b)) => a == b,
+            (FlagValue::Uint(a), FlagValue::Uint(b)) => a == b,
+            (FlagValue::Float(a), FlagValue::Float(b)) => a == b,
+            (FlagValue::List(a), FlagValue::List(b)) => a == b,
+            (FlagValue::Secret(a), FlagValue::Secret(b)) => a == b,
+            (FlagValue::DateTime(a), FlagValue::DateTime(b)) => a == b,
+            #[cfg(feature = "uuid")]
+            (FlagValue::Uuid(a), FlagValue::Uuid(b)) => a == b,
+            #[cfg(feature = "url")]
+            (FlagValue::Url(a), FlagValue::Url(b)) => a == b,
+            #[cfg(feature = "regex")]
+            (FlagValue::Regex(a), FlagValue::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl Flag {
+    /// Create new instance of `Flag`
+    ///
+    /// A single character name is given as `-v` as well as `--v`,
+    /// help showing the short form.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let bool_flag = Flag::new("bool", FlagType::Bool);
+    /// let float_flag = Flag::new("float", FlagType::Float);
+    /// let short_flag = Flag::new("v", FlagType::Bool);
+    /// ```
+    pub fn new<T: Into<String>>(name: T, flag_type: FlagType) -> Self {
+        let name = name.into();
+        if let Err(e) = check_name(&name, "name") {
+            panic!("{}", e)
+        }
+
+        Self {
+            name,
+            description: None,
+            long_description: None,
+            usage: None,
+            flag_type,
+            alias: None,
+            hidden_alias: None,
+            default_value: None,
+            hidden: false,
+            choices: None,
+            #[cfg(feature = "url")]
+            allowed_schemes: None,
+            display_order: None,
+            repeatable: false,
+            number_of_values: None,
+            value_terminator: None,
+            allow_hyphen_values: false,
+            completer: None,
+            from_file: false,
+            required: false,
+            prompt: false,
+            env: None,
+            trim: false,
+            lowercase: false,
+            validators: Vec::new(),
+            digit_separators: false,
+            decimal_comma: false,
+            global: false,
+            help_section: None,
+            value_hint: None,
+            overrides: Vec::new(),
+            #[cfg(feature = "glob")]
+            glob_expand: false,
+            #[cfg(feature = "glob")]
+            keep_unmatched_glob: false,
+        }
+    }
+
+    /// Standard repeatable `--verbose, -v` flag, counted with
+    /// `Context::count_flag`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Flag};
+    ///
+    /// let app = App::new("cli").flag(Flag::verbose());
+    /// ```
+    pub fn verbose() -> Self {
+        Flag::new("verbose", FlagType::Bool)
+            .description("Show more output, repeat for more detail")
+            .alias("v")
+            .repeatable()
+    }
+
+    /// Standard `--quiet, -q` flag
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Flag};
+    ///
+    /// let app = App::new("cli").flag(Flag::quiet());
+    /// ```
+    pub fn quiet() -> Self {
+        Flag::new("quiet", FlagType::Bool)
+            .description("Only show errors")
+            .alias("q")
+    }
+
+    /// Standard `--force, -f` flag
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Command, Flag};
+    ///
+    /// let command = Command::new("rm").flag(Flag::force());
+    /// ```
+    pub fn force() -> Self {
+        Flag::new("force", FlagType::Bool)
+            .description("Go ahead without asking, overwriting existing files")
+            .alias("f")
+    }
+
+    /// Standard `--output, -o <FILE>` flag
+    ///
+    /// The usage names the value `FILE`, set another usage when changing
+    /// the alias.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Command, Flag};
+    ///
+    /// let command = Command::new("build").flag(Flag::output().default_value("out.txt"));
+    /// ```
+    pub fn output() -> Self {
+        Flag::new("output", FlagType::String)
+            .description("Write output to FILE")
+            .usage("--output, -o <FILE>")
+            .alias("o")
+            .value_hint(ValueHint::AnyPath)
+    }
+
+    /// Set description of the flag
+    ///
+    /// The description is shown next to the flag syntax in help, and in
+    /// generated documents and completions.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let bool_flag = Flag::new("bool", FlagType::Bool)
+    ///     .description("Enable the feature");
+    /// ```
+    pub fn description<T: Into<String>>(mut self, description: T) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set description of the flag shown by `--help`, which may span lines
+    ///
+    /// `-h` shows the description set with `description` instead, and
+    /// `--help` falls back to it when no long description is set.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let flag = Flag::new("jobs", FlagType::Uint)
+    ///     .description("Number of parallel jobs")
+    ///     .long_description("Number of parallel jobs\nDefaults to the number of CPUs");
+    /// ```
+    pub fn long_description<T: Into<String>>(mut self, description: T) -> Self {
+        self.long_description = Some(description.into());
+        self
+    }
+
+    /// Set syntax of the flag, shown in help and errors instead of the generated one
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("define", FlagType::String)
+    ///     .usage("--define, -D <KEY=VALUE>")
+    ///     .description("Define a variable");
+    /// ```
+    pub fn usage<T: Into<String>>(mut self, usage: T) -> Self {
+        self.usage = Some(usage.into());
+        self
+    }
+
+    /// Set alias of the flag
+    ///
+    /// Aliases follow the rules of flag names, and cannot be empty, the name
+    /// of the flag or another alias of it. Panics for invalid aliases. Help
+    /// shows them after the name in the order they were added, like
+    /// `--string, -s, -str <string>`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let bool_flag = Flag::new("bool", FlagType::Bool)
+    ///     .alias("b");
+    ///
+    /// let string_flag = Flag::new("string", FlagType::String)
+    ///     .alias("s")
+    ///     .alias("str");
+    /// ```
+    pub fn alias<T: Into<String>>(self, name: T) -> Self {
+        match self.try_alias(name) {
+            Ok(flag) => flag,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Set alias of the flag like `alias`, returning why the alias is invalid
+    /// instead of panicking
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let flag = Flag::new("force", FlagType::Bool);
+    /// assert!(flag.clone().try_alias("f").is_ok());
+    /// assert!(flag.try_alias("--force").is_err());
+    /// ```
+    pub fn try_alias<T: Into<String>>(mut self, name: T) -> Result<Self, String> {
+        let name = name.into();
+        self.check_alias(&name)?;
+        self.alias.get_or_insert_with(Vec::new).push(name);
+        Ok(self)
+    }
+
+    /// Set alias of the flag matched like the others but left out of help,
+    /// completions, generated documents and suggestions, for old spellings
+    /// of renamed flags
+    ///
+    /// Hidden aliases follow the rules of aliases. Panics for invalid aliases.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let flag = Flag::new("color", FlagType::String).hidden_alias("colour");
+    /// assert_eq!(flag.hidden_aliases(), ["colour".to_string()]);
+    /// assert!(flag.aliases().is_empty());
+    /// ```
+    pub fn hidden_alias<T: Into<String>>(mut self, name: T) -> Self {
+        let name = name.into();
+        if let Err(e) = self.check_alias(&name) {
+            panic!("{}", e);
+        }
+        self.hidden_alias.get_or_insert_with(Vec::new).push(name);
+        self
+    }
+
+    /// Error for an alias that cannot be added to the flag
+    fn check_alias(&self, name: &str) -> Result<(), String> {
+        if name.is_empty() {
+            return Err(r#""" is invalid flag alias. Flag alias cannnot be empty."#.to_string());
+        }
+        check_name(name, "alias")?;
+        if name == self.name {
+            return Err(format!(
+                r#""{}" is invalid flag alias. Flag alias cannnot be the flag name."#,
+                name
+            ));
+        }
+        if self.all_aliases().any(|alias| alias == name) {
+            return Err(format!(
+                r#""{}" is invalid flag alias. Flag "{}" already has this alias."#,
+                name, self.name
+            ));
+        }
+        Ok(())
+    }
+
+    /// Set default value of the flag
+    ///
+    /// The default value is parsed like a command line value
+    /// when the flag is not specified.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let int_flag = Flag::new("int", FlagType::Int)
+    ///     .default_value("10");
+    /// ```
+    pub fn default_value<T: Into<String>>(mut self, value: T) -> Self {
+        self.default_value = Some(value.into());
+        self
+    }
+
+    /// Set section of help listing the flag, after the `Flags:` section
+    ///
+    /// Sections are listed in order of first appearance, each listing its
+    /// flags in declaration order, and markdown documents give each one its
+    /// own table. Sections only group flags in help, parsing is the same.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let format = Flag::new("format", FlagType::String)
+    ///     .help_section("Output");
+    /// ```
+    pub fn help_section<T: Into<String>>(mut self, section: T) -> Self {
+        self.help_section = Some(section.into());
+        self
+    }
+
+    /// Hide the flag from help and generated documents
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let bool_flag = Flag::new("debug", FlagType::Bool)
+    ///     .hidden();
+    /// ```
+    pub fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    /// Set values accepted by the flag
+    ///
+    /// Any other value is treated as a value type mismatch.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("format", FlagType::String)
+    ///     .choices(&["json", "yaml", "toml"]);
+    /// ```
+    pub fn choices(mut self, choices: &[&str]) -> Self {
+        self.choices = Some(choices.iter().map(|c| c.to_string()).collect());
+        self
+    }
+
+    /// Set schemes accepted by the url flag
+    ///
+    /// Schemes are compared ignoring case. A URL of any other scheme is
+    /// rejected with an error naming the accepted ones.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let url_flag = Flag::new("endpoint", FlagType::Url)
+    ///     .allowed_schemes(&["http", "https"]);
+    /// ```
+    #[cfg(feature = "url")]
+    pub fn allowed_schemes(mut self, schemes: &[&str]) -> Self {
+        self.allowed_schemes = Some(schemes.iter().map(|s| s.to_ascii_lowercase()).collect());
+        self
+    }
+
+    /// Set display order of the flag
+    ///
+    /// Flags are listed in ascending order, flags without one come after
+    /// those with a lower order. Ties keep the declaration order.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let bool_flag = Flag::new("bool", FlagType::Bool)
+    ///     .display_order(1);
+    /// ```
+    pub fn display_order(mut self, order: u32) -> Self {
+        self.display_order = Some(order);
+        self
+    }
+
+    /// Allow the flag to be given multiple times
+    ///
+    /// Occurrences are counted by `Context::count_flag`, and a single character
+    /// alias of a bool flag can be stacked so that `-vvv` counts as three.
+    /// The values of every occurrence of other flags are read with
+    /// `Context::value_groups`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let bool_flag = Flag::new("verbose", FlagType::Bool)
+    ///     .alias("v")
+    ///     .repeatable();
+    /// ```
+    pub fn repeatable(mut self) -> Self {
+        self.repeatable = true;
+        self
+    }
+
+    /// Take the next argument as the value even when it starts with a dash
+    ///
+    /// A value like `-x` after a flag is otherwise read as another flag,
+    /// leaving the flag without a value. A bare `--` still ends the flags.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let title = Flag::new("title", FlagType::String).allow_hyphen_values();
+    /// ```
+    pub fn allow_hyphen_values(mut self) -> Self {
+        self.allow_hyphen_values = true;
+        self
+    }
+
+    /// Make the flag of an app available to every command
+    ///
+    /// A global flag is read by commands as if they declared it, and may be
+    /// given before the command name as well as after it, so `cli -v build`
+    /// and `cli build -v` are the same. Commands declaring a flag of the same
+    /// name read their own flag instead. The help of the other commands lists
+    /// it under `Global Flags`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context, Flag};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::verbose().global())
+    ///     .command(Command::new("build").action(|c: &Context| {
+    ///         assert_eq!(c.count_flag("verbose"), 1);
+    ///     }));
+    /// app.run(vec!["cli".to_string(), "-v".to_string(), "build".to_string()]);
+    /// ```
+    pub fn global(mut self) -> Self {
+        self.global = true;
+        self
+    }
+
+    /// Allow values of the flag to be read from a file
+    ///
+    /// A value given as `@path` is replaced by the contents of the file,
+    /// without a single trailing newline, before its type is checked.
+    /// A value starting with `@@` stands for the value starting with `@`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("token", FlagType::String)
+    ///     .allow_from_file();
+    /// ```
+    pub fn allow_from_file(mut self) -> Self {
+        self.from_file = true;
+        self
+    }
+
+    /// Expand glob patterns given as values to the paths they match
+    ///
+    /// Values with `*`, `?` or `[` are matched against the current directory
+    /// before the type and validators check them, each matching path becoming
+    /// a value read with `Context::values`. A pattern matching no path is an
+    /// error, see `keep_unmatched_glob`.
+    ///
+    /// Shells on Windows pass patterns through as given. Shells on Unix
+    /// expand unquoted patterns themselves, so values naming an existing
+    /// path are left as they are and never expanded twice; quote patterns
+    /// there, like `'*.log'`, to leave them to the app.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let files = Flag::new("input", FlagType::String)
+    ///     .repeatable()
+    ///     .glob_expand();
+    /// ```
+    #[cfg(feature = "glob")]
+    pub fn glob_expand(mut self) -> Self {
+        self.glob_expand = true;
+        self
+    }
+
+    /// Keep glob patterns matching no path as the value instead of failing
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let files = Flag::new("input", FlagType::String)
+    ///     .glob_expand()
+    ///     .keep_unmatched_glob();
+    /// ```
+    #[cfg(feature = "glob")]
+    pub fn keep_unmatched_glob(mut self) -> Self {
+        self.keep_unmatched_glob = true;
+        self
+    }
+
+    /// Set environment variable read when the flag is not given
+    ///
+    /// The variable takes precedence over the variables named after the flag
+    /// with `App::env_prefix`, prompting and the default value.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let secret_flag = Flag::new("token", FlagType::Secret)
+    ///     .env("API_TOKEN");
+    /// ```
+    pub fn env<T: Into<String>>(mut self, name: T) -> Self {
+        self.env = Some(name.into());
+        self
+    }
+
+    /// Remove leading and trailing whitespace of the values of a string flag
+    ///
+    /// Values are trimmed wherever they come from, before choices and
+    /// validators see them. Secret values are kept as given.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("name", FlagType::String)
+    ///     .trim();
+    /// ```
+    pub fn trim(mut self) -> Self {
+        self.trim = true;
+        self
+    }
+
+    /// Lowercase the values of a string flag
+    ///
+    /// Like `trim`, values are lowercased before choices and validators see
+    /// them, so that `--format JSON` matches the `json` choice.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("format", FlagType::String)
+    ///     .choices(&["json", "yaml"])
+    ///     .to_lowercase();
+    /// ```
+    pub fn to_lowercase(mut self) -> Self {
+        self.lowercase = true;
+        self
+    }
+
+    /// Accept single underscores between digits of the values of a number
+    /// flag, like `10_000_000`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let int_flag = Flag::new("max-bytes", FlagType::Uint)
+    ///     .allow_digit_separators();
+    /// ```
+    pub fn allow_digit_separators(mut self) -> Self {
+        self.digit_separators = true;
+        self
+    }
+
+    /// Accept a comma as decimal separator of the values of a float flag,
+    /// like `1,5`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let float_flag = Flag::new("ratio", FlagType::Float)
+    ///     .decimal_comma();
+    /// ```
+    pub fn decimal_comma(mut self) -> Self {
+        self.decimal_comma = true;
+        self
+    }
+
+    /// Add check of the flag values
+    ///
+    /// Checks run in order after the type check on every value of the flag,
+    /// the first error being reported before the action runs.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("branch", FlagType::String).validator(|v: &str| {
+    ///     if v.contains(' ') {
+    ///         Err("must not contain spaces".to_string())
+    ///     } else {
+    ///         Ok(())
+    ///     }
+    /// });
+    /// ```
+    pub fn validator(mut self, validator: impl Into<Validator>) -> Self {
+        self.validators.push(validator.into());
+        self
+    }
+
+    /// Add check that the flag values match the regular expression
+    ///
+    /// The pattern is compiled once, here. Values not matching it are
+    /// rejected with an error showing the pattern.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the pattern is not a valid regular expression.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let name = Flag::new("name", FlagType::String).matches(r"^[a-z][a-z0-9-]*$");
+    /// ```
+    ///
+    /// ```should_panic
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let name = Flag::new("name", FlagType::String).matches(r"^[a-z");
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn matches(self, pattern: &str) -> Self {
+        let regex = match regex::Regex::new(pattern) {
+            Ok(regex) => regex,
+            Err(e) => panic!(
+                r#"Invalid pattern "{}" of flag "{}": {}"#,
+                pattern, self.name, e
+            ),
+        };
+        self.validator(move |v: &str| {
+            if regex.is_match(v) {
+                Ok(())
+            } else {
+                Err(format!("must match the pattern {}", regex.as_str()))
+            }
+        })
+    }
 
-impl Flag {
-    /// Create new instance of `Flag`
+    /// Require the flag to be given, unless it has a default value
+    ///
+    /// A missing secret flag is asked for without echo when stdin is a terminal.
     ///
     /// Example
     ///
     /// ```
     /// use seahorse::{Flag, FlagType};
     ///
-    /// let bool_flag = Flag::new("bool", FlagType::Bool);
-    /// let float_flag = Flag::new("float", FlagType::Float);
+    /// let string_flag = Flag::new("name", FlagType::String)
+    ///     .required();
     /// ```
-    pub fn new<T: Into<String>>(name: T, flag_type: FlagType) -> Self {
-        let name = name.into();
-        if name.starts_with('-') {
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Override the flag of the name, so that when both are given the one
+    /// given last counts and the other is left out as if it was not given
+    ///
+    /// Overriding goes both ways, whichever of the flags sets it, and is
+    /// settled before required flags, flag groups and values are checked. A
+    /// flag given on the command line overrides one read from the
+    /// environment or asked for.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let all = Flag::new("all", FlagType::Bool).overrides_with("mine");
+    /// let mine = Flag::new("mine", FlagType::Bool);
+    /// ```
+    pub fn overrides_with<T: Into<String>>(mut self, name: T) -> Self {
+        self.overrides.push(name.into());
+        self
+    }
+
+    /// Ask for the value when the flag is not given and has no default value
+    ///
+    /// The answer is checked like a value given on the command line. When
+    /// stdin is not a terminal, or the app is run with `--no-input`, the flag
+    /// is reported as missing instead. Panics for bool flags.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("name", FlagType::String)
+    ///     .prompt_if_missing();
+    /// ```
+    pub fn prompt_if_missing(mut self) -> Self {
+        if self.flag_type == FlagType::Bool {
             panic!(
-                r#""{}" is invalid flag name. Flag name cannnot start with "-"."#,
-                name
+                r#""{}" cannot prompt for a value. Only non-bool flags take values."#,
+                self.name
             )
         }
-        if name.contains('=') {
+        self.prompt = true;
+        self
+    }
+
+    /// Set the number of values taken by each occurrence of the flag
+    ///
+    /// The values are the tokens following the flag up to another flag or `--`,
+    /// read with `Context::values`. Panics for bool flags or zero values.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let int_flag = Flag::new("point", FlagType::Int)
+    ///     .number_of_values(2);
+    /// ```
+    pub fn number_of_values(mut self, n: usize) -> Self {
+        if self.flag_type == FlagType::Bool || n == 0 {
             panic!(
-                r#""{}" is invalid flag name. Flag name cannnot contain "="."#,
-                name
+                r#""{}" cannot take {} values. Only non-bool flags take values."#,
+                self.name, n
             )
         }
-        if name.contains(' ') {
+        self.number_of_values = Some(n);
+        self
+    }
+
+    /// Take the arguments after each occurrence of the flag as its values
+    /// up to the terminator, which is left out of the values and of the
+    /// positional arguments
+    ///
+    /// The values also end at another flag, `--` or the end of the command
+    /// line, and at the number of values when set. Values are read with
+    /// `Context::values`, and a value given by the environment is split at
+    /// whitespace. Panics for bool flags.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Context, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::new("env", FlagType::String).value_terminator(";"))
+    ///     .action(|c: &Context| {
+    ///         assert_eq!(c.values::<String>("env"), Ok(vec!["A=1".to_string(), "B=2".to_string()]));
+    ///         assert_eq!(c.args, ["build.sh"]);
+    ///     });
+    /// let args = ["cli", "--env", "A=1", "B=2", ";", "build.sh"];
+    /// app.run(args.iter().map(|a| a.to_string()).collect());
+    /// ```
+    pub fn value_terminator<T: Into<String>>(mut self, terminator: T) -> Self {
+        if self.flag_type == FlagType::Bool {
             panic!(
-                r#""{}" is invalid flag name. Flag name cannnot contain whitespaces."#,
-                name
+                r#""{}" cannot take values up to a terminator. Only non-bool flags take values."#,
+                self.name
             )
         }
+        self.value_terminator = Some(terminator.into());
+        self
+    }
 
-        Self {
-            name,
-            description: None,
-            flag_type,
-            alias: None,
+    /// Whether occurrences of the flag take several values
+    pub(crate) fn takes_values(&self) -> bool {
+        self.number_of_values.is_some() || self.value_terminator.is_some()
+    }
+
+    /// Set kind of value completion scripts complete the value with
+    ///
+    /// Flags expanding glob patterns and `Flag::output` complete paths
+    /// unless set. Choices and completers take precedence.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType, ValueHint};
+    ///
+    /// let dir = Flag::new("dir", FlagType::String).value_hint(ValueHint::DirPath);
+    /// ```
+    pub fn value_hint(mut self, hint: ValueHint) -> Self {
+        self.value_hint = Some(hint);
+        self
+    }
+
+    /// Hint completion scripts complete the value with, paths for flags
+    /// expanding glob patterns
+    pub(crate) fn completion_hint(&self) -> Option<ValueHint> {
+        #[cfg(feature = "glob")]
+        {
+            if self.glob_expand {
+                return Some(self.value_hint.unwrap_or(ValueHint::AnyPath));
+            }
         }
+        self.value_hint
     }
 
-    /// Set description of the flag
+    /// Set completer of the flag value
+    ///
+    /// The completer is called by the generated completion scripts
+    /// when choices are not set.
     ///
     /// Example
     ///
     /// ```
-    /// use seahorse::{Flag, FlagType};
+    /// use seahorse::{Context, Flag, FlagType};
     ///
-    /// let bool_flag = Flag::new("bool", FlagType::Bool)
-    ///     .description("cli cmd Hello --bool");
+    /// let string_flag = Flag::new("project", FlagType::String)
+    ///     .complete_with(|_: &Context, prefix: &str| {
+    ///         vec![format!("{}-api", prefix), format!("{}-web", prefix)]
+    ///     });
     /// ```
-    pub fn description<T: Into<String>>(mut self, description: T) -> Self {
-        self.description = Some(description.into());
+    pub fn complete_with(mut self, completer: impl Into<Completer>) -> Self {
+        self.completer = Some(completer.into());
         self
     }
 
-    /// Set alias of the flag
+    /// Name of the flag
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Aliases of the flag, in registration order
     ///
     /// Example
     ///
     /// ```
     /// use seahorse::{Flag, FlagType};
     ///
-    /// let bool_flag = Flag::new("bool", FlagType::Bool)
-    ///     .alias("b");
-    ///
-    /// let string_flag = Flag::new("string", FlagType::String)
-    ///     .alias("s")
-    ///     .alias("str");
+    /// let flag = Flag::new("verbose", FlagType::Bool).alias("v");
+    /// assert_eq!(flag.aliases(), ["v".to_string()]);
     /// ```
-    pub fn alias<T: Into<String>>(mut self, name: T) -> Self {
-        if let Some(ref mut alias) = self.alias {
-            (*alias).push(name.into());
+    pub fn aliases(&self) -> &[String] {
+        self.alias.as_ref().map_or(&[], Vec::as_slice)
+    }
+
+    /// Hidden aliases of the flag, set with `hidden_alias`
+    pub fn hidden_aliases(&self) -> &[String] {
+        self.hidden_alias.as_ref().map_or(&[], Vec::as_slice)
+    }
+
+    /// Aliases of the flag matched on the command line, hidden ones included
+    pub(crate) fn all_aliases(&self) -> impl Iterator<Item = &String> {
+        self.alias
+            .iter()
+            .flatten()
+            .chain(self.hidden_alias.iter().flatten())
+    }
+
+    /// Description of the flag, if set
+    pub fn get_description(&self) -> Option<&str> {
+        self.description.as_ref().map(String::as_str)
+    }
+
+    /// Type of the flag values
+    pub fn flag_type(&self) -> &FlagType {
+        &self.flag_type
+    }
+
+    /// Default value of the flag, if set
+    pub fn get_default_value(&self) -> Option<&str> {
+        self.default_value.as_ref().map(String::as_str)
+    }
+
+    /// Whether the flag is hidden from help and generated documents
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Standard `--verbose` and `--quiet` flags
+    #[cfg(feature = "log")]
+    pub(crate) fn verbosity() -> Vec<Flag> {
+        vec![
+            Flag::verbose().description("Log more, repeat for more detail"),
+            Flag::quiet().description("Only log errors"),
+        ]
+    }
+
+    /// Usage of the flag like `--port, -p <int>`, the name followed by the
+    /// visible aliases in the order they were added, unless set
+    pub(crate) fn usage_text(&self) -> String {
+        if let Some(usage) = &self.usage {
+            return usage.clone();
+        }
+
+        let mut usage = String::new();
+        usage += self.name_prefix();
+        usage += &self.name;
+        for alias in self.alias.iter().flatten() {
+            usage += ", -";
+            usage += alias;
+        }
+        self.push_values_usage(&mut usage);
+        usage
+    }
+
+    /// Usage of the flag by its name like `--port <int>`
+    pub(crate) fn long_usage_text(&self) -> String {
+        let mut usage = String::new();
+        usage += self.name_prefix();
+        usage += &self.name;
+        self.push_values_usage(&mut usage);
+        usage
+    }
+
+    fn push_values_usage(&self, usage: &mut String) {
+        if self.flag_type != FlagType::Bool {
+            for _ in 0..self.number_of_values.unwrap_or(1) {
+                *usage += " <";
+                *usage += self.flag_type.name();
+                *usage += ">";
+            }
+            if let Some(terminator) = &self.value_terminator {
+                if self.number_of_values.is_none() {
+                    *usage += "...";
+                }
+                *usage += " ";
+                *usage += terminator;
+            }
+        }
+    }
+
+    /// Environment variables read in order when the flag is not given, the
+    /// one set with `env` followed by those named after the flag with the
+    /// prefix, `PREFIX_COMMAND_FLAG` for the commands at the path and then
+    /// `PREFIX_FLAG`
+    pub(crate) fn env_names(&self, prefix: Option<&str>, commands: &[String]) -> Vec<String> {
+        let mut names: Vec<String> = self.env.iter().cloned().collect();
+        if let Some(prefix) = prefix {
+            if !commands.is_empty() {
+                names.push(env_name(&[prefix, &commands.join("_"), &self.name]));
+            }
+            names.push(env_name(&[prefix, &self.name]));
+        }
+        names
+    }
+
+    /// Whether the name is a single character, matching the short form as well
+    pub(crate) fn is_short_name(&self) -> bool {
+        self.name.chars().count() == 1
+    }
+
+    /// Flag given by its name, `-v` for single character names and `--name` otherwise
+    pub(crate) fn name_text(&self) -> String {
+        format!("{}{}", self.name_prefix(), self.name)
+    }
+
+    /// Dashes of the flag given by its name
+    pub(crate) fn name_prefix(&self) -> &'static str {
+        if self.is_short_name() {
+            "-"
         } else {
-            self.alias = Some(vec![name.into()]);
+            "--"
         }
-        self
     }
 
     /// Get flag position from command line argument
+    ///
+    /// A lone `-`, standing for stdin or stdout, never matches a flag.
     pub fn option_index(&self, v: &[String]) -> Option<usize> {
-        match &self.alias {
-            Some(alias) => v.iter().position(|r| {
-                r == &format!("--{}", &self.name) || alias.iter().any(|a| r == &format!("-{}", a))
-            }),
-            None => v.iter().position(|r| r == &format!("--{}", &self.name)),
+        v.iter().position(|r| self.is_named_by(r))
+    }
+
+    /// Whether the token gives the flag, as `--name`, `-n` for single
+    /// character names or `-alias`, compared without building the dashed
+    /// forms
+    pub(crate) fn is_named_by(&self, token: &str) -> bool {
+        if !token.starts_with('-') {
+            return false;
         }
+        let short = &token[1..];
+        (short.starts_with('-') && short[1..] == *self.name)
+            || (self.is_short_name() && short == self.name)
+            || (!short.is_empty() && self.all_aliases().any(|a| a == short))
     }
 
-    /// Get flag value
-    pub fn value(&self, v: Option<String>) -> Result<FlagValue, FlagError> {
+    /// Get default flag value
+    pub fn default_flag_value(&self) -> Option<Result<FlagValue, FlagError>> {
+        let default_value = self.default_value.as_ref()?;
         match self.flag_type {
-            FlagType::Bool => Ok(FlagValue::Bool(true)),
-            FlagType::String => match v {
-                Some(s) => Ok(FlagValue::String(s)),
-                None => Err(FlagError::ArgumentError),
-            },
-            FlagType::Int => match v {
-                Some(i) => match i.parse::<isize>() {
-                    Ok(i) => Ok(FlagValue::Int(i)),
-                    Err(_) => Err(FlagError::ValueTypeError),
-                },
-                None => Err(FlagError::ArgumentError),
-            },
-            FlagType::Uint => match v {
-                Some(i) => match i.parse::<usize>() {
-                    Ok(i) => Ok(FlagValue::Uint(i)),
-                    Err(_) => Err(FlagError::ValueTypeError),
-                },
-                None => Err(FlagError::ArgumentError),
-            },
-            FlagType::Float => match v {
-                Some(f) => match f.parse::<f64>() {
-                    Ok(f) => Ok(FlagValue::Float(f)),
-                    Err(_) => Err(FlagError::ValueTypeError),
-                },
-                None => Err(FlagError::ArgumentError),
-            },
+            FlagType::Bool => Some(self.flag_type.parse(default_value.to_owned())),
+            _ => Some(self.value(Some(default_value.to_owned()))),
+        }
+    }
+
+    /// Get value of the flag given on the command line from the token following it
+    ///
+    /// Bool flags take no token and are `true` once given. Flags absent from the
+    /// command line have no value, `Context` reporting them as `FlagError::NotFound`
+    /// so that they stay distinct from a bool flag defaulting to `false`.
+    pub fn value(&self, v: Option<String>) -> Result<FlagValue, FlagError> {
+        if let (Some(choices), Some(v)) = (&self.choices, &v) {
+            if !choices.contains(v) {
+                return Err(FlagError::ValueTypeError);
+            }
+        }
+
+        match (&self.flag_type, v) {
+            (FlagType::Bool, _) => Ok(FlagValue::Bool(true)),
+            (flag_type, Some(v)) => flag_type.parse(v),
+            (_, None) => Err(FlagError::ArgumentError),
+        }
+    }
+}
+
+/// Error for a flag named as one of the flags already registered
+pub(crate) fn check_registered<'a>(
+    flag: &Flag,
+    registered: impl IntoIterator<Item = &'a Flag>,
+) -> Result<(), String> {
+    if registered.into_iter().any(|other| other.name == flag.name) {
+        return Err(format!(
+            r#"Flag name "{}" is already registered."#,
+            flag.name
+        ));
+    }
+    Ok(())
+}
+
+/// Error for an alias of the flag that is the name or an alias of one of the
+/// other flags, flags of the same name being left to override each other
+pub(crate) fn check_aliases<'a>(
+    flag: &Flag,
+    others: impl IntoIterator<Item = &'a Flag>,
+) -> Result<(), String> {
+    for other in others {
+        if other.name == flag.name {
+            continue;
+        }
+        let shared = flag
+            .all_aliases()
+            .find(|alias| **alias == other.name || other.all_aliases().any(|a| a == *alias))
+            .map(|alias| (flag, alias, other))
+            .or_else(|| {
+                other
+                    .all_aliases()
+                    .find(|alias| **alias == flag.name)
+                    .map(|alias| (other, alias, flag))
+            });
+        if let Some((owner, alias, user)) = shared {
+            return Err(format!(
+                r#"Alias "{}" of flag "{}" is also used by flag "{}"."#,
+                alias, owner.name, user.name
+            ));
         }
     }
+    Ok(())
+}
+
+/// Environment variable name of the parts, uppercased with dashes written as
+/// underscores
+fn env_name(parts: &[&str]) -> String {
+    parts.join("_").to_uppercase().replace('-', "_")
+}
+
+/// Error for a flag name or alias that cannot be written on the command line
+fn check_name(name: &str, kind: &str) -> Result<(), String> {
+    let rule = if name.starts_with('-') {
+        r#"start with "-""#
+    } else if name.contains('=') {
+        r#"contain "=""#
+    } else if name.contains(' ') {
+        "contain whitespaces"
+    } else {
+        return Ok(());
+    };
+    Err(format!(
+        r#""{}" is invalid flag {}. Flag {} cannnot {}."#,
+        name, kind, kind, rule
+    ))
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::error::FlagError;
     use crate::{Flag, FlagType, FlagValue};
 
     #[test]
     fn opiton_index() {
-        let v = vec![
+        let v = [
             "cli".to_string(),
             "command".to_string(),
             "-a".to_string(),
@@ -181,6 +1306,11 @@ mod tests {
             let f = Flag::new("dance", FlagType::Bool);
             assert_eq!(f.option_index(&v), None);
         }
+        {
+            let f = Flag::new("stdin", FlagType::Bool).alias("s");
+            let v = ["-".to_string(), "--stdin".to_string()];
+            assert_eq!(f.option_index(&v), Some(1));
+        }
     }
 
     #[test]
@@ -201,26 +1331,152 @@ mod tests {
         Flag::new("cool flag", FlagType::Bool);
     }
 
+    #[test]
+    #[should_panic]
+    fn alias_fail_1() {
+        Flag::new("bool", FlagType::Bool).alias("b=o");
+    }
+
+    #[test]
+    #[should_panic]
+    fn alias_fail_2() {
+        Flag::new("bool", FlagType::Bool).alias("--bool");
+    }
+
+    #[test]
+    #[should_panic]
+    fn alias_fail_3() {
+        Flag::new("bool", FlagType::Bool).alias("b o");
+    }
+
+    #[test]
+    #[should_panic]
+    fn alias_fail_4() {
+        Flag::new("bool", FlagType::Bool).alias("");
+    }
+
+    #[test]
+    #[should_panic]
+    fn alias_fail_5() {
+        Flag::new("bool", FlagType::Bool).alias("bool");
+    }
+
+    #[test]
+    #[should_panic]
+    fn alias_fail_6() {
+        Flag::new("bool", FlagType::Bool).alias("b").alias("b");
+    }
+
+    #[test]
+    fn try_alias_test() {
+        let flag = || Flag::new("force", FlagType::Bool);
+        assert_eq!(flag().try_alias("f").unwrap().aliases(), ["f".to_string()]);
+        assert_eq!(
+            flag().try_alias("-f").unwrap_err(),
+            r#""-f" is invalid flag alias. Flag alias cannnot start with "-"."#
+        );
+        assert_eq!(
+            flag().try_alias("").unwrap_err(),
+            r#""" is invalid flag alias. Flag alias cannnot be empty."#
+        );
+        assert_eq!(
+            flag().alias("f").try_alias("f").unwrap_err(),
+            r#""f" is invalid flag alias. Flag "force" already has this alias."#
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn number_of_values_fail() {
+        Flag::new("bool", FlagType::Bool).number_of_values(2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn value_terminator_fail() {
+        Flag::new("bool", FlagType::Bool).value_terminator(";");
+    }
+
+    #[test]
+    fn usage_text_test() {
+        let f = Flag::new("point", FlagType::Float)
+            .alias("p")
+            .number_of_values(2);
+        assert_eq!(f.usage_text(), "--point, -p <float> <float>");
+        assert_eq!(
+            f.value_terminator(";").usage_text(),
+            "--point, -p <float> <float> ;"
+        );
+        assert_eq!(
+            Flag::new("env", FlagType::String)
+                .value_terminator("+")
+                .usage_text(),
+            "--env <string>... +"
+        );
+        assert_eq!(Flag::new("bool", FlagType::Bool).usage_text(), "--bool");
+        assert_eq!(
+            Flag::new("o", FlagType::String).alias("out").usage_text(),
+            "-o, -out <string>"
+        );
+    }
+
+    #[test]
+    fn generated_usage_text_test() {
+        let usage = |flag_type: FlagType| Flag::new("output", flag_type).usage_text();
+        assert_eq!(usage(FlagType::Bool), "--output");
+        assert_eq!(usage(FlagType::String), "--output <string>");
+        assert_eq!(usage(FlagType::Int), "--output <int>");
+        assert_eq!(usage(FlagType::Uint), "--output <uint>");
+        assert_eq!(usage(FlagType::Float), "--output <float>");
+        assert_eq!(
+            Flag::new("output", FlagType::String)
+                .alias("o")
+                .alias("out")
+                .usage_text(),
+            "--output, -o, -out <string>"
+        );
+    }
+
+    #[test]
+    fn short_name_test() {
+        let f = Flag::new("v", FlagType::Bool);
+        assert_eq!(f.option_index(&["-v".to_string()]), Some(0));
+        assert_eq!(f.option_index(&["--v".to_string()]), Some(0));
+        assert_eq!(f.option_index(&["-vv".to_string()]), None);
+    }
+
+    #[test]
+    fn is_named_by_test() {
+        let f = Flag::new("output", FlagType::String)
+            .alias("o")
+            .hidden_alias("out");
+        for token in &["--output", "-o", "-out"] {
+            assert!(f.is_named_by(token), "{}", token);
+        }
+        for token in &["output", "-output", "--o", "--out", "-", "--", "", "o"] {
+            assert!(!f.is_named_by(token), "{}", token);
+        }
+    }
+
     #[test]
     fn bool_flag_test() {
         let bool_flag = Flag::new("bool", FlagType::Bool);
-        let v = vec![
+        let v = [
             "cli".to_string(),
             "command".to_string(),
             "args".to_string(),
             "--bool".to_string(),
         ];
 
-        match bool_flag.value(Some(v[3].to_owned())) {
-            Ok(FlagValue::Bool(val)) => assert!(val),
-            _ => assert!(false),
-        }
+        assert!(bool_flag.option_index(&v).is_some());
+        assert_eq!(bool_flag.value(None), Ok(FlagValue::Bool(true)));
+        assert_eq!(bool_flag.default_flag_value(), None);
     }
 
     #[test]
     fn string_flag_test() {
         let string_flag = Flag::new("string", FlagType::String);
-        let v = vec![
+        let v = [
             "cli".to_string(),
             "command".to_string(),
             "args".to_string(),
@@ -230,14 +1486,14 @@ mod tests {
 
         match string_flag.value(Some(v[4].to_owned())) {
             Ok(FlagValue::String(val)) => assert_eq!("test".to_string(), val),
-            _ => assert!(false),
+            _ => panic!(),
         }
     }
 
     #[test]
     fn int_flag_test() {
         let int_flag = Flag::new("int", FlagType::Int);
-        let v = vec![
+        let v = [
             "cli".to_string(),
             "command".to_string(),
             "args".to_string(),
@@ -247,14 +1503,19 @@ mod tests {
 
         match int_flag.value(Some(v[4].to_owned())) {
             Ok(FlagValue::Int(val)) => assert_eq!(100, val),
-            _ => assert!(false),
+            _ => panic!(),
+        }
+        // Beyond the range of a 32-bit isize
+        match int_flag.value(Some("-3000000000".to_string())) {
+            Ok(FlagValue::Int(val)) => assert_eq!(-3_000_000_000, val),
+            _ => panic!(),
         }
     }
 
     #[test]
     fn uint_flag_test() {
         let uint_flag = Flag::new("uint", FlagType::Uint);
-        let v = vec![
+        let v = [
             "cli".to_string(),
             "command".to_string(),
             "args".to_string(),
@@ -264,14 +1525,14 @@ mod tests {
 
         match uint_flag.value(Some(v[4].to_owned())) {
             Ok(FlagValue::Uint(val)) => assert_eq!(1234567891011, val),
-            _ => assert!(false),
+            _ => panic!(),
         }
     }
 
     #[test]
     fn float_flag_test() {
         let float_flag = Flag::new("float", FlagType::Float);
-        let v = vec![
+        let v = [
             "cli".to_string(),
             "command".to_string(),
             "args".to_string(),
@@ -281,7 +1542,117 @@ mod tests {
 
         match float_flag.value(Some(v[4].to_owned())) {
             Ok(FlagValue::Float(val)) => assert_eq!(1.23, val),
-            _ => assert!(false),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn default_value_test() {
+        let int_flag = Flag::new("int", FlagType::Int).default_value("10");
+        assert_eq!(int_flag.default_flag_value(), Some(Ok(FlagValue::Int(10))));
+
+        let bool_flag = Flag::new("bool", FlagType::Bool).default_value("false");
+        assert_eq!(
+            bool_flag.default_flag_value(),
+            Some(Ok(FlagValue::Bool(false)))
+        );
+
+        let float_flag = Flag::new("float", FlagType::Float);
+        assert_eq!(float_flag.default_flag_value(), None);
+    }
+
+    #[test]
+    fn standard_flags_test() {
+        let usages: Vec<String> = [
+            Flag::verbose(),
+            Flag::quiet(),
+            Flag::force(),
+            Flag::output(),
+        ]
+        .iter()
+        .map(Flag::usage_text)
+        .collect();
+        assert_eq!(
+            usages,
+            [
+                "--verbose, -v",
+                "--quiet, -q",
+                "--force, -f",
+                "--output, -o <FILE>"
+            ]
+        );
+        assert!(Flag::verbose().repeatable);
+        assert_eq!(Flag::output().flag_type, FlagType::String);
+    }
+
+    #[test]
+    fn choices_flag_test() {
+        let string_flag = Flag::new("format", FlagType::String).choices(&["json", "yaml"]);
+
+        assert_eq!(
+            string_flag.value(Some("json".to_string())),
+            Ok(FlagValue::String("json".to_string()))
+        );
+        assert_eq!(
+            string_flag.value(Some("toml".to_string())),
+            Err(FlagError::ValueTypeError)
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn matches_test() {
+        let flags = vec![Flag::new("name", FlagType::String).matches(r"^[a-z][a-z0-9-]*$")];
+        let check = |value: &str| {
+            let args = vec!["--name".to_string(), value.to_string()];
+            crate::parser::FlagMatches::parse(args, &flags)
+                .check_values(&flags)
+                .map_err(|e| e.to_string())
+        };
+
+        assert_eq!(check("web-1"), Ok(()));
+        assert_eq!(
+            check("Web"),
+            Err(
+                r#"Invalid value "Web" for flag "name": must match the pattern ^[a-z][a-z0-9-]*$"#
+                    .to_string()
+            )
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    #[should_panic(expected = r#"Invalid pattern "[a-z" of flag "name": regex parse error"#)]
+    fn invalid_pattern_test() {
+        let _ = Flag::new("name", FlagType::String).matches("[a-z");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_flag_test() {
+        let flag = Flag::new("filter", FlagType::Regex);
+        match flag.value(Some("^ab+$".to_string())) {
+            Ok(FlagValue::Regex(regex)) => assert!(regex.is_match("abbb")),
+            value => panic!("{:?}", value),
         }
+        assert_eq!(
+            flag.value(Some("(".to_string())),
+            Err(FlagError::ValueTypeError)
+        );
+
+        let flags = vec![flag];
+        let args = vec!["--filter".to_string(), "a(".to_string()];
+        let error = crate::parser::FlagMatches::parse(args, &flags)
+            .check_values(&flags)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with(
+                "Invalid value \"a(\" for flag \"filter\", expected regex: regex parse error:"
+            ),
+            "{}",
+            error
+        );
+        assert!(error.contains("unclosed group"), "{}", error);
     }
 }
@@ -0,0 +1,207 @@
+use crate::error::{Error, ErrorKind};
+use crate::parser::FlagMatches;
+use crate::{Flag, FlagType};
+
+/// `FlagGroup` type.
+///
+/// Flags of a command checked together after parsing
+#[derive(Clone, Debug, Default)]
+pub struct FlagGroup {
+    /// Group name
+    pub name: String,
+    /// Names of the member flags
+    pub members: Vec<String>,
+    /// Fail before the action runs when no member is given
+    pub required: bool,
+    /// Fail before the action runs when several members are given
+    pub exclusive: bool,
+    /// Bool member given when no member is
+    pub default_member: Option<String>,
+}
+
+impl FlagGroup {
+    /// Create new instance of `FlagGroup`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::FlagGroup;
+    ///
+    /// let group = FlagGroup::new("input");
+    /// ```
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Add member flag of the group by name
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::FlagGroup;
+    ///
+    /// let group = FlagGroup::new("input")
+    ///     .member("file")
+    ///     .member("url");
+    /// ```
+    pub fn member<T: Into<String>>(mut self, name: T) -> Self {
+        self.members.push(name.into());
+        self
+    }
+
+    /// Require at least one member of the group to be given
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::FlagGroup;
+    ///
+    /// let group = FlagGroup::new("input")
+    ///     .member("file")
+    ///     .member("url")
+    ///     .required();
+    /// ```
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Allow at most one member of the group to be given
+    ///
+    /// Combined with `required`, exactly one member must be given.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::FlagGroup;
+    ///
+    /// let group = FlagGroup::new("format")
+    ///     .member("json")
+    ///     .member("yaml")
+    ///     .exclusive();
+    /// ```
+    pub fn exclusive(mut self) -> Self {
+        self.exclusive = true;
+        self
+    }
+
+    /// Set bool member given when no member of the group is
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::FlagGroup;
+    ///
+    /// let group = FlagGroup::new("format")
+    ///     .member("json")
+    ///     .member("yaml")
+    ///     .member("table")
+    ///     .exclusive()
+    ///     .default_member("table");
+    /// ```
+    pub fn default_member<T: Into<String>>(mut self, name: T) -> Self {
+        self.default_member = Some(name.into());
+        self
+    }
+
+    /// Panic when a member or the default member is not a flag of the command
+    pub(crate) fn check_members(&self, flags: &Option<Vec<Flag>>) {
+        let find = |name: &str| flags.iter().flatten().find(|f| f.name == name);
+        for member in &self.members {
+            if find(member).is_none() {
+                panic!(
+                    r#"Flag "{}" of flag group "{}" is not registered."#,
+                    member, self.name
+                );
+            }
+        }
+
+        if let Some(default_member) = &self.default_member {
+            if !self.members.contains(default_member) {
+                panic!(
+                    r#"Default member "{}" is not a member of flag group "{}"."#,
+                    default_member, self.name
+                );
+            }
+            if find(default_member).map(|f| &f.flag_type) != Some(&FlagType::Bool) {
+                panic!(
+                    r#"Default member "{}" of flag group "{}" is not a bool flag."#,
+                    default_member, self.name
+                );
+            }
+        }
+    }
+
+    /// Member flags of the group, in member order
+    fn flags<'a>(&'a self, flags: &'a [Flag]) -> impl Iterator<Item = (usize, &'a Flag)> + 'a {
+        self.members.iter().filter_map(move |member| {
+            flags
+                .iter()
+                .enumerate()
+                .find(|(_, flag)| &flag.name == member)
+        })
+    }
+
+    /// Error when the flags given on the command line break the group rules,
+    /// giving the default member when no member is given
+    pub(crate) fn check(&self, flags: &[Flag], matches: &mut FlagMatches) -> Result<(), Error> {
+        let given: Vec<String> = self
+            .flags(flags)
+            .filter(|(index, _)| matches.occurrences[*index].count > 0)
+            .map(|(_, flag)| flag.name_text())
+            .collect();
+
+        if self.exclusive && given.len() > 1 {
+            let (second, group) = (given[1].clone(), self.name.clone());
+            return Err(Error::new(ErrorKind::FlagGroup, move |m| {
+                m.conflicting_flags(&given, &group)
+            })
+            .token(second));
+        }
+
+        if !given.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(default_member) = &self.default_member {
+            if let Some((index, _)) = self.flags(flags).find(|(_, f)| &f.name == default_member) {
+                matches.occurrences[index].count = 1;
+            }
+            return Ok(());
+        }
+
+        if self.required {
+            let names: Vec<String> = self
+                .flags(flags)
+                .map(|(_, flag)| flag.name_text())
+                .collect();
+            let group = self.name.clone();
+            return Err(Error::new(ErrorKind::FlagGroup, move |m| {
+                m.missing_group(&group, &names)
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Usage of the group like `(--file <string> | --stdin)` when a member
+    /// must be given, or `[--json | --yaml]` when at most one can be
+    pub(crate) fn usage_text(&self, flags: &[Flag]) -> Option<String> {
+        let members = || {
+            self.flags(flags)
+                .map(|(_, flag)| flag.long_usage_text())
+                .collect::<Vec<String>>()
+                .join(" | ")
+        };
+        if self.required && self.default_member.is_none() {
+            Some(format!("({})", members()))
+        } else if self.exclusive {
+            Some(format!("[{}]", members()))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use crate::Flag;
+
+/// `FlagSet` type.
+///
+/// Named set of flags defined once and added to several commands with
+/// `Command::flag_set`
+///
+/// Example
+///
+/// ```
+/// use seahorse::{App, Command, Flag, FlagSet, FlagType};
+///
+/// let output = FlagSet::new("Output")
+///     .flag(Flag::new("format", FlagType::String).alias("f"))
+///     .flag(Flag::new("no-header", FlagType::Bool))
+///     .section();
+///
+/// let app = App::new("cli")
+///     .command(Command::new("list").flag_set(&output))
+///     .command(Command::new("show").flag_set(&output));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FlagSet {
+    /// Set name, the heading of its flags in help as a section
+    pub name: String,
+    /// Flags of the set
+    pub flags: Vec<Flag>,
+    /// List the flags in help under the set name instead of `Flags:`
+    pub section: bool,
+}
+
+impl FlagSet {
+    /// Create new instance of `FlagSet`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::FlagSet;
+    ///
+    /// let output = FlagSet::new("Output");
+    /// ```
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Add flag of the set
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagSet, FlagType};
+    ///
+    /// let output = FlagSet::new("Output")
+    ///     .flag(Flag::new("format", FlagType::String))
+    ///     .flag(Flag::new("color", FlagType::Bool));
+    /// ```
+    pub fn flag(mut self, flag: Flag) -> Self {
+        self.flags.push(flag);
+        self
+    }
+
+    /// List the flags in help in a section headed by the set name
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagSet, FlagType};
+    ///
+    /// let output = FlagSet::new("Output")
+    ///     .flag(Flag::new("format", FlagType::String))
+    ///     .section();
+    /// ```
+    pub fn section(mut self) -> Self {
+        self.section = true;
+        self
+    }
+
+    /// Flags of the set as added to a command
+    pub(crate) fn to_flags(&self) -> Vec<Flag> {
+        self.flags
+            .iter()
+            .map(|flag| match (self.section, &flag.help_section) {
+                (true, None) => flag.clone().help_section(self.name.clone()),
+                _ => flag.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{App, Command, Context, Flag, FlagSet, FlagType, Help};
+    use std::sync::{Arc, Mutex};
+
+    fn output() -> FlagSet {
+        FlagSet::new("Output")
+            .flag(
+                Flag::new("format", FlagType::String)
+                    .alias("f")
+                    .description("Output format"),
+            )
+            .flag(Flag::new("no-header", FlagType::Bool).description("Leave out the header"))
+    }
+
+    #[test]
+    fn flag_set_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let push = |name: &'static str| {
+            let log = log.clone();
+            move |c: &Context| {
+                log.lock().unwrap().push(format!(
+                    "{} {:?} {}",
+                    name,
+                    c.string_flag("format").ok(),
+                    c.bool_flag("no-header")
+                ))
+            }
+        };
+        let output = output();
+        let app = App::new("cli")
+            .command(Command::new("list").action(push("list")).flag_set(&output))
+            .command(
+                Command::new("show")
+                    .action(push("show"))
+                    .flag(Flag::new("all", FlagType::Bool))
+                    .flag_set(&output),
+            );
+        let run = |args: &[&str]| app.try_run(args.iter().map(|a| a.to_string()).collect());
+
+        assert_eq!(run(&["cli", "list", "-f", "json"]), Ok(0));
+        assert_eq!(run(&["cli", "show", "--no-header", "--format=csv"]), Ok(0));
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![r#"list Some("json") false"#, r#"show Some("csv") true"#]
+        );
+        assert_eq!(output.flags.len(), 2);
+    }
+
+    #[test]
+    fn section_test() {
+        let help = |set: &FlagSet| {
+            App::new("cli")
+                .flag(Flag::new("all", FlagType::Bool).description("Show all"))
+                .flag_set(set)
+                .help_text()
+        };
+        assert!(help(&output()).contains(
+            "Flags:\n\
+             \t--all                 : Show all\n\
+             \t--format, -f <string> : Output format\n\
+             \t--no-header           : Leave out the header\n\
+             \t--help, -h            : Show help\n"
+        ));
+        assert!(help(&output().section()).contains(
+            "Flags:\n\
+             \t--all                 : Show all\n\
+             \t--help, -h            : Show help\n\n\
+             Output:\n\
+             \t--format, -f <string> : Output format\n\
+             \t--no-header           : Leave out the header\n"
+        ));
+    }
+
+    #[test]
+    fn duplicate_test() {
+        let command = Command::new("list").flag(Flag::new("no-header", FlagType::Bool));
+        assert_eq!(
+            output()
+                .flags
+                .into_iter()
+                .try_fold(command, Command::try_flag)
+                .err(),
+            Some(r#"Flag name "no-header" is already registered."#.to_string())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Alias "f" of flag "format" is also used by flag "force"."#)]
+    fn alias_conflict_test() {
+        let _ = Command::new("rm")
+            .flag(Flag::new("force", FlagType::Bool).alias("f"))
+            .flag_set(&output());
+    }
+}
@@ -0,0 +1,49 @@
+use crate::error::FlagError;
+use crate::{Command, Context};
+
+/// Type built from the flags and arguments of a `Context`
+///
+/// Example
+///
+/// ```
+/// use seahorse::error::FlagError;
+/// use seahorse::{Context, FromContext};
+///
+/// struct Greet {
+///     name: String,
+///     loud: bool,
+/// }
+///
+/// impl FromContext for Greet {
+///     fn from_context(c: &Context) -> Result<Self, FlagError> {
+///         Ok(Self {
+///             name: c.string_flag("name")?,
+///             loud: c.bool_flag("loud"),
+///         })
+///     }
+/// }
+/// ```
+pub trait FromContext: Sized {
+    fn from_context(c: &Context) -> Result<Self, FlagError>;
+}
+
+/// Type describing a command, implemented by `#[derive(Command)]`
+/// with the `derive` feature
+pub trait CommandArgs: FromContext + 'static {
+    /// Command with the flags of the type, without action
+    fn command() -> Command;
+
+    /// Command whose action parses the type and passes it to the handler
+    ///
+    /// When the context cannot be parsed, the error and the command help are
+    /// printed instead.
+    fn command_with<F: Fn(Self) + Send + Sync + 'static>(handler: F) -> Command {
+        Self::command().action(move |c: &Context| match Self::from_context(c) {
+            Ok(args) => handler(args),
+            Err(e) => {
+                c.streams().err(&format!("{}\n", e));
+                c.help();
+            }
+        })
+    }
+}
@@ -1,7 +1,602 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::messages::Messages;
+use crate::{Arg, Command, Flag};
+
+/// Display order of items that don't set one
+pub(crate) const DEFAULT_DISPLAY_ORDER: u32 = 999;
+
 pub(crate) trait Help {
     fn help_text(&self) -> String;
+}
+
+/// Help text as printed by `--help`, followed by a blank line
+pub(crate) fn render(text: &str) -> String {
+    format!("{}\n", text)
+}
+
+/// Item listed in help, completions and generated documents
+pub(crate) trait HelpItem {
+    fn help_name(&self) -> &str;
+    fn help_order(&self) -> u32;
+    fn is_hidden(&self) -> bool;
+}
+
+impl HelpItem for Flag {
+    fn help_name(&self) -> &str {
+        &self.name
+    }
+
+    fn help_order(&self) -> u32 {
+        self.display_order.unwrap_or(DEFAULT_DISPLAY_ORDER)
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+}
+
+impl HelpItem for Command {
+    fn help_name(&self) -> &str {
+        &self.name
+    }
+
+    fn help_order(&self) -> u32 {
+        self.display_order.unwrap_or(DEFAULT_DISPLAY_ORDER)
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+}
+
+impl<T: HelpItem> HelpItem for Arc<T> {
+    fn help_name(&self) -> &str {
+        (**self).help_name()
+    }
+
+    fn help_order(&self) -> u32 {
+        (**self).help_order()
+    }
+
+    fn is_hidden(&self) -> bool {
+        (**self).is_hidden()
+    }
+}
+
+/// Form of help asked for, `-h` giving the short one and `--help` the long one
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum HelpForm {
+    /// Usage, short descriptions and lists only
+    Short,
+    /// Everything, long descriptions preferred
+    Long,
+}
+
+impl HelpForm {
+    /// Description of the form, the long description for the long form when set
+    pub(crate) fn description<'a>(
+        self,
+        description: &'a Option<String>,
+        long_description: &'a Option<String>,
+    ) -> Option<&'a String> {
+        match self {
+            HelpForm::Long => long_description.as_ref().or(description.as_ref()),
+            HelpForm::Short => description.as_ref(),
+        }
+    }
+}
+
+/// Text with the lines after the first indented by a tab, keeping the relative indentation
+pub(crate) fn indent(text: &str) -> Cow<'_, str> {
+    if text.contains('\n') {
+        Cow::Owned(text.replace('\n', "\n\t"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// `Args:` section listing the declared positional arguments in order, empty
+/// when there are none
+pub(crate) fn arg_list(args: &[Arg], messages: &dyn Messages) -> String {
+    let mut text = String::new();
+    let usages: Vec<String> = args.iter().map(Arg::usage).collect();
+    let width = match usages.iter().map(String::len).max() {
+        Some(width) => width,
+        None => return text,
+    };
+    text += &format!("{}:\n", messages.args_heading());
+
+    for (arg, usage) in args.iter().zip(&usages) {
+        text += &format!("\t{}", usage);
+        match &arg.description {
+            Some(description) if !description.is_empty() => {
+                let description =
+                    description.replace('\n', &format!("\n\t{}", " ".repeat(width + 3)));
+                text += &format!("{} : {}\n", " ".repeat(width - usage.len()), description);
+            }
+            _ => text += "\n",
+        }
+    }
+    text + "\n"
+}
+
+/// `Flags:` section listing the flags followed by the built-in flags, empty
+/// when there are none, and a section per help section of the flags after it
+/// in order of first appearance
+///
+/// The short form shows the first line of each description, the long form
+/// the long description when set, its lines aligned after the flag syntax
+/// across the sections, followed by the environment variables of the flag.
+pub(crate) fn flag_list(
+    flags: &[&Flag],
+    builtins: &[(String, String)],
+    form: HelpForm,
+    messages: &dyn Messages,
+    env_names: impl Fn(&Flag) -> Vec<String>,
+) -> String {
+    let mut text = String::new();
+    let usages: Vec<String> = flags.iter().map(|f| f.usage_text()).collect();
+    let width = match usages
+        .iter()
+        .map(String::len)
+        .chain(builtins.iter().map(|b| b.0.len()))
+        .max()
+    {
+        Some(width) => width,
+        None => return text,
+    };
+
+    let mut sections: Vec<Option<&str>> = Vec::new();
+    for flag in flags {
+        let section = flag.help_section.as_ref().map(String::as_str);
+        if section.is_some() && !sections.contains(&section) {
+            sections.push(section);
+        }
+    }
+    if !builtins.is_empty() || flags.iter().any(|f| f.help_section.is_none()) {
+        sections.insert(0, None);
+    }
+
+    for (index, section) in sections.into_iter().enumerate() {
+        if index > 0 {
+            text += "\n";
+        }
+        match section {
+            Some(section) => text += &format!("{}:\n", section),
+            None => text += &format!("{}:\n", messages.flags_heading()),
+        }
+        for (flag, usage) in flags.iter().zip(&usages) {
+            if flag.help_section.as_ref().map(String::as_str) != section {
+                continue;
+            }
+            text += &format!("\t{}", usage);
+            let mut description = match form.description(&flag.description, &flag.long_description)
+            {
+                Some(description) => match form {
+                    HelpForm::Short => description.lines().next().unwrap_or_default().to_string(),
+                    HelpForm::Long => {
+                        description.replace('\n', &format!("\n\t{}", " ".repeat(width + 3)))
+                    }
+                },
+                None => String::new(),
+            };
+            let names = match form {
+                HelpForm::Short => Vec::new(),
+                HelpForm::Long => env_names(flag),
+            };
+            if !names.is_empty() {
+                if !description.is_empty() {
+                    description.push(' ');
+                }
+                description += &messages.env_hint(&names);
+            }
+            if description.is_empty() {
+                text += "\n";
+            } else {
+                text += &format!("{} : {}\n", " ".repeat(width - usage.len()), description);
+            }
+        }
+
+        if section.is_none() {
+            for (flag, description) in builtins {
+                text += &format!("\t{}", flag);
+                if description.is_empty() {
+                    text += "\n";
+                } else {
+                    text += &format!("{} : {}\n", " ".repeat(width - flag.len()), description);
+                }
+            }
+        }
+    }
+
+    text
+}
+
+/// `Commands:` section listing the commands, uncategorized ones first and
+/// then each category under its own heading in order of first appearance,
+/// empty when there are none
+///
+/// Names followed by their aliases like `build (alias: b)` are padded to a
+/// common column, and descriptions are wrapped to fit the width when given.
+/// Commands without a description are listed by name alone.
+pub(crate) fn command_list(
+    commands: &[&Arc<Command>],
+    width: Option<usize>,
+    messages: &dyn Messages,
+) -> String {
+    let mut text = String::new();
+    let names: Vec<String> = commands
+        .iter()
+        .map(|c| match &c.alias {
+            Some(alias) => format!("{} {}", c.name, messages.aliases_hint(alias)),
+            None => c.name.clone(),
+        })
+        .collect();
+    let name_max_len = match names.iter().map(String::len).max() {
+        Some(len) => len,
+        None => return text,
+    };
+    // A tab is taken as 8 columns, followed by the names and " : "
+    let description_width = width
+        .and_then(|width| width.checked_sub(8 + name_max_len + 3))
+        .filter(|&width| width >= MIN_WRAP_WIDTH);
+
+    let mut categories: Vec<Option<&str>> = Vec::new();
+    for command in commands {
+        let category = command.category.as_ref().map(String::as_str);
+        if !categories.contains(&category) {
+            categories.push(category);
+        }
+    }
+    categories.sort_by_key(Option::is_some);
+
+    for category in categories {
+        match category {
+            Some(category) => text += &format!("\n{}:\n", category),
+            None => text += &format!("\n{}:\n", messages.commands_heading()),
+        }
+        for (command, name) in commands.iter().zip(&names) {
+            if command.category.as_ref().map(String::as_str) != category {
+                continue;
+            }
+            let description = command.description.as_ref().map_or("", String::as_str);
+            let lines = match description_width {
+                Some(width) => wrap(description, width),
+                None => vec![description.to_string()],
+            };
+            if lines[0].is_empty() {
+                text += &format!("\t{}\n", name);
+                continue;
+            }
+            text += &format!(
+                "\t{} {}: {}\n",
+                name,
+                " ".repeat(name_max_len - name.len()),
+                lines[0]
+            );
+            for line in &lines[1..] {
+                text += &format!("\t{}{}\n", " ".repeat(name_max_len + 3), line);
+            }
+        }
+    }
+
+    text
+}
+
+/// Narrowest width descriptions are wrapped to, below which they aren't
+const MIN_WRAP_WIDTH: usize = 20;
+
+/// Lines of the text broken at whitespace to fit the width, words longer
+/// than the width keeping a line of their own
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for word in text.split_whitespace() {
+        let line = lines.last_mut().unwrap();
+        if line.is_empty() {
+            line.push_str(word);
+        } else if line.chars().count() + 1 + word.chars().count() <= width {
+            line.push(' ');
+            line.push_str(word);
+        } else {
+            lines.push(word.to_string());
+        }
+    }
+    lines
+}
+
+/// Visible items sorted by display order.
+/// Ties are broken by declaration order, or by name when `alphabetical` is set.
+pub(crate) fn visible<T: HelpItem>(items: &Option<Vec<T>>, alphabetical: bool) -> Vec<&T> {
+    let mut items: Vec<&T> = match items {
+        Some(items) => items.iter().filter(|i| !i.is_hidden()).collect(),
+        None => Vec::new(),
+    };
+
+    items.sort_by(|a, b| {
+        let order = a.help_order().cmp(&b.help_order());
+        if alphabetical {
+            order.then_with(|| a.help_name().cmp(b.help_name()))
+        } else {
+            order
+        }
+    });
+
+    items
+}
+
+/// Built-in flag printing the help
+///
+/// Example
+///
+/// ```
+/// use seahorse::{App, HelpFlag};
+///
+/// let app = App::new("cli").help_flag("assist", Some('?'));
+/// assert_eq!(
+///     app.help_flag,
+///     HelpFlag::Enabled {
+///         name: "assist".to_string(),
+///         short: Some('?'),
+///     }
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum HelpFlag {
+    /// Setting of the parent command, `--help, -h` for the app
+    Inherit,
+    /// Flag named `--name`, with `-short` given as well when set
+    Enabled { name: String, short: Option<char> },
+    /// No built-in help flag
+    Disabled,
+}
+
+impl Default for HelpFlag {
+    fn default() -> Self {
+        HelpFlag::Inherit
+    }
+}
+
+impl HelpFlag {
+    /// Setting applying below the parent setting
+    pub(crate) fn or<'a>(&'a self, parent: &'a HelpFlag) -> &'a HelpFlag {
+        match self {
+            HelpFlag::Inherit => parent,
+            _ => self,
+        }
+    }
+
+    /// Long name and short name of the flag, unless disabled
+    pub(crate) fn names(&self) -> Option<(&str, Option<char>)> {
+        match self {
+            HelpFlag::Inherit => Some(("help", Some('h'))),
+            HelpFlag::Enabled { name, short } => Some((name, *short)),
+            HelpFlag::Disabled => None,
+        }
+    }
+
+    /// Whether the command line argument is the flag
+    pub(crate) fn matches(&self, arg: &str) -> bool {
+        let short = self.names().and_then(|(_, short)| short);
+        let mut chars = arg.chars();
+        self.matches_long(arg)
+            || (chars.next() == Some('-')
+                && chars.next().is_some()
+                && chars.next().is_none()
+                && short.map_or(false, |s| arg.ends_with(s)))
+    }
+
+    /// Form of help asked for by the arguments before `--`, the long form when
+    /// both are given
+    pub(crate) fn form(&self, args: &[String]) -> Option<HelpForm> {
+        let mut args = args.iter().take_while(|arg| *arg != "--");
+        if args.clone().any(|arg| self.matches_long(arg)) {
+            Some(HelpForm::Long)
+        } else if args.any(|arg| self.matches(arg)) {
+            Some(HelpForm::Short)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the command line argument is the long form of the flag
+    pub(crate) fn matches_long(&self, arg: &str) -> bool {
+        match self.names() {
+            Some((name, _)) => arg.starts_with("--") && &arg[2..] == name,
+            None => false,
+        }
+    }
+
+    /// Whether the flags before the first positional argument or `--` give
+    /// `--help=json` or `--help json`, asking for the JSON dump of the app
+    pub(crate) fn asks_json(&self, args: &[String]) -> bool {
+        for (index, arg) in args.iter().enumerate() {
+            if arg == "--" || !arg.starts_with('-') {
+                break;
+            }
+            let value = match arg.find('=') {
+                Some(eq) if self.matches_long(&arg[..eq]) => Some(&arg[eq + 1..]),
+                _ if self.matches_long(arg) => args.get(index + 1).map(String::as_str),
+                _ => None,
+            };
+            if value == Some("json") {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Flag as shown in help, like `--help, -h`
+    pub(crate) fn usage(&self) -> Option<String> {
+        self.names().map(|(name, short)| match short {
+            Some(short) => format!("--{}, -{}", name, short),
+            None => format!("--{}", name),
+        })
+    }
+
+    /// Words completing the flag
+    pub(crate) fn words(&self) -> Vec<String> {
+        match self.names() {
+            Some((name, short)) => {
+                let mut words = vec![format!("--{}", name)];
+                words.extend(short.map(|s| format!("-{}", s)));
+                words
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Panic when one of the flags is given like the help flag
+    pub(crate) fn check<'a>(&self, flags: impl IntoIterator<Item = &'a Flag>) {
+        if let Err(e) = self.try_check(flags) {
+            panic!("{}", e);
+        }
+    }
+
+    /// Why one of the flags cannot be given along the help flag
+    pub(crate) fn try_check<'a>(
+        &self,
+        flags: impl IntoIterator<Item = &'a Flag>,
+    ) -> Result<(), String> {
+        match self.names() {
+            Some((name, short)) => check_builtin(flags, name, short, "help", || {
+                self.usage().unwrap_or_default()
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Error for the first of the flags named as the built-in flag or having its
+/// short form as alias or as single character name
+fn check_builtin<'a>(
+    flags: impl IntoIterator<Item = &'a Flag>,
+    name: &str,
+    short: Option<char>,
+    builtin: &str,
+    usage: impl Fn() -> String,
+) -> Result<(), String> {
+    for flag in flags {
+        let short_conflict = short.map_or(false, |s| {
+            let short = s.to_string();
+            (flag.is_short_name() && flag.name == short) || flag.all_aliases().any(|a| *a == short)
+        });
+        if flag.name == name || short_conflict {
+            return Err(format!(
+                r#"Flag "{}" conflicts with the {} flag "{}"."#,
+                flag.name,
+                builtin,
+                usage()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Built-in flag printing the version of apps and commands that have one
+///
+/// Example
+///
+/// ```
+/// use seahorse::{App, VersionFlag};
+///
+/// let app = App::new("cli").version("1.0.0").version_flag("version", None);
+/// assert_eq!(
+///     app.version_flag,
+///     VersionFlag::Enabled {
+///         name: "version".to_string(),
+///         short: None,
+///     }
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum VersionFlag {
+    /// Flag named `--name`, with `-short` given as well when set
+    Enabled { name: String, short: Option<char> },
+    /// No built-in version flag, `--version` being read like other flags
+    Disabled,
+}
+
+impl Default for VersionFlag {
+    fn default() -> Self {
+        VersionFlag::Enabled {
+            name: "version".to_string(),
+            short: Some('V'),
+        }
+    }
+}
+
+impl VersionFlag {
+    /// Long name and short name of the flag, unless disabled
+    pub(crate) fn names(&self) -> Option<(&str, Option<char>)> {
+        match self {
+            VersionFlag::Enabled { name, short } => Some((name, *short)),
+            VersionFlag::Disabled => None,
+        }
+    }
+
+    /// Whether the command line argument is the long form of the flag
+    pub(crate) fn matches_long(&self, arg: &str) -> bool {
+        match self.names() {
+            Some((name, _)) => arg.starts_with("--") && &arg[2..] == name,
+            None => false,
+        }
+    }
+
+    /// Whether the command line argument is the short form of the flag
+    pub(crate) fn matches_short(&self, arg: &str) -> bool {
+        match self.names() {
+            Some((_, Some(short))) => {
+                let mut chars = arg.chars();
+                chars.next() == Some('-') && chars.next() == Some(short) && chars.next().is_none()
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the command line argument is the flag
+    pub(crate) fn matches(&self, arg: &str) -> bool {
+        self.matches_long(arg) || self.matches_short(arg)
+    }
+
+    /// Flag as shown in help, like `--version, -V`
+    pub(crate) fn usage(&self) -> Option<String> {
+        self.names().map(|(name, short)| match short {
+            Some(short) => format!("--{}, -{}", name, short),
+            None => format!("--{}", name),
+        })
+    }
+
+    /// Words completing the flag
+    pub(crate) fn words(&self) -> Vec<String> {
+        match self.names() {
+            Some((name, short)) => {
+                let mut words = vec![format!("--{}", name)];
+                words.extend(short.map(|s| format!("-{}", s)));
+                words
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Panic when one of the flags is given like the version flag
+    pub(crate) fn check<'a>(&self, flags: impl IntoIterator<Item = &'a Flag>) {
+        if let Err(e) = self.try_check(flags) {
+            panic!("{}", e);
+        }
+    }
 
-    fn help(&self) {
-        println!("{}", self.help_text());
+    /// Why one of the flags cannot be given along the version flag
+    pub(crate) fn try_check<'a>(
+        &self,
+        flags: impl IntoIterator<Item = &'a Flag>,
+    ) -> Result<(), String> {
+        match self.names() {
+            Some((name, short)) => check_builtin(flags, name, short, "version", || {
+                self.usage().unwrap_or_default()
+            }),
+            None => Ok(()),
+        }
     }
 }
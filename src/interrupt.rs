@@ -0,0 +1,235 @@
+// Statics with `Mutex::new` need a newer Rust than the crate's minimum, as
+// the `interrupt` feature does
+#![allow(clippy::incompatible_msrv)]
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::task::Waker;
+use std::thread;
+use std::time::Duration;
+
+use crate::{Action, Context};
+
+/// Source of the interrupts ending actions, Ctrl-C unless set with
+/// [`App::signal_source`](crate::App::signal_source)
+///
+/// Example
+///
+/// ```
+/// use seahorse::SignalSource;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// /// Interrupt after the action checked a number of times
+/// struct After(AtomicUsize);
+///
+/// impl SignalSource for After {
+///     fn interrupted(&self) -> bool {
+///         let left = self.0.load(Ordering::SeqCst);
+///         self.0.store(left.saturating_sub(1), Ordering::SeqCst);
+///         left == 0
+///     }
+/// }
+/// ```
+pub trait SignalSource: Send + Sync {
+    /// Start listening for interrupts, called before the action runs
+    fn install(&self) {}
+
+    /// Whether an interrupt was received
+    fn interrupted(&self) -> bool;
+
+    /// Wake the waker on the next interrupt so that a pending async action is
+    /// cancelled, sources that don't only being checked when it's polled
+    fn wake_on_interrupt(&self, _waker: &Waker) {}
+}
+
+/// Interrupts sent with Ctrl-C, read from `SIGINT` on Unix, never received
+/// elsewhere
+///
+/// A second Ctrl-C exits the process at once with status 130, for actions
+/// that don't check [`Context::interrupted`](crate::Context::interrupted).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CtrlC;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static WAKER: Mutex<Option<Waker>> = Mutex::new(None);
+static WATCHER: Once = Once::new();
+
+/// Time between checks for Ctrl-C of the thread waking async actions
+const WATCH_INTERVAL: Duration = Duration::from_millis(50);
+
+impl SignalSource for CtrlC {
+    #[cfg(unix)]
+    fn install(&self) {
+        const SIGINT: i32 = 2;
+        extern "C" {
+            fn signal(signum: i32, handler: extern "C" fn(i32)) -> usize;
+            fn _exit(status: i32) -> !;
+        }
+        extern "C" fn on_sigint(_: i32) {
+            if INTERRUPTED.swap(true, Ordering::SeqCst) {
+                // Safety: `_exit` is async-signal-safe
+                unsafe { _exit(130) }
+            }
+        }
+        // Safety: the handler only touches an atomic and calls `_exit`
+        unsafe {
+            signal(SIGINT, on_sigint);
+        }
+    }
+
+    fn interrupted(&self) -> bool {
+        INTERRUPTED.load(Ordering::SeqCst)
+    }
+
+    fn wake_on_interrupt(&self, waker: &Waker) {
+        if let Ok(mut current) = WAKER.lock() {
+            *current = Some(waker.clone());
+        }
+        WATCHER.call_once(|| {
+            thread::spawn(|| loop {
+                thread::sleep(WATCH_INTERVAL);
+                if INTERRUPTED.load(Ordering::SeqCst) {
+                    if let Some(waker) = WAKER.lock().ok().and_then(|mut w| w.take()) {
+                        waker.wake();
+                    }
+                }
+            });
+        });
+    }
+}
+
+/// Handling of interrupts set on the app
+#[derive(Clone)]
+pub(crate) struct Interrupt {
+    /// Action run when the action is interrupted, before the after actions,
+    /// interrupts being left to the app when not set
+    pub(crate) action: Option<Action>,
+    /// Status of interrupted runs
+    pub(crate) code: i32,
+    pub(crate) source: Arc<dyn SignalSource>,
+}
+
+impl Interrupt {
+    /// Start listening for interrupts of the action of the context
+    pub(crate) fn install(context: &Context) {
+        if let Some(interrupt) = context.interrupt() {
+            interrupt.source.install();
+        }
+    }
+
+    /// Run the interrupt action once the action of the context returned
+    /// interrupted, then request the status of interrupted runs unless the
+    /// action exited
+    pub(crate) fn handle(context: &Context) {
+        if !context.interrupted() {
+            return;
+        }
+        if let Some(interrupt) = context.interrupt() {
+            if let Some(action) = &interrupt.action {
+                action.run_to_exit(context);
+            }
+            context.set_exit_code(interrupt.code);
+        }
+    }
+}
+
+impl Default for Interrupt {
+    fn default() -> Self {
+        Self {
+            action: None,
+            code: 130,
+            source: Arc::new(CtrlC),
+        }
+    }
+}
+
+impl fmt::Debug for Interrupt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Interrupt")
+            .field("code", &self.code)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SignalSource;
+    use crate::{App, Command, Context};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// Source interrupting once checked a number of times
+    struct After(AtomicUsize);
+
+    impl SignalSource for After {
+        fn interrupted(&self) -> bool {
+            let left = self.0.load(Ordering::SeqCst);
+            self.0.store(left.saturating_sub(1), Ordering::SeqCst);
+            left == 0
+        }
+    }
+
+    #[test]
+    fn on_interrupt_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let push = |text: &'static str| {
+            let log = log.clone();
+            move |c: &Context| {
+                log.lock()
+                    .unwrap()
+                    .push(format!("{} {:?}", text, c.exit_code()))
+            }
+        };
+        let watch = {
+            let log = log.clone();
+            move |c: &Context| {
+                let mut ticks = 0;
+                while !c.interrupted() {
+                    ticks += 1;
+                }
+                log.lock().unwrap().push(format!("watched {}", ticks));
+            }
+        };
+        let app = |checks: usize| {
+            App::new("cli")
+                .command(
+                    Command::new("watch")
+                        .action(watch.clone())
+                        .after_action(push("command after")),
+                )
+                .command(Command::new("exit").action(|c: &Context| c.exit(3)))
+                .on_interrupt(push("interrupted"))
+                .after_action(push("app after"))
+                .signal_source(After(AtomicUsize::new(checks)))
+        };
+        let args = |command: &str| vec!["cli".to_string(), command.to_string()];
+
+        // Checked 3 times by the action, then once more by the app
+        assert_eq!(app(3).try_run(args("watch")), Ok(130));
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "watched 3",
+                "interrupted None",
+                "command after Some(130)",
+                "app after Some(130)"
+            ]
+        );
+
+        log.lock().unwrap().clear();
+        assert_eq!(app(0).interrupt_exit_code(2).try_run(args("watch")), Ok(2));
+        assert_eq!(log.lock().unwrap()[0], "watched 0");
+
+        // Not interrupted once the action returns
+        log.lock().unwrap().clear();
+        assert_eq!(app(1).try_run(args("exit")), Ok(3));
+        assert_eq!(*log.lock().unwrap(), vec!["app after Some(3)"]);
+
+        // Interrupts are ignored without an interrupt action
+        let app = App::new("cli")
+            .action(|c: &Context| assert!(!c.interrupted()))
+            .signal_source(After(AtomicUsize::new(0)));
+        assert_eq!(app.try_run(args("watch")), Ok(0));
+    }
+}
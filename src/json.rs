@@ -0,0 +1,199 @@
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use crate::{App, Command, Flag, FlagType};
+
+/// Version of the JSON dump schema, bumped on incompatible changes
+pub(crate) const SCHEMA_VERSION: u32 = 1;
+
+/// JSON value written by the dump
+enum Json {
+    Null,
+    Bool(bool),
+    Number(u32),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    fn string(value: &Option<String>) -> Self {
+        match value {
+            Some(value) => Json::String(value.clone()),
+            None => Json::Null,
+        }
+    }
+
+    fn strings(values: &Option<Vec<String>>) -> Self {
+        Json::Array(
+            values
+                .iter()
+                .flatten()
+                .map(|v| Json::String(v.clone()))
+                .collect(),
+        )
+    }
+
+    fn write<W: Write + ?Sized>(&self, indent: usize, w: &mut W) -> io::Result<()> {
+        match self {
+            Json::Null => write!(w, "null"),
+            Json::Bool(b) => write!(w, "{}", b),
+            Json::Number(n) => write!(w, "{}", n),
+            Json::String(s) => write!(w, "{}", escape(s)),
+            Json::Array(values) if values.is_empty() => write!(w, "[]"),
+            Json::Array(values) => {
+                writeln!(w, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    write!(w, "{}", "  ".repeat(indent + 1))?;
+                    value.write(indent + 1, w)?;
+                    writeln!(w, "{}", if i + 1 < values.len() { "," } else { "" })?;
+                }
+                write!(w, "{}]", "  ".repeat(indent))
+            }
+            Json::Object(fields) => {
+                writeln!(w, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    write!(w, "{}\"{}\": ", "  ".repeat(indent + 1), key)?;
+                    value.write(indent + 1, w)?;
+                    writeln!(w, "{}", if i + 1 < fields.len() { "," } else { "" })?;
+                }
+                write!(w, "{}}}", "  ".repeat(indent))
+            }
+        }
+    }
+}
+
+/// Write the JSON dump of the app
+pub(crate) fn write_app<W: Write + ?Sized>(app: &App, w: &mut W) -> io::Result<()> {
+    let path = vec![app.name.clone()];
+    let json = Json::Object(vec![
+        ("schema_version", Json::Number(SCHEMA_VERSION)),
+        ("name", Json::String(app.name.clone())),
+        ("version", Json::string(&app.version)),
+        ("author", Json::string(&app.author)),
+        ("description", Json::string(&app.description)),
+        ("usage", Json::string(&app.usage)),
+        ("flags", flags(&app.flags, &path, app)),
+        ("commands", commands(&path, &app.commands, app)),
+    ]);
+
+    json.write(0, w)?;
+    writeln!(w)
+}
+
+fn commands(parent_path: &[String], commands: &Option<Vec<Arc<Command>>>, app: &App) -> Json {
+    Json::Array(
+        commands
+            .iter()
+            .flatten()
+            .map(|c| command(parent_path, c, app))
+            .collect(),
+    )
+}
+
+fn command(parent_path: &[String], command: &Command, app: &App) -> Json {
+    let path = command.path(parent_path);
+    Json::Object(vec![
+        ("name", Json::String(command.name.clone())),
+        (
+            "path",
+            Json::Array(path.iter().map(|p| Json::String(p.clone())).collect()),
+        ),
+        ("aliases", Json::strings(&command.alias)),
+        ("hidden_aliases", Json::strings(&command.hidden_alias)),
+        ("category", Json::string(&command.category)),
+        ("hidden", Json::Bool(command.hidden)),
+        ("description", Json::string(&command.description)),
+        ("usage", Json::string(&command.usage)),
+        ("flags", flags(&command.flags, &path, app)),
+        ("commands", commands(&path, &command.commands, app)),
+    ])
+}
+
+fn flags(flags: &Option<Vec<Flag>>, path: &[String], app: &App) -> Json {
+    Json::Array(flags.iter().flatten().map(|f| flag(f, path, app)).collect())
+}
+
+fn flag(flag: &Flag, path: &[String], app: &App) -> Json {
+    Json::Object(vec![
+        ("name", Json::String(flag.name.clone())),
+        ("aliases", Json::strings(&flag.alias)),
+        ("hidden_aliases", Json::strings(&flag.hidden_alias)),
+        ("type", Json::String(flag.flag_type.name().to_string())),
+        ("hidden", Json::Bool(flag.hidden)),
+        ("description", Json::string(&flag.description)),
+        ("default", Json::string(&flag.default_value)),
+        ("choices", Json::strings(&flag.choices)),
+        ("sources", sources(flag, path, app)),
+    ])
+}
+
+/// Where values of the flag at the command path can come from, in order
+/// of precedence
+fn sources(flag: &Flag, path: &[String], app: &App) -> Json {
+    let source = |kind: &str| vec![("kind", Json::String(kind.to_string()))];
+    let commands = &path[1..];
+    let mut sources = vec![source("command_line")];
+    let env_prefix = app.env_prefix.as_ref().map(String::as_str);
+    for name in flag.env_names(env_prefix, commands) {
+        let mut environment = source("environment");
+        environment.push(("var", Json::String(name)));
+        sources.push(environment);
+    }
+    let secret = flag.flag_type == FlagType::Secret;
+    if (flag.prompt || (flag.required && secret)) && flag.default_value.is_none() {
+        sources.push(source("prompt"));
+    }
+    #[cfg(feature = "config")]
+    {
+        if let Some(file) = &app.config_file {
+            let keys = (0..=commands.len())
+                .rev()
+                .map(|n| {
+                    let mut key = commands[..n].to_vec();
+                    key.push(flag.name.clone());
+                    Json::String(key.join("."))
+                })
+                .collect();
+            let mut config = source("config_file");
+            config.push(("file", Json::String(file.display().to_string())));
+            config.push(("keys", Json::Array(keys)));
+            sources.push(config);
+        }
+    }
+    if flag.default_value.is_some() {
+        sources.push(source("default"));
+    }
+    Json::Array(sources.into_iter().map(Json::Object).collect())
+}
+
+/// Quote a string as a JSON string literal
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape;
+
+    #[test]
+    fn escape_test() {
+        assert_eq!(escape("plain"), "\"plain\"");
+        assert_eq!(escape("a \"b\"\\c"), "\"a \\\"b\\\"\\\\c\"");
+        assert_eq!(escape("line\nnext\u{1}"), "\"line\\nnext\\u0001\"");
+    }
+}
@@ -1,14 +1,71 @@
 mod action;
 mod app;
+mod app_settings;
+mod arg;
+mod check;
 mod command;
+mod compiled;
+mod completion;
+mod config;
 mod context;
+mod datetime;
+#[cfg(feature = "dotenv")]
+mod dotenv;
 pub mod error;
+#[cfg(feature = "async")]
+mod executor;
 mod flag;
+mod flag_group;
+mod flag_set;
+mod from_context;
 mod help;
+#[cfg(feature = "interrupt")]
+mod interrupt;
+mod json;
+mod markdown;
+mod messages;
+mod outcome;
+mod pager;
+mod parser;
+mod prompt;
+mod secret;
+mod split;
+mod streams;
+mod suggestion;
+mod terminal;
+pub mod theme;
+mod tree;
+mod unknown;
+mod validator;
 
 pub use action::Action;
 pub use app::App;
+pub use app_settings::AppSettings;
+pub use arg::Arg;
+pub use check::{Problem, ProblemKind, Severity};
 pub use command::Command;
-pub use context::Context;
+pub use compiled::CompiledApp;
+pub use completion::{Completer, Shell, ValueHint};
+pub use context::{Context, ValueSource};
+pub use datetime::DateTime;
+pub use error::Error;
 pub use flag::{Flag, FlagType, FlagValue};
+pub use flag_group::FlagGroup;
+pub use flag_set::FlagSet;
+pub use from_context::{CommandArgs, FromContext};
 use help::Help;
+pub use help::{HelpFlag, VersionFlag};
+#[cfg(feature = "interrupt")]
+pub use interrupt::{CtrlC, SignalSource};
+pub use messages::{DefaultMessages, Messages};
+pub use outcome::Outcome;
+pub use pager::Pager;
+pub use prompt::Prompt;
+#[cfg(feature = "derive")]
+pub use seahorse_derive::Command;
+pub use secret::Secret;
+pub use split::{split, SplitError};
+pub use terminal::Terminal;
+pub use theme::Theme;
+pub use unknown::{UnknownCommand, UnknownCommandHandler, UnknownFlag, UnknownFlagHandler};
+pub use validator::Validator;
@@ -0,0 +1,239 @@
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use crate::{help, App, Arg, Command, Flag};
+
+/// Write the markdown document of the app
+pub(crate) fn write_app<W: Write + ?Sized>(app: &App, w: &mut W) -> io::Result<()> {
+    writeln!(w, "# {}", app.name)?;
+    write_text(&app.before_help, w)?;
+
+    if let Some(description) = &app.description {
+        writeln!(w, "\n{}", description)?;
+    }
+
+    if let Some(usage) = &app.usage {
+        write_usage(usage, w)?;
+    }
+
+    if let Some(version) = &app.version {
+        writeln!(w, "\nVersion: {}", version)?;
+    }
+
+    write_flags(&app.flags, app.sort_help_alphabetically, w)?;
+    write_text(&app.after_help, w)?;
+    write_commands(
+        app.display_name(),
+        &app.commands,
+        app.sort_help_alphabetically,
+        2,
+        w,
+    )
+}
+
+/// Write visible commands, uncategorized ones first and then
+/// each category as a sub-heading in order of first appearance
+fn write_commands<W: Write + ?Sized>(
+    parent_path: &str,
+    commands: &Option<Vec<Arc<Command>>>,
+    alphabetical: bool,
+    level: usize,
+    w: &mut W,
+) -> io::Result<()> {
+    let commands = help::visible(commands, alphabetical);
+
+    for command in commands.iter().filter(|c| c.category.is_none()) {
+        write_command(parent_path, command, level, w)?;
+    }
+
+    let mut categories: Vec<&str> = Vec::new();
+    for command in &commands {
+        if let Some(category) = &command.category {
+            if !categories.contains(&category.as_str()) {
+                categories.push(category);
+            }
+        }
+    }
+
+    for category in categories {
+        writeln!(w, "\n{} {}", heading(level), category)?;
+        for command in commands
+            .iter()
+            .filter(|c| c.category.as_ref().map(String::as_str) == Some(category))
+        {
+            write_command(parent_path, command, level + 1, w)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_command<W: Write + ?Sized>(
+    parent_path: &str,
+    command: &Command,
+    level: usize,
+    w: &mut W,
+) -> io::Result<()> {
+    let path = format!("{} {}", parent_path, command.name);
+    writeln!(w, "\n{} {}", heading(level), path)?;
+    write_text(&command.before_help, w)?;
+
+    if let Some(alias) = &command.alias {
+        let alias = alias
+            .iter()
+            .map(|a| format!("`{}`", a))
+            .collect::<Vec<String>>()
+            .join(", ");
+        writeln!(w, "\nAliases: {}", alias)?;
+    }
+
+    if let Some(description) = &command.description {
+        writeln!(w, "\n{}", description)?;
+    }
+
+    if let Some(usage) = &command.usage {
+        write_usage(usage, w)?;
+    }
+
+    if let Some(version) = &command.version {
+        writeln!(w, "\nVersion: {}", version)?;
+    }
+
+    write_args(&command.args, w)?;
+    write_flags(&command.flags, command.sort_help_alphabetically, w)?;
+    write_examples(&command.examples, w)?;
+    write_text(&command.after_help, w)?;
+    write_commands(
+        &path,
+        &command.commands,
+        command.sort_help_alphabetically,
+        level + 1,
+        w,
+    )
+}
+
+fn write_text<W: Write + ?Sized>(text: &Option<String>, w: &mut W) -> io::Result<()> {
+    match text {
+        Some(text) => writeln!(w, "\n{}", text),
+        None => Ok(()),
+    }
+}
+
+fn write_usage<W: Write + ?Sized>(usage: &str, w: &mut W) -> io::Result<()> {
+    writeln!(w, "\n```\n{}\n```", usage)
+}
+
+fn write_args<W: Write + ?Sized>(args: &Option<Vec<Arg>>, w: &mut W) -> io::Result<()> {
+    let args = match args {
+        Some(args) if !args.is_empty() => args,
+        _ => return Ok(()),
+    };
+
+    writeln!(w, "\n| Argument | Type | Default | Description |")?;
+    writeln!(w, "| --- | --- | --- | --- |")?;
+
+    for arg in args {
+        let default_value = match &arg.default_value {
+            Some(default_value) => format!("`{}`", default_value),
+            None => String::new(),
+        };
+        let description = match &arg.description {
+            Some(description) => escape(description),
+            None => String::new(),
+        };
+
+        writeln!(
+            w,
+            "| `{}` | {} | {} | {} |",
+            arg.name_text(),
+            arg.arg_type.name(),
+            default_value,
+            description
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Write the table of the flags, followed by a table per help section of
+/// the flags in order of first appearance
+fn write_flags<W: Write + ?Sized>(
+    flags: &Option<Vec<Flag>>,
+    alphabetical: bool,
+    w: &mut W,
+) -> io::Result<()> {
+    let flags = help::visible(flags, alphabetical);
+
+    let mut sections: Vec<Option<&str>> = Vec::new();
+    for flag in &flags {
+        let section = flag.help_section.as_ref().map(String::as_str);
+        if !sections.contains(&section) {
+            sections.push(section);
+        }
+    }
+    sections.sort_by_key(Option::is_some);
+
+    for section in sections {
+        if let Some(section) = section {
+            writeln!(w, "\n{}:", section)?;
+        }
+        writeln!(w, "\n| Flag | Aliases | Type | Default | Description |")?;
+        writeln!(w, "| --- | --- | --- | --- | --- |")?;
+
+        for flag in flags
+            .iter()
+            .filter(|f| f.help_section.as_ref().map(String::as_str) == section)
+        {
+            let alias = match &flag.alias {
+                Some(alias) => alias
+                    .iter()
+                    .map(|a| format!("`-{}`", a))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                None => String::new(),
+            };
+            let default_value = match &flag.default_value {
+                Some(default_value) => format!("`{}`", default_value),
+                None => String::new(),
+            };
+            let description = match &flag.description {
+                Some(description) => escape(description),
+                None => String::new(),
+            };
+
+            writeln!(
+                w,
+                "| `{}` | {} | {} | {} | {} |",
+                flag.name_text(),
+                alias,
+                flag.flag_type.name(),
+                default_value,
+                description
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_examples<W: Write + ?Sized>(examples: &[(String, String)], w: &mut W) -> io::Result<()> {
+    if examples.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(w, "\nExamples:")?;
+    for (description, command_line) in examples {
+        writeln!(w, "\n{}\n\n```\n{}\n```", description, command_line)?;
+    }
+
+    Ok(())
+}
+
+fn heading(level: usize) -> String {
+    "#".repeat(level.min(6))
+}
+
+/// Escape characters that break a table cell
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
@@ -0,0 +1,455 @@
+/// Catalog of the messages the app writes itself, set with `App::messages`
+///
+/// Every method has the English message as default, so a catalog overrides
+/// the messages it translates and keeps the others. Names of flags,
+/// commands and files, values and counts are passed as arguments for each
+/// language to place them. Headings are written without their colon.
+///
+/// Example
+///
+/// ```
+/// use seahorse::{App, Messages};
+///
+/// struct German;
+///
+/// impl Messages for German {
+///     fn usage_heading(&self) -> String {
+///         "Aufruf".to_string()
+///     }
+///
+///     fn missing_flag(&self, flag: &str) -> String {
+///         format!("Die Option \"{}\" fehlt", flag)
+///     }
+/// }
+///
+/// let app = App::new("cli").messages(German);
+/// ```
+pub trait Messages: Send + Sync {
+    /// Heading of the app name in help
+    fn name_heading(&self) -> String {
+        "Name".to_string()
+    }
+
+    /// Heading of the app author in help
+    fn author_heading(&self) -> String {
+        "Author".to_string()
+    }
+
+    /// Heading of the description in help
+    fn description_heading(&self) -> String {
+        "Description".to_string()
+    }
+
+    /// Heading of the usage line in help
+    fn usage_heading(&self) -> String {
+        "Usage".to_string()
+    }
+
+    /// Heading of the aliases of a command in its help
+    fn aliases_heading(&self) -> String {
+        "Aliases".to_string()
+    }
+
+    /// Heading of the positional arguments in help
+    fn args_heading(&self) -> String {
+        "Args".to_string()
+    }
+
+    /// Heading of the flags without a help section in help
+    fn flags_heading(&self) -> String {
+        "Flags".to_string()
+    }
+
+    /// Heading of the global flags of the app in the help of its commands
+    fn global_flags_heading(&self) -> String {
+        "Global Flags".to_string()
+    }
+
+    /// Heading of the commands without a category in help
+    fn commands_heading(&self) -> String {
+        "Commands".to_string()
+    }
+
+    /// Heading of the examples in help
+    fn examples_heading(&self) -> String {
+        "Examples".to_string()
+    }
+
+    /// Heading of the version in help
+    fn version_heading(&self) -> String {
+        "Version".to_string()
+    }
+
+    /// Description of the built-in help flag
+    fn help_flag_description(&self) -> String {
+        "Show help".to_string()
+    }
+
+    /// Description of the built-in version flag
+    fn version_flag_description(&self) -> String {
+        "Show version".to_string()
+    }
+
+    /// Environment variables a flag is read from, after its description in
+    /// the long help
+    fn env_hint(&self, vars: &[String]) -> String {
+        format!("[env: {}]", vars.join(", "))
+    }
+
+    /// Aliases of a command after its name in the commands of help
+    fn aliases_hint(&self, aliases: &[String]) -> String {
+        match aliases {
+            [alias] => format!("(alias: {})", alias),
+            _ => format!("(aliases: {})", aliases.join(", ")),
+        }
+    }
+
+    /// Line after the commands of the app help, telling how to get help of
+    /// a command
+    fn command_help_hint(&self, bin: &str) -> String {
+        format!("See '{} help <command>' for more information.", bin)
+    }
+
+    /// Usage line after an error
+    fn usage_hint(&self, usage: &str) -> String {
+        format!("Usage: {}", usage)
+    }
+
+    /// Line after an error telling the command line showing help
+    fn help_hint(&self, command_line: &str) -> String {
+        format!("Try '{}' for more information.", command_line)
+    }
+
+    /// Argument causing an error, counting from 1, on command lines too long
+    /// to underline it
+    fn at_argument(&self, position: usize, arg: &str) -> String {
+        format!("at argument {} \"{}\"", position, arg)
+    }
+
+    /// Question asking for the value of a missing flag
+    fn prompt_question(&self, flag: &str) -> String {
+        format!("{}: ", flag)
+    }
+
+    /// Command given to `help` matching no command of the path
+    fn unknown_command(&self, command: &str, path: &str) -> String {
+        format!("Unknown command \"{}\" in \"{}\".", command, path)
+    }
+
+    /// Abbreviated command matching several commands
+    fn ambiguous_command(&self, command: &str, candidates: &[String]) -> String {
+        format!(
+            "Command \"{}\" is ambiguous, it could be {}",
+            command,
+            candidates.join(", ")
+        )
+    }
+
+    /// Flag matching no flag
+    fn unknown_flag(&self, flag: &str) -> String {
+        format!("Unknown flag \"{}\".", flag)
+    }
+
+    /// Long flag name written with a single dash, like `-release`
+    fn single_dash_flag(&self, flag: &str, long: &str) -> String {
+        format!(
+            "Unknown flag: found \"{}\"; did you mean \"{}\"?",
+            flag, long
+        )
+    }
+
+    /// Suggestion after an unknown command or flag
+    fn did_you_mean(&self, suggestion: &str) -> String {
+        format!("Did you mean \"{}\"?", suggestion)
+    }
+
+    /// Suggestion of a command alias after an unknown command
+    fn did_you_mean_alias(&self, alias: &str, command: &str) -> String {
+        format!("Did you mean \"{}\" (alias of \"{}\")?", alias, command)
+    }
+
+    /// Required flag not given
+    fn missing_flag(&self, flag: &str) -> String {
+        format!("Missing required flag \"{}\"", flag)
+    }
+
+    /// Answer to the question asking for a flag that cannot be read
+    fn prompt_failed(&self, flag: &str, error: &str) -> String {
+        format!("Failed to read flag \"{}\": {}", flag, error)
+    }
+
+    /// File given as `@path` to a flag that cannot be read
+    fn flag_file_failed(&self, path: &str, flag: &str, error: &str) -> String {
+        format!(
+            "Failed to read file \"{}\" for flag \"{}\": {}",
+            path, flag, error
+        )
+    }
+
+    /// Flag not allowing values that look like flags followed by one,
+    /// `assignment` writing them together
+    fn value_looks_like_flag(&self, flag: &str, value: &str, assignment: &str) -> String {
+        format!(
+            "Flag \"{}\" takes a value but \"{}\" looks like a flag, write {}",
+            flag, value, assignment
+        )
+    }
+
+    /// Flag occurrence missing some of the values the flag takes
+    fn wrong_number_of_values(&self, flag: &str, expected: usize, given: usize) -> String {
+        format!(
+            "Flag \"{}\" takes {} values but {} given",
+            flag, expected, given
+        )
+    }
+
+    /// Flag taking a value given as the last argument, without one
+    fn missing_flag_value(&self, flag: &str) -> String {
+        format!("Missing value for flag \"{}\"", flag)
+    }
+
+    /// Value given to a bool flag
+    fn flag_takes_no_value(&self, flag: &str) -> String {
+        format!("Flag \"{}\" takes no value", flag)
+    }
+
+    /// Empty value of a flag whose type rejects it
+    fn empty_flag_value(&self, flag: &str, expected: &str) -> String {
+        format!("Empty value for flag \"{}\", expected {}", flag, expected)
+    }
+
+    /// Value that is not of the flag type, `examples` showing accepted forms
+    /// for types with several
+    fn invalid_flag_value(
+        &self,
+        value: &str,
+        flag: &str,
+        expected: &str,
+        examples: &[String],
+    ) -> String {
+        let mut message = format!(
+            "Invalid value \"{}\" for flag \"{}\", expected {}",
+            value, flag, expected
+        );
+        if !examples.is_empty() {
+            message += &format!(" like {}", or_list(examples));
+        }
+        message
+    }
+
+    /// Value of a regex flag that is not a valid regex
+    fn invalid_regex(&self, value: &str, flag: &str, error: &str) -> String {
+        format!(
+            "Invalid value \"{}\" for flag \"{}\", expected regex: {}",
+            value, flag, error
+        )
+    }
+
+    /// URL of a scheme the url flag does not allow
+    fn scheme_not_allowed(&self, scheme: &str, flag: &str, allowed: &[String]) -> String {
+        format!(
+            "Scheme \"{}\" of flag \"{}\" is not allowed, expected {}",
+            scheme,
+            flag,
+            or_list(allowed)
+        )
+    }
+
+    /// Flag value rejected by a validator or by the flag itself
+    fn rejected_flag_value(&self, value: &str, flag: &str, reason: &str) -> String {
+        format!(
+            "Invalid value \"{}\" for flag \"{}\": {}",
+            value, flag, reason
+        )
+    }
+
+    /// Value of the built-in `--color` flag that is not a color choice
+    fn invalid_color(&self, error: &str) -> String {
+        format!("Invalid value for flag \"color\": {}", error)
+    }
+
+    /// Glob pattern given to a flag that cannot be parsed
+    fn invalid_flag_pattern(&self, pattern: &str, flag: &str, error: &str) -> String {
+        format!(
+            "Invalid pattern \"{}\" for flag \"{}\": {}",
+            pattern, flag, error
+        )
+    }
+
+    /// Glob pattern given to an argument that cannot be parsed
+    fn invalid_arg_pattern(&self, pattern: &str, arg: &str, error: &str) -> String {
+        format!(
+            "Invalid pattern \"{}\" for argument \"{}\": {}",
+            pattern, arg, error
+        )
+    }
+
+    /// Glob pattern given to a flag matching no path
+    fn flag_pattern_unmatched(&self, pattern: &str, flag: &str) -> String {
+        format!(
+            "Pattern \"{}\" for flag \"{}\" matches no path",
+            pattern, flag
+        )
+    }
+
+    /// Glob pattern given to an argument matching no path
+    fn arg_pattern_unmatched(&self, pattern: &str, arg: &str) -> String {
+        format!(
+            "Pattern \"{}\" for argument \"{}\" matches no path",
+            pattern, arg
+        )
+    }
+
+    /// Fewer arguments than the command takes
+    fn too_few_args(&self, min: usize) -> String {
+        format!("Expected at least {} argument{}", min, plural(min))
+    }
+
+    /// More arguments than the command takes
+    fn too_many_args(&self, max: usize) -> String {
+        format!("Expected at most {} argument{}", max, plural(max))
+    }
+
+    /// Required arguments not given, named like `<FILE>`
+    fn missing_args(&self, args: &[String]) -> String {
+        format!(
+            "Missing required argument{}: {}",
+            plural(args.len()),
+            args.join(", ")
+        )
+    }
+
+    /// Argument value that is not of the argument type
+    fn invalid_arg_value(&self, value: &str, arg: &str, expected: &str) -> String {
+        format!(
+            "Invalid value \"{}\" for argument \"{}\", expected {}",
+            value, arg, expected
+        )
+    }
+
+    /// Argument value rejected by a validator
+    fn rejected_arg_value(&self, value: &str, arg: &str, reason: &str) -> String {
+        format!(
+            "Invalid value \"{}\" for argument \"{}\": {}",
+            value, arg, reason
+        )
+    }
+
+    /// Flags of an exclusive flag group given together
+    fn conflicting_flags(&self, flags: &[String], group: &str) -> String {
+        format!(
+            "Flags {} of flag group \"{}\" cannot be used together",
+            flags.join(", "),
+            group
+        )
+    }
+
+    /// Required flag group none of whose flags is given
+    fn missing_group(&self, group: &str, flags: &[String]) -> String {
+        format!(
+            "Flag group \"{}\" requires one of {}",
+            group,
+            flags.join(", ")
+        )
+    }
+
+    /// Argument files including each other deeper than allowed
+    fn argfile_too_deep(&self, path: &str, depth: usize) -> String {
+        format!(
+            "Argument file \"{}\" is nested deeper than {} levels",
+            path, depth
+        )
+    }
+
+    /// Argument file that cannot be read
+    fn argfile_failed(&self, path: &str, error: &str) -> String {
+        format!("Failed to read argument file \"{}\": {}", path, error)
+    }
+
+    /// Config file that cannot be read or parsed
+    fn config_failed(&self, file: &str, error: &str) -> String {
+        format!("{}: {}", file, error)
+    }
+
+    /// Value of a config file key that is not of the flag type
+    fn invalid_config_value(&self, file: &str, key: &str, expected: &str) -> String {
+        format!("{}: \"{}\" must be a valid {} value", file, key, expected)
+    }
+
+    /// Quote of a command line given as a string without its closing
+    /// quote, at a position counting characters from 1
+    fn unterminated_quote(&self, quote: char, position: usize) -> String {
+        format!(
+            "Unterminated {} quote starting at character {}",
+            if quote == '\'' { "single" } else { "double" },
+            position
+        )
+    }
+
+    /// Backslash at the end of a command line given as a string
+    fn trailing_backslash(&self) -> String {
+        "Backslash at the end of the line".to_string()
+    }
+
+    /// User alias whose expansion leads back to it
+    fn recursive_alias(&self, alias: &str) -> String {
+        format!("Alias \"{}\" expands to itself", alias)
+    }
+
+    /// Shell without a known directory for completion scripts, or home
+    /// directory not set
+    fn no_completion_dir(&self, shell: &str) -> String {
+        format!("No completion directory known for {}", shell)
+    }
+
+    /// Completion script installed before, kept unless forced
+    fn completion_exists(&self, path: &str) -> String {
+        format!("Completion script \"{}\" already exists", path)
+    }
+
+    /// Completion script that cannot be written
+    fn completion_failed(&self, path: &str, error: &str) -> String {
+        format!(
+            "Failed to install completion script \"{}\": {}",
+            path, error
+        )
+    }
+
+    /// Completion script installed by the `completions install` command
+    fn completion_installed(&self, path: &str) -> String {
+        format!("Installed completion script to \"{}\"", path)
+    }
+
+    /// Line after installing a zsh completion script, telling how zsh finds it
+    fn zsh_fpath_hint(&self) -> String {
+        "Add 'fpath=(~/.zfunc $fpath)' before 'compinit' in ~/.zshrc".to_string()
+    }
+
+    /// Help that cannot be written
+    fn help_failed(&self, error: &str) -> String {
+        format!("Failed to write help: {}", error)
+    }
+}
+
+/// Messages in English, used by apps without a catalog
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultMessages;
+
+impl Messages for DefaultMessages {}
+
+/// Words listed like `a, b or c`
+fn or_list(words: &[String]) -> String {
+    match words.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
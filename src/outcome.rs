@@ -0,0 +1,50 @@
+use crate::Error;
+
+/// What running a command line did, returned by `App::handle`
+// Made once per run, boxing the error would only add an allocation
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    /// Help was shown, of the command at the path or of the app
+    HelpShown {
+        /// Path of the command the help is of, starting with the app name
+        target: Vec<String>,
+    },
+    /// Version of the app or of a command was shown
+    VersionShown,
+    /// Action of a command or of the app ran
+    Ran {
+        /// Path of the command whose action ran, starting with the app name
+        command: Vec<String>,
+        /// Status requested with `Context::exit`, 0 when none is
+        exit_code: i32,
+    },
+    /// Command line handled without running an action, like the hidden
+    /// `__complete` command, `--help=json` or an unknown command handler
+    /// taking over
+    Done {
+        /// Status the run ends with
+        exit_code: i32,
+    },
+    /// Command line could not be run
+    Error(Error),
+}
+
+impl Outcome {
+    /// Status of the run, 1 for errors
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Outcome::HelpShown { .. } | Outcome::VersionShown => 0,
+            Outcome::Ran { exit_code, .. } | Outcome::Done { exit_code } => *exit_code,
+            Outcome::Error(_) => 1,
+        }
+    }
+
+    /// Status of the run, or its error
+    pub(crate) fn into_result(self) -> Result<i32, Error> {
+        match self {
+            Outcome::Error(e) => Err(e),
+            outcome => Ok(outcome.exit_code()),
+        }
+    }
+}
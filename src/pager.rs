@@ -0,0 +1,183 @@
+use std::env;
+use std::fmt;
+use std::io::{self, Write};
+use std::process::{self, Stdio};
+use std::sync::Arc;
+
+use crate::help;
+use crate::messages::Messages;
+use crate::streams::Streams;
+use crate::terminal::{self, Stream};
+use crate::theme::{ColorChoice, Theme};
+
+/// Pager showing help longer than the terminal
+///
+/// The default pager runs `$PAGER`, or `less -FRX` when it's not set, and
+/// pages only when stdout is a terminal and `NOPAGER` is not set. Help is
+/// printed as is when the pager cannot be started.
+///
+/// Example
+///
+/// ```
+/// use seahorse::Pager;
+///
+/// let pager = Pager::from(|text: &str| {
+///     println!("{}", text);
+///     Ok(())
+/// })
+/// .terminal(Some(40));
+/// assert!(pager.pages(&"line\n".repeat(50)));
+/// assert!(!pager.pages("short help"));
+/// ```
+#[derive(Clone)]
+pub struct Pager {
+    inner: Arc<PagerFn>,
+    terminal: Terminal,
+}
+
+type PagerFn = dyn Fn(&str) -> io::Result<()> + Send + Sync;
+
+/// Height of the terminal stdout is, looked up when help is shown unless known
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Terminal {
+    Detect,
+    Known(Option<usize>),
+}
+
+impl Pager {
+    /// Pager running `$PAGER`, or `less -FRX`
+    pub fn system() -> Self {
+        Self::from(spawn)
+    }
+
+    /// Set height of the terminal stdout is, `None` when stdout is not a
+    /// terminal, instead of looking it up
+    pub fn terminal(mut self, height: Option<usize>) -> Self {
+        self.terminal = Terminal::Known(height);
+        self
+    }
+
+    /// Whether the text is longer than the terminal and shown through the pager
+    pub fn pages(&self, text: &str) -> bool {
+        let height = match self.terminal {
+            Terminal::Known(height) => height,
+            Terminal::Detect if env::var_os("NOPAGER").is_some() => None,
+            Terminal::Detect => terminal::height(),
+        };
+        height
+            .map(|height| text.lines().count() > height)
+            .unwrap_or(false)
+    }
+
+    /// Show the text through the pager when it's long, printing it otherwise
+    /// or when the pager fails to start
+    pub(crate) fn show(&self, text: &str) {
+        if !(self.pages(text) && (self.inner)(text).is_ok()) {
+            print!("{}", help::render(text));
+        }
+    }
+}
+
+/// Print the help to the output stream, styled by the color choice and
+/// through the pager when set and the stream is stdout, with the headings of
+/// the catalog
+pub(crate) fn show(
+    pager: Option<&Pager>,
+    theme: Option<&Theme>,
+    color: ColorChoice,
+    messages: &dyn Messages,
+    streams: &Streams,
+    text: &str,
+) {
+    let painted;
+    let text = match streams.theme(theme, color, Stream::Stdout) {
+        Some(theme) => {
+            painted = theme.paint_help_in(text, messages);
+            &painted
+        }
+        None => text,
+    };
+    match pager {
+        Some(pager) if streams.out.is_none() => pager.show(text),
+        _ => streams.out(&help::render(text)),
+    }
+}
+
+impl Default for Pager {
+    fn default() -> Self {
+        Self::system()
+    }
+}
+
+impl fmt::Debug for Pager {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Pager")
+    }
+}
+
+impl<F> From<F> for Pager
+where
+    for<'a> F: Fn(&'a str) -> io::Result<()> + Send + Sync + 'static,
+{
+    fn from(value: F) -> Self {
+        Self {
+            inner: Arc::new(value),
+            terminal: Terminal::Detect,
+        }
+    }
+}
+
+/// Write the text to the standard input of `$PAGER`, failing when it's
+/// empty or cannot be started
+fn spawn(text: &str) -> io::Result<()> {
+    let command = env::var("PAGER").unwrap_or_else(|_| "less -FRX".to_string());
+    let mut words = command.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "PAGER is empty"))?;
+    let mut child = process::Command::new(program)
+        .args(words)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager closes its input when quit before the end
+        let _ = writeln!(stdin, "{}", text);
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pager;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn pages_test() {
+        let paged = Arc::new(Mutex::new(Vec::new()));
+        let log = paged.clone();
+        let pager = |height: Option<usize>| {
+            let log = log.clone();
+            Pager::from(move |text: &str| {
+                log.lock().unwrap().push(text.to_string());
+                Ok(())
+            })
+            .terminal(height)
+        };
+        let long = "line\n".repeat(30);
+
+        assert!(pager(Some(24)).pages(&long));
+        assert!(!pager(Some(30)).pages(&long));
+        assert!(!pager(None).pages(&long));
+
+        pager(Some(24)).show(&long);
+        pager(Some(24)).show("short");
+        pager(None).show(&long);
+        assert_eq!(*paged.lock().unwrap(), vec![long.clone()]);
+
+        let failing =
+            Pager::from(|_: &str| Err(io::Error::from(io::ErrorKind::NotFound))).terminal(Some(24));
+        failing.show(&long);
+    }
+}
@@ -0,0 +1,2271 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::datetime;
+use crate::error::{Error, ErrorKind};
+use crate::messages::Messages;
+use crate::suggestion::Candidate;
+#[cfg(feature = "glob")]
+use crate::Arg;
+use crate::{
+    help, suggestion, Command, DateTime, Flag, FlagType, Prompt, UnknownFlag, UnknownFlagHandler,
+    ValueSource,
+};
+
+/// Classification of a command line token
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Kind {
+    /// Occurrence of the flag at the index
+    Flag(usize),
+    /// Value of the flag at the index
+    Value(usize),
+    /// Argument that is not a flag
+    Positional,
+    /// `--`, ending flag parsing
+    Separator,
+    /// Argument after `--`
+    Trailing,
+    /// Occurrence or value of the flag at the index, left out because a flag
+    /// overriding it is given after it
+    Overridden(usize),
+}
+
+/// Classified command line token
+#[derive(Debug)]
+pub(crate) struct Token {
+    pub(crate) arg: String,
+    pub(crate) kind: Kind,
+    /// Index of the argument the token was read from, `None` for values
+    /// not given on the command line
+    pub(crate) index: Option<usize>,
+    /// Why the token is positional, shown when tracing parsing
+    pub(crate) reason: Reason,
+}
+
+/// Why a token got its kind, when the kind alone doesn't tell
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Reason {
+    /// Nothing more than the kind
+    Kind,
+    /// Positional argument naming a flag given before that is not repeatable
+    Repeated,
+    /// Positional argument looking like a flag that matches none
+    Unknown,
+    /// Argument after the first positional one of a command reading every
+    /// argument after it as positional
+    AfterPositional,
+}
+
+/// Where the values of a flag come from, shown when tracing parsing
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Source {
+    CommandLine,
+    /// Environment variable of the name
+    Env(String),
+    Prompt,
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Source::CommandLine
+    }
+}
+
+/// Occurrences of a flag on the command line
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Occurrence {
+    /// Number of occurrences, stacked aliases counting once per character
+    pub(crate) count: usize,
+    /// Positions of the value tokens of each matched occurrence
+    pub(crate) values: Vec<Vec<usize>>,
+    /// Position of the first token not taken as a value because it looks
+    /// like a flag, leaving an occurrence without its value
+    pub(crate) refused: Option<usize>,
+    /// Whether an occurrence taking a single value was the last argument or
+    /// followed by `--`
+    pub(crate) unfinished: bool,
+    pub(crate) source: Source,
+}
+
+/// Command line arguments parsed against flags in a single pass
+#[derive(Debug)]
+pub(crate) struct FlagMatches {
+    /// Tokens in command line order
+    pub(crate) tokens: Vec<Token>,
+    /// Occurrences of each flag, in flag order
+    pub(crate) occurrences: Vec<Occurrence>,
+    /// Whether arguments after the first positional one are all positional
+    stop_at_positional: bool,
+}
+
+impl FlagMatches {
+    /// Walk the arguments once, resolving flags by name and alias
+    ///
+    /// The first occurrence of a flag is matched and takes the next token as
+    /// its value unless it is a bool flag or the token looks like a flag, or
+    /// the next tokens up to another flag or `--` when it takes several values;
+    /// later occurrences are positional unless the flag is repeatable.
+    /// Everything after `--` is trailing.
+    ///
+    /// A flag written `--name=` with nothing after `=` takes an empty value,
+    /// bool flags included so that `check_values` can reject it, and one
+    /// written `--name=-x` takes `-x` whatever it looks like.
+    pub(crate) fn parse(args: Vec<String>, flags: &[Flag]) -> Self {
+        Self::parse_with(args, flags, false)
+    }
+
+    /// Walk the arguments like `parse`, reading every argument from the first
+    /// positional argument on as positional when `stop_at_positional` is set
+    pub(crate) fn parse_with(args: Vec<String>, flags: &[Flag], stop_at_positional: bool) -> Self {
+        Self::parse_indexed(args, flags, None, stop_at_positional)
+    }
+
+    /// Walk the arguments like `parse_with`, looking flags up in the index
+    /// of a compiled app when given
+    pub(crate) fn parse_indexed(
+        args: Vec<String>,
+        flags: &[Flag],
+        index: Option<&FlagIndex<String>>,
+        stop_at_positional: bool,
+    ) -> Self {
+        let table = FlagTable::with_index(flags, index);
+        let mut occurrences = vec![Occurrence::default(); flags.len()];
+        let mut tokens: Vec<Token> = Vec::with_capacity(args.len());
+        let mut args = args.into_iter().enumerate().peekable();
+
+        while let Some((position, mut arg)) = args.next() {
+            let position = Some(position);
+            if arg == "--" {
+                tokens.push(Token {
+                    arg,
+                    kind: Kind::Separator,
+                    index: position,
+                    reason: Reason::Kind,
+                });
+                tokens.extend(args.by_ref().map(|(position, arg)| Token {
+                    arg,
+                    kind: Kind::Trailing,
+                    index: Some(position),
+                    reason: Reason::Kind,
+                }));
+                break;
+            }
+
+            let empty_value = is_empty_assignment(&arg);
+            if empty_value {
+                arg.pop();
+            }
+            let mut assigned = None;
+            let found = match table.find(&arg) {
+                None => match assignment(&arg).filter(|_| !empty_value) {
+                    Some(eq) if table.find(&arg[..eq]).is_some() => {
+                        assigned = Some(arg.split_off(eq + 1));
+                        arg.pop();
+                        table.find(&arg)
+                    }
+                    _ => None,
+                },
+                found => found,
+            };
+            let (kind, reason) = match found {
+                Some((index, n)) if table.counted(index) && !empty_value && assigned.is_none() => {
+                    occurrences[index].count += n;
+                    (Kind::Flag(index), Reason::Kind)
+                }
+                Some((index, _)) if occurrences[index].count == 0 || flags[index].repeatable => {
+                    occurrences[index].count += 1;
+                    tokens.push(Token {
+                        arg,
+                        kind: Kind::Flag(index),
+                        index: position,
+                        reason: Reason::Kind,
+                    });
+                    if empty_value {
+                        occurrences[index].values.push(vec![tokens.len()]);
+                        tokens.push(Token {
+                            arg: String::new(),
+                            kind: Kind::Value(index),
+                            index: position,
+                            reason: Reason::Kind,
+                        });
+                        continue;
+                    }
+                    let mut values = Vec::new();
+                    if let Some(value) = assigned {
+                        values.push(tokens.len());
+                        tokens.push(Token {
+                            arg: value,
+                            kind: Kind::Value(index),
+                            index: position,
+                            reason: Reason::Kind,
+                        });
+                    }
+                    if flags[index].flag_type == FlagType::Bool {
+                        if !values.is_empty() {
+                            occurrences[index].values.push(values);
+                        }
+                        continue;
+                    }
+
+                    if let Some(terminator) = &flags[index].value_terminator {
+                        let limit = flags[index].number_of_values.unwrap_or(std::usize::MAX);
+                        loop {
+                            let position = match args.peek() {
+                                Some((_, next)) if next == terminator => {
+                                    args.next();
+                                    break;
+                                }
+                                _ if values.len() >= limit => break,
+                                Some((position, next)) if table.accepts(index, next) => *position,
+                                _ => break,
+                            };
+                            values.push(tokens.len());
+                            tokens.push(Token {
+                                arg: args.next().map(|(_, arg)| arg).unwrap_or_default(),
+                                kind: Kind::Value(index),
+                                index: Some(position),
+                                reason: Reason::Kind,
+                            });
+                        }
+                        occurrences[index].values.push(values);
+                        continue;
+                    }
+
+                    match flags[index].number_of_values {
+                        None if !values.is_empty() => {}
+                        None => match args.peek() {
+                            Some((_, next)) if table.accepts(index, next) => {
+                                let (position, value) = args.next().unwrap_or_default();
+                                values.push(tokens.len());
+                                tokens.push(Token {
+                                    arg: value,
+                                    kind: Kind::Value(index),
+                                    index: Some(position),
+                                    reason: Reason::Kind,
+                                });
+                            }
+                            Some((_, next)) if next != "--" => {
+                                let refused = &mut occurrences[index].refused;
+                                *refused = refused.or(Some(tokens.len()));
+                            }
+                            _ => occurrences[index].unfinished = true,
+                        },
+                        Some(n) => {
+                            while values.len() < n {
+                                let position = match args.peek() {
+                                    Some((position, next)) if table.accepts(index, next) => {
+                                        *position
+                                    }
+                                    _ => break,
+                                };
+                                values.push(tokens.len());
+                                tokens.push(Token {
+                                    arg: args.next().map(|(_, arg)| arg).unwrap_or_default(),
+                                    kind: Kind::Value(index),
+                                    index: Some(position),
+                                    reason: Reason::Kind,
+                                });
+                            }
+                        }
+                    }
+                    occurrences[index].values.push(values);
+                    continue;
+                }
+                _ => {
+                    let reason = if found.is_some() {
+                        Reason::Repeated
+                    } else if looks_like_flag(&arg) {
+                        Reason::Unknown
+                    } else {
+                        Reason::Kind
+                    };
+                    if empty_value {
+                        arg.push('=');
+                    }
+                    // Flags left as positional arguments keep their value as
+                    // the next one, as when given apart
+                    let value = match assigned {
+                        Some(value) => Some(value),
+                        None if reason == Reason::Unknown => match arg.find('=') {
+                            Some(eq) if !empty_value => {
+                                let value = arg.split_off(eq + 1);
+                                arg.pop();
+                                Some(value)
+                            }
+                            _ => None,
+                        },
+                        None => None,
+                    };
+                    tokens.push(Token {
+                        arg,
+                        kind: Kind::Positional,
+                        index: position,
+                        reason,
+                    });
+                    if let Some(value) = value {
+                        tokens.push(Token {
+                            arg: value,
+                            kind: Kind::Positional,
+                            index: position,
+                            reason: Reason::Kind,
+                        });
+                    }
+                    if stop_at_positional {
+                        let offset = position.map_or(0, |p| p + 1);
+                        let rest = args.by_ref().map(|(_, arg)| arg).collect();
+                        tokens.extend(Self::parse(rest, &[]).tokens.into_iter().map(|token| {
+                            Token {
+                                index: token.index.map(|p| p + offset),
+                                reason: match token.kind {
+                                    Kind::Positional => Reason::AfterPositional,
+                                    _ => token.reason,
+                                },
+                                ..token
+                            }
+                        }));
+                        break;
+                    }
+                    continue;
+                }
+            };
+            tokens.push(Token {
+                arg,
+                kind,
+                index: position,
+                reason,
+            });
+        }
+
+        Self {
+            tokens,
+            occurrences,
+            stop_at_positional,
+        }
+    }
+
+    /// Positions of the value tokens of each occurrence of the flag at the index
+    pub(crate) fn groups(&self, index: usize) -> &[Vec<usize>] {
+        &self.occurrences[index].values
+    }
+
+    /// Token at the position
+    pub(crate) fn arg(&self, position: usize) -> &str {
+        &self.tokens[position].arg
+    }
+
+    /// Arguments the tokens were read from, the flag and value of `--name=`
+    /// joined again
+    pub(crate) fn line(&self) -> Vec<String> {
+        let mut line: Vec<String> = Vec::with_capacity(self.tokens.len());
+        let mut last = None;
+        for token in &self.tokens {
+            match token.index {
+                None => continue,
+                Some(index) if last == Some(index) => {
+                    if let Some(arg) = line.last_mut() {
+                        arg.push('=');
+                        *arg += &token.arg;
+                    }
+                }
+                Some(_) => line.push(token.arg.clone()),
+            }
+            last = token.index;
+        }
+        line
+    }
+
+    /// Error located at the argument of the token at the position, and at
+    /// the flag when the token is a value of it
+    pub(crate) fn locate(&self, error: Error, position: usize) -> Error {
+        let token = &self.tokens[position];
+        let index = match token.index {
+            Some(index) => index,
+            None => return error,
+        };
+        let flag_index = match token.kind {
+            Kind::Value(flag) => self.tokens[..position]
+                .iter()
+                .rev()
+                .find(|token| token.kind == Kind::Flag(flag))
+                .and_then(|token| token.index)
+                .filter(|&flag_index| flag_index != index),
+            _ => None,
+        };
+        error.at(self.line(), index, flag_index)
+    }
+
+    /// Let the handler resolve positional arguments looking like flags, in
+    /// order, parsing the arguments again after a flag is replaced
+    ///
+    /// Flags the handler rejects are reported with the closest known flags.
+    pub(crate) fn resolve_unknown(
+        mut self,
+        flags: &[Flag],
+        handler: &UnknownFlagHandler,
+        path: &[String],
+    ) -> Result<Self, Error> {
+        let mut position = 0;
+        while let Some(offset) = self.tokens[position..]
+            .iter()
+            .position(|token| token.kind == Kind::Positional && looks_like_flag(&token.arg))
+        {
+            position += offset;
+            let token = &self.tokens[position].arg;
+            match handler.handle(token, path) {
+                UnknownFlag::Ignore => {}
+                UnknownFlag::Replace(name) => match flags.iter().find(|f| f.name == name) {
+                    Some(flag) => {
+                        self.tokens[position].arg = flag.name_text();
+                        let args = self.tokens.into_iter().map(|token| token.arg).collect();
+                        self = Self::parse_with(args, flags, self.stop_at_positional);
+                    }
+                    None => return Err(self.locate(unknown_flag_error(token, flags), position)),
+                },
+                UnknownFlag::Error => {
+                    return Err(self.locate(unknown_flag_error(token, flags), position))
+                }
+            }
+            position += 1;
+        }
+
+        Ok(self)
+    }
+
+    /// Read missing flags from the first of their environment variables set,
+    /// named by `env_names`, then ask for the values
+    /// of missing prompting flags when the prompt is interactive, failing on the
+    /// first required or prompting flag left missing
+    ///
+    /// Missing required secret flags are prompting flags, asked without echo.
+    pub(crate) fn fill_missing(
+        &mut self,
+        flags: &[Flag],
+        prompt: &Prompt,
+        messages: &dyn Messages,
+        env_names: impl Fn(&Flag) -> Vec<String>,
+    ) -> Result<(), Error> {
+        for (index, flag) in flags.iter().enumerate() {
+            if self.occurrences[index].count > 0 {
+                continue;
+            }
+            let set = env_names(flag)
+                .into_iter()
+                .find_map(|name| env::var(&name).ok().map(|value| (name, value)));
+            if let Some((name, value)) = set {
+                self.supply(index, flag, value, Source::Env(name));
+                continue;
+            }
+            if !(flag.required || flag.prompt) || flag.default_value.is_some() {
+                continue;
+            }
+
+            let secret = flag.flag_type == FlagType::Secret;
+            if !((flag.prompt || secret) && prompt.is_interactive()) {
+                let name = flag.name.clone();
+                return Err(
+                    Error::new(ErrorKind::MissingFlag, move |m| m.missing_flag(&name))
+                        .token(flag.name_text())
+                        .flag(flag.name.as_str()),
+                );
+            }
+
+            let question = messages.prompt_question(&flag.name);
+            let answer = if secret {
+                prompt.ask_secret(&question)
+            } else {
+                prompt.ask(&question)
+            };
+            let answer = answer.map_err(|e| {
+                let (name, reason) = (flag.name.clone(), e.to_string());
+                Error::new(ErrorKind::Io, move |m| m.prompt_failed(&name, &reason))
+                    .token(flag.name_text())
+                    .flag(flag.name.as_str())
+                    .cause(e)
+            })?;
+            self.supply(index, flag, answer, Source::Prompt);
+        }
+
+        Ok(())
+    }
+
+    /// Leave out the flags overridden by a flag given after them, pairs of
+    /// flags overriding each other keeping the one given last
+    ///
+    /// Flags not given on the command line come before every flag that is,
+    /// two such flags being both kept.
+    pub(crate) fn apply_overrides(&mut self, flags: &[Flag]) {
+        let last = |matches: &Self, index: usize| {
+            matches
+                .tokens
+                .iter()
+                .rposition(|token| token.kind == Kind::Flag(index) && token.index.is_some())
+        };
+        for (a, flag) in flags.iter().enumerate() {
+            for (b, other) in flags.iter().enumerate().skip(a + 1) {
+                let overriding =
+                    flag.overrides.contains(&other.name) || other.overrides.contains(&flag.name);
+                if !overriding || self.occurrences[a].count == 0 || self.occurrences[b].count == 0 {
+                    continue;
+                }
+                let (last_a, last_b) = (last(self, a), last(self, b));
+                if last_a.is_none() && last_b.is_none() {
+                    continue;
+                }
+                let earlier = if last_a < last_b { a } else { b };
+                self.occurrences[earlier] = Occurrence::default();
+                for token in &mut self.tokens {
+                    if token.kind == Kind::Flag(earlier) || token.kind == Kind::Value(earlier) {
+                        token.kind = Kind::Overridden(earlier);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Add the value of the flag at the index missing from the command line,
+    /// split at whitespace when the flag takes several values, bool flags
+    /// being set whatever the value
+    fn supply(&mut self, index: usize, flag: &Flag, value: String, source: Source) {
+        self.occurrences[index].count = 1;
+        self.occurrences[index].source = source;
+        if flag.flag_type == FlagType::Bool {
+            return;
+        }
+        let values: Vec<String> = if flag.takes_values() {
+            value.split_whitespace().map(String::from).collect()
+        } else {
+            vec![value]
+        };
+        let positions = (self.tokens.len()..self.tokens.len() + values.len()).collect();
+        self.tokens.extend(values.into_iter().map(|arg| Token {
+            arg,
+            kind: Kind::Value(index),
+            index: None,
+            reason: Reason::Kind,
+        }));
+        self.occurrences[index].values.push(positions);
+    }
+
+    /// Replace `@path` values of flags reading values from files by the file contents,
+    /// failing on the first file that cannot be read
+    pub(crate) fn read_files(&mut self, flags: &[Flag]) -> Result<(), Error> {
+        for position in 0..self.tokens.len() {
+            let token = &self.tokens[position];
+            let flag = match token.kind {
+                Kind::Value(index) if flags[index].from_file => &flags[index],
+                _ => continue,
+            };
+            if token.arg.starts_with("@@") {
+                self.tokens[position].arg.remove(0);
+                continue;
+            }
+            if !token.arg.starts_with('@') {
+                continue;
+            }
+
+            let path = &token.arg[1..];
+            let mut contents = fs::read_to_string(path).map_err(|e| {
+                let (file, name, reason) = (path.to_string(), flag.name.clone(), e.to_string());
+                let error = Error::new(ErrorKind::Io, move |m| {
+                    m.flag_file_failed(&file, &name, &reason)
+                })
+                .token(path)
+                .flag(flag.name.as_str())
+                .cause(e);
+                self.locate(error, position)
+            })?;
+            if contents.ends_with('\n') {
+                contents.pop();
+                if contents.ends_with('\r') {
+                    contents.pop();
+                }
+            }
+            self.tokens[position].arg = contents;
+        }
+
+        Ok(())
+    }
+
+    /// Trim and lowercase the values of string flags asking for it, and
+    /// rewrite the values of number flags accepting digit separators or a
+    /// decimal comma in the strict form when they are well formed
+    pub(crate) fn normalize_values(&mut self, flags: &[Flag]) {
+        for token in &mut self.tokens {
+            let flag = match token.kind {
+                Kind::Value(index) => &flags[index],
+                _ => continue,
+            };
+            match flag.flag_type {
+                FlagType::String => {}
+                FlagType::Int | FlagType::Uint | FlagType::Float => {
+                    if let Some(number) = relaxed_number(&token.arg, flag) {
+                        token.arg = number;
+                    }
+                    continue;
+                }
+                _ => continue,
+            }
+            if flag.trim {
+                let trimmed = token.arg.trim();
+                if trimmed.len() != token.arg.len() {
+                    token.arg = trimmed.to_string();
+                }
+            }
+            if flag.lowercase {
+                token.arg = token.arg.to_lowercase();
+            }
+        }
+    }
+
+    /// Replace glob patterns given to flags and positional arguments
+    /// expanding them by the paths they match, failing on the first invalid
+    /// pattern or pattern matching no path unless it is kept
+    ///
+    /// The first path takes the place of the pattern on the command line.
+    #[cfg(feature = "glob")]
+    pub(crate) fn expand_globs(&mut self, flags: &[Flag], args: &[Arg]) -> Result<(), Error> {
+        let mut expansions = Vec::new();
+        let mut positional = 0;
+        for (position, token) in self.tokens.iter().enumerate() {
+            let (is_flag, name, keep_unmatched) = match token.kind {
+                Kind::Value(index) if flags[index].glob_expand => {
+                    let flag = &flags[index];
+                    (true, &flag.name, flag.keep_unmatched_glob)
+                }
+                Kind::Positional | Kind::Trailing => {
+                    positional += 1;
+                    let arg = args
+                        .get(positional - 1)
+                        .or_else(|| args.last().filter(|arg| arg.variadic));
+                    match arg {
+                        Some(arg) if arg.glob_expand => (false, &arg.name, arg.keep_unmatched_glob),
+                        _ => continue,
+                    }
+                }
+                _ => continue,
+            };
+            if !is_glob(&token.arg) {
+                continue;
+            }
+            let paths = glob_paths(&token.arg).map_err(|e| {
+                let (pattern, name, reason) = (token.arg.clone(), name.clone(), e.msg);
+                let error = Error::new(ErrorKind::InvalidValue, move |m| {
+                    if is_flag {
+                        m.invalid_flag_pattern(&pattern, &name, reason)
+                    } else {
+                        m.invalid_arg_pattern(&pattern, &name, reason)
+                    }
+                })
+                .token(token.arg.as_str());
+                self.locate(error, position)
+            })?;
+            if paths.is_empty() && !keep_unmatched {
+                let (pattern, name) = (token.arg.clone(), name.clone());
+                let error = Error::new(ErrorKind::InvalidValue, move |m| {
+                    if is_flag {
+                        m.flag_pattern_unmatched(&pattern, &name)
+                    } else {
+                        m.arg_pattern_unmatched(&pattern, &name)
+                    }
+                })
+                .token(token.arg.as_str());
+                return Err(self.locate(error, position));
+            }
+            if !paths.is_empty() {
+                expansions.push((position, paths));
+            }
+        }
+        if expansions.is_empty() {
+            return Ok(());
+        }
+
+        // New positions of the tokens at each former position
+        let mut moved = Vec::with_capacity(self.tokens.len());
+        let mut tokens = Vec::with_capacity(self.tokens.len());
+        let mut expansions = expansions.into_iter().peekable();
+        for (position, token) in self.tokens.drain(..).enumerate() {
+            let start = tokens.len();
+            match expansions.peek() {
+                Some((expanded, _)) if *expanded == position => {
+                    let paths = expansions.next().map(|(_, paths)| paths);
+                    for (i, arg) in paths.into_iter().flatten().enumerate() {
+                        tokens.push(Token {
+                            arg,
+                            kind: token.kind,
+                            index: token.index.filter(|_| i == 0),
+                            reason: token.reason,
+                        });
+                    }
+                }
+                _ => tokens.push(token),
+            }
+            moved.push(start..tokens.len());
+        }
+        self.tokens = tokens;
+        for occurrence in &mut self.occurrences {
+            for values in &mut occurrence.values {
+                *values = values.iter().flat_map(|&p| moved[p].clone()).collect();
+            }
+            occurrence.refused = occurrence.refused.map(|p| moved[p].start);
+        }
+        Ok(())
+    }
+
+    /// Error for the first flag value that is not a valid number of the flag type,
+    /// value given to a bool flag with `--name=`, value failing a check of the
+    /// flag, last argument missing its value, occurrence missing some of the
+    /// values the flag takes, or value
+    /// looking like a flag left out of a flag not allowing it
+    pub(crate) fn check_values(&self, flags: &[Flag]) -> Result<(), Error> {
+        for (flag, occurrence) in flags.iter().zip(&self.occurrences) {
+            if let Some(position) = occurrence.refused {
+                return Err(self.locate(
+                    {
+                        let (name, value) = (flag.name.clone(), self.tokens[position].arg.clone());
+                        let assignment = format!("{}={}", flag.name_text(), value);
+                        Error::new(ErrorKind::WrongNumberOfValues, move |m| {
+                            m.value_looks_like_flag(&name, &value, &assignment)
+                        })
+                    }
+                    .token(flag.name_text())
+                    .flag(flag.name.as_str()),
+                    position,
+                ));
+            }
+        }
+        for (index, (flag, occurrence)) in flags.iter().zip(&self.occurrences).enumerate() {
+            let (n, single) = match flag.number_of_values {
+                Some(n) => (n, false),
+                None if occurrence.unfinished => (1, true),
+                None => continue,
+            };
+            if let Some((nth, values)) = occurrence
+                .values
+                .iter()
+                .enumerate()
+                .find(|(_, values)| values.len() < n)
+            {
+                let (name, given) = (flag.name.clone(), values.len());
+                let error = Error::new(ErrorKind::WrongNumberOfValues, move |m| {
+                    if single {
+                        m.missing_flag_value(&name)
+                    } else {
+                        m.wrong_number_of_values(&name, n, given)
+                    }
+                })
+                .token(flag.name_text())
+                .flag(flag.name.as_str());
+                let occurrence = self
+                    .tokens
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, token)| token.kind == Kind::Flag(index))
+                    .nth(nth);
+                return Err(match occurrence {
+                    Some((position, _)) => self.locate(error, position),
+                    None => error,
+                });
+            }
+        }
+
+        for (position, token) in self.tokens.iter().enumerate() {
+            let flag = match token.kind {
+                Kind::Value(index) => &flags[index],
+                _ => continue,
+            };
+            if flag.flag_type == FlagType::Bool {
+                let name = flag.name.clone();
+                return Err(self.locate(
+                    Error::new(ErrorKind::InvalidValue, move |m| {
+                        m.flag_takes_no_value(&name)
+                    })
+                    .token(flag.name_text())
+                    .flag(flag.name.as_str()),
+                    position,
+                ));
+            }
+            let valid = match flag.flag_type {
+                FlagType::Int => token.arg.parse::<i64>().is_ok(),
+                FlagType::Uint => token.arg.parse::<u64>().is_ok(),
+                FlagType::Float => token.arg.parse::<f64>().is_ok(),
+                FlagType::DateTime => token.arg.parse::<DateTime>().is_ok(),
+                #[cfg(feature = "uuid")]
+                FlagType::Uuid => token.arg.parse::<uuid::Uuid>().is_ok(),
+                #[cfg(feature = "url")]
+                FlagType::Url => match (token.arg.parse::<url::Url>(), &flag.allowed_schemes) {
+                    (Ok(ref url), Some(schemes))
+                        if !schemes.iter().any(|scheme| scheme == url.scheme()) =>
+                    {
+                        let (scheme, name) = (url.scheme().to_string(), flag.name.clone());
+                        let schemes = schemes.clone();
+                        return Err(self.locate(
+                            Error::new(ErrorKind::InvalidValue, move |m| {
+                                m.scheme_not_allowed(&scheme, &name, &schemes)
+                            })
+                            .token(token.arg.as_str())
+                            .flag(flag.name.as_str()),
+                            position,
+                        ));
+                    }
+                    (url, _) => url.is_ok(),
+                },
+                #[cfg(feature = "regex")]
+                FlagType::Regex => match regex::Regex::new(&token.arg) {
+                    Ok(_) => true,
+                    Err(e) => {
+                        let (value, name, reason) =
+                            (token.arg.clone(), flag.name.clone(), e.to_string());
+                        return Err(self.locate(
+                            Error::new(ErrorKind::InvalidValue, move |m| {
+                                m.invalid_regex(&value, &name, &reason)
+                            })
+                            .token(token.arg.as_str())
+                            .flag(flag.name.as_str())
+                            .cause(e),
+                            position,
+                        ));
+                    }
+                },
+                FlagType::Bool | FlagType::String | FlagType::Secret => true,
+            };
+            if !valid && token.arg.is_empty() {
+                let (name, expected) = (flag.name.clone(), flag.flag_type.name());
+                return Err(self.locate(
+                    Error::new(ErrorKind::InvalidValue, move |m| {
+                        m.empty_flag_value(&name, expected)
+                    })
+                    .token(flag.name_text())
+                    .flag(flag.name.as_str()),
+                    position,
+                ));
+            }
+            if !valid {
+                let (value, name) = (token.arg.clone(), flag.name.clone());
+                let (expected, examples) = (flag.flag_type.name(), examples(flag));
+                return Err(self.locate(
+                    Error::new(ErrorKind::InvalidValue, move |m| {
+                        m.invalid_flag_value(&value, &name, expected, &examples)
+                    })
+                    .token(token.arg.as_str())
+                    .flag(flag.name.as_str()),
+                    position,
+                ));
+            }
+            for validator in &flag.validators {
+                if let Err(e) = validator.validate(&token.arg) {
+                    let (value, name) = (token.arg.clone(), flag.name.clone());
+                    return Err(self.locate(
+                        Error::new(ErrorKind::InvalidValue, move |m| {
+                            m.rejected_flag_value(&value, &name, &e)
+                        })
+                        .token(token.arg.as_str())
+                        .flag(flag.name.as_str()),
+                        position,
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse trace of the arguments, a line per token telling how it was read
+    ///
+    /// Lines start with `seahorse: ` and values of secret flags are hidden,
+    /// so the trace can be pasted in bug reports.
+    pub(crate) fn trace_args(&self, flags: &[Flag], path: &[String]) -> String {
+        let mut trace = format!("{}parsing {:?}\n", TRACE_PREFIX, path.join(" "));
+        for token in &self.tokens {
+            let position = match token.index {
+                Some(position) => position,
+                None => continue,
+            };
+            let arg = match token.kind {
+                Kind::Value(index) => shown_value(&flags[index], &token.arg),
+                _ => format!("{:?}", token.arg),
+            };
+            let how = match (token.kind, token.reason) {
+                (Kind::Flag(index), _) if token.arg.starts_with("--") => {
+                    format!("flag \"{}\" by name", flags[index].name)
+                }
+                (Kind::Flag(index), _) => format!("flag \"{}\" by alias", flags[index].name),
+                (Kind::Value(index), _) => format!("value of flag \"{}\"", flags[index].name),
+                (Kind::Positional, Reason::Repeated) => {
+                    "positional, flag given before and not repeatable".to_string()
+                }
+                (Kind::Positional, Reason::Unknown) => "positional, unknown flag".to_string(),
+                (Kind::Positional, Reason::AfterPositional) => {
+                    "positional, after the first positional argument".to_string()
+                }
+                (Kind::Positional, Reason::Kind) => "positional".to_string(),
+                (Kind::Separator, _) => "end of flags".to_string(),
+                (Kind::Trailing, _) => "trailing".to_string(),
+                (Kind::Overridden(index), _) => {
+                    format!("flag \"{}\" overridden by a later flag", flags[index].name)
+                }
+            };
+            trace += &format!("{}arg {} {}: {}\n", TRACE_PREFIX, position, arg, how);
+        }
+        trace
+    }
+
+    /// Where the value of the flag at the index comes from, `configured`
+    /// naming the key of the config file for flags whose default it sets
+    pub(crate) fn source<F>(&self, index: usize, flag: &Flag, configured: F) -> Option<ValueSource>
+    where
+        F: Fn(&Flag) -> Option<ValueSource>,
+    {
+        let occurrence = &self.occurrences[index];
+        if occurrence.count > 0 {
+            return Some(match &occurrence.source {
+                Source::CommandLine => ValueSource::CommandLine,
+                Source::Env(name) => ValueSource::Environment(name.clone()),
+                Source::Prompt => ValueSource::Prompt,
+            });
+        }
+        flag.default_value
+            .as_ref()
+            .map(|_| configured(flag).unwrap_or(ValueSource::Default))
+    }
+
+    /// Parse trace of the flags, a line per flag with its resolved value and
+    /// where it comes from, `configured` naming the key of the config file
+    /// for flags whose default it sets
+    pub(crate) fn trace_flags<F>(&self, flags: &[Flag], configured: F) -> String
+    where
+        F: Fn(&Flag) -> Option<ValueSource>,
+    {
+        let mut trace = String::new();
+        for (index, (flag, occurrence)) in flags.iter().zip(&self.occurrences).enumerate() {
+            let source = self.source(index, flag, &configured);
+            let line = if occurrence.count == 0 {
+                match (&flag.default_value, source) {
+                    (Some(value), Some(source)) => {
+                        format!("= {} ({})", shown_value(flag, value), source)
+                    }
+                    _ => "unset".to_string(),
+                }
+            } else {
+                let values: Vec<String> = occurrence
+                    .values
+                    .iter()
+                    .flatten()
+                    .map(|&position| shown_value(flag, &self.tokens[position].arg))
+                    .collect();
+                let value = match values.len() {
+                    0 => "true".to_string(),
+                    1 => values.join(""),
+                    _ => format!("[{}]", values.join(", ")),
+                };
+                let source = source.unwrap_or(ValueSource::CommandLine);
+                match occurrence.count {
+                    1 => format!("= {} ({})", value, source),
+                    n => format!("= {} ({}, {} times)", value, source, n),
+                }
+            };
+            trace += &format!("{}flag \"{}\" {}\n", TRACE_PREFIX, flag.name, line);
+        }
+        trace
+    }
+
+    /// Positional and trailing arguments, in command line order
+    pub(crate) fn into_args(self) -> Vec<String> {
+        let mut args = Vec::with_capacity(self.tokens.len());
+        args.extend(
+            self.tokens
+                .into_iter()
+                .filter(|token| token.kind == Kind::Positional || token.kind == Kind::Trailing)
+                .map(|token| token.arg),
+        );
+        args
+    }
+}
+
+/// Start of the lines of parse traces
+const TRACE_PREFIX: &str = "seahorse: ";
+
+/// Value of the flag quoted for parse traces, hidden for secret flags
+fn shown_value(flag: &Flag, value: &str) -> String {
+    match flag.flag_type {
+        FlagType::Secret => "\"***\"".to_string(),
+        _ => format!("{:?}", value),
+    }
+}
+
+/// Number of leading arguments that are global flags and the values they
+/// take, stopping at the first argument that is not
+///
+/// A flag taking a value takes the next argument whatever it is, so in
+/// `--config build` the value is `build` rather than a command name.
+pub(crate) fn leading_global_flags(args: &[String], flags: &[Flag]) -> usize {
+    leading_global_spans(args, flags)
+        .last()
+        .map_or(0, |span| span.1.end)
+}
+
+/// Position of the flag and arguments of each leading global flag with the
+/// values it takes, like `leading_global_flags` counts them
+pub(crate) fn leading_global_spans(args: &[String], flags: &[Flag]) -> Vec<(usize, Range<usize>)> {
+    let mut spans = Vec::new();
+    if !flags.iter().any(|flag| flag.global) {
+        return spans;
+    }
+    let table = FlagTable::new(flags);
+    let mut position = 0;
+    while let Some(arg) = args.get(position) {
+        let start = position;
+        let empty_value = is_empty_assignment(arg);
+        // Values may be assigned, like `--config=a`
+        let assigned = if empty_value || table.find(arg).is_some() || !arg.starts_with('-') {
+            None
+        } else {
+            arg.find('=')
+        };
+        let name = match assigned {
+            Some(eq) => &arg[..eq],
+            None if empty_value => &arg[..arg.len() - 1],
+            None => arg.as_str(),
+        };
+        let index = match table.find(name) {
+            Some((index, _)) if flags[index].global => index,
+            _ => break,
+        };
+        position += 1;
+        let terminator = flags[index].value_terminator.as_ref();
+        let n = match (flags[index].number_of_values, assigned) {
+            _ if empty_value || flags[index].flag_type == FlagType::Bool => 0,
+            (None, Some(_)) if terminator.is_none() => 0,
+            (None, _) if terminator.is_some() => std::usize::MAX,
+            (None, _) => 1,
+            (Some(n), Some(_)) => n.saturating_sub(1),
+            (Some(n), None) => n,
+        };
+        let mut taken = 0;
+        while let Some(next) = args.get(position).filter(|_| n > 0) {
+            if Some(next) == terminator {
+                position += 1;
+                break;
+            }
+            if taken == n || !table.accepts(index, next) {
+                break;
+            }
+            position += 1;
+            taken += 1;
+        }
+        spans.push((index, start..position.min(args.len())));
+    }
+    spans
+}
+
+/// Arguments with flags written `/name` and `/name:value` rewritten to
+/// `--name` and `--name=value`, and aliases to `-a`, before `--`
+///
+/// Arguments starting with `/` that name no flag, like paths, are left as is.
+pub(crate) fn slash_flags(args: Vec<String>, flags: &[Flag]) -> Vec<String> {
+    let slash_flag = |arg: &str| {
+        let name = arg.split(':').next().unwrap_or_default();
+        flags.iter().find_map(|flag| {
+            if flag.name == name {
+                Some(format!("--{}", name))
+            } else if flag.all_aliases().any(|alias| alias == name) {
+                Some(format!("-{}", name))
+            } else {
+                None
+            }
+        })
+    };
+    if !args
+        .iter()
+        .take_while(|arg| *arg != "--")
+        .any(|arg| arg.starts_with('/'))
+    {
+        return args;
+    }
+
+    let mut rewritten = Vec::with_capacity(args.len());
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            rewritten.push(arg);
+            rewritten.extend(args);
+            break;
+        }
+        let flag = if arg.starts_with('/') {
+            slash_flag(&arg[1..])
+        } else {
+            None
+        };
+        match (flag, arg.find(':')) {
+            (Some(flag), None) => rewritten.push(flag),
+            (Some(flag), Some(colon)) => rewritten.push(format!("{}={}", flag, &arg[colon + 1..])),
+            (None, _) => rewritten.push(arg),
+        }
+    }
+    rewritten
+}
+
+/// Error of an argument written like a flag and matching none of the flags
+pub(crate) fn check_known_flag(arg: &str, flags: &[Flag]) -> Result<(), Error> {
+    let arg = &arg[..assignment(arg).unwrap_or(arg.len())];
+    if looks_like_flag(arg) && FlagTable::new(flags).find(arg).is_none() {
+        Err(unknown_flag_error(arg, flags))
+    } else {
+        Ok(())
+    }
+}
+
+/// Positions of flags by name and alias
+pub(crate) struct FlagIndex<K> {
+    names: HashMap<K, usize>,
+    aliases: HashMap<K, usize>,
+}
+
+impl<'a> FlagIndex<&'a str> {
+    fn new(flags: &'a [Flag]) -> Self {
+        let mut names = HashMap::with_capacity(flags.len());
+        let mut aliases = HashMap::new();
+        for (index, flag) in flags.iter().enumerate() {
+            names.entry(flag.name.as_str()).or_insert(index);
+            if flag.is_short_name() {
+                aliases.entry(flag.name.as_str()).or_insert(index);
+            }
+            for alias in flag.all_aliases() {
+                aliases.entry(alias.as_str()).or_insert(index);
+            }
+        }
+
+        Self { names, aliases }
+    }
+}
+
+impl FlagIndex<String> {
+    /// Index owning its keys, kept across runs by a compiled app
+    pub(crate) fn owned(flags: &[Flag]) -> Self {
+        let index = FlagIndex::new(flags);
+        let own = |map: HashMap<&str, usize>| {
+            map.into_iter()
+                .map(|(key, index)| (key.to_string(), index))
+                .collect()
+        };
+        Self {
+            names: own(index.names),
+            aliases: own(index.aliases),
+        }
+    }
+}
+
+/// Flags looked up by name and alias, with an index built once per parse
+/// unless a compiled app has one
+struct FlagTable<'a> {
+    flags: &'a [Flag],
+    index: Index<'a>,
+}
+
+enum Index<'a> {
+    Built(FlagIndex<&'a str>),
+    Compiled(&'a FlagIndex<String>),
+}
+
+impl<'a> FlagTable<'a> {
+    fn new(flags: &'a [Flag]) -> Self {
+        Self::with_index(flags, None)
+    }
+
+    fn with_index(flags: &'a [Flag], index: Option<&'a FlagIndex<String>>) -> Self {
+        Self {
+            flags,
+            index: match index {
+                Some(index) => Index::Compiled(index),
+                None => Index::Built(FlagIndex::new(flags)),
+            },
+        }
+    }
+
+    fn name(&self, name: &str) -> Option<usize> {
+        match &self.index {
+            Index::Built(index) => index.names.get(name),
+            Index::Compiled(index) => index.names.get(name),
+        }
+        .cloned()
+    }
+
+    fn alias(&self, alias: &str) -> Option<usize> {
+        match &self.index {
+            Index::Built(index) => index.aliases.get(alias),
+            Index::Compiled(index) => index.aliases.get(alias),
+        }
+        .cloned()
+    }
+
+    /// Whether every occurrence of the flag at the index is counted
+    fn counted(&self, index: usize) -> bool {
+        let flag = &self.flags[index];
+        flag.repeatable && flag.flag_type == FlagType::Bool
+    }
+
+    /// Whether the flag at the index takes the next argument as a value,
+    /// which is never `--` and looks like no flag unless the flag allows it
+    fn accepts(&self, index: usize, next: &str) -> bool {
+        let flag = &self.flags[index];
+        if next == "--" {
+            return false;
+        }
+        flag.allow_hyphen_values
+            || if flag.takes_values() {
+                self.find(next).is_none()
+            } else {
+                !looks_like_flag(next)
+            }
+    }
+
+    /// Position of the flag matching the argument and its number of
+    /// occurrences, stacked aliases of repeatable flags like `-vvv` counting
+    /// once per character. A lone `-` is never a flag.
+    fn find(&self, arg: &str) -> Option<(usize, usize)> {
+        if arg == "-" {
+            return None;
+        }
+        if arg.starts_with("--") {
+            return self.name(&arg[2..]).map(|index| (index, 1));
+        }
+        if !arg.starts_with('-') {
+            return None;
+        }
+
+        let alias = &arg[1..];
+        if let Some(index) = self.alias(alias) {
+            return Some((index, 1));
+        }
+
+        let first = alias.chars().next()?;
+        if !alias.chars().all(|c| c == first) {
+            return None;
+        }
+        let index = self.alias(&alias[..first.len_utf8()])?;
+        if self.counted(index) {
+            Some((index, alias.chars().count()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Number written with the digit separators or decimal comma the flag accepts,
+/// in the form parsed by the flag type, unless it already is or is not well formed
+///
+/// Separators are single underscores between digits, so `1_000` is read as
+/// `1000` while `1__000`, `_1` and `1_` are left to fail parsing. A decimal
+/// comma is read as a point in floats without one.
+fn relaxed_number(value: &str, flag: &Flag) -> Option<String> {
+    if !flag.digit_separators && !flag.decimal_comma {
+        return None;
+    }
+    let mut number = value.to_string();
+    if flag.digit_separators && value.contains('_') {
+        let bytes = value.as_bytes();
+        let digit = |i: Option<usize>| {
+            i.and_then(|i| bytes.get(i))
+                .map(u8::is_ascii_digit)
+                .unwrap_or(false)
+        };
+        let separated = bytes
+            .iter()
+            .enumerate()
+            .filter(|&(_, &b)| b == b'_')
+            .all(|(i, _)| digit(i.checked_sub(1)) && digit(Some(i + 1)));
+        if !separated {
+            return None;
+        }
+        number.retain(|c| c != '_');
+    }
+    if flag.decimal_comma
+        && flag.flag_type == FlagType::Float
+        && !number.contains('.')
+        && number.matches(',').count() == 1
+    {
+        number = number.replace(',', ".");
+    }
+    if number == value {
+        None
+    } else {
+        Some(number)
+    }
+}
+
+/// Examples of the forms of values accepted, shown in errors for types
+/// with a format and numbers accepted in forms besides the strict one
+fn examples(flag: &Flag) -> Vec<String> {
+    let formats: &[&str] = match flag.flag_type {
+        FlagType::DateTime => &datetime::FORMATS,
+        #[cfg(feature = "uuid")]
+        FlagType::Uuid => &["xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx"],
+        #[cfg(feature = "url")]
+        FlagType::Url => &["https://example.com"],
+        _ => &[],
+    };
+    if !formats.is_empty() {
+        return formats.iter().map(|format| format.to_string()).collect();
+    }
+    let mut examples = Vec::new();
+    if flag.digit_separators {
+        examples.push("1_000".to_string());
+    }
+    if flag.decimal_comma && flag.flag_type == FlagType::Float {
+        examples.push("1,5".to_string());
+    }
+    examples
+}
+
+/// Whether the value is a glob pattern rather than a path, having `*`, `?`
+/// or `[` and naming no existing path
+#[cfg(feature = "glob")]
+fn is_glob(value: &str) -> bool {
+    value.contains(|c| c == '*' || c == '?' || c == '[') && !std::path::Path::new(value).exists()
+}
+
+/// Paths matching the pattern from the current directory, in alphabetical
+/// order, leaving out those that cannot be read
+#[cfg(feature = "glob")]
+fn glob_paths(pattern: &str) -> Result<Vec<String>, glob::PatternError> {
+    Ok(glob::glob(pattern)?
+        .filter_map(Result::ok)
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect())
+}
+
+/// Whether the argument is written like a flag rather than a number or `-`
+fn looks_like_flag(arg: &str) -> bool {
+    arg.starts_with('-') && arg != "-" && arg.parse::<f64>().is_err()
+}
+
+/// Error of a flag matching no flag, suggesting the closest visible flags
+///
+/// A long flag name written with a single dash like `-release` is pointed at
+/// the flag before looking for similar names.
+fn unknown_flag_error(arg: &str, flags: &[Flag]) -> Error {
+    if arg.len() > 2 && arg.starts_with('-') && !arg.starts_with("--") {
+        if let Some(flag) = flags.iter().find(|flag| flag.name == arg[1..]) {
+            let (given, long) = (arg.to_string(), flag.name_text());
+            let suggestion = long.clone();
+            return Error::new(ErrorKind::UnknownFlag, move |m| {
+                m.single_dash_flag(&given, &long)
+            })
+            .token(arg)
+            .suggestion(suggestion);
+        }
+    }
+    let mut names = Vec::new();
+    for flag in flags.iter().filter(|flag| !flag.hidden) {
+        names.push(flag.name_text());
+        names.extend(
+            flag.alias
+                .iter()
+                .flatten()
+                .map(|alias| format!("-{}", alias)),
+        );
+    }
+    let names: Vec<Candidate> = names.iter().map(|name| Candidate::new(name)).collect();
+
+    let suggestion = suggestion::similar(arg, &names)
+        .first()
+        .map(|s| s.name.to_string());
+    let (given, similar) = (arg.to_string(), suggestion.clone());
+    let error = Error::new(ErrorKind::UnknownFlag, move |m| match &similar {
+        Some(similar) => format!("{} {}", m.unknown_flag(&given), m.did_you_mean(similar)),
+        None => m.unknown_flag(&given),
+    })
+    .token(arg);
+    match suggestion {
+        Some(suggestion) => error.suggestion(suggestion),
+        None => error,
+    }
+}
+
+/// Deepest nesting of argument files read from argument files
+const MAX_ARGFILE_DEPTH: usize = 4;
+
+/// Replace `@file` arguments after the app name by the arguments read from the file,
+/// one per line with empty lines skipped, expanding `@file` lines in turn.
+/// `@@arg` stands for the literal `@arg`.
+pub(crate) fn expand_argfiles(args: Vec<String>) -> Result<Vec<String>, Error> {
+    if !args.iter().skip(1).any(|arg| arg.starts_with('@')) {
+        return Ok(args);
+    }
+
+    let mut expanded = Vec::with_capacity(args.len());
+    let mut args = args.into_iter();
+    expanded.extend(args.next());
+    for arg in args {
+        expand_argfile(arg, 0, &mut expanded)?;
+    }
+    Ok(expanded)
+}
+
+fn expand_argfile(arg: String, depth: usize, expanded: &mut Vec<String>) -> Result<(), Error> {
+    if arg.starts_with("@@") {
+        expanded.push(arg[1..].to_string());
+        return Ok(());
+    }
+    if !arg.starts_with('@') || arg.len() == 1 {
+        expanded.push(arg);
+        return Ok(());
+    }
+
+    let path = &arg[1..];
+    if depth == MAX_ARGFILE_DEPTH {
+        let file = path.to_string();
+        return Err(Error::new(ErrorKind::Io, move |m| {
+            m.argfile_too_deep(&file, MAX_ARGFILE_DEPTH)
+        })
+        .token(path));
+    }
+    let contents = fs::read_to_string(path).map_err(|e| {
+        let (file, reason) = (path.to_string(), e.to_string());
+        Error::new(ErrorKind::Io, move |m| m.argfile_failed(&file, &reason))
+            .token(path)
+            .cause(e)
+    })?;
+    for line in contents.lines().filter(|line| !line.is_empty()) {
+        expand_argfile(line.to_string(), depth + 1, expanded)?;
+    }
+    Ok(())
+}
+
+/// Command named by the token, by name or alias
+pub(crate) fn find_command<'a>(
+    commands: &'a Option<Vec<Arc<Command>>>,
+    token: &str,
+) -> Option<&'a Command> {
+    commands
+        .iter()
+        .flatten()
+        .find(|command| command.name == token || command.all_aliases().any(|a| a == token))
+        .map(|command| &**command)
+}
+
+/// Command named by the token like [`find_command`], or when abbreviations are
+/// allowed the single command with a name or alias starting with the token,
+/// failing when several visible commands do
+///
+/// Hidden commands are matched but never listed as candidates.
+pub(crate) fn select_command<'a>(
+    commands: &'a Option<Vec<Arc<Command>>>,
+    token: &str,
+    abbreviate: bool,
+) -> Result<Option<&'a Command>, Error> {
+    let exact = find_command(commands, token);
+    if exact.is_some() || !abbreviate || token.is_empty() {
+        return Ok(exact);
+    }
+
+    let (visible, hidden): (Vec<&Command>, Vec<&Command>) = commands
+        .iter()
+        .flatten()
+        .map(|command| &**command)
+        .filter(|command| {
+            command.name.starts_with(token) || command.all_aliases().any(|a| a.starts_with(token))
+        })
+        .partition(|command| !command.hidden);
+    match (visible.len(), hidden.len()) {
+        (1, _) => Ok(Some(visible[0])),
+        (0, 1) => Ok(Some(hidden[0])),
+        (0, _) => Ok(None),
+        _ => {
+            let names: Vec<String> = visible.iter().map(|c| c.name.clone()).collect();
+            let command = token.to_string();
+            Err(Error::new(ErrorKind::AmbiguousCommand, move |m| {
+                m.ambiguous_command(&command, &names)
+            })
+            .token(token))
+        }
+    }
+}
+
+/// Error of a token naming none of the commands under the parent path,
+/// suggesting the closest name or alias of the visible ones
+pub(crate) fn unknown_command_error(
+    commands: &Option<Vec<Arc<Command>>>,
+    token: &str,
+    parent: &str,
+) -> Error {
+    let visible = help::visible(commands, false);
+    let names: Vec<Candidate> = visible
+        .iter()
+        .map(|c| Candidate::new(&c.name))
+        .chain(visible.iter().flat_map(|c| {
+            c.aliases()
+                .iter()
+                .map(move |a| Candidate::alias(a, &c.name))
+        }))
+        .collect();
+    let suggestion = suggestion::similar(token, &names).first().cloned();
+    let (command, parent) = (token.to_string(), parent.to_string());
+    let similar = suggestion.map(|s| {
+        let canonical = if s.is_alias() {
+            Some(s.canonical.to_string())
+        } else {
+            None
+        };
+        (s.name.to_string(), canonical)
+    });
+    let error = Error::new(ErrorKind::UnknownCommand, move |m| {
+        let message = m.unknown_command(&command, &parent);
+        match &similar {
+            Some((alias, Some(canonical))) => {
+                format!("{} {}", message, m.did_you_mean_alias(alias, canonical))
+            }
+            Some((similar, None)) => format!("{} {}", message, m.did_you_mean(similar)),
+            None => message,
+        }
+    })
+    .token(token);
+    match suggestion {
+        Some(suggestion) => error.suggestion(suggestion.name),
+        None => error,
+    }
+}
+
+/// Whether the argument is a flag followed by `=` and nothing else, like `--name=`
+fn is_empty_assignment(arg: &str) -> bool {
+    arg.starts_with('-') && arg.find('=') == Some(arg.len() - 1)
+}
+
+/// Position of the `=` of a flag assigned a value like `--name=value`,
+/// split by the parser so that the value stays with the flag it names
+fn assignment(arg: &str) -> Option<usize> {
+    if !arg.starts_with('-') {
+        return None;
+    }
+    arg.find('=')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        expand_argfiles, leading_global_spans, select_command, slash_flags, FlagMatches, Kind,
+    };
+    use crate::error::ErrorKind;
+    use crate::{
+        Command, DefaultMessages, Flag, FlagType, Prompt, UnknownFlag, UnknownFlagHandler,
+    };
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn groups(matches: &FlagMatches, index: usize) -> Vec<Vec<&str>> {
+        matches
+            .groups(index)
+            .iter()
+            .map(|group| group.iter().map(|&p| matches.arg(p)).collect())
+            .collect()
+    }
+
+    fn flags() -> Vec<Flag> {
+        vec![
+            Flag::new("name", FlagType::String).alias("n"),
+            Flag::new("force", FlagType::Bool).alias("f"),
+            Flag::new("verbose", FlagType::Bool).alias("v").repeatable(),
+        ]
+    }
+
+    #[test]
+    fn classify_test() {
+        let flags = flags();
+        let matches = FlagMatches::parse(
+            args(&[
+                "a", "-n", "x", "-vv", "--force", "--force", "--", "--name", "b",
+            ]),
+            &flags,
+        );
+        let kinds: Vec<Kind> = matches.tokens.iter().map(|t| t.kind).collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                Kind::Positional,
+                Kind::Flag(0),
+                Kind::Value(0),
+                Kind::Flag(2),
+                Kind::Flag(1),
+                Kind::Positional,
+                Kind::Separator,
+                Kind::Trailing,
+                Kind::Trailing,
+            ]
+        );
+        assert_eq!(groups(&matches, 0), vec![vec!["x"]]);
+        assert!(groups(&matches, 1).is_empty());
+        assert_eq!(matches.occurrences[2].count, 2);
+        assert_eq!(matches.into_args(), args(&["a", "--force", "--name", "b"]));
+    }
+
+    #[test]
+    fn trace_test() {
+        let mut flags = flags();
+        flags.push(Flag::new("point", FlagType::Int).number_of_values(2));
+        flags.push(Flag::new("token", FlagType::Secret));
+        flags.push(Flag::new("port", FlagType::Uint).default_value("80"));
+        flags.push(Flag::new("tag", FlagType::String));
+        let matches = FlagMatches::parse(
+            args(&[
+                "a",
+                "-n",
+                "x",
+                "-vv",
+                "--token=hunter2",
+                "--point",
+                "1",
+                "2",
+                "--name",
+                "y",
+                "--tag=",
+                "--colour",
+                "--",
+                "--force",
+            ]),
+            &flags,
+        );
+        let trace = matches.trace_args(&flags, &args(&["cli", "add"]))
+            + &matches.trace_flags(&flags, |_| None);
+        assert_eq!(
+            trace,
+            "seahorse: parsing \"cli add\"\n\
+             seahorse: arg 0 \"a\": positional\n\
+             seahorse: arg 1 \"-n\": flag \"name\" by alias\n\
+             seahorse: arg 2 \"x\": value of flag \"name\"\n\
+             seahorse: arg 3 \"-vv\": flag \"verbose\" by alias\n\
+             seahorse: arg 4 \"--token\": flag \"token\" by name\n\
+             seahorse: arg 4 \"***\": value of flag \"token\"\n\
+             seahorse: arg 5 \"--point\": flag \"point\" by name\n\
+             seahorse: arg 6 \"1\": value of flag \"point\"\n\
+             seahorse: arg 7 \"2\": value of flag \"point\"\n\
+             seahorse: arg 8 \"--name\": positional, flag given before and not repeatable\n\
+             seahorse: arg 9 \"y\": positional\n\
+             seahorse: arg 10 \"--tag\": flag \"tag\" by name\n\
+             seahorse: arg 10 \"\": value of flag \"tag\"\n\
+             seahorse: arg 11 \"--colour\": positional, unknown flag\n\
+             seahorse: arg 12 \"--\": end of flags\n\
+             seahorse: arg 13 \"--force\": trailing\n\
+             seahorse: flag \"name\" = \"x\" (command line)\n\
+             seahorse: flag \"force\" unset\n\
+             seahorse: flag \"verbose\" = true (command line, 2 times)\n\
+             seahorse: flag \"point\" = [\"1\", \"2\"] (command line)\n\
+             seahorse: flag \"token\" = \"***\" (command line)\n\
+             seahorse: flag \"port\" = \"80\" (default)\n\
+             seahorse: flag \"tag\" = \"\" (command line)\n"
+        );
+        assert!(!trace.contains("hunter2"));
+
+        let matches = FlagMatches::parse_with(args(&["run", "-f", "x"]), &flags, true);
+        assert!(matches.trace_args(&flags, &args(&["cli"])).ends_with(
+            "seahorse: arg 1 \"-f\": positional, after the first positional argument\n\
+                        seahorse: arg 2 \"x\": positional, after the first positional argument\n"
+        ));
+    }
+
+    #[test]
+    fn short_name_test() {
+        let flags = vec![
+            Flag::new("v", FlagType::Bool).alias("verbose").repeatable(),
+            Flag::new("o", FlagType::String),
+        ];
+        let matches = FlagMatches::parse(
+            args(&["-v", "--v", "-verbose", "-vv", "-o", "out", "--o", "b"]),
+            &flags,
+        );
+
+        assert_eq!(matches.occurrences[0].count, 5);
+        assert_eq!(groups(&matches, 1), vec![vec!["out"]]);
+        assert_eq!(matches.into_args(), args(&["--o", "b"]));
+    }
+
+    #[test]
+    fn missing_value_test() {
+        let flags = flags();
+        let matches = FlagMatches::parse(args(&["--name"]), &flags);
+
+        assert_eq!(matches.occurrences[0].count, 1);
+        assert_eq!(groups(&matches, 0), vec![Vec::<&str>::new()]);
+        assert!(matches.into_args().is_empty());
+    }
+
+    #[test]
+    fn missing_last_value_test() {
+        let flags = flags();
+        let matches = FlagMatches::parse(args(&["a", "--name"]), &flags);
+        let error = matches.check_values(&flags).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::WrongNumberOfValues);
+        assert_eq!(error.flag, Some("name".to_string()));
+        assert_eq!(error.index, Some(1));
+        assert_eq!(error.to_string(), "Missing value for flag \"name\"");
+
+        let matches = FlagMatches::parse(args(&["--name", "--", "a"]), &flags);
+        let error = matches.check_values(&flags).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::WrongNumberOfValues);
+        assert_eq!(error.index, Some(0));
+
+        let matches = FlagMatches::parse(args(&["--name=", "--", "a"]), &flags);
+        assert!(matches.check_values(&flags).is_ok());
+    }
+
+    #[test]
+    fn stdin_test() {
+        let flags = vec![
+            Flag::new("input", FlagType::String).alias("i"),
+            Flag::new("force", FlagType::Bool),
+        ];
+        let matches = FlagMatches::parse(args(&["-", "--input", "-", "-", "-f"]), &flags);
+        let kinds: Vec<Kind> = matches.tokens.iter().map(|t| t.kind).collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                Kind::Positional,
+                Kind::Flag(0),
+                Kind::Value(0),
+                Kind::Positional,
+                Kind::Positional,
+            ]
+        );
+        assert_eq!(groups(&matches, 0), vec![vec!["-"]]);
+        assert_eq!(matches.occurrences[1].count, 0);
+        assert_eq!(matches.into_args(), args(&["-", "-", "-f"]));
+    }
+
+    #[test]
+    fn hyphen_values_test() {
+        let flags = vec![
+            Flag::new("title", FlagType::String).allow_hyphen_values(),
+            Flag::new("name", FlagType::String),
+            Flag::new("offset", FlagType::Int),
+        ];
+        let parse = |a: &[&str]| FlagMatches::parse(args(a), &flags);
+
+        let matches = parse(&["--title", "-x", "--offset", "-5"]);
+        assert_eq!(groups(&matches, 0), vec![vec!["-x"]]);
+        assert_eq!(groups(&matches, 2), vec![vec!["-5"]]);
+        assert!(matches.check_values(&flags).is_ok());
+
+        let matches = parse(&["--name", "-x"]);
+        assert_eq!(groups(&matches, 1), vec![Vec::<&str>::new()]);
+        assert_eq!(
+            matches.check_values(&flags).map_err(|e| e.to_string()),
+            Err(
+                "Flag \"name\" takes a value but \"-x\" looks like a flag, write --name=-x"
+                    .to_string()
+            )
+        );
+
+        let matches = parse(&["--name=-x", "--title", "--", "-y"]);
+        assert_eq!(groups(&matches, 1), vec![vec!["-x"]]);
+        assert_eq!(groups(&matches, 0), vec![Vec::<&str>::new()]);
+        assert_eq!(
+            matches.check_values(&flags).map_err(|e| e.to_string()),
+            Err("Missing value for flag \"title\"".to_string())
+        );
+        assert_eq!(matches.into_args(), args(&["-y"]));
+    }
+
+    #[test]
+    fn fill_missing_test() {
+        let flags = vec![
+            Flag::new("name", FlagType::String).prompt_if_missing(),
+            Flag::new("port", FlagType::Int).prompt_if_missing(),
+            Flag::new("point", FlagType::Int)
+                .number_of_values(2)
+                .prompt_if_missing(),
+            Flag::new("mode", FlagType::String).required(),
+            Flag::new("level", FlagType::Int)
+                .prompt_if_missing()
+                .default_value("1"),
+        ];
+        let prompt = Prompt::from(|question: &str| match question {
+            "name: " => Ok("alice".to_string()),
+            "port: " => Ok("http".to_string()),
+            _ => Ok("1 2".to_string()),
+        });
+        let fill = |a: &[&str], prompt: &Prompt| {
+            let mut matches = FlagMatches::parse(args(a), &flags);
+            matches
+                .fill_missing(&flags, prompt, &DefaultMessages, |flag| {
+                    flag.env_names(None, &[])
+                })
+                .map(|_| matches)
+        };
+
+        let matches = fill(&["--mode", "fast", "--port", "80"], &prompt).unwrap();
+        assert_eq!(groups(&matches, 0), vec![vec!["alice"]]);
+        assert_eq!(groups(&matches, 1), vec![vec!["80"]]);
+        assert_eq!(groups(&matches, 2), vec![vec!["1", "2"]]);
+        assert_eq!(matches.occurrences[4].count, 0);
+        assert!(matches.check_values(&flags).is_ok());
+
+        let matches = fill(&["--mode", "fast"], &prompt).unwrap();
+        assert_eq!(
+            matches.check_values(&flags).map_err(|e| e.to_string()),
+            Err("Invalid value \"http\" for flag \"port\", expected int".to_string())
+        );
+
+        assert_eq!(
+            fill(&["--port", "80"], &prompt)
+                .err()
+                .map(|e| e.to_string()),
+            Some("Missing required flag \"mode\"".to_string())
+        );
+        assert_eq!(
+            fill(&["--mode", "fast"], &Prompt::non_interactive())
+                .err()
+                .map(|e| e.to_string()),
+            Some("Missing required flag \"name\"".to_string())
+        );
+    }
+
+    #[test]
+    fn secret_fill_missing_test() {
+        std::env::set_var("SEAHORSE_SECRET_FILL_MISSING_TEST", "from env");
+        let flags = vec![
+            Flag::new("token", FlagType::Secret).required(),
+            Flag::new("user", FlagType::String)
+                .prompt_if_missing()
+                .env("SEAHORSE_SECRET_FILL_MISSING_TEST"),
+        ];
+        let prompt = Prompt::from(|_: &str| Ok("echoed".to_string()))
+            .secret(|question: &str| Ok(format!("hidden {}", question)));
+        let fill = |a: &[&str], prompt: &Prompt| {
+            let mut matches = FlagMatches::parse(args(a), &flags);
+            matches
+                .fill_missing(&flags, prompt, &DefaultMessages, |flag| {
+                    flag.env_names(None, &[])
+                })
+                .map(|_| matches)
+        };
+
+        let matches = fill(&[], &prompt).unwrap();
+        assert_eq!(groups(&matches, 0), vec![vec!["hidden token: "]]);
+        assert_eq!(groups(&matches, 1), vec![vec!["from env"]]);
+
+        let matches = fill(&["--user", "bob"], &prompt).unwrap();
+        assert_eq!(groups(&matches, 1), vec![vec!["bob"]]);
+
+        assert_eq!(
+            fill(&[], &Prompt::non_interactive())
+                .err()
+                .map(|e| e.to_string()),
+            Some("Missing required flag \"token\"".to_string())
+        );
+    }
+
+    #[test]
+    fn resolve_unknown_test() {
+        let flags = vec![
+            Flag::new("color", FlagType::String),
+            Flag::new("verbose", FlagType::Bool).alias("v"),
+        ];
+        let handler = UnknownFlagHandler::from(|flag: &str, path: &[String]| {
+            assert_eq!(path, ["cli".to_string()]);
+            match flag {
+                "--colour" => UnknownFlag::Replace("color".to_string()),
+                "--loud" => UnknownFlag::Replace("verbose".to_string()),
+                "--keep" => UnknownFlag::Ignore,
+                "--legacy" => UnknownFlag::Replace("legacy".to_string()),
+                _ => UnknownFlag::Error,
+            }
+        });
+        let resolve = |a: &[&str]| {
+            FlagMatches::parse(args(a), &flags).resolve_unknown(&flags, &handler, &args(&["cli"]))
+        };
+
+        let matches = resolve(&["--keep", "--colour", "red", "a", "--loud", "-1", "-"]).unwrap();
+        assert_eq!(groups(&matches, 0), vec![vec!["red"]]);
+        assert_eq!(matches.occurrences[1].count, 1);
+        assert_eq!(matches.into_args(), args(&["--keep", "a", "-1", "-"]));
+
+        let matches = resolve(&["--", "--colr"]).unwrap();
+        assert_eq!(matches.into_args(), args(&["--colr"]));
+        assert_eq!(
+            resolve(&["--colr", "red"]).err().map(|e| e.to_string()),
+            Some(r#"Unknown flag "--colr". Did you mean "--color"?"#.to_string())
+        );
+        assert_eq!(
+            resolve(&["--legacy"]).err().map(|e| e.to_string()),
+            Some(r#"Unknown flag "--legacy"."#.to_string())
+        );
+
+        let error = resolve(&["-color", "red"]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"Unknown flag: found "-color"; did you mean "--color"?"#
+        );
+        assert_eq!(error.suggestion, Some("--color".to_string()));
+        assert_eq!(
+            resolve(&["-colr"]).err().map(|e| e.to_string()),
+            Some(r#"Unknown flag "-colr". Did you mean "--color"?"#.to_string())
+        );
+        assert_eq!(
+            resolve(&["-bogus"]).err().map(|e| e.to_string()),
+            Some(r#"Unknown flag "-bogus"."#.to_string())
+        );
+    }
+
+    #[test]
+    fn select_command_test() {
+        let commands = Some(
+            vec![
+                Command::new("status"),
+                Command::new("stash").alias("save"),
+                Command::new("st"),
+                Command::new("serve"),
+                Command::new("sync").hidden(),
+                Command::new("debug").hidden(),
+            ]
+            .into_iter()
+            .map(Command::shared)
+            .collect(),
+        );
+        let select = |token: &str| {
+            select_command(&commands, token, true)
+                .map(|command| command.map(|c| c.name.as_str()))
+                .map_err(|e| e.to_string())
+        };
+
+        assert_eq!(select("stat"), Ok(Some("status")));
+        assert_eq!(select("sa"), Ok(Some("stash")));
+        assert_eq!(select("st"), Ok(Some("st")));
+        assert_eq!(select("save"), Ok(Some("stash")));
+        assert_eq!(select("se"), Ok(Some("serve")));
+        assert_eq!(select("de"), Ok(Some("debug")));
+        assert_eq!(select("sy"), Ok(Some("sync")));
+        assert_eq!(select("x"), Ok(None));
+        assert_eq!(
+            select("sta"),
+            Err(r#"Command "sta" is ambiguous, it could be status, stash"#.to_string())
+        );
+        assert_eq!(
+            select("s"),
+            Err(r#"Command "s" is ambiguous, it could be status, stash, st, serve"#.to_string())
+        );
+        assert!(select_command(&commands, "stat", false).unwrap().is_none());
+    }
+
+    #[test]
+    fn read_files_test() {
+        let file = std::env::temp_dir().join("seahorse_read_files_test.txt");
+        std::fs::write(&file, "42\n").unwrap();
+        let path = format!("@{}", file.display());
+        let flags = vec![
+            Flag::new("port", FlagType::Int).allow_from_file(),
+            Flag::new("name", FlagType::String).allow_from_file(),
+            Flag::new("raw", FlagType::String),
+        ];
+        let read = |a: &[&str]| {
+            let mut matches = FlagMatches::parse(args(a), &flags);
+            matches.read_files(&flags).map(|_| {
+                matches
+                    .tokens
+                    .iter()
+                    .filter(|t| t.kind != Kind::Positional)
+                    .map(|t| t.arg.clone())
+                    .collect::<Vec<String>>()
+            })
+        };
+
+        assert_eq!(
+            read(&["--port", &path, "--raw", &path, &path]),
+            Ok(args(&["--port", "42", "--raw", &path]))
+        );
+        assert_eq!(
+            read(&["--name", "@@me", "--port", "@@1"]),
+            Ok(args(&["--name", "@me", "--port", "@1"]))
+        );
+
+        let missing = std::env::temp_dir().join("seahorse_read_files_missing.txt");
+        let error = read(&["--name", &format!("@{}", missing.display())])
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with(&format!(
+            "Failed to read file \"{}\" for flag \"name\": ",
+            missing.display()
+        )));
+
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn number_of_values_test() {
+        let flags = vec![
+            Flag::new("point", FlagType::Int)
+                .number_of_values(2)
+                .repeatable(),
+            Flag::new("size", FlagType::Int).number_of_values(2),
+            Flag::new("force", FlagType::Bool).alias("f"),
+        ];
+        let parse = |a: &[&str]| FlagMatches::parse(args(a), &flags);
+
+        let matches = parse(&["--point", "1", "2", "a", "--point", "3", "4"]);
+        assert_eq!(groups(&matches, 0), vec![vec!["1", "2"], vec!["3", "4"]]);
+        assert!(matches.check_values(&flags).is_ok());
+        assert_eq!(matches.into_args(), args(&["a"]));
+
+        let matches = parse(&["--size", "1", "-f", "2", "3"]);
+        assert_eq!(groups(&matches, 1), vec![vec!["1"]]);
+        assert_eq!(matches.occurrences[2].count, 1);
+        assert_eq!(
+            matches.check_values(&flags).map_err(|e| e.to_string()),
+            Err("Flag \"size\" takes 2 values but 1 given".to_string())
+        );
+
+        // Short of values at the end of the arguments as in their middle
+        let matches = parse(&["a", "--size", "1"]);
+        assert_eq!(groups(&matches, 1), vec![vec!["1"]]);
+        let error = matches.check_values(&flags).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Flag \"size\" takes 2 values but 1 given"
+        );
+        assert_eq!(error.index, Some(1));
+
+        let matches = parse(&["--point", "1", "2", "--point", "3"]);
+        let error = matches.check_values(&flags).unwrap_err();
+        assert_eq!(error.flag, Some("point".to_string()));
+        assert_eq!(error.index, Some(3));
+
+        let matches = parse(&["--size"]);
+        assert_eq!(
+            matches.check_values(&flags).map_err(|e| e.to_string()),
+            Err("Flag \"size\" takes 2 values but 0 given".to_string())
+        );
+
+        let matches = parse(&["--size", "1", "--", "2"]);
+        assert_eq!(groups(&matches, 1), vec![vec!["1"]]);
+        assert!(matches.check_values(&flags).is_err());
+        assert_eq!(matches.into_args(), args(&["2"]));
+
+        let matches = parse(&["--size", "1", "x"]);
+        assert_eq!(
+            matches.check_values(&flags).map_err(|e| e.to_string()),
+            Err("Invalid value \"x\" for flag \"size\", expected int".to_string())
+        );
+    }
+
+    #[test]
+    fn value_terminator_test() {
+        let flags = vec![
+            Flag::new("env", FlagType::String)
+                .value_terminator(";")
+                .repeatable(),
+            Flag::new("point", FlagType::Int)
+                .number_of_values(2)
+                .value_terminator("+")
+                .repeatable(),
+            Flag::new("force", FlagType::Bool).alias("f"),
+        ];
+        let parse = |a: &[&str]| FlagMatches::parse(args(a), &flags);
+
+        let matches = parse(&["run", "--env", "A=1", "B=2", ";", "build.sh"]);
+        assert_eq!(groups(&matches, 0), vec![vec!["A=1", "B=2"]]);
+        assert_eq!(matches.into_args(), args(&["run", "build.sh"]));
+
+        let matches = parse(&["--env", "A=1", "-f", "x", "--env", "B=2", "C=3", "--", "y"]);
+        assert_eq!(groups(&matches, 0), vec![vec!["A=1"], vec!["B=2", "C=3"]]);
+        assert_eq!(matches.occurrences[2].count, 1);
+        assert_eq!(matches.into_args(), args(&["x", "y"]));
+
+        let matches = parse(&["--env", "A=1", ";"]);
+        assert_eq!(groups(&matches, 0), vec![vec!["A=1"]]);
+        assert!(matches.into_args().is_empty());
+
+        let matches = parse(&["--point", "1", "2", "+", "3", "--point", "4", "+", "5"]);
+        assert_eq!(groups(&matches, 1), vec![vec!["1", "2"], vec!["4"]]);
+        assert_eq!(
+            matches.check_values(&flags).map_err(|e| e.to_string()),
+            Err("Flag \"point\" takes 2 values but 1 given".to_string())
+        );
+        assert_eq!(matches.into_args(), args(&["3", "5"]));
+    }
+
+    #[test]
+    fn relaxed_number_test() {
+        let flags = vec![
+            Flag::new("max", FlagType::Uint).allow_digit_separators(),
+            Flag::new("ratio", FlagType::Float)
+                .allow_digit_separators()
+                .decimal_comma(),
+            Flag::new("strict", FlagType::Float),
+        ];
+        let values = |a: &[&str]| {
+            let mut matches = FlagMatches::parse(args(a), &flags);
+            matches.normalize_values(&flags);
+            matches
+                .check_values(&flags)
+                .map(|_| {
+                    matches
+                        .tokens
+                        .iter()
+                        .filter(|t| match t.kind {
+                            Kind::Value(_) => true,
+                            _ => false,
+                        })
+                        .map(|t| t.arg.clone())
+                        .collect::<Vec<String>>()
+                })
+                .map_err(|e| e.to_string())
+        };
+
+        assert_eq!(
+            values(&["--max", "10_000_000", "--ratio", "1_000,5"]),
+            Ok(vec!["10000000".to_string(), "1000.5".to_string()])
+        );
+        assert_eq!(values(&["--ratio", "1,5"]), Ok(vec!["1.5".to_string()]));
+        assert_eq!(
+            values(&["--max", "1__0"]),
+            Err("Invalid value \"1__0\" for flag \"max\", expected uint like 1_000".to_string())
+        );
+        assert!(values(&["--max", "_1"]).is_err());
+        assert!(values(&["--max", "1_"]).is_err());
+        assert_eq!(
+            values(&["--ratio", "1,000,5"]),
+            Err(
+                "Invalid value \"1,000,5\" for flag \"ratio\", expected float like 1_000 or 1,5"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            values(&["--strict", "1,5"]),
+            Err("Invalid value \"1,5\" for flag \"strict\", expected float".to_string())
+        );
+        assert_eq!(
+            values(&["--strict", "1_0"]),
+            Err("Invalid value \"1_0\" for flag \"strict\", expected float".to_string())
+        );
+    }
+
+    #[test]
+    fn leading_global_spans_test() {
+        let mut zero = Flag::new("point", FlagType::Int).global();
+        // Not allowed by the builder, but the field is public
+        zero.number_of_values = Some(0);
+        let flags = vec![
+            Flag::new("config", FlagType::String).global(),
+            zero,
+            Flag::new("local", FlagType::Bool),
+        ];
+        assert_eq!(
+            leading_global_spans(&args(&["--config", "a", "--point=1", "run"]), &flags),
+            vec![(0, 0..2), (1, 2..3)]
+        );
+        assert!(leading_global_spans(&args(&["--local", "--config", "a"]), &flags).is_empty());
+    }
+
+    #[test]
+    fn assignment_test() {
+        let flags = flags();
+        let parse = |a: &[&str]| FlagMatches::parse(args(a), &flags);
+
+        let matches = parse(&["--name=a=b", "x=y", "--", "--force=d"]);
+        assert_eq!(groups(&matches, 0), vec![vec!["a=b"]]);
+        assert!(matches.check_values(&flags).is_ok());
+        assert_eq!(matches.into_args(), args(&["x=y", "--force=d"]));
+
+        let matches = parse(&["-n=c", "--colour=always"]);
+        assert_eq!(groups(&matches, 0), vec![vec!["c"]]);
+        assert_eq!(matches.line(), args(&["-n=c", "--colour=always"]));
+        assert_eq!(matches.into_args(), args(&["--colour", "always"]));
+
+        // Values given to bool flags are rejected rather than left as positional
+        for arg in &["--verbose=false", "--verbose=true", "-f=false", "--force=="] {
+            let matches = parse(&[arg]);
+            let error = matches.check_values(&flags).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidValue);
+            assert!(matches.into_args().is_empty());
+        }
+    }
+
+    #[test]
+    fn slash_flags_test() {
+        let flags = vec![
+            Flag::new("quiet", FlagType::Bool).alias("q"),
+            Flag::new("out", FlagType::String).alias("o"),
+        ];
+        assert_eq!(
+            slash_flags(
+                args(&[
+                    "/quiet",
+                    "/out:file.txt",
+                    "/usr/bin/foo",
+                    "/q",
+                    "/o:a:b",
+                    "/out:"
+                ]),
+                &flags
+            ),
+            args(&[
+                "--quiet",
+                "--out=file.txt",
+                "/usr/bin/foo",
+                "-q",
+                "-o=a:b",
+                "--out="
+            ])
+        );
+        assert_eq!(
+            slash_flags(args(&["/x", "/quiet/", "--", "/quiet"]), &flags),
+            args(&["/x", "/quiet/", "--", "/quiet"])
+        );
+
+        let matches = FlagMatches::parse(
+            slash_flags(args(&["/usr/bin/foo", "/q", "/out:log.txt"]), &flags),
+            &flags,
+        );
+        assert_eq!(matches.occurrences[0].count, 1);
+        assert_eq!(groups(&matches, 1), vec![vec!["log.txt"]]);
+        assert_eq!(matches.into_args(), args(&["/usr/bin/foo"]));
+
+        // Values starting with `-` stay with the flag, like `--out=-x`
+        let matches = FlagMatches::parse(slash_flags(args(&["/o:-x", "a"]), &flags), &flags);
+        assert!(matches.check_values(&flags).is_ok());
+        assert_eq!(groups(&matches, 1), vec![vec!["-x"]]);
+        assert_eq!(matches.into_args(), args(&["a"]));
+    }
+
+    #[test]
+    fn expand_argfiles_test() {
+        let dir = std::env::temp_dir();
+        let inner = dir.join("seahorse_argfile_inner.txt");
+        let outer = dir.join("seahorse_argfile_outer.txt");
+        let looped = dir.join("seahorse_argfile_loop.txt");
+        std::fs::write(&inner, "--name\nwith space\n").unwrap();
+        std::fs::write(&outer, format!("a\r\n\n@{}\n@@b\n", inner.display())).unwrap();
+        std::fs::write(&looped, format!("@{}\n", looped.display())).unwrap();
+        let expand = |a: &[&str]| expand_argfiles(args(a)).map_err(|e| e.to_string());
+
+        assert_eq!(
+            expand(&["cli", &format!("@{}", outer.display()), "@", "@@c"]),
+            Ok(args(&["cli", "a", "--name", "with space", "@b", "@", "@c"]))
+        );
+        assert_eq!(
+            expand(&["cli", &format!("@{}", looped.display())]),
+            Err(format!(
+                "Argument file \"{}\" is nested deeper than 4 levels",
+                looped.display()
+            ))
+        );
+
+        let missing = dir.join("seahorse_argfile_missing.txt");
+        let error = expand(&["cli", &format!("@{}", missing.display())])
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with(&format!(
+            "Failed to read argument file \"{}\": ",
+            missing.display()
+        )));
+
+        for file in &[inner, outer, looped] {
+            std::fs::remove_file(file).unwrap();
+        }
+    }
+}
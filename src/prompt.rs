@@ -0,0 +1,360 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+
+/// Source of answers to interactive questions
+///
+/// The default prompt writes the question to stderr and reads the answer
+/// from stdin, without echo for secrets, and is interactive when stdin
+/// is a terminal.
+///
+/// Example
+///
+/// ```
+/// use seahorse::Prompt;
+///
+/// let prompt = Prompt::from(|question: &str| Ok(format!("answer to {}", question)));
+/// assert_eq!(prompt.ask("name: ").unwrap(), "answer to name: ");
+/// ```
+#[derive(Clone)]
+pub struct Prompt {
+    inner: Arc<PromptFn>,
+    secret: Arc<PromptFn>,
+    interactive: bool,
+    yes_flag: Cow<'static, str>,
+}
+
+type PromptFn = dyn Fn(&str) -> io::Result<String> + Send + Sync;
+
+impl Prompt {
+    /// Prompt writing to stderr and reading from stdin
+    pub fn stdio() -> Self {
+        Self {
+            inner: Arc::new(|question: &str| {
+                write_question(question)?;
+                read_line(&mut io::stdin().lock())
+            }),
+            secret: Arc::new(|question: &str| {
+                write_question(question)?;
+                let echo = Echo::disable();
+                let line = read_line(&mut io::stdin().lock());
+                if echo.disabled {
+                    eprintln!();
+                }
+                line
+            }),
+            interactive: is_terminal(),
+            yes_flag: Cow::Borrowed("yes"),
+        }
+    }
+
+    /// Prompt that never asks, used to keep runs non-interactive
+    pub fn non_interactive() -> Self {
+        let fail: Arc<PromptFn> = Arc::new(|_: &str| {
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                "prompt is not interactive",
+            ))
+        });
+        Self {
+            inner: fail.clone(),
+            secret: fail,
+            interactive: false,
+            yes_flag: Cow::Borrowed("yes"),
+        }
+    }
+
+    /// Prompt that never asks, keeping the yes flag of this prompt
+    pub(crate) fn without_input(&self) -> Self {
+        Self {
+            yes_flag: self.yes_flag.clone(),
+            ..Self::non_interactive()
+        }
+    }
+
+    /// Set function answering secret questions, the function answering
+    /// other questions being used otherwise
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Prompt;
+    ///
+    /// let prompt = Prompt::from(|_: &str| Ok("alice".to_string()))
+    ///     .secret(|_: &str| Ok("hunter2".to_string()));
+    /// assert_eq!(prompt.ask_secret("token: ").unwrap(), "hunter2");
+    /// ```
+    pub fn secret<F>(mut self, f: F) -> Self
+    where
+        for<'a> F: Fn(&'a str) -> io::Result<String> + Send + Sync + 'static,
+    {
+        self.secret = Arc::new(f);
+        self
+    }
+
+    /// Set name of the bool flag answering yes to all confirmations,
+    /// `yes` by default
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Prompt;
+    ///
+    /// let prompt = Prompt::stdio().yes_flag("force");
+    /// assert_eq!(prompt.yes_flag_name(), "force");
+    /// ```
+    pub fn yes_flag<T: Into<String>>(mut self, name: T) -> Self {
+        self.yes_flag = Cow::Owned(name.into());
+        self
+    }
+
+    /// Name of the bool flag answering yes to all confirmations
+    pub fn yes_flag_name(&self) -> &str {
+        &self.yes_flag
+    }
+
+    /// Whether questions can be asked
+    pub fn is_interactive(&self) -> bool {
+        self.interactive
+    }
+
+    /// Ask the question, returning the answer without its line ending
+    pub fn ask(&self, question: &str) -> io::Result<String> {
+        (self.inner)(question)
+    }
+
+    /// Ask the question without echoing the answer
+    pub fn ask_secret(&self, question: &str) -> io::Result<String> {
+        (self.secret)(question)
+    }
+
+    /// Ask the yes or no question until answered with y, yes, n or no in any
+    /// case, returning the default on an empty answer, when the answer cannot
+    /// be read or when the prompt is not interactive
+    pub(crate) fn confirm(&self, message: &str, default: bool) -> bool {
+        if !self.interactive {
+            return default;
+        }
+        let question = format!("{} {} ", message, if default { "[Y/n]" } else { "[y/N]" });
+        loop {
+            let answer = match self.ask(&question) {
+                Ok(answer) => answer,
+                Err(_) => return default,
+            };
+            match answer.trim().to_lowercase().as_str() {
+                "" => return default,
+                "y" | "yes" => return true,
+                "n" | "no" => return false,
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Default for Prompt {
+    fn default() -> Self {
+        Self::stdio()
+    }
+}
+
+impl fmt::Debug for Prompt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Prompt")
+    }
+}
+
+impl<F> From<F> for Prompt
+where
+    for<'a> F: Fn(&'a str) -> io::Result<String> + Send + Sync + 'static,
+{
+    fn from(value: F) -> Self {
+        let inner: Arc<PromptFn> = Arc::new(value);
+        Self {
+            secret: inner.clone(),
+            inner,
+            interactive: true,
+            yes_flag: Cow::Borrowed("yes"),
+        }
+    }
+}
+
+fn write_question(question: &str) -> io::Result<()> {
+    let mut stderr = io::stderr();
+    stderr.write_all(question.as_bytes())?;
+    stderr.flush()
+}
+
+/// Terminal echo turned off until dropped
+struct Echo {
+    disabled: bool,
+}
+
+#[cfg(unix)]
+impl Echo {
+    fn disable() -> Self {
+        Self {
+            disabled: stty("-echo"),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Echo {
+    fn drop(&mut self) {
+        if self.disabled {
+            stty("echo");
+        }
+    }
+}
+
+#[cfg(unix)]
+fn stty(setting: &str) -> bool {
+    std::process::Command::new("stty")
+        .arg(setting)
+        .stdin(std::process::Stdio::inherit())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(windows)]
+mod console {
+    extern "system" {
+        pub fn GetStdHandle(handle: u32) -> *mut u8;
+        pub fn GetConsoleMode(handle: *mut u8, mode: *mut u32) -> i32;
+        pub fn SetConsoleMode(handle: *mut u8, mode: u32) -> i32;
+    }
+    pub const STD_INPUT_HANDLE: u32 = -10i32 as u32;
+    pub const ENABLE_ECHO_INPUT: u32 = 0x4;
+}
+
+#[cfg(windows)]
+impl Echo {
+    fn disable() -> Self {
+        let mut mode = 0;
+        // Safety: the handle is only queried and set, and mode outlives the call
+        let disabled = unsafe {
+            let handle = console::GetStdHandle(console::STD_INPUT_HANDLE);
+            console::GetConsoleMode(handle, &mut mode) != 0
+                && console::SetConsoleMode(handle, mode & !console::ENABLE_ECHO_INPUT) != 0
+        };
+        Self { disabled }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for Echo {
+    fn drop(&mut self) {
+        if self.disabled {
+            let mut mode = 0;
+            // Safety: as in disable
+            unsafe {
+                let handle = console::GetStdHandle(console::STD_INPUT_HANDLE);
+                if console::GetConsoleMode(handle, &mut mode) != 0 {
+                    console::SetConsoleMode(handle, mode | console::ENABLE_ECHO_INPUT);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+impl Echo {
+    fn disable() -> Self {
+        Self { disabled: false }
+    }
+}
+
+/// Line read from the reader without its line ending, failing at end of input
+fn read_line<R: BufRead>(reader: &mut R) -> io::Result<String> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "no input given",
+        ));
+    }
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    Ok(line)
+}
+
+#[cfg(unix)]
+fn is_terminal() -> bool {
+    extern "C" {
+        fn isatty(fd: i32) -> i32;
+    }
+    // Safety: isatty only inspects the file descriptor
+    unsafe { isatty(0) == 1 }
+}
+
+#[cfg(windows)]
+fn is_terminal() -> bool {
+    let mut mode = 0;
+    // Safety: the handle is only queried, and mode outlives the call
+    unsafe {
+        console::GetConsoleMode(console::GetStdHandle(console::STD_INPUT_HANDLE), &mut mode) != 0
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_terminal() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_line, Prompt};
+
+    #[test]
+    fn read_line_test() {
+        let mut input = "alice\r\nbob\n\ncarol".as_bytes();
+        assert_eq!(read_line(&mut input).unwrap(), "alice");
+        assert_eq!(read_line(&mut input).unwrap(), "bob");
+        assert_eq!(read_line(&mut input).unwrap(), "");
+        assert_eq!(read_line(&mut input).unwrap(), "carol");
+        assert!(read_line(&mut input).is_err());
+    }
+
+    #[test]
+    fn confirm_test() {
+        let answer = |answers: &'static [&'static str]| {
+            let next = std::sync::atomic::AtomicUsize::new(0);
+            Prompt::from(move |_: &str| {
+                let answer = answers
+                    .get(next.fetch_add(1, std::sync::atomic::Ordering::SeqCst))
+                    .copied();
+                answer
+                    .map(String::from)
+                    .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::UnexpectedEof))
+            })
+        };
+
+        assert!(answer(&["y"]).confirm("Delete?", false));
+        assert!(answer(&["YES"]).confirm("Delete?", false));
+        assert!(!answer(&["n"]).confirm("Delete?", true));
+        assert!(!answer(&["No"]).confirm("Delete?", true));
+        assert!(answer(&[""]).confirm("Delete?", true));
+        assert!(!answer(&[""]).confirm("Delete?", false));
+        assert!(answer(&["maybe", " y "]).confirm("Delete?", false));
+        assert!(!answer(&[]).confirm("Delete?", false));
+        assert!(Prompt::non_interactive().confirm("Delete?", true));
+        assert!(!Prompt::non_interactive().confirm("Delete?", false));
+
+        let question = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
+        let asked = question.clone();
+        let prompt = Prompt::from(move |q: &str| {
+            *asked.lock().unwrap() = q.to_string();
+            Ok("y".to_string())
+        });
+        prompt.confirm("Delete?", false);
+        assert_eq!(*question.lock().unwrap(), "Delete? [y/N] ");
+        prompt.confirm("Delete?", true);
+        assert_eq!(*question.lock().unwrap(), "Delete? [Y/n] ");
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt;
+
+/// Value of a secret flag, redacted when debug printed
+///
+/// Example
+///
+/// ```
+/// use seahorse::Secret;
+///
+/// let token = Secret::new("abc123");
+/// assert_eq!(token.expose(), "abc123");
+/// assert_eq!(format!("{:?}", token), "Secret(****)");
+/// ```
+#[derive(Clone, PartialEq)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Create new instance of `Secret`
+    pub fn new<T: Into<String>>(value: T) -> Self {
+        Secret(value.into())
+    }
+
+    /// Secret value
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Secret value, consuming the secret
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Secret(****)")
+    }
+}
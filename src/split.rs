@@ -0,0 +1,182 @@
+use std::error;
+use std::fmt;
+
+/// Error of `split`, for a line a POSIX shell would not run either
+#[derive(PartialEq, Clone, Debug)]
+pub enum SplitError {
+    /// Quote without its closing quote
+    UnterminatedQuote {
+        /// Quote character, `'` or `"`
+        quote: char,
+        /// Position of the opening quote in the line, counting characters
+        /// from 1
+        position: usize,
+    },
+    /// Backslash at the end of the line, escaping nothing
+    TrailingBackslash,
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SplitError::UnterminatedQuote { quote, position } => write!(
+                f,
+                "Unterminated {} quote starting at character {}",
+                if quote == '\'' { "single" } else { "double" },
+                position
+            ),
+            SplitError::TrailingBackslash => f.write_str("Backslash at the end of the line"),
+        }
+    }
+}
+
+impl error::Error for SplitError {}
+
+/// Split the line into arguments the way a POSIX shell does, without
+/// expanding anything
+///
+/// Whitespace separates arguments. Single quotes keep everything up to the
+/// next single quote as is, double quotes keep everything up to the next
+/// unescaped double quote, where a backslash escapes `"`, `\`, `$`, `` ` ``
+/// and newlines and is kept before other characters. Outside quotes a
+/// backslash escapes any character, and a backslash followed by a newline
+/// joins the lines. Quotes written next to other characters are part of the
+/// same argument, `""` alone is an empty argument.
+///
+/// Example
+///
+/// ```
+/// use seahorse::split;
+///
+/// let args = split(r#"cli add --name "Alice Smith" it\'s 'a "b"'"#).unwrap();
+/// assert_eq!(args, ["cli", "add", "--name", "Alice Smith", "it's", "a \"b\""]);
+/// assert!(split("cli 'add").is_err());
+/// ```
+pub fn split(line: &str) -> Result<Vec<String>, SplitError> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    // Whether an argument is started, which quotes do even when empty
+    let mut started = false;
+    let mut chars = line.chars().enumerate();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\'' => {
+                started = true;
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) => break,
+                        Some((_, c)) => arg.push(c),
+                        None => return Err(unterminated('\'', i)),
+                    }
+                }
+            }
+            '"' => {
+                started = true;
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, '\n')) => {}
+                            Some((_, c)) if "\"\\$`".contains(c) => arg.push(c),
+                            Some((_, c)) => {
+                                arg.push('\\');
+                                arg.push(c);
+                            }
+                            None => return Err(unterminated('"', i)),
+                        },
+                        Some((_, c)) => arg.push(c),
+                        None => return Err(unterminated('"', i)),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some((_, '\n')) => {}
+                Some((_, c)) => {
+                    started = true;
+                    arg.push(c);
+                }
+                None => return Err(SplitError::TrailingBackslash),
+            },
+            c if c.is_whitespace() => {
+                if started {
+                    args.push(std::mem::replace(&mut arg, String::new()));
+                    started = false;
+                }
+            }
+            c => {
+                started = true;
+                arg.push(c);
+            }
+        }
+    }
+    if started {
+        args.push(arg);
+    }
+    Ok(args)
+}
+
+fn unterminated(quote: char, index: usize) -> SplitError {
+    SplitError::UnterminatedQuote {
+        quote,
+        position: index + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split, SplitError};
+
+    fn args(line: &str) -> Vec<String> {
+        split(line).unwrap()
+    }
+
+    #[test]
+    fn whitespace_test() {
+        assert_eq!(args("cli add  file.txt"), ["cli", "add", "file.txt"]);
+        assert_eq!(args("  cli\tadd\n"), ["cli", "add"]);
+        assert!(args("").is_empty());
+        assert!(args(" \t ").is_empty());
+    }
+
+    #[test]
+    fn quote_test() {
+        assert_eq!(
+            args(r#"cli add --name "Alice Smith" file.txt"#),
+            ["cli", "add", "--name", "Alice Smith", "file.txt"]
+        );
+        assert_eq!(args(r#"'a "b" \c'"#), [r#"a "b" \c"#]);
+        assert_eq!(
+            args(r#"--name="Alice Smith"x'y z'"#),
+            ["--name=Alice Smithxy z"]
+        );
+        assert_eq!(args(r#"cli "" ''"#), ["cli", "", ""]);
+    }
+
+    #[test]
+    fn escape_test() {
+        assert_eq!(args(r"a\ b \'c\' \\"), ["a b", "'c'", "\\"]);
+        assert_eq!(args(r#""\"\\\$\`" "\n\a""#), ["\"\\$`", r"\n\a"]);
+        assert_eq!(args("a\\\nb \"c\\\nd\""), ["ab", "cd"]);
+        assert_eq!(args("\\\n"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn error_test() {
+        assert_eq!(
+            split(r#"cli add --name "Alice"#),
+            Err(SplitError::UnterminatedQuote {
+                quote: '"',
+                position: 16
+            })
+        );
+        assert_eq!(
+            split("cli 'add").unwrap_err().to_string(),
+            "Unterminated single quote starting at character 5"
+        );
+        assert_eq!(
+            split(r#""a\"#).unwrap_err().to_string(),
+            "Unterminated double quote starting at character 1"
+        );
+        assert_eq!(split(r"cli \"), Err(SplitError::TrailingBackslash));
+    }
+}
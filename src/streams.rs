@@ -0,0 +1,70 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use crate::terminal::Stream;
+use crate::theme::{self, ColorChoice, Theme};
+
+/// Writer set with `App::out` or `App::err`, shared by the runs of the app
+type SharedWriter = Arc<Mutex<dyn Write + Send>>;
+
+/// Streams the app writes to, help and version going to `out` and errors,
+/// warnings and traces to `err`, stdout and stderr unless set
+#[derive(Clone, Default)]
+pub(crate) struct Streams {
+    pub(crate) out: Option<SharedWriter>,
+    pub(crate) err: Option<SharedWriter>,
+}
+
+/// Streams of commands run without an app
+pub(crate) static STANDARD: Streams = Streams {
+    out: None,
+    err: None,
+};
+
+impl Streams {
+    /// Write the text to the output stream
+    pub(crate) fn out(&self, text: &str) {
+        let _ = self.with(Stream::Stdout, |w| w.write_all(text.as_bytes()));
+    }
+
+    /// Write the text to the error stream
+    pub(crate) fn err(&self, text: &str) {
+        let _ = self.with(Stream::Stderr, |w| w.write_all(text.as_bytes()));
+    }
+
+    /// Call the function with the writer of the stream
+    pub(crate) fn with<T>(&self, stream: Stream, f: impl FnOnce(&mut dyn Write) -> T) -> T {
+        match self.writer(stream) {
+            Some(writer) => {
+                let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+                f(&mut *writer)
+            }
+            None => match stream {
+                Stream::Stdout => f(&mut io::stdout()),
+                Stream::Stderr => f(&mut io::stderr()),
+            },
+        }
+    }
+
+    /// Theme to style output written to the stream like `theme::for_stream`,
+    /// set writers being styled only when the choice is `Always`
+    pub(crate) fn theme<'a>(
+        &self,
+        theme: Option<&'a Theme>,
+        color: ColorChoice,
+        stream: Stream,
+    ) -> Option<&'a Theme> {
+        match (self.writer(stream), color) {
+            (None, _) => theme::for_stream(theme, color, stream),
+            (Some(_), ColorChoice::Always) => theme,
+            (Some(_), _) => None,
+        }
+    }
+
+    fn writer(&self, stream: Stream) -> Option<&SharedWriter> {
+        match stream {
+            Stream::Stdout => self.out.as_ref(),
+            Stream::Stderr => self.err.as_ref(),
+        }
+    }
+}
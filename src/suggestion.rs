@@ -0,0 +1,116 @@
+/// Name that can be suggested, with the name it stands for when it is an
+/// alias
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Candidate<'a> {
+    /// Name as suggested
+    pub(crate) name: &'a str,
+    /// Name of the command or flag the name is an alias of, the name itself
+    /// otherwise
+    pub(crate) canonical: &'a str,
+}
+
+impl<'a> Candidate<'a> {
+    pub(crate) fn new(name: &'a str) -> Self {
+        Self::alias(name, name)
+    }
+
+    pub(crate) fn alias(name: &'a str, canonical: &'a str) -> Self {
+        Self { name, canonical }
+    }
+
+    /// Whether the name is an alias of another name
+    pub(crate) fn is_alias(&self) -> bool {
+        self.name != self.canonical
+    }
+}
+
+/// Candidates close enough to the input to be suggested, closest first,
+/// with only the closest of the names standing for the same canonical name
+///
+/// Candidates as close as each other keep their order.
+pub(crate) fn similar<'a>(input: &str, candidates: &[Candidate<'a>]) -> Vec<Candidate<'a>> {
+    let mut similar: Vec<(usize, Candidate)> = candidates
+        .iter()
+        .map(|c| (distance(input, c.name), *c))
+        .filter(|(d, c)| *d <= threshold(c.name) || c.name.starts_with(input))
+        .collect();
+
+    similar.sort_by_key(|(d, _)| *d);
+    let mut suggested: Vec<Candidate> = Vec::with_capacity(similar.len());
+    for (_, candidate) in similar {
+        if suggested.iter().all(|c| c.canonical != candidate.canonical) {
+            suggested.push(candidate);
+        }
+    }
+    suggested
+}
+
+fn threshold(candidate: &str) -> usize {
+    (candidate.chars().count() / 3).max(1)
+}
+
+/// Levenshtein distance between two strings
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                prev.min(row[j]).min(row[j + 1]) + 1
+            };
+            prev = cur;
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{distance, similar, Candidate};
+
+    #[test]
+    fn distance_test() {
+        assert_eq!(distance("add", "add"), 0);
+        assert_eq!(distance("ad", "add"), 1);
+        assert_eq!(distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn similar_test() {
+        let candidates: Vec<Candidate> = ["add", "remove", "rename"]
+            .iter()
+            .map(|name| Candidate::new(name))
+            .collect();
+        let names = |input: &str| -> Vec<&str> {
+            similar(input, &candidates).iter().map(|c| c.name).collect()
+        };
+        assert_eq!(names("ad"), vec!["add"]);
+        assert_eq!(names("remve"), vec!["remove"]);
+        assert!(names("xyz").is_empty());
+    }
+
+    #[test]
+    fn alias_test() {
+        let candidates = [
+            Candidate::new("install"),
+            Candidate::alias("in", "install"),
+            Candidate::new("init"),
+        ];
+        assert_eq!(
+            similar("ini", &candidates),
+            vec![Candidate::alias("in", "install"), Candidate::new("init")]
+        );
+        assert_eq!(
+            similar("instal", &candidates),
+            vec![Candidate::new("install")]
+        );
+        assert!(similar("inn", &candidates)[0].is_alias());
+    }
+}
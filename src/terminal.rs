@@ -0,0 +1,97 @@
+use std::env;
+use std::process::{Command, Stdio};
+
+/// Standard stream written to
+#[derive(Clone, Copy)]
+pub(crate) enum Stream {
+    Stdout = 1,
+    Stderr = 2,
+}
+
+/// Whether the stream is a terminal
+#[cfg(unix)]
+pub(crate) fn is_terminal(stream: Stream) -> bool {
+    extern "C" {
+        fn isatty(fd: i32) -> i32;
+    }
+    // Safety: isatty only inspects the file descriptor
+    unsafe { isatty(stream as i32) == 1 }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn is_terminal(_: Stream) -> bool {
+    false
+}
+
+/// Terminal the app runs in, as actions see it through `Context`
+///
+/// It is detected when a command line is dispatched, like help detects it,
+/// unless set with `App::terminal` or `Context::with_terminal`, which tests of
+/// actions adapting their output use.
+///
+/// Example
+///
+/// ```
+/// use seahorse::{App, Context, Terminal};
+///
+/// let app = App::new("cli")
+///     .action(|c: &Context| assert_eq!(c.terminal_width(), Some(100)))
+///     .terminal(Terminal {
+///         stdout_is_tty: true,
+///         stderr_is_tty: true,
+///         width: Some(100),
+///     });
+/// assert_eq!(app.try_run(vec!["cli".to_string()]), Ok(0));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Terminal {
+    /// Whether stdout is a terminal
+    pub stdout_is_tty: bool,
+    /// Whether stderr is a terminal
+    pub stderr_is_tty: bool,
+    /// Number of columns of the terminal stdout is, `None` when stdout is
+    /// not a terminal
+    pub width: Option<usize>,
+}
+
+impl Terminal {
+    /// Terminal the app runs in, the width read from `COLUMNS` or `stty size`
+    pub fn detect() -> Self {
+        Self {
+            stdout_is_tty: is_terminal(Stream::Stdout),
+            stderr_is_tty: is_terminal(Stream::Stderr),
+            width: width(),
+        }
+    }
+}
+
+/// Number of lines of the terminal stdout is, from `LINES` or `stty size`
+pub(crate) fn height() -> Option<usize> {
+    size("LINES", 0)
+}
+
+/// Number of columns of the terminal stdout is, from `COLUMNS` or `stty size`
+pub(crate) fn width() -> Option<usize> {
+    size("COLUMNS", 1)
+}
+
+/// Size from the environment variable, or the field of `stty size`, on a terminal
+fn size(var: &str, field: usize) -> Option<usize> {
+    if !is_terminal(Stream::Stdout) {
+        return None;
+    }
+    if let Some(size) = env::var(var).ok().and_then(|s| s.parse().ok()) {
+        return Some(size);
+    }
+    let output = Command::new("stty")
+        .arg("size")
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .nth(field)?
+        .parse()
+        .ok()
+}
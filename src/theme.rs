@@ -0,0 +1,427 @@
+use std::env;
+use std::str::FromStr;
+
+use crate::error::{Error, ErrorHandler};
+use crate::messages::{DefaultMessages, Messages};
+use crate::streams::Streams;
+use crate::terminal::{self, Stream};
+
+/// Terminal color of a style
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn code(self) -> u8 {
+        match self {
+            Color::Black => 30,
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+        }
+    }
+}
+
+/// Weight of a style
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Weight {
+    Normal,
+    Bold,
+    Dim,
+}
+
+/// Style of a part of help or errors
+///
+/// Example
+///
+/// ```
+/// use seahorse::theme::{Color, Style};
+///
+/// let style = Style::color(Color::Cyan).bold();
+/// assert_eq!(style.paint("Flags:"), "\x1b[1;36mFlags:\x1b[0m");
+/// assert_eq!(Style::plain().paint("Flags:"), "Flags:");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Style {
+    /// Color of the text, the terminal's when `None`
+    pub color: Option<Color>,
+    /// Weight of the text
+    pub weight: Weight,
+}
+
+impl Style {
+    /// Style leaving text as is
+    pub fn plain() -> Self {
+        Self {
+            color: None,
+            weight: Weight::Normal,
+        }
+    }
+
+    /// Style coloring text
+    pub fn color(color: Color) -> Self {
+        Self {
+            color: Some(color),
+            weight: Weight::Normal,
+        }
+    }
+
+    /// Set text bold
+    pub fn bold(mut self) -> Self {
+        self.weight = Weight::Bold;
+        self
+    }
+
+    /// Set text dim
+    pub fn dim(mut self) -> Self {
+        self.weight = Weight::Dim;
+        self
+    }
+
+    /// Wrap the text in the escapes of the style
+    pub fn paint(&self, text: &str) -> String {
+        let mut codes = Vec::new();
+        match self.weight {
+            Weight::Normal => {}
+            Weight::Bold => codes.push("1".to_string()),
+            Weight::Dim => codes.push("2".to_string()),
+        }
+        if let Some(color) = self.color {
+            codes.push(color.code().to_string());
+        }
+        if codes.is_empty() || text.is_empty() {
+            return text.to_string();
+        }
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self::plain()
+    }
+}
+
+/// Styles of help and errors written to a terminal
+///
+/// Styles are only applied when the output is a terminal and `NO_COLOR` is
+/// not set, so redirected output stays plain whatever the theme, unless the
+/// [`ColorChoice`] of the app says otherwise.
+///
+/// Example
+///
+/// ```
+/// use seahorse::theme::{Color, Style, Theme};
+/// use seahorse::App;
+///
+/// let app = App::new("cli").theme(Theme {
+///     header: Style::color(Color::Cyan).bold(),
+///     error: Style::plain().bold(),
+///     ..Theme::default()
+/// });
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    /// Section headers of help such as `Flags:`
+    pub header: Style,
+    /// Command names listed in help
+    pub command: Style,
+    /// Flags listed in help
+    pub flag: Style,
+    /// Error messages
+    pub error: Style,
+    /// Usage line and help hint following errors
+    pub hint: Style,
+}
+
+impl Theme {
+    /// Theme leaving help and errors unstyled
+    pub fn plain() -> Self {
+        Self {
+            header: Style::plain(),
+            command: Style::plain(),
+            flag: Style::plain(),
+            error: Style::plain(),
+            hint: Style::plain(),
+        }
+    }
+
+    /// Style the help text
+    pub fn paint_help(&self, text: &str) -> String {
+        self.paint_help_in(text, &DefaultMessages)
+    }
+
+    /// Style the help text written with the headings of the catalog
+    pub(crate) fn paint_help_in(&self, text: &str, messages: &dyn Messages) -> String {
+        let heading = |name: String| name + ":";
+        let lists = [
+            heading(messages.args_heading()),
+            heading(messages.flags_heading()),
+        ];
+        let fixed = [
+            heading(messages.name_heading()),
+            heading(messages.author_heading()),
+            heading(messages.description_heading()),
+            heading(messages.usage_heading()),
+            heading(messages.aliases_heading()),
+            heading(messages.examples_heading()),
+            heading(messages.version_heading()),
+        ];
+        let mut painted = String::with_capacity(text.len());
+        let mut section = "";
+        for line in lines(text) {
+            let (content, end) = split_newline(line);
+            if !content.starts_with(char::is_whitespace) && content.ends_with(':') {
+                section = content;
+                painted += &self.header.paint(content);
+            } else if content.starts_with('\t') {
+                let item = &content[1..];
+                // Sections other than the fixed ones are command categories
+                let style = if lists.iter().any(|h| h == section) {
+                    self.flag
+                } else if section.is_empty() || fixed.iter().any(|h| h == section) {
+                    Style::plain()
+                } else if item.starts_with('-') {
+                    // Flag sections list flags
+                    self.flag
+                } else {
+                    self.command
+                };
+                // Command aliases like `add (alias: a)` are part of the name
+                let name_len = match item.find(" : ") {
+                    Some(index) => item[..index].trim_end().len(),
+                    None => item.len(),
+                };
+                painted.push('\t');
+                painted += &style.paint(&item[..name_len]);
+                painted += &item[name_len..];
+            } else {
+                painted += content;
+            }
+            painted += end;
+        }
+        painted
+    }
+
+    /// Style the error as written by the error handler
+    ///
+    /// The first line is the message, and the lines after it other than the
+    /// indented command line are the usage line and help hint.
+    pub fn paint_error(&self, text: &str) -> String {
+        let mut painted = String::with_capacity(text.len());
+        for (i, line) in lines(text).into_iter().enumerate() {
+            let (content, end) = split_newline(line);
+            if i == 0 {
+                painted += &self.error.paint(content);
+            } else if !content.is_empty() && !content.starts_with(char::is_whitespace) {
+                painted += &self.hint.paint(content);
+            } else {
+                painted += content;
+            }
+            painted += end;
+        }
+        painted
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            header: Style::color(Color::Yellow).bold(),
+            command: Style::color(Color::Green),
+            flag: Style::color(Color::Cyan),
+            error: Style::color(Color::Red).bold(),
+            hint: Style::plain().dim(),
+        }
+    }
+}
+
+/// Lines of the text, each with its newline
+fn lines(text: &str) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut rest = text;
+    while let Some(index) = rest.find('\n') {
+        lines.push(&rest[..=index]);
+        rest = &rest[index + 1..];
+    }
+    if !rest.is_empty() {
+        lines.push(rest);
+    }
+    lines
+}
+
+fn split_newline(line: &str) -> (&str, &str) {
+    if line.ends_with('\n') {
+        line.split_at(line.len() - 1)
+    } else {
+        (line, "")
+    }
+}
+
+/// When help and errors are styled
+///
+/// Example
+///
+/// ```
+/// use seahorse::theme::ColorChoice;
+///
+/// assert_eq!("always".parse(), Ok(ColorChoice::Always));
+/// assert!("sometimes".parse::<ColorChoice>().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorChoice {
+    /// Style output written to a terminal unless `NO_COLOR` is set
+    Auto,
+    /// Style output wherever it's written
+    Always,
+    /// Never style output
+    Never,
+}
+
+impl ColorChoice {
+    /// Names of the choices, as given to `--color`
+    pub(crate) const NAMES: [&'static str; 3] = ["auto", "always", "never"];
+}
+
+impl Default for ColorChoice {
+    fn default() -> Self {
+        ColorChoice::Auto
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!("expected auto, always or never, got \"{}\"", s)),
+        }
+    }
+}
+
+/// Theme to style output written to the stream, `None` when the choice is
+/// `Never`, or `Auto` and the stream is not a terminal or `NO_COLOR` is set
+pub(crate) fn for_stream(
+    theme: Option<&Theme>,
+    color: ColorChoice,
+    stream: Stream,
+) -> Option<&Theme> {
+    match color {
+        ColorChoice::Always => theme,
+        ColorChoice::Never => None,
+        ColorChoice::Auto
+            if env::var_os("NO_COLOR").is_some() || !terminal::is_terminal(stream) =>
+        {
+            None
+        }
+        ColorChoice::Auto => theme,
+    }
+}
+
+/// Write the error to the error stream with the handler, styled by the
+/// color choice
+pub(crate) fn report(
+    handler: &ErrorHandler,
+    theme: Option<&Theme>,
+    color: ColorChoice,
+    streams: &Streams,
+    error: &Error,
+) {
+    match streams.theme(theme, color, Stream::Stderr) {
+        Some(theme) => {
+            let mut written = Vec::new();
+            handler.handle(error, &mut written);
+            streams.err(&theme.paint_error(&String::from_utf8_lossy(&written)));
+        }
+        None => streams.with(Stream::Stderr, |w| handler.handle(error, w)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{for_stream, Color, ColorChoice, Style, Theme};
+    use crate::terminal::Stream;
+
+    const HELP: &str = "Name:\n\tcli\n\nUsage:\n\tcli [command] [flags]\n\n\
+                        Flags:\n\t--verbose, -v : Verbose output\n\t--help, -h    : Show help\n\n\
+                        Commands:\n\tadd (alias: a) : Add a remote\n";
+
+    #[test]
+    fn paint_help_test() {
+        let theme = Theme {
+            header: Style::color(Color::Cyan).bold(),
+            command: Style::color(Color::Blue),
+            flag: Style::plain().dim(),
+            ..Theme::plain()
+        };
+        assert_eq!(
+            theme.paint_help(HELP),
+            "\x1b[1;36mName:\x1b[0m\n\tcli\n\n\
+             \x1b[1;36mUsage:\x1b[0m\n\tcli [command] [flags]\n\n\
+             \x1b[1;36mFlags:\x1b[0m\n\
+             \t\x1b[2m--verbose, -v\x1b[0m : Verbose output\n\
+             \t\x1b[2m--help, -h\x1b[0m    : Show help\n\n\
+             \x1b[1;36mCommands:\x1b[0m\n\t\x1b[34madd (alias: a)\x1b[0m : Add a remote\n"
+        );
+        assert!(Theme::default()
+            .paint_help(HELP)
+            .contains("\x1b[1;33mFlags:"));
+        assert_eq!(Theme::plain().paint_help(HELP), HELP);
+        assert_eq!(
+            theme.paint_help("Remote management:\n\tpush : Push changes\n"),
+            "\x1b[1;36mRemote management:\x1b[0m\n\t\x1b[34mpush\x1b[0m : Push changes\n"
+        );
+    }
+
+    #[test]
+    fn paint_error_test() {
+        let error =
+            "Unknown flag \"--x\"\nUsage: cli [flags]\nTry 'cli --help' for more information.\n";
+        let theme = Theme {
+            error: Style::color(Color::Magenta),
+            ..Theme::default()
+        };
+        assert_eq!(
+            theme.paint_error(error),
+            "\x1b[35mUnknown flag \"--x\"\x1b[0m\n\
+             \x1b[2mUsage: cli [flags]\x1b[0m\n\
+             \x1b[2mTry 'cli --help' for more information.\x1b[0m\n"
+        );
+        assert_eq!(Theme::plain().paint_error(error), error);
+        assert!(!Theme::plain().paint_error(error).contains('\x1b'));
+    }
+
+    #[test]
+    fn for_stream_test() {
+        let theme = Theme::default();
+        assert_eq!(
+            for_stream(Some(&theme), ColorChoice::Always, Stream::Stderr),
+            Some(&theme)
+        );
+        assert_eq!(
+            for_stream(Some(&theme), ColorChoice::Never, Stream::Stdout),
+            None
+        );
+        assert_eq!(for_stream(None, ColorChoice::Always, Stream::Stdout), None);
+        assert_eq!("never".parse(), Ok(ColorChoice::Never));
+        assert_eq!(
+            "yes".parse::<ColorChoice>(),
+            Err("expected auto, always or never, got \"yes\"".to_string())
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use crate::{App, Command, Flag};
+
+/// Write the command tree of the app, listing the flags of each command
+/// when `flags` is set
+pub(crate) fn write_app<W: Write + ?Sized>(app: &App, flags: bool, w: &mut W) -> io::Result<()> {
+    writeln!(w, "{}{}", app.name, flag_count(&app.flags))?;
+    write_level(&app.flags, &app.commands, flags, 1, w)
+}
+
+/// Write the flags and commands one level below a command
+fn write_level<W: Write + ?Sized>(
+    flags: &Option<Vec<Flag>>,
+    commands: &Option<Vec<Arc<Command>>>,
+    show_flags: bool,
+    depth: usize,
+    w: &mut W,
+) -> io::Result<()> {
+    let indent = "  ".repeat(depth);
+    if show_flags {
+        for flag in flags.iter().flatten() {
+            writeln!(w, "{}{}", indent, flag_line(flag))?;
+        }
+    }
+    for command in commands.iter().flatten() {
+        writeln!(w, "{}{}", indent, command_line(command))?;
+        write_level(&command.flags, &command.commands, show_flags, depth + 1, w)?;
+    }
+    Ok(())
+}
+
+/// Command with its aliases, category, hidden marker and flag count, like
+/// `remote [aliases: r] [category: Sync] (2 flags)`
+fn command_line(command: &Command) -> String {
+    let mut line = command.name.clone();
+    push_list(
+        &mut line,
+        "aliases",
+        command.alias.iter().flatten().cloned(),
+    );
+    push_list(
+        &mut line,
+        "hidden aliases",
+        command.hidden_alias.iter().flatten().cloned(),
+    );
+    if let Some(category) = &command.category {
+        line += &format!(" [category: {}]", category);
+    }
+    if command.hidden {
+        line += " [hidden]";
+    }
+    line + &flag_count(&command.flags)
+}
+
+/// Flag with its type and markers, like `--port, -p : uint [global]`
+fn flag_line(flag: &Flag) -> String {
+    let mut line = flag.name_text();
+    for alias in flag.alias.iter().flatten() {
+        line += &format!(", -{}", alias);
+    }
+    line += &format!(" : {}", flag.flag_type.name());
+    push_list(
+        &mut line,
+        "hidden aliases",
+        flag.hidden_alias
+            .iter()
+            .flatten()
+            .map(|a| format!("-{}", a)),
+    );
+    if flag.global {
+        line += " [global]";
+    }
+    if flag.hidden {
+        line += " [hidden]";
+    }
+    line
+}
+
+/// ` (n flags)`, empty without flags
+fn flag_count(flags: &Option<Vec<Flag>>) -> String {
+    match flags.as_ref().map_or(0, Vec::len) {
+        0 => String::new(),
+        1 => " (1 flag)".to_string(),
+        n => format!(" ({} flags)", n),
+    }
+}
+
+/// Append ` [label: a, b]` unless there are no items
+fn push_list(line: &mut String, label: &str, items: impl Iterator<Item = String>) {
+    let items: Vec<String> = items.collect();
+    if !items.is_empty() {
+        *line += &format!(" [{}: {}]", label, items.join(", "));
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// Resolution of an unknown command returned by the handler set with
+/// [`App::on_unknown_command`](crate::App::on_unknown_command)
+#[derive(Clone, Debug, PartialEq)]
+pub enum UnknownCommand {
+    /// The handler dealt with the command, the app stops
+    Handled,
+    /// Run the command with this name instead
+    Retry(String),
+    /// Run the app as if there were no handler
+    Fallthrough,
+}
+
+/// Handler of command names matching no command, receiving the name and the
+/// arguments following it
+///
+/// Example
+///
+/// ```
+/// use seahorse::{UnknownCommand, UnknownCommandHandler};
+///
+/// let handler = UnknownCommandHandler::from(|name: &str, _: &[String]| match name {
+///     "rm" => UnknownCommand::Retry("remove".to_string()),
+///     _ => UnknownCommand::Fallthrough,
+/// });
+/// ```
+#[derive(Clone)]
+pub struct UnknownCommandHandler {
+    inner: Arc<UnknownCommandFn>,
+}
+
+type UnknownCommandFn = dyn Fn(&str, &[String]) -> UnknownCommand + Send + Sync;
+
+impl UnknownCommandHandler {
+    pub fn handle(&self, name: &str, args: &[String]) -> UnknownCommand {
+        (self.inner)(name, args)
+    }
+}
+
+impl fmt::Debug for UnknownCommandHandler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("UnknownCommandHandler")
+    }
+}
+
+impl<F> From<F> for UnknownCommandHandler
+where
+    for<'a, 'b> F: Fn(&'a str, &'b [String]) -> UnknownCommand + Send + Sync + 'static,
+{
+    fn from(value: F) -> Self {
+        Self {
+            inner: Arc::new(value),
+        }
+    }
+}
+
+/// Resolution of an unknown flag returned by the handler set with
+/// [`App::on_unknown_flag`](crate::App::on_unknown_flag)
+#[derive(Clone, Debug, PartialEq)]
+pub enum UnknownFlag {
+    /// Keep the flag as a positional argument
+    Ignore,
+    /// Fail before the action runs
+    Error,
+    /// Read the flag as the flag with this name
+    Replace(String),
+}
+
+/// Handler of flags matching no flag, receiving the flag and the path of the
+/// command it is given to
+///
+/// Example
+///
+/// ```
+/// use seahorse::{UnknownFlag, UnknownFlagHandler};
+///
+/// let handler = UnknownFlagHandler::from(|flag: &str, _: &[String]| match flag {
+///     "--colour" => UnknownFlag::Replace("color".to_string()),
+///     _ => UnknownFlag::Error,
+/// });
+/// ```
+#[derive(Clone)]
+pub struct UnknownFlagHandler {
+    inner: Arc<UnknownFlagFn>,
+}
+
+type UnknownFlagFn = dyn Fn(&str, &[String]) -> UnknownFlag + Send + Sync;
+
+impl UnknownFlagHandler {
+    pub fn handle(&self, flag: &str, path: &[String]) -> UnknownFlag {
+        (self.inner)(flag, path)
+    }
+}
+
+impl fmt::Debug for UnknownFlagHandler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("UnknownFlagHandler")
+    }
+}
+
+impl<F> From<F> for UnknownFlagHandler
+where
+    for<'a, 'b> F: Fn(&'a str, &'b [String]) -> UnknownFlag + Send + Sync + 'static,
+{
+    fn from(value: F) -> Self {
+        Self {
+            inner: Arc::new(value),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// Check of a command line value, returning why the value is rejected
+///
+/// Example
+///
+/// ```
+/// use seahorse::Validator;
+///
+/// let validator = Validator::from(|v: &str| match v.parse::<u16>() {
+///     Ok(port) if port > 0 => Ok(()),
+///     _ => Err("must be between 1 and 65535".to_string()),
+/// });
+/// ```
+#[derive(Clone)]
+pub struct Validator {
+    inner: Arc<ValidatorFn>,
+}
+
+type ValidatorFn = dyn Fn(&str) -> Result<(), String> + Send + Sync;
+
+impl Validator {
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        (self.inner)(value)
+    }
+}
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Validator")
+    }
+}
+
+impl<F> From<F> for Validator
+where
+    for<'a> F: Fn(&'a str) -> Result<(), String> + Send + Sync + 'static,
+{
+    fn from(value: F) -> Self {
+        Self {
+            inner: Arc::new(value),
+        }
+    }
+}
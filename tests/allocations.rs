@@ -0,0 +1,82 @@
+use seahorse::{App, Command, Context, Flag, FlagType};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn action(c: &Context) {
+    assert_eq!(c.string_flag("name"), Ok("origin".to_string()));
+}
+
+fn app() -> App {
+    App::new("cli").command(
+        Command::new("remote")
+            .flag(Flag::new("verbose", FlagType::Bool).alias("v"))
+            .command(
+                Command::new("add")
+                    .action(action)
+                    .flag(Flag::new("name", FlagType::String).alias("n"))
+                    .flag(Flag::new("fetch", FlagType::Bool).alias("f"))
+                    .flag(Flag::new("depth", FlagType::Int)),
+            ),
+    )
+}
+
+/// Allocations made by running the app, argument vector excluded
+fn allocations(app: &App, args: &[&str]) -> usize {
+    let args = args.iter().map(|a| a.to_string()).collect();
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    app.run(args);
+    ALLOCATIONS.load(Ordering::SeqCst) - before
+}
+
+#[test]
+fn run_allocations_test() {
+    let app = app();
+    let args = ["cli", "remote", "add", "--name=origin", "-f"];
+    let base = allocations(&app, &args);
+    let positional = allocations(
+        &app,
+        &[&args[..], &["a", "b", "c", "d", "e", "f", "g", "h"]].concat(),
+    );
+
+    // Arguments are moved through parsing, never copied
+    assert_eq!(positional, base);
+    assert!(base < 90, "{} allocations", base);
+}
+
+#[test]
+fn compiled_allocations_test() {
+    let app = app();
+    let args = ["cli", "remote", "add", "--name=origin", "-f"];
+    let base = allocations(&app, &args);
+    let app = app.compile();
+    let args = args.iter().map(|a| a.to_string()).collect();
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    app.run(args);
+    let compiled = ALLOCATIONS.load(Ordering::SeqCst) - before;
+
+    // Flag tables and the help text are built once
+    assert!(
+        compiled < base,
+        "{} allocations, {} compiling",
+        compiled,
+        base
+    );
+}
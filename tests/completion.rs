@@ -0,0 +1,397 @@
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+use std::sync::{Arc, Mutex};
+
+use seahorse::error::ErrorKind;
+use seahorse::{App, Arg, Command, Context, Flag, FlagType, Shell, ValueHint};
+
+fn app() -> App {
+    App::new("cli")
+        .description("Sample cli")
+        .flag(
+            Flag::new("verbose", FlagType::Bool)
+                .description("Verbose output")
+                .alias("v"),
+        )
+        .flag(Flag::new("debug", FlagType::Bool).hidden())
+        .command(
+            Command::new("remote")
+                .description("Manage remotes")
+                .alias("r")
+                .flag(
+                    Flag::new("format", FlagType::String)
+                        .description("Output format")
+                        .alias("f")
+                        .choices(&["json", "yaml"]),
+                )
+                .command(
+                    Command::new("add")
+                        .description("Add a remote: it's fetched right away")
+                        .flag(
+                            Flag::new("name", FlagType::String)
+                                .description("Remote name [default: 'origin']"),
+                        ),
+                )
+                .command(
+                    Command::new("remove")
+                        .flag(Flag::new("force", FlagType::Bool).usage("--force, skip checks")),
+                ),
+        )
+        .command(Command::new("secret").hidden())
+}
+
+fn generate(shell: Shell) -> String {
+    let mut script = Vec::new();
+    app().generate_completion(shell, &mut script).unwrap();
+    String::from_utf8(script).unwrap()
+}
+
+#[test]
+fn bash_completion_test() {
+    let script = generate(Shell::Bash);
+
+    assert!(script.contains("complete -F _cli 'cli'"));
+    assert!(script.contains("opts='remote r --verbose -v --help -h'"));
+    assert!(script.contains("'cli,remote' | 'cli,r')"));
+    assert!(script.contains("opts='add remove --format -f --help -h'"));
+    assert!(script.contains("'cli__remote,add')"));
+    assert!(script.contains("opts='--name --help -h'"));
+    assert!(script.contains("COMPREPLY=($(compgen -W 'json yaml' -- \"${cur}\"))"));
+    assert!(!script.contains("secret"));
+    assert!(!script.contains("debug"));
+}
+
+#[test]
+fn zsh_completion_test() {
+    assert_eq!(generate(Shell::Zsh), include_str!("golden/completion.zsh"));
+}
+
+#[test]
+fn fish_completion_test() {
+    assert_eq!(
+        generate(Shell::Fish),
+        include_str!("golden/completion.fish")
+    );
+}
+
+#[test]
+fn powershell_completion_test() {
+    assert_eq!(
+        generate(Shell::PowerShell),
+        include_str!("golden/completion.ps1")
+    );
+}
+
+#[test]
+fn completion_display_order_test() {
+    let app = App::new("cli")
+        .command(Command::new("world"))
+        .command(Command::new("hello").display_order(0));
+    let mut script = Vec::new();
+    app.generate_completion(Shell::Bash, &mut script).unwrap();
+    assert!(String::from_utf8(script)
+        .unwrap()
+        .contains("opts='hello world --help -h'"));
+
+    let app = App::new("cli")
+        .command(Command::new("world"))
+        .command(Command::new("hello"))
+        .sort_help_alphabetically();
+    let mut script = Vec::new();
+    app.generate_completion(Shell::Bash, &mut script).unwrap();
+    assert!(String::from_utf8(script)
+        .unwrap()
+        .contains("opts='hello world --help -h'"));
+}
+
+fn dynamic_app() -> App {
+    App::new("cli").command(
+        Command::new("deploy")
+            .flag(
+                Flag::new("project", FlagType::String)
+                    .alias("p")
+                    .complete_with(|_: &Context, _: &str| vec!["api".to_string()]),
+            )
+            .complete_args_with(|_: &Context, _: &str| vec!["staging".to_string()]),
+    )
+}
+
+fn generate_dynamic(shell: Shell) -> String {
+    let mut script = Vec::new();
+    dynamic_app()
+        .generate_completion(shell, &mut script)
+        .unwrap();
+    String::from_utf8(script).unwrap()
+}
+
+#[test]
+fn dynamic_completion_test() {
+    let delegate = r#"COMPREPLY=($("${COMP_WORDS[0]}" __complete "$((COMP_CWORD - 1))" "${COMP_WORDS[@]:1}"))"#;
+    let bash = generate_dynamic(Shell::Bash);
+    assert_eq!(bash.matches(delegate).count(), 2);
+    assert!(!bash.contains("compgen -W \"${opts}\" -- \"${cur}\"))\n            ;;\n    esac"));
+
+    let zsh = generate_dynamic(Shell::Zsh);
+    assert!(zsh.contains("    local -a __cli_words\n    __cli_words=(\"${words[@]}\")\n"));
+    assert!(zsh.contains("'(--project -p)'{--project=,-p}':project:__cli_complete'"));
+    assert!(zsh.contains("'*: :__cli_complete'"));
+    assert!(zsh.contains("__cli_complete() {"));
+
+    let fish = generate_dynamic(Shell::Fish);
+    assert!(fish.starts_with("function __fish_cli_complete\n"));
+    assert!(fish.contains("'cli' __complete (count $tokens) $tokens (commandline -ct)"));
+    assert!(fish.contains("-l 'project' -s 'p' -r -f -a '(__fish_cli_complete)'"));
+    assert!(fish.contains(
+        "complete -c 'cli' -n '__fish_seen_subcommand_from deploy' -f -a '(__fish_cli_complete)'"
+    ));
+
+    let powershell = generate_dynamic(Shell::PowerShell);
+    assert!(powershell.contains("$words = @($commandElements | Select-Object -Skip 1"));
+    assert!(powershell.contains("            & 'cli' __complete $index @words | ForEach-Object"));
+}
+
+fn choices_app() -> App {
+    App::new("cli").command(
+        Command::new("export")
+            .flag(
+                Flag::new("format", FlagType::String)
+                    .description("Output format")
+                    .choices(&["json", "yaml", "toml"]),
+            )
+            .flag(
+                Flag::new("region", FlagType::String)
+                    .alias("r")
+                    .choices(&["us east", "eu-west", "it's"]),
+            ),
+    )
+}
+
+fn generate_choices(shell: Shell) -> String {
+    let mut script = Vec::new();
+    choices_app()
+        .generate_completion(shell, &mut script)
+        .unwrap();
+    String::from_utf8(script).unwrap()
+}
+
+#[test]
+fn choices_completion_test() {
+    assert_eq!(
+        generate_choices(Shell::Bash),
+        include_str!("golden/choices.bash")
+    );
+    assert_eq!(
+        generate_choices(Shell::Zsh),
+        include_str!("golden/choices.zsh")
+    );
+    assert_eq!(
+        generate_choices(Shell::Fish),
+        include_str!("golden/choices.fish")
+    );
+}
+
+/// App three commands deep, with aliases and a hidden command at each level
+fn nested_app() -> App {
+    App::new("cli")
+        .flag(Flag::new("verbose", FlagType::Bool).alias("v"))
+        .command(
+            Command::new("remote")
+                .alias("r")
+                .description("Manage remotes")
+                .command(
+                    Command::new("branch")
+                        .alias("b")
+                        .description("Manage remote branches")
+                        .command(
+                            Command::new("add")
+                                .alias("a")
+                                .description("Add a branch")
+                                .flag(Flag::new("track", FlagType::Bool).alias("t")),
+                        )
+                        .command(Command::new("prune").flag(Flag::new("dry-run", FlagType::Bool)))
+                        .command(Command::new("debug").hidden()),
+                )
+                .command(Command::new("remove").alias("rm")),
+        )
+}
+
+fn generate_nested(shell: Shell) -> String {
+    let mut script = Vec::new();
+    nested_app()
+        .generate_completion(shell, &mut script)
+        .unwrap();
+    String::from_utf8(script).unwrap()
+}
+
+#[test]
+fn nested_completion_test() {
+    let golden = [
+        (Shell::Bash, include_str!("golden/nested.bash")),
+        (Shell::Zsh, include_str!("golden/nested.zsh")),
+        (Shell::Fish, include_str!("golden/nested.fish")),
+        (Shell::PowerShell, include_str!("golden/nested.ps1")),
+    ];
+    for (shell, golden) in golden.iter() {
+        let script = generate_nested(*shell);
+        assert_eq!(script, *golden, "{:?}", shell);
+        assert!(!script.contains("debug"), "{:?}", shell);
+    }
+}
+
+/// App with a flag and an argument of each value hint
+fn hints_app() -> App {
+    let flag = |name: &str, hint| Flag::new(name, FlagType::String).value_hint(hint);
+    let arg = |name: &str, hint| Arg::new(name, FlagType::String).value_hint(hint);
+    App::new("cli")
+        .command(
+            Command::new("build")
+                .flag(Flag::output())
+                .flag(flag("config", ValueHint::FilePath).alias("c"))
+                .flag(flag("target-dir", ValueHint::DirPath))
+                .flag(flag("linker", ValueHint::ExecutablePath))
+                .flag(flag("label", ValueHint::Other))
+                .arg(arg("SRC", ValueHint::FilePath)),
+        )
+        .command(Command::new("cd").arg(arg("DIR", ValueHint::DirPath)))
+        .command(Command::new("exec").arg(arg("PROGRAM", ValueHint::ExecutablePath)))
+        .command(Command::new("open").arg(arg("PATH", ValueHint::AnyPath)))
+        .command(Command::new("tag").arg(arg("NAME", ValueHint::Other)))
+}
+
+#[test]
+fn value_hint_completion_test() {
+    let golden = [
+        (Shell::Bash, include_str!("golden/hints.bash")),
+        (Shell::Zsh, include_str!("golden/hints.zsh")),
+        (Shell::Fish, include_str!("golden/hints.fish")),
+        (Shell::PowerShell, include_str!("golden/hints.ps1")),
+    ];
+    for (shell, golden) in golden.iter() {
+        let mut script = Vec::new();
+        hints_app()
+            .generate_completion(*shell, &mut script)
+            .unwrap();
+        assert_eq!(String::from_utf8(script).unwrap(), *golden, "{:?}", shell);
+    }
+}
+
+/// Writer keeping what the app writes for the test to read
+#[derive(Clone, Default)]
+struct Shared(Arc<Mutex<Vec<u8>>>);
+
+impl Shared {
+    fn take(&self) -> String {
+        String::from_utf8(std::mem::replace(&mut *self.0.lock().unwrap(), Vec::new())).unwrap()
+    }
+}
+
+impl Write for Shared {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// The only test of the binary reading the environment
+#[test]
+fn install_completion_test() {
+    let dir = env::temp_dir().join(format!("seahorse-install-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let home = dir.join("home");
+    env::set_var("HOME", &home);
+    env::remove_var("XDG_DATA_HOME");
+    env::remove_var("XDG_CONFIG_HOME");
+
+    let out = Shared::default();
+    let app = app().command(Command::completions()).out(out.clone());
+    let script = |shell: Shell| {
+        let mut script = Vec::new();
+        app.generate_completion(shell, &mut script).unwrap();
+        String::from_utf8(script).unwrap()
+    };
+    let installed = |path: &Path, shell: Shell| {
+        assert_eq!(
+            fs::read_to_string(path).unwrap(),
+            script(shell),
+            "{:?}",
+            shell
+        );
+    };
+
+    let bash = home.join(".local/share/bash-completion/completions/cli");
+    let zsh = home.join(".zfunc/_cli");
+    let fish = home.join(".config/fish/completions/cli.fish");
+    for &(shell, path) in &[
+        (Shell::Bash, &bash),
+        (Shell::Zsh, &zsh),
+        (Shell::Fish, &fish),
+    ] {
+        assert_eq!(app.install_completion(shell).as_ref(), Ok(path));
+        installed(path, shell);
+    }
+
+    fs::write(&bash, "old").unwrap();
+    let error = app.install_completion(Shell::Bash).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Completion);
+    assert_eq!(
+        error.message,
+        format!("Completion script \"{}\" already exists", bash.display())
+    );
+    assert_eq!(fs::read_to_string(&bash).unwrap(), "old");
+    assert_eq!(app.reinstall_completion(Shell::Bash), Ok(bash.clone()));
+    installed(&bash, Shell::Bash);
+    assert_eq!(
+        app.install_completion(Shell::PowerShell)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::Completion
+    );
+
+    env::set_var("XDG_DATA_HOME", dir.join("data"));
+    env::set_var("XDG_CONFIG_HOME", dir.join("config"));
+    let run = |line: &str| app.try_run(line.split(' ').map(String::from).collect());
+    let bash = dir.join("data/bash-completion/completions/cli");
+    assert_eq!(run("cli completions install bash"), Ok(0));
+    assert_eq!(
+        out.take(),
+        format!("Installed completion script to \"{}\"\n", bash.display())
+    );
+    installed(&bash, Shell::Bash);
+    assert_eq!(
+        run("cli completions install bash").unwrap_err().kind(),
+        ErrorKind::Completion
+    );
+    assert_eq!(run("cli completions install bash --force"), Ok(0));
+    out.take();
+
+    assert_eq!(run("cli completions install fish"), Ok(0));
+    installed(&dir.join("config/fish/completions/cli.fish"), Shell::Fish);
+    out.take();
+    fs::remove_file(&zsh).unwrap();
+    assert_eq!(run("cli completions install zsh"), Ok(0));
+    assert_eq!(
+        out.take(),
+        format!(
+            "Installed completion script to \"{}\"\n\
+             Add 'fpath=(~/.zfunc $fpath)' before 'compinit' in ~/.zshrc\n",
+            zsh.display()
+        )
+    );
+    installed(&zsh, Shell::Zsh);
+    assert_eq!(
+        run("cli completions install tcsh").unwrap_err().kind(),
+        ErrorKind::InvalidValue
+    );
+    // Installed by the app rather than by an action of the command
+    let completions = Command::completions();
+    assert!(completions.commands.unwrap()[0].action.is_none());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
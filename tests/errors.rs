@@ -0,0 +1,296 @@
+use seahorse::error::{ErrorHandler, ErrorKind};
+use seahorse::{App, Arg, Command, Context, Error, Flag, FlagType, UnknownFlag};
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::process;
+
+const CHILD: &str = "SEAHORSE_ERRORS_TEST_ARGS";
+/// Directory of the files the child app writes its output and errors to
+const STREAMS: &str = "SEAHORSE_ERRORS_TEST_STREAMS";
+
+fn app() -> App {
+    App::new("cli")
+        .add_color_flag()
+        .error_handler(|e: &Error, w: &mut dyn Write| match e.kind {
+            ErrorKind::MissingArgument => {
+                let _ = writeln!(w, "{}: {}", e.command.join(" "), e.message);
+            }
+            _ => ErrorHandler::default().handle(e, w),
+        })
+        .prompt(|question: &str| {
+            eprint!("{}", question);
+            Ok("alice".to_string())
+        })
+        .command(
+            Command::new("login")
+                .action(|c: &Context| println!("login as {:?}", c.string_flag("user")))
+                .flag(Flag::new("user", FlagType::String).prompt_if_missing()),
+        )
+        .command(
+            Command::new("deploy")
+                .action(|c: &Context| c.exit(3))
+                .after_action(|c: &Context| println!("released lock, status {:?}", c.exit_code())),
+        )
+        .command(
+            Command::new("serve")
+                .action(|c: &Context| println!("serving on {:?}", c.int_flag("port")))
+                .flag(Flag::new("port", FlagType::Int).alias("p")),
+        )
+        .command(
+            Command::new("cp")
+                .action(|c: &Context| println!("copying {:?}", c.args))
+                .arg(Arg::new("SRC", FlagType::String).required())
+                .arg(Arg::new("DEST", FlagType::String).required()),
+        )
+        .command(
+            Command::new("greet")
+                .action(|c: &Context| println!("hello {:?}", c.args))
+                .arg(Arg::new("NAME", FlagType::String).validator(|v: &str| {
+                    if v.chars().all(char::is_alphabetic) {
+                        Ok(())
+                    } else {
+                        Err("must only contain letters".to_string())
+                    }
+                })),
+        )
+}
+
+/// Runs the app with the arguments in a child process, returning
+/// its exit code, stdout and stderr
+fn run(args: &str) -> (Option<i32>, String, String) {
+    run_in(process::Command::new(env::current_exe().unwrap()).env(CHILD, args))
+}
+
+fn run_in(command: &mut process::Command) -> (Option<i32>, String, String) {
+    let output = command
+        .args(&["--exact", "child", "--nocapture", "--test-threads", "1"])
+        .output()
+        .unwrap();
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn child() {
+    if let Ok(args) = env::var(CHILD) {
+        let app = match env::var_os(STREAMS) {
+            Some(dir) => {
+                let dir = Path::new(&dir);
+                app()
+                    .out(File::create(dir.join("out")).unwrap())
+                    .err(File::create(dir.join("err")).unwrap())
+            }
+            None => app(),
+        };
+        app.run(args.split(' ').map(String::from).collect());
+    }
+}
+
+#[test]
+fn streams_test() {
+    let dir = env::temp_dir().join(format!("seahorse-streams-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let run = |args: &str| {
+        let (code, _, stderr) = run_in(
+            process::Command::new(env::current_exe().unwrap())
+                .env(CHILD, args)
+                .env(STREAMS, &dir),
+        );
+        let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+        (code, read("out"), read("err"), stderr)
+    };
+
+    let (code, out, err, stderr) = run("cli serve --port http");
+    assert_eq!(code, Some(1));
+    assert_eq!(out, "");
+    assert!(err.starts_with("Invalid value \"http\" for flag \"port\", expected int\n"));
+    assert!(!stderr.contains("Invalid value"));
+
+    let (code, out, err, _) = run("cli serve --help");
+    assert_eq!(code, Some(0));
+    assert!(out.contains("Usage:\n\tcli serve [flags]"));
+    assert_eq!(err, "");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn invalid_number_test() {
+    let (code, stdout, stderr) = run("cli serve --port http");
+
+    assert_eq!(code, Some(1));
+    assert!(!stdout.contains("serving"));
+    assert!(stderr.contains(
+        "Invalid value \"http\" for flag \"port\", expected int\n  \
+         cli serve --port http\n            \
+         ~~~~~~ ^^^^\n\
+         Usage: cli serve [flags] [args]\n\
+         Try 'cli serve --help' for more information.\n"
+    ));
+
+    let (code, stdout, _) = run("cli serve -p 8080");
+    assert_eq!(code, Some(0));
+    assert!(stdout.contains("serving on Ok(8080)"));
+}
+
+#[test]
+fn missing_args_test() {
+    let (code, stdout, stderr) = run("cli cp");
+
+    assert_eq!(code, Some(1));
+    assert!(!stdout.contains("copying"));
+    assert!(stderr.contains("cli cp: Missing required arguments: <SRC>, <DEST>\n"));
+    assert!(!stderr.contains("Usage:"));
+
+    let (code, stdout, _) = run("cli cp --help");
+    assert_eq!(code, Some(0));
+    assert!(stdout.contains("Usage:\n\tcli cp [flags] <SRC> <DEST>"));
+}
+
+#[test]
+fn arg_validator_test() {
+    let (code, stdout, stderr) = run("cli greet b0b");
+
+    assert_eq!(code, Some(1));
+    assert!(!stdout.contains("hello"));
+    assert!(
+        stderr.contains("Invalid value \"b0b\" for argument \"NAME\": must only contain letters\n")
+    );
+
+    let (code, stdout, _) = run("cli greet bob");
+    assert_eq!(code, Some(0));
+    assert!(stdout.contains("hello [\"bob\"]"));
+}
+
+#[test]
+fn prompt_test() {
+    let (code, stdout, stderr) = run("cli login");
+    assert_eq!(code, Some(0));
+    assert!(stderr.contains("user: "));
+    assert!(stdout.contains("login as Ok(\"alice\")"));
+
+    let (code, stdout, stderr) = run("cli login --user bob");
+    assert_eq!(code, Some(0));
+    assert!(!stderr.contains("user: "));
+    assert!(stdout.contains("login as Ok(\"bob\")"));
+
+    let (code, stdout, stderr) = run("cli login --no-input");
+    assert_eq!(code, Some(1));
+    assert!(!stdout.contains("login as"));
+    assert!(stderr.contains(
+        "Missing required flag \"user\"\nUsage: cli login [flags] [args]\n\
+         Try 'cli login --help' for more information.\n"
+    ));
+}
+
+#[test]
+fn exit_test() {
+    let (code, stdout, _) = run("cli deploy");
+    assert_eq!(code, Some(3));
+    assert!(stdout.contains("released lock, status Some(3)"));
+}
+
+#[test]
+fn color_flag_test() {
+    let (code, _, stderr) = run("cli serve --port http --color always");
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("\x1b[1;31mInvalid value \"http\""));
+
+    let (_, _, stderr) = run("cli --color=always serve --port http");
+    assert!(stderr.contains("\x1b["));
+    let (_, _, stderr) = run("cli serve --port http --color never");
+    assert!(stderr.starts_with("Invalid value \"http\""));
+    assert!(!stderr.contains('\x1b'));
+    let (_, _, stderr) = run("cli serve --port http --color auto");
+    assert!(!stderr.contains('\x1b'));
+
+    let (code, stdout, _) = run("cli --color always --help");
+    assert_eq!(code, Some(0));
+    assert!(stdout.contains("\x1b[1;33mFlags:\x1b[0m"));
+    assert!(stdout.contains("--color"));
+    let (_, stdout, _) = run("cli serve --help --color never");
+    assert!(stdout.contains("Flags:"));
+    assert!(!stdout.contains('\x1b'));
+
+    let (code, stdout, _) = run("cli serve -p 8080 --color never");
+    assert_eq!(code, Some(0));
+    assert!(stdout.contains("serving on Ok(8080)"));
+
+    let (code, _, stderr) = run("cli --color rainbow serve");
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains(
+        "Invalid value for flag \"color\": expected auto, always or never, got \"rainbow\""
+    ));
+}
+
+/// App failing without a child process, through `try_run`
+fn failing_app() -> App {
+    App::new("cli")
+        .flag(Flag::new("user", FlagType::String).required())
+        .on_unknown_flag(|_: &str, _: &[String]| UnknownFlag::Error)
+        .action(|_: &Context| {})
+        .command(
+            Command::new("serve")
+                .flag(Flag::new("port", FlagType::Int).alias("p"))
+                .flag(Flag::new("motd", FlagType::String).allow_from_file())
+                .action(|_: &Context| {}),
+        )
+}
+
+fn try_run(line: &str) -> Error {
+    failing_app().try_run_from_str(line).unwrap_err()
+}
+
+#[test]
+fn error_payload_test() {
+    use std::error::Error as _;
+
+    let missing = try_run("cli");
+    assert_eq!(missing.kind(), ErrorKind::MissingFlag);
+    assert_eq!(missing.flag.as_ref().map(String::as_str), Some("user"));
+    assert_eq!(missing.command, ["cli"]);
+    assert!(missing.source().is_none());
+
+    let invalid = try_run("cli serve -p http");
+    assert_eq!(invalid.kind(), ErrorKind::InvalidValue);
+    assert_eq!(invalid.flag.as_ref().map(String::as_str), Some("port"));
+    assert_eq!(invalid.token.as_ref().map(String::as_str), Some("http"));
+    assert_eq!(invalid.command, ["cli", "serve"]);
+
+    let unknown = try_run("cli serve --prot 80");
+    assert_eq!(unknown.kind(), ErrorKind::UnknownFlag);
+    assert_eq!(unknown.token.as_ref().map(String::as_str), Some("--prot"));
+    assert_eq!(
+        unknown.suggestion.as_ref().map(String::as_str),
+        Some("--port")
+    );
+    assert_eq!(unknown.flag, None);
+
+    let unreadable = try_run("cli serve --motd @/nonexistent/seahorse-motd");
+    assert_eq!(unreadable.kind(), ErrorKind::Io);
+    assert_eq!(unreadable.flag.as_ref().map(String::as_str), Some("motd"));
+    let source = unreadable.source().unwrap();
+    assert_eq!(
+        source
+            .downcast_ref::<std::io::Error>()
+            .map(std::io::Error::kind),
+        Some(std::io::ErrorKind::NotFound)
+    );
+
+    let unsplit = try_run("cli 'serve");
+    assert_eq!(unsplit.kind(), ErrorKind::Split);
+    assert_eq!(
+        unsplit
+            .source()
+            .and_then(|e| e.downcast_ref::<seahorse::SplitError>()),
+        Some(&seahorse::SplitError::UnterminatedQuote {
+            quote: '\'',
+            position: 5
+        })
+    );
+}
@@ -0,0 +1,46 @@
+_cli() {
+    local i cur prev cmd opts
+    COMPREPLY=()
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+    cmd="cli"
+
+    if [[ "${cur}" == "=" ]]; then
+        cur=""
+    elif [[ "${prev}" == "=" && ${COMP_CWORD} -gt 1 ]]; then
+        prev="${COMP_WORDS[COMP_CWORD-2]}"
+    fi
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${cmd},${COMP_WORDS[i]}" in
+            'cli,export')
+                cmd="cli__export"
+                ;;
+        esac
+    done
+
+    case "${cmd}" in
+        cli)
+            opts='export --help -h'
+            COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            ;;
+        cli__export)
+            opts='--format --region -r --help -h'
+            case "${prev}" in
+                '--format')
+                    COMPREPLY=($(compgen -W 'json yaml toml' -- "${cur}"))
+                    return 0
+                    ;;
+                '--region' | '-r')
+                    local IFS=$'\n'
+                    COMPREPLY=($(compgen -W "$(printf '%q\n' 'us east' 'eu-west' 'it'\''s')" -- "${cur}"))
+                    [[ ${#COMPREPLY[@]} -gt 0 ]] && COMPREPLY=($(printf '%q\n' "${COMPREPLY[@]}"))
+                    return 0
+                    ;;
+            esac
+            COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            ;;
+    esac
+}
+
+complete -F _cli 'cli'
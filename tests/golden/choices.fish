@@ -0,0 +1,5 @@
+complete -c 'cli' -n '__fish_use_subcommand' -f -a 'export'
+complete -c 'cli' -n '__fish_use_subcommand' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from export' -l 'format' -r -f -a 'json yaml toml' -d 'Output format'
+complete -c 'cli' -n '__fish_seen_subcommand_from export' -l 'region' -s 'r' -r -f -a 'us\\ east eu-west it\\\'s'
+complete -c 'cli' -n '__fish_seen_subcommand_from export' -l 'help' -s 'h' -d 'Show help'
@@ -0,0 +1,12 @@
+complete -c 'cli' -n '__fish_use_subcommand' -f -a 'remote' -d 'Manage remotes'
+complete -c 'cli' -n '__fish_use_subcommand' -f -a 'r' -d 'Manage remotes'
+complete -c 'cli' -n '__fish_use_subcommand' -l 'verbose' -s 'v' -d 'Verbose output'
+complete -c 'cli' -n '__fish_use_subcommand' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and not __fish_seen_subcommand_from add remove' -f -a 'add' -d 'Add a remote: it\'s fetched right away'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and not __fish_seen_subcommand_from add remove' -f -a 'remove'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and not __fish_seen_subcommand_from add remove' -l 'format' -s 'f' -r -f -a 'json yaml' -d 'Output format'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and not __fish_seen_subcommand_from add remove' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and __fish_seen_subcommand_from add' -l 'name' -r -d 'Remote name [default: \'origin\']'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and __fish_seen_subcommand_from add' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and __fish_seen_subcommand_from remove' -l 'force' -d '--force, skip checks'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and __fish_seen_subcommand_from remove' -l 'help' -s 'h' -d 'Show help'
@@ -0,0 +1,91 @@
+using namespace System.Management.Automation
+using namespace System.Management.Automation.Language
+
+Register-ArgumentCompleter -Native -CommandName 'cli' -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $commandElements = $commandAst.CommandElements
+    $command = @(
+        'cli'
+        for ($i = 1; $i -lt $commandElements.Count; $i++) {
+            $element = $commandElements[$i]
+            if ($element -isnot [StringConstantExpressionAst] -or
+                $element.StringConstantType -ne [StringConstantType]::BareWord -or
+                $element.Value.StartsWith('-') -or
+                $element.Value -eq $wordToComplete) {
+                break
+            }
+            $element.Value
+        }) -join ';'
+
+    $index = $commandElements.Count - 1
+    if ($wordToComplete -ne '') { $index-- }
+    $previous = $commandElements[$index].ToString()
+
+    $completions = @(switch ($command) {
+        'cli' {
+            [CompletionResult]::new('remote', 'remote', [CompletionResultType]::ParameterValue, 'Manage remotes')
+            [CompletionResult]::new('r', 'r', [CompletionResultType]::ParameterValue, 'Manage remotes')
+            [CompletionResult]::new('--verbose', 'verbose', [CompletionResultType]::ParameterName, 'Verbose output')
+            [CompletionResult]::new('-v', 'v', [CompletionResultType]::ParameterName, 'Verbose output')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote' {
+            if ($previous -eq '--format' -or $previous -eq '-f') {
+                [CompletionResult]::new('json', 'json', [CompletionResultType]::ParameterValue, 'json')
+                [CompletionResult]::new('yaml', 'yaml', [CompletionResultType]::ParameterValue, 'yaml')
+                break
+            }
+            [CompletionResult]::new('add', 'add', [CompletionResultType]::ParameterValue, 'Add a remote: it''s fetched right away')
+            [CompletionResult]::new('remove', 'remove', [CompletionResultType]::ParameterValue, 'remove')
+            [CompletionResult]::new('--format', 'format', [CompletionResultType]::ParameterName, 'Output format')
+            [CompletionResult]::new('-f', 'f', [CompletionResultType]::ParameterName, 'Output format')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r' {
+            if ($previous -eq '--format' -or $previous -eq '-f') {
+                [CompletionResult]::new('json', 'json', [CompletionResultType]::ParameterValue, 'json')
+                [CompletionResult]::new('yaml', 'yaml', [CompletionResultType]::ParameterValue, 'yaml')
+                break
+            }
+            [CompletionResult]::new('add', 'add', [CompletionResultType]::ParameterValue, 'Add a remote: it''s fetched right away')
+            [CompletionResult]::new('remove', 'remove', [CompletionResultType]::ParameterValue, 'remove')
+            [CompletionResult]::new('--format', 'format', [CompletionResultType]::ParameterName, 'Output format')
+            [CompletionResult]::new('-f', 'f', [CompletionResultType]::ParameterName, 'Output format')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote;add' {
+            [CompletionResult]::new('--name', 'name', [CompletionResultType]::ParameterName, 'Remote name [default: ''origin'']')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r;add' {
+            [CompletionResult]::new('--name', 'name', [CompletionResultType]::ParameterName, 'Remote name [default: ''origin'']')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote;remove' {
+            [CompletionResult]::new('--force', 'force', [CompletionResultType]::ParameterName, '--force, skip checks')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r;remove' {
+            [CompletionResult]::new('--force', 'force', [CompletionResultType]::ParameterName, '--force, skip checks')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+    })
+
+    $completions.Where{ $_.CompletionText -like "$wordToComplete*" } |
+        Sort-Object -Property ListItemText
+}
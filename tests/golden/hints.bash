@@ -0,0 +1,112 @@
+_cli() {
+    local i cur prev cmd opts
+    COMPREPLY=()
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+    cmd="cli"
+
+    if [[ "${cur}" == "=" ]]; then
+        cur=""
+    elif [[ "${prev}" == "=" && ${COMP_CWORD} -gt 1 ]]; then
+        prev="${COMP_WORDS[COMP_CWORD-2]}"
+    fi
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${cmd},${COMP_WORDS[i]}" in
+            'cli,build')
+                cmd="cli__build"
+                ;;
+            'cli,cd')
+                cmd="cli__cd"
+                ;;
+            'cli,exec')
+                cmd="cli__exec"
+                ;;
+            'cli,open')
+                cmd="cli__open"
+                ;;
+            'cli,tag')
+                cmd="cli__tag"
+                ;;
+        esac
+    done
+
+    case "${cmd}" in
+        cli)
+            opts='build cd exec open tag --help -h'
+            COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            ;;
+        cli__build)
+            opts='--output -o --config -c --target-dir --linker --label --help -h'
+            case "${prev}" in
+                '--output' | '-o')
+                    compopt -o default 2>/dev/null
+                    COMPREPLY=()
+                    return 0
+                    ;;
+                '--config' | '-c')
+                    compopt -o filenames 2>/dev/null
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return 0
+                    ;;
+                '--target-dir')
+                    compopt -o filenames 2>/dev/null
+                    COMPREPLY=($(compgen -d -- "${cur}"))
+                    return 0
+                    ;;
+                '--linker')
+                    compopt -o filenames 2>/dev/null
+                    COMPREPLY=($(compgen -f -- "${cur}"))
+                    return 0
+                    ;;
+                '--label')
+                    COMPREPLY=()
+                    return 0
+                    ;;
+            esac
+            if [[ "${cur}" == -* ]]; then
+                COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            else
+                compopt -o filenames 2>/dev/null
+                COMPREPLY=($(compgen -f -- "${cur}"))
+            fi
+            ;;
+        cli__cd)
+            opts='--help -h'
+            if [[ "${cur}" == -* ]]; then
+                COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            else
+                compopt -o filenames 2>/dev/null
+                COMPREPLY=($(compgen -d -- "${cur}"))
+            fi
+            ;;
+        cli__exec)
+            opts='--help -h'
+            if [[ "${cur}" == -* ]]; then
+                COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            else
+                compopt -o filenames 2>/dev/null
+                COMPREPLY=($(compgen -f -- "${cur}"))
+            fi
+            ;;
+        cli__open)
+            opts='--help -h'
+            if [[ "${cur}" == -* ]]; then
+                COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            else
+                compopt -o default 2>/dev/null
+                COMPREPLY=()
+            fi
+            ;;
+        cli__tag)
+            opts='--help -h'
+            if [[ "${cur}" == -* ]]; then
+                COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            else
+                COMPREPLY=()
+            fi
+            ;;
+    esac
+}
+
+complete -F _cli 'cli'
@@ -0,0 +1,21 @@
+complete -c 'cli' -n '__fish_use_subcommand' -f -a 'build'
+complete -c 'cli' -n '__fish_use_subcommand' -f -a 'cd'
+complete -c 'cli' -n '__fish_use_subcommand' -f -a 'exec'
+complete -c 'cli' -n '__fish_use_subcommand' -f -a 'open'
+complete -c 'cli' -n '__fish_use_subcommand' -f -a 'tag'
+complete -c 'cli' -n '__fish_use_subcommand' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from build' -l 'output' -s 'o' -r --force-files -d 'Write output to FILE'
+complete -c 'cli' -n '__fish_seen_subcommand_from build' -l 'config' -s 'c' -r --force-files
+complete -c 'cli' -n '__fish_seen_subcommand_from build' -l 'target-dir' -r -f -a '(__fish_complete_directories)'
+complete -c 'cli' -n '__fish_seen_subcommand_from build' -l 'linker' -r --force-files
+complete -c 'cli' -n '__fish_seen_subcommand_from build' -l 'label' -r -f
+complete -c 'cli' -n '__fish_seen_subcommand_from build' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from build' --force-files
+complete -c 'cli' -n '__fish_seen_subcommand_from cd' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from cd' -f -a '(__fish_complete_directories)'
+complete -c 'cli' -n '__fish_seen_subcommand_from exec' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from exec' --force-files
+complete -c 'cli' -n '__fish_seen_subcommand_from open' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from open' --force-files
+complete -c 'cli' -n '__fish_seen_subcommand_from tag' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from tag' -f
@@ -0,0 +1,84 @@
+using namespace System.Management.Automation
+using namespace System.Management.Automation.Language
+
+Register-ArgumentCompleter -Native -CommandName 'cli' -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $commandElements = $commandAst.CommandElements
+    $command = @(
+        'cli'
+        for ($i = 1; $i -lt $commandElements.Count; $i++) {
+            $element = $commandElements[$i]
+            if ($element -isnot [StringConstantExpressionAst] -or
+                $element.StringConstantType -ne [StringConstantType]::BareWord -or
+                $element.Value.StartsWith('-') -or
+                $element.Value -eq $wordToComplete) {
+                break
+            }
+            $element.Value
+        }) -join ';'
+
+    $index = $commandElements.Count - 1
+    if ($wordToComplete -ne '') { $index-- }
+    $previous = $commandElements[$index].ToString()
+
+    $completions = @(switch ($command) {
+        'cli' {
+            [CompletionResult]::new('build', 'build', [CompletionResultType]::ParameterValue, 'build')
+            [CompletionResult]::new('cd', 'cd', [CompletionResultType]::ParameterValue, 'cd')
+            [CompletionResult]::new('exec', 'exec', [CompletionResultType]::ParameterValue, 'exec')
+            [CompletionResult]::new('open', 'open', [CompletionResultType]::ParameterValue, 'open')
+            [CompletionResult]::new('tag', 'tag', [CompletionResultType]::ParameterValue, 'tag')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;build' {
+            if ($previous -eq '--output' -or $previous -eq '-o') {
+                break
+            }
+            if ($previous -eq '--config' -or $previous -eq '-c') {
+                break
+            }
+            if ($previous -eq '--target-dir') {
+                break
+            }
+            if ($previous -eq '--linker') {
+                break
+            }
+            [CompletionResult]::new('--output', 'output', [CompletionResultType]::ParameterName, 'Write output to FILE')
+            [CompletionResult]::new('-o', 'o', [CompletionResultType]::ParameterName, 'Write output to FILE')
+            [CompletionResult]::new('--config', 'config', [CompletionResultType]::ParameterName, 'config')
+            [CompletionResult]::new('-c', 'c', [CompletionResultType]::ParameterName, 'config')
+            [CompletionResult]::new('--target-dir', 'target-dir', [CompletionResultType]::ParameterName, 'target-dir')
+            [CompletionResult]::new('--linker', 'linker', [CompletionResultType]::ParameterName, 'linker')
+            [CompletionResult]::new('--label', 'label', [CompletionResultType]::ParameterName, 'label')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;cd' {
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;exec' {
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;open' {
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;tag' {
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+    })
+
+    $completions.Where{ $_.CompletionText -like "$wordToComplete*" } |
+        Sort-Object -Property ListItemText
+}
@@ -0,0 +1,62 @@
+_cli() {
+    local i cur prev cmd opts
+    COMPREPLY=()
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+    cmd="cli"
+
+    if [[ "${cur}" == "=" ]]; then
+        cur=""
+    elif [[ "${prev}" == "=" && ${COMP_CWORD} -gt 1 ]]; then
+        prev="${COMP_WORDS[COMP_CWORD-2]}"
+    fi
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${cmd},${COMP_WORDS[i]}" in
+            'cli,remote' | 'cli,r')
+                cmd="cli__remote"
+                ;;
+            'cli__remote,branch' | 'cli__remote,b')
+                cmd="cli__remote__branch"
+                ;;
+            'cli__remote,remove' | 'cli__remote,rm')
+                cmd="cli__remote__remove"
+                ;;
+            'cli__remote__branch,add' | 'cli__remote__branch,a')
+                cmd="cli__remote__branch__add"
+                ;;
+            'cli__remote__branch,prune')
+                cmd="cli__remote__branch__prune"
+                ;;
+        esac
+    done
+
+    case "${cmd}" in
+        cli)
+            opts='remote r --verbose -v --help -h'
+            COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            ;;
+        cli__remote)
+            opts='branch b remove rm --help -h'
+            COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            ;;
+        cli__remote__branch)
+            opts='add a prune --help -h'
+            COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            ;;
+        cli__remote__branch__add)
+            opts='--track -t --help -h'
+            COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            ;;
+        cli__remote__branch__prune)
+            opts='--dry-run --help -h'
+            COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            ;;
+        cli__remote__remove)
+            opts='--help -h'
+            COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+            ;;
+    esac
+}
+
+complete -F _cli 'cli'
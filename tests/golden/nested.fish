@@ -0,0 +1,18 @@
+complete -c 'cli' -n '__fish_use_subcommand' -f -a 'remote' -d 'Manage remotes'
+complete -c 'cli' -n '__fish_use_subcommand' -f -a 'r' -d 'Manage remotes'
+complete -c 'cli' -n '__fish_use_subcommand' -l 'verbose' -s 'v'
+complete -c 'cli' -n '__fish_use_subcommand' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and not __fish_seen_subcommand_from branch b remove rm' -f -a 'branch' -d 'Manage remote branches'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and not __fish_seen_subcommand_from branch b remove rm' -f -a 'b' -d 'Manage remote branches'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and not __fish_seen_subcommand_from branch b remove rm' -f -a 'remove'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and not __fish_seen_subcommand_from branch b remove rm' -f -a 'rm'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and not __fish_seen_subcommand_from branch b remove rm' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and __fish_seen_subcommand_from branch b; and not __fish_seen_subcommand_from add a prune' -f -a 'add' -d 'Add a branch'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and __fish_seen_subcommand_from branch b; and not __fish_seen_subcommand_from add a prune' -f -a 'a' -d 'Add a branch'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and __fish_seen_subcommand_from branch b; and not __fish_seen_subcommand_from add a prune' -f -a 'prune'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and __fish_seen_subcommand_from branch b; and not __fish_seen_subcommand_from add a prune' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and __fish_seen_subcommand_from branch b; and __fish_seen_subcommand_from add a' -l 'track' -s 't'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and __fish_seen_subcommand_from branch b; and __fish_seen_subcommand_from add a' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and __fish_seen_subcommand_from branch b; and __fish_seen_subcommand_from prune' -l 'dry-run'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and __fish_seen_subcommand_from branch b; and __fish_seen_subcommand_from prune' -l 'help' -s 'h' -d 'Show help'
+complete -c 'cli' -n '__fish_seen_subcommand_from remote r; and __fish_seen_subcommand_from remove rm' -l 'help' -s 'h' -d 'Show help'
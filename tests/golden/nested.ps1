@@ -0,0 +1,189 @@
+using namespace System.Management.Automation
+using namespace System.Management.Automation.Language
+
+Register-ArgumentCompleter -Native -CommandName 'cli' -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $commandElements = $commandAst.CommandElements
+    $command = @(
+        'cli'
+        for ($i = 1; $i -lt $commandElements.Count; $i++) {
+            $element = $commandElements[$i]
+            if ($element -isnot [StringConstantExpressionAst] -or
+                $element.StringConstantType -ne [StringConstantType]::BareWord -or
+                $element.Value.StartsWith('-') -or
+                $element.Value -eq $wordToComplete) {
+                break
+            }
+            $element.Value
+        }) -join ';'
+
+    $index = $commandElements.Count - 1
+    if ($wordToComplete -ne '') { $index-- }
+    $previous = $commandElements[$index].ToString()
+
+    $completions = @(switch ($command) {
+        'cli' {
+            [CompletionResult]::new('remote', 'remote', [CompletionResultType]::ParameterValue, 'Manage remotes')
+            [CompletionResult]::new('r', 'r', [CompletionResultType]::ParameterValue, 'Manage remotes')
+            [CompletionResult]::new('--verbose', 'verbose', [CompletionResultType]::ParameterName, 'verbose')
+            [CompletionResult]::new('-v', 'v', [CompletionResultType]::ParameterName, 'verbose')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote' {
+            [CompletionResult]::new('branch', 'branch', [CompletionResultType]::ParameterValue, 'Manage remote branches')
+            [CompletionResult]::new('b', 'b', [CompletionResultType]::ParameterValue, 'Manage remote branches')
+            [CompletionResult]::new('remove', 'remove', [CompletionResultType]::ParameterValue, 'remove')
+            [CompletionResult]::new('rm', 'rm', [CompletionResultType]::ParameterValue, 'remove')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r' {
+            [CompletionResult]::new('branch', 'branch', [CompletionResultType]::ParameterValue, 'Manage remote branches')
+            [CompletionResult]::new('b', 'b', [CompletionResultType]::ParameterValue, 'Manage remote branches')
+            [CompletionResult]::new('remove', 'remove', [CompletionResultType]::ParameterValue, 'remove')
+            [CompletionResult]::new('rm', 'rm', [CompletionResultType]::ParameterValue, 'remove')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote;branch' {
+            [CompletionResult]::new('add', 'add', [CompletionResultType]::ParameterValue, 'Add a branch')
+            [CompletionResult]::new('a', 'a', [CompletionResultType]::ParameterValue, 'Add a branch')
+            [CompletionResult]::new('prune', 'prune', [CompletionResultType]::ParameterValue, 'prune')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote;b' {
+            [CompletionResult]::new('add', 'add', [CompletionResultType]::ParameterValue, 'Add a branch')
+            [CompletionResult]::new('a', 'a', [CompletionResultType]::ParameterValue, 'Add a branch')
+            [CompletionResult]::new('prune', 'prune', [CompletionResultType]::ParameterValue, 'prune')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r;branch' {
+            [CompletionResult]::new('add', 'add', [CompletionResultType]::ParameterValue, 'Add a branch')
+            [CompletionResult]::new('a', 'a', [CompletionResultType]::ParameterValue, 'Add a branch')
+            [CompletionResult]::new('prune', 'prune', [CompletionResultType]::ParameterValue, 'prune')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r;b' {
+            [CompletionResult]::new('add', 'add', [CompletionResultType]::ParameterValue, 'Add a branch')
+            [CompletionResult]::new('a', 'a', [CompletionResultType]::ParameterValue, 'Add a branch')
+            [CompletionResult]::new('prune', 'prune', [CompletionResultType]::ParameterValue, 'prune')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote;branch;add' {
+            [CompletionResult]::new('--track', 'track', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('-t', 't', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote;branch;a' {
+            [CompletionResult]::new('--track', 'track', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('-t', 't', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote;b;add' {
+            [CompletionResult]::new('--track', 'track', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('-t', 't', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote;b;a' {
+            [CompletionResult]::new('--track', 'track', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('-t', 't', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r;branch;add' {
+            [CompletionResult]::new('--track', 'track', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('-t', 't', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r;branch;a' {
+            [CompletionResult]::new('--track', 'track', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('-t', 't', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r;b;add' {
+            [CompletionResult]::new('--track', 'track', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('-t', 't', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r;b;a' {
+            [CompletionResult]::new('--track', 'track', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('-t', 't', [CompletionResultType]::ParameterName, 'track')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote;branch;prune' {
+            [CompletionResult]::new('--dry-run', 'dry-run', [CompletionResultType]::ParameterName, 'dry-run')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote;b;prune' {
+            [CompletionResult]::new('--dry-run', 'dry-run', [CompletionResultType]::ParameterName, 'dry-run')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r;branch;prune' {
+            [CompletionResult]::new('--dry-run', 'dry-run', [CompletionResultType]::ParameterName, 'dry-run')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r;b;prune' {
+            [CompletionResult]::new('--dry-run', 'dry-run', [CompletionResultType]::ParameterName, 'dry-run')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote;remove' {
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;remote;rm' {
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r;remove' {
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+        'cli;r;rm' {
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Show help')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Show help')
+            break
+        }
+    })
+
+    $completions.Where{ $_.CompletionText -like "$wordToComplete*" } |
+        Sort-Object -Property ListItemText
+}
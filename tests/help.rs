@@ -0,0 +1,158 @@
+use seahorse::{App, Arg, Command, Context, Flag, FlagType};
+
+fn app() -> App {
+    App::new("cli")
+        .description("Representative cli")
+        .version("1.0.0")
+        .action(|c: &Context| println!("{:?}", c.args))
+        .flag(
+            Flag::new("verbose", FlagType::Bool)
+                .description("Verbose output")
+                .alias("v"),
+        )
+        .command(
+            Command::new("copy")
+                .description("Copy files")
+                .alias("cp")
+                .arg(Arg::new("SRC", FlagType::String).required())
+                .arg(Arg::new("DEST", FlagType::String).required())
+                .flag(
+                    Flag::new("force", FlagType::Bool)
+                        .description("Overwrite existing files")
+                        .alias("f"),
+                ),
+        )
+}
+
+#[test]
+fn help_golden_test() {
+    let app = app();
+    assert_eq!(app.render_help(), include_str!("golden/help.txt"));
+
+    let mut written = Vec::new();
+    app.write_help(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), app.render_help());
+}
+
+#[test]
+fn command_help_golden_test() {
+    let app = app();
+    assert_eq!(
+        app.commands()[0].render_help("cli"),
+        include_str!("golden/copy_help.txt")
+    );
+}
+
+fn serve() -> Command {
+    Command::new("serve")
+        .description("Serve the site")
+        .flag(
+            Flag::new("port", FlagType::Int)
+                .description("Port to listen on")
+                .alias("p")
+                .help_section("Network"),
+        )
+        .flag(
+            Flag::new("open", FlagType::Bool)
+                .description("Open the site in a browser")
+                .alias("o"),
+        )
+        .flag(
+            Flag::new("cert", FlagType::String)
+                .description("Certificate file")
+                .help_section("TLS"),
+        )
+        .flag(
+            Flag::new("host", FlagType::String)
+                .description("Address to bind")
+                .help_section("Network"),
+        )
+        .flag(
+            Flag::new("key", FlagType::String)
+                .description("Private key file")
+                .help_section("TLS"),
+        )
+        .flag(Flag::new("watch", FlagType::Bool).description("Rebuild on changes"))
+}
+
+#[test]
+fn help_section_golden_test() {
+    assert_eq!(
+        serve().render_help("cli"),
+        include_str!("golden/serve_help.txt")
+    );
+
+    let app = App::new("cli").command(serve().action(|c: &Context| {
+        assert_eq!(c.int_flag("port"), Ok(8080));
+        assert_eq!(c.string_flag("cert"), Ok("site.pem".to_string()));
+        assert!(c.bool_flag("watch"));
+    }));
+    let args = [
+        "cli", "serve", "-p", "8080", "--watch", "--cert", "site.pem",
+    ];
+    assert_eq!(
+        app.try_run(args.iter().map(|a| a.to_string()).collect()),
+        Ok(0)
+    );
+}
+
+fn build() -> Command {
+    Command::new("build")
+        .description("Build the project")
+        .alias("b")
+        .alias("bd")
+        .hidden_alias("make")
+        .action(|c: &Context| assert_eq!(c.uint_flag("jobs"), Ok(4)))
+        .flag(
+            Flag::new("jobs", FlagType::Uint)
+                .description("Parallel jobs")
+                .alias("j")
+                .alias("J")
+                .hidden_alias("threads"),
+        )
+        .flag(Flag::new("release", FlagType::Bool).description("Optimized build"))
+}
+
+#[test]
+fn alias_help_golden_test() {
+    let app = App::new("cli")
+        .action(|_: &Context| {})
+        .command(build())
+        .command(Command::new("test").description("Run the tests"));
+    assert_eq!(app.render_help(), include_str!("golden/alias_app_help.txt"));
+    assert_eq!(
+        app.commands()[0].render_help("cli"),
+        include_str!("golden/build_help.txt")
+    );
+
+    for args in &[
+        ["cli", "b", "-j", "4"],
+        ["cli", "make", "-J", "4"],
+        ["cli", "bd", "-threads", "4"],
+    ] {
+        assert_eq!(
+            app.try_run(args.iter().map(|a| a.to_string()).collect()),
+            Ok(0)
+        );
+    }
+}
+
+#[test]
+fn no_description_golden_test() {
+    let app = App::new("cli")
+        .flag(Flag::new("quiet", FlagType::Bool).alias("q"))
+        .flag(Flag::new("level", FlagType::Uint).description(""))
+        .command(Command::new("build").alias("b"))
+        .command(
+            Command::new("test")
+                .description("Run the tests")
+                .arg(Arg::new("FILTER", FlagType::String).description("")),
+        );
+    let help = app.render_help();
+    assert!(help.lines().all(|line| !line.ends_with(' ')));
+    assert_eq!(help, include_str!("golden/no_description_help.txt"));
+    assert_eq!(
+        app.commands()[1].render_help("cli"),
+        include_str!("golden/no_description_test_help.txt")
+    );
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use seahorse::{App, Command, Flag, FlagType};
+
+fn app() -> App {
+    App::new("cli")
+        .description("Manage remotes")
+        .flag(Flag::new("verbose", FlagType::Bool).alias("v"))
+        .command(
+            Command::new("remote")
+                .description("Manage remotes")
+                .category("Sync")
+                .command(
+                    Command::new("add")
+                        .alias("a")
+                        .flag(Flag::new("name", FlagType::String).description("Remote name")),
+                )
+                .command(Command::new("prune").hidden()),
+        )
+        .command(
+            Command::new("serve")
+                .flag(
+                    Flag::new("port", FlagType::Uint)
+                        .alias("p")
+                        .default_value("8080"),
+                )
+                .flag(Flag::new("debug", FlagType::Bool).hidden()),
+        )
+}
+
+/// One line per flag and command, indented by depth
+fn tree(commands: &[Arc<Command>], depth: usize, lines: &mut Vec<String>) {
+    for command in commands {
+        lines.push(format!(
+            "{}{} aliases={:?} description={:?} category={:?} hidden={}",
+            "  ".repeat(depth),
+            command.name(),
+            command.aliases(),
+            command.get_description(),
+            command.get_category(),
+            command.is_hidden()
+        ));
+        for flag in command.flags() {
+            lines.push(flag_line(flag, depth + 1));
+        }
+        tree(command.subcommands(), depth + 1, lines);
+    }
+}
+
+fn flag_line(flag: &Flag, depth: usize) -> String {
+    format!(
+        "{}--{} {} aliases={:?} description={:?} default={:?} hidden={}",
+        "  ".repeat(depth),
+        flag.name(),
+        flag.flag_type().name(),
+        flag.aliases(),
+        flag.get_description(),
+        flag.get_default_value(),
+        flag.is_hidden()
+    )
+}
+
+#[test]
+fn tree_test() {
+    let app = app();
+    let mut lines = vec![format!("{} {:?}", app.name(), app.get_description())];
+    lines.extend(app.flags().iter().map(|flag| flag_line(flag, 1)));
+    tree(app.commands(), 1, &mut lines);
+
+    assert_eq!(
+        lines,
+        vec![
+            r#"cli Some("Manage remotes")"#,
+            r#"  --verbose bool aliases=["v"] description=None default=None hidden=false"#,
+            r#"  remote aliases=[] description=Some("Manage remotes") category=Some("Sync") hidden=false"#,
+            r#"    add aliases=["a"] description=None category=None hidden=false"#,
+            r#"      --name string aliases=[] description=Some("Remote name") default=None hidden=false"#,
+            r#"    prune aliases=[] description=None category=None hidden=true"#,
+            r#"  serve aliases=[] description=None category=None hidden=false"#,
+            r#"    --port uint aliases=["p"] description=None default=Some("8080") hidden=false"#,
+            r#"    --debug bool aliases=[] description=None default=None hidden=true"#,
+        ]
+    );
+}
@@ -0,0 +1,105 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use seahorse::{App, Command, Context, Flag, FlagType};
+
+fn app() -> App {
+    App::new("cli")
+        .description("Representative \"cli\"")
+        .author("Jane Doe")
+        .version("1.0.0")
+        .action(|c: &Context| println!("{:?}", c.args))
+        .flag(
+            Flag::new("verbose", FlagType::Bool)
+                .description("Verbose output")
+                .alias("v"),
+        )
+        .flag(Flag::new("debug", FlagType::Bool).hidden())
+        .command(
+            Command::new("hello")
+                .description("Say hello")
+                .usage("cli hello [name]")
+                .alias("h")
+                .hidden_alias("hi")
+                .flag(
+                    Flag::new("count", FlagType::Int)
+                        .description("Repeat count")
+                        .alias("c")
+                        .env("CLI_COUNT")
+                        .default_value("1"),
+                )
+                .flag(
+                    Flag::new("lang", FlagType::String)
+                        .hidden_alias("language")
+                        .choices(&["en", "ja"])
+                        .default_value("en"),
+                )
+                .command(Command::new("world").description("Say hello to the world")),
+        )
+        .command(
+            Command::new("add")
+                .description("Add numbers")
+                .category("Math")
+                .flag(Flag::new("float", FlagType::Float).alias("f")),
+        )
+        .command(Command::new("secret").hidden())
+}
+
+#[test]
+fn json_golden_test() {
+    let mut dump = Vec::new();
+    app().generate_json(&mut dump).unwrap();
+
+    assert_eq!(
+        String::from_utf8(dump).unwrap(),
+        include_str!("golden/dump.json")
+    );
+}
+
+#[derive(Clone, Default)]
+struct Shared(Arc<Mutex<Vec<u8>>>);
+
+impl Shared {
+    fn take(&self) -> String {
+        String::from_utf8(std::mem::replace(&mut *self.0.lock().unwrap(), Vec::new())).unwrap()
+    }
+}
+
+impl Write for Shared {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn help_json_test() {
+    let out = Shared::default();
+    let app = app().out(out.clone());
+    let run = |line: &str| app.try_run(line.split(' ').map(String::from).collect());
+
+    for line in &[
+        "cli --help=json",
+        "cli --help json",
+        "cli --verbose --help=json",
+    ] {
+        assert_eq!(run(line), Ok(0), "{}", line);
+        assert_eq!(out.take(), include_str!("golden/dump.json"), "{}", line);
+    }
+
+    // Help of the command rather than the dump of the app
+    for line in &["cli add 1 --help json", "cli add --help=json"] {
+        assert_eq!(run(line), Ok(0), "{}", line);
+        let help = out.take();
+        assert!(help.contains("Add numbers"), "{}", line);
+        assert!(!help.starts_with('{'), "{}", line);
+    }
+
+    // Arguments of the action after `--`
+    assert_eq!(run("cli -- --help json"), Ok(0));
+    assert_eq!(out.take(), "");
+}
@@ -0,0 +1,78 @@
+use seahorse::{App, Arg, Command, Context, Flag, FlagType};
+
+fn app() -> App {
+    App::new("cli")
+        .description("Representative cli")
+        .usage("cli [command] [arg]")
+        .version("1.0.0")
+        .before_help("Welcome to cli")
+        .after_help("See https://example.com/docs for more")
+        .action(|c: &Context| println!("{:?}", c.args))
+        .flag(
+            Flag::new("verbose", FlagType::Bool)
+                .description("Verbose output")
+                .alias("v"),
+        )
+        .flag(Flag::new("debug", FlagType::Bool).hidden())
+        .command(
+            Command::new("hello")
+                .description("Say hello")
+                .usage("cli hello [name]")
+                .alias("h")
+                .after_help("Greets politely")
+                .example("Greet Alice", "cli hello Alice")
+                .example("Greet three times", "cli hello --count 3 Bob")
+                .flag(
+                    Flag::new("count", FlagType::Int)
+                        .description("Repeat count | times")
+                        .alias("c")
+                        .default_value("1"),
+                )
+                .flag(
+                    Flag::new("loud", FlagType::Bool)
+                        .description("Shout the greeting")
+                        .help_section("Style"),
+                )
+                .flag(
+                    Flag::new("emoji", FlagType::String)
+                        .description("Emoji to end with")
+                        .help_section("Style"),
+                )
+                .command(
+                    Command::new("world")
+                        .description("Say hello to the world")
+                        .usage("cli hello world"),
+                ),
+        )
+        .command(
+            Command::new("add")
+                .description("Add numbers")
+                .usage("cli add [num...]")
+                .version("0.3.0")
+                .category("Math")
+                .arg(
+                    Arg::new("NUM", FlagType::Int)
+                        .description("Numbers to add")
+                        .required()
+                        .variadic(),
+                ),
+        )
+        .command(
+            Command::new("sub")
+                .description("Subtract numbers")
+                .category("Math")
+                .flag(Flag::new("float", FlagType::Float).alias("f")),
+        )
+        .command(Command::new("secret").hidden())
+}
+
+#[test]
+fn markdown_golden_test() {
+    let mut doc = Vec::new();
+    app().generate_markdown(&mut doc).unwrap();
+
+    assert_eq!(
+        String::from_utf8(doc).unwrap(),
+        include_str!("golden/markdown.md")
+    );
+}
@@ -0,0 +1,144 @@
+use seahorse::{App, Arg, Command, Context, Flag, FlagGroup, FlagType, Prompt};
+use std::panic::{self, AssertUnwindSafe};
+
+/// App with a flag of each type and a couple of commands, the same as the
+/// fuzz target's
+fn app() -> App {
+    let action = |c: &Context| {
+        let _ = c.string_flag("name");
+        let _ = c.values::<i64>("point");
+        let _ = c.get::<u16>("port");
+    };
+    App::new("cli")
+        .use_pager(false)
+        .prompt(Prompt::non_interactive())
+        .action(action)
+        .flag(Flag::new("verbose", FlagType::Bool).alias("v").global())
+        .flag(Flag::new("name", FlagType::String).alias("n"))
+        .flag(Flag::new("count", FlagType::Int).alias("c"))
+        .flag(Flag::new("port", FlagType::Uint).alias("p"))
+        .flag(Flag::new("ratio", FlagType::Float))
+        .flag(Flag::new("token", FlagType::Secret))
+        .command(
+            Command::new("add")
+                .alias("a")
+                .action(action)
+                .flag(Flag::new("point", FlagType::Int).number_of_values(2))
+                .flag(Flag::new("tag", FlagType::String).repeatable())
+                .arg(Arg::new("NAME", FlagType::String).required())
+                .arg(Arg::new("PORT", FlagType::Uint)),
+        )
+        .command(
+            Command::new("remote")
+                .command(
+                    Command::new("prune")
+                        .action(action)
+                        .flag(Flag::new("dry-run", FlagType::Bool))
+                        .flag(Flag::new("all", FlagType::Bool))
+                        .flag_group(FlagGroup::new("mode").member("dry-run").member("all")),
+                )
+                .command(Command::new("show").action(action).max_args(1)),
+        )
+}
+
+const TOKENS: &[&str] = &[
+    "",
+    "-",
+    "--",
+    "=",
+    "-=",
+    "--=",
+    "cli",
+    "add",
+    "a",
+    "remote",
+    "prune",
+    "show",
+    "help",
+    "json",
+    "-h",
+    "--help",
+    "-V",
+    "--version",
+    "-v",
+    "-vv",
+    "--verbose",
+    "--verbose=",
+    "--verbose=true",
+    "-n",
+    "--name",
+    "--name=",
+    "--name=x=y",
+    "-c",
+    "--count",
+    "--count=-1",
+    "-p",
+    "--port",
+    "--port=http",
+    "--ratio",
+    "--ratio=1e400",
+    "--token",
+    "--point",
+    "--tag",
+    "--dry-run",
+    "--all",
+    "--unknown",
+    "-x",
+    "-name",
+    "-0",
+    "1",
+    "-1",
+    "18446744073709551616",
+    "1.5",
+    "NaN",
+    "é",
+    "--é",
+    "-é",
+    "@",
+    "/name",
+    "/n:",
+];
+
+/// Tokens drawn with a xorshift generator, seeded so failures reproduce
+struct Tokens(u64);
+
+impl Tokens {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn args(&mut self) -> Vec<String> {
+        let len = (self.next() % 8) as usize;
+        let mut args = Vec::with_capacity(len + 1);
+        if self.next() % 16 != 0 {
+            args.push("cli".to_string());
+        }
+        for _ in 0..len {
+            let token = TOKENS[(self.next() % TOKENS.len() as u64) as usize];
+            let other = TOKENS[(self.next() % TOKENS.len() as u64) as usize];
+            args.push(match self.next() % 8 {
+                0 => format!("{}={}", token, other),
+                1 => format!("{}{}", token, other),
+                _ => token.to_string(),
+            });
+        }
+        args
+    }
+}
+
+#[test]
+fn try_run_never_panics_test() {
+    let app = app();
+    assert_eq!(app.try_run(Vec::new()), Ok(0));
+    assert_eq!(app.try_run(vec!["remote".to_string()]), Ok(0));
+
+    let mut tokens = Tokens(0x2545_f491_4f6c_dd1d);
+    for _ in 0..5000 {
+        let args = tokens.args();
+        let run = panic::catch_unwind(AssertUnwindSafe(|| app.try_run(args.clone())));
+        assert!(run.is_ok(), "try_run panicked for {:?}", args);
+    }
+}
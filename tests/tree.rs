@@ -0,0 +1,54 @@
+use seahorse::{App, Command, Flag, FlagType};
+
+fn app() -> App {
+    App::new("cli")
+        .flag(Flag::new("verbose", FlagType::Bool).alias("v").global())
+        .flag(Flag::new("debug", FlagType::Bool).hidden())
+        .command(
+            Command::new("remote")
+                .alias("r")
+                .category("Sync")
+                .flag(Flag::new("dry-run", FlagType::Bool).alias("n"))
+                .command(
+                    Command::new("add")
+                        .alias("a")
+                        .hidden_alias("new")
+                        .flag(Flag::new("name", FlagType::String))
+                        .flag(
+                            Flag::new("tag", FlagType::String)
+                                .hidden_alias("label")
+                                .value_terminator(";"),
+                        ),
+                )
+                .command(Command::new("prune").hidden()),
+        )
+        .command(
+            Command::new("serve")
+                .category("Run")
+                .flag(Flag::new("port", FlagType::Uint).alias("p"))
+                .flag(Flag::new("bind", FlagType::String)),
+        )
+        .command(Command::new("version"))
+}
+
+#[test]
+fn tree_golden_test() {
+    let mut tree = Vec::new();
+    app().print_tree(&mut tree).unwrap();
+
+    assert_eq!(
+        String::from_utf8(tree).unwrap(),
+        include_str!("golden/tree.txt")
+    );
+}
+
+#[test]
+fn flag_tree_golden_test() {
+    let mut tree = Vec::new();
+    app().print_flag_tree(&mut tree).unwrap();
+
+    assert_eq!(
+        String::from_utf8(tree).unwrap(),
+        include_str!("golden/tree_flags.txt")
+    );
+}